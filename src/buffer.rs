@@ -1,13 +1,13 @@
 use crate::leb128::{encode_i32_to_leb128, encode_u32_to_leb128};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Default, PartialEq, Eq)]
 pub struct Buffer {
     pub bytes: Vec<u8>,
 }
 
 impl Buffer {
     pub fn new() -> Self {
-        Self { bytes: vec![] }
+        Self::default()
     }
 
     pub fn write_u8(&mut self, value: u8) {
@@ -54,7 +54,7 @@ impl Buffer {
 
 #[cfg(test)]
 mod leb128 {
-    use crate::parser::Parser;
+    use crate::leb128::{decode_signed_leb128, decode_unsigned_leb128};
 
     use super::*;
 
@@ -65,84 +65,84 @@ mod leb128 {
         assert_eq!(buffer.bytes, vec![0x00]);
         let mut value: u32;
         let mut size: u32;
-        (value, size) = Parser::read_u32(&mut buffer.bytes).expect("Invalid u32");
+        (value, size) = decode_unsigned_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(value, 0);
         assert_eq!(size, 1);
         buffer.clear();
 
         buffer.write_u32(1);
         assert_eq!(buffer.bytes, vec![0x01]);
-        (value, size) = Parser::read_u32(&mut buffer.bytes).expect("Invalid u32");
+        (value, size) = decode_unsigned_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(value, 1);
         assert_eq!(size, 1);
         buffer.clear();
 
         buffer.write_u32(127);
         assert_eq!(buffer.bytes, vec![0x7F]);
-        (value, size) = Parser::read_u32(&mut buffer.bytes).expect("Invalid u32");
+        (value, size) = decode_unsigned_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(value, 127);
         assert_eq!(size, 1);
         buffer.clear();
 
         buffer.write_u32(128);
         assert_eq!(buffer.bytes, vec![0x80, 0x01]);
-        (value, size) = Parser::read_u32(&mut buffer.bytes).expect("Invalid u32");
+        (value, size) = decode_unsigned_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(value, 128);
         assert_eq!(size, 2);
         buffer.clear();
 
         buffer.write_u32(129);
         assert_eq!(buffer.bytes, vec![0x81, 0x01]);
-        (value, size) = Parser::read_u32(&mut buffer.bytes).expect("Invalid u32");
+        (value, size) = decode_unsigned_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(value, 129);
         assert_eq!(size, 2);
         buffer.clear();
 
         buffer.write_u32(130);
         assert_eq!(buffer.bytes, vec![0x82, 0x01]);
-        (value, size) = Parser::read_u32(&mut buffer.bytes).expect("Invalid u32");
+        (value, size) = decode_unsigned_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(value, 130);
         assert_eq!(size, 2);
         buffer.clear();
 
         buffer.write_u32(255);
         assert_eq!(buffer.bytes, vec![0xFF, 0x01]);
-        (value, size) = Parser::read_u32(&mut buffer.bytes).expect("Invalid u32");
+        (value, size) = decode_unsigned_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(value, 255);
         assert_eq!(size, 2);
         buffer.clear();
 
         buffer.write_u32(256);
         assert_eq!(buffer.bytes, vec![0x80, 0x02]);
-        (value, size) = Parser::read_u32(&mut buffer.bytes).expect("Invalid u32");
+        (value, size) = decode_unsigned_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(value, 256);
         assert_eq!(size, 2);
         buffer.clear();
 
         buffer.write_u32(1023);
         assert_eq!(buffer.bytes, vec![0xFF, 0x07]);
-        (value, size) = Parser::read_u32(&mut buffer.bytes).expect("Invalid u32");
+        (value, size) = decode_unsigned_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(value, 1023);
         assert_eq!(size, 2);
         buffer.clear();
 
         buffer.write_u32(1024);
         assert_eq!(buffer.bytes, vec![0x80, 0x08]);
-        (value, size) = Parser::read_u32(&mut buffer.bytes).expect("Invalid u32");
+        (value, size) = decode_unsigned_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(value, 1024);
         assert_eq!(size, 2);
         buffer.clear();
 
         buffer.write_u32(65535);
         assert_eq!(buffer.bytes, vec![0xFF, 0xFF, 0x03]);
-        (value, size) = Parser::read_u32(&mut buffer.bytes).expect("Invalid u32");
+        (value, size) = decode_unsigned_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(value, 65535);
         assert_eq!(size, 3);
         buffer.clear();
 
         buffer.write_u32(65536);
         assert_eq!(buffer.bytes, vec![0x80, 0x80, 0x04]);
-        (value, size) = Parser::read_u32(&mut buffer.bytes).expect("Invalid u32");
+        (value, size) = decode_unsigned_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(value, 65536);
         assert_eq!(size, 3);
         buffer.clear();
@@ -156,28 +156,28 @@ mod leb128 {
         let mut i_value: i32;
         let mut size: u32;
 
-        (i_value, size) = Parser::read_i32(&mut buffer.bytes).expect("Invalid u32");
+        (i_value, size) = decode_signed_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(i_value, 0);
         assert_eq!(size, 1);
         buffer.clear();
 
         buffer.write_i32(-1);
         assert_eq!(buffer.bytes, vec![0x7F]);
-        (i_value, size) = Parser::read_i32(&mut buffer.bytes).expect("Invalid u32");
+        (i_value, size) = decode_signed_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(i_value, -1);
         assert_eq!(size, 1);
         buffer.clear();
 
         buffer.write_i32(-64);
         assert_eq!(buffer.bytes, vec![0x40]);
-        (i_value, size) = Parser::read_i32(&mut buffer.bytes).expect("Invalid u32");
+        (i_value, size) = decode_signed_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(i_value, -64);
         assert_eq!(size, 1);
         buffer.clear();
 
         buffer.write_i32(-128);
         assert_eq!(buffer.bytes, vec![0x80, 0x7F]);
-        (i_value, size) = Parser::read_i32(&mut buffer.bytes).expect("Invalid u32");
+        (i_value, size) = decode_signed_leb128(&buffer.bytes).expect("Invalid u32");
         assert_eq!(i_value, -128);
         assert_eq!(size, 2);
         buffer.clear();
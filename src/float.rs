@@ -0,0 +1,174 @@
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use crate::trap::Trap;
+
+/// Declares a float type that is stored as its raw bit pattern, so that NaN
+/// payloads pass through the stack, locals and memory unchanged.
+// https://webassembly.github.io/spec/core/exec/numerics.html#floating-point-operations
+macro_rules! float_type {
+    ($name:ident, $float:ident, $bits:ident) => {
+        #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+        pub struct $name($bits);
+
+        impl $name {
+            const SIGN_MASK: $bits = 1 << ($bits::BITS - 1);
+
+            pub fn from_bits(bits: $bits) -> Self {
+                Self(bits)
+            }
+
+            pub fn to_bits(self) -> $bits {
+                self.0
+            }
+
+            pub fn to_float(self) -> $float {
+                $float::from_bits(self.0)
+            }
+
+            pub fn is_nan(self) -> bool {
+                self.to_float().is_nan()
+            }
+
+            // abs and copysign only touch the sign bit, even for NaNs
+            pub fn abs(self) -> Self {
+                Self(self.0 & !Self::SIGN_MASK)
+            }
+
+            pub fn copysign(self, sign: Self) -> Self {
+                Self((self.0 & !Self::SIGN_MASK) | (sign.0 & Self::SIGN_MASK))
+            }
+
+            pub fn sqrt(self) -> Self {
+                self.to_float().sqrt().into()
+            }
+
+            pub fn ceil(self) -> Self {
+                self.to_float().ceil().into()
+            }
+
+            pub fn floor(self) -> Self {
+                self.to_float().floor().into()
+            }
+
+            pub fn trunc(self) -> Self {
+                self.to_float().trunc().into()
+            }
+
+            /// Rounds to the nearest integer, ties to even.
+            pub fn nearest(self) -> Self {
+                self.to_float().round_ties_even().into()
+            }
+
+            /// Unlike `f32::min`, a NaN operand makes the result NaN, and -0 is less than +0.
+            pub fn min(self, other: Self) -> Self {
+                let (lhs, rhs) = (self.to_float(), other.to_float());
+                if lhs.is_nan() || rhs.is_nan() {
+                    // arithmetic on a NaN operand propagates it
+                    return (lhs + rhs).into();
+                }
+                if lhs == rhs {
+                    // only differs for zeros, where the negative one wins
+                    return Self(self.0 | other.0);
+                }
+                if lhs < rhs {
+                    self
+                } else {
+                    other
+                }
+            }
+
+            /// Unlike `f32::max`, a NaN operand makes the result NaN, and +0 is greater than -0.
+            pub fn max(self, other: Self) -> Self {
+                let (lhs, rhs) = (self.to_float(), other.to_float());
+                if lhs.is_nan() || rhs.is_nan() {
+                    return (lhs + rhs).into();
+                }
+                if lhs == rhs {
+                    // only differs for zeros, where the positive one wins
+                    return Self(self.0 & other.0);
+                }
+                if lhs > rhs {
+                    self
+                } else {
+                    other
+                }
+            }
+        }
+
+        impl From<$float> for $name {
+            fn from(value: $float) -> Self {
+                Self(value.to_bits())
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            // flips the sign bit, even for NaNs
+            fn neg(self) -> Self {
+                Self(self.0 ^ Self::SIGN_MASK)
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                (self.to_float() + rhs.to_float()).into()
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                (self.to_float() - rhs.to_float()).into()
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                (self.to_float() * rhs.to_float()).into()
+            }
+        }
+
+        impl Div for $name {
+            type Output = Self;
+
+            fn div(self, rhs: Self) -> Self {
+                (self.to_float() / rhs.to_float()).into()
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if self.is_nan() {
+                    write!(f, "nan:{:#x}", self.0)
+                } else {
+                    write!(f, "{:?}", self.to_float())
+                }
+            }
+        }
+    };
+}
+
+float_type!(F32, f32, u32);
+float_type!(F64, f64, u64);
+
+/// Truncates `value` towards zero for `iN.trunc_fM_*`, trapping on NaN and on
+/// values outside the exclusive bounds `(min, max)` of the target integer type.
+// every f32 is exactly representable as an f64, so both widths are checked here
+pub fn checked_trunc(value: f64, min: f64, max: f64) -> Result<f64, Trap> {
+    if value.is_nan() {
+        return Err(Trap::InvalidConversionToInteger);
+    }
+    if value <= min || value >= max {
+        return Err(Trap::IntegerOverflow);
+    }
+    Ok(value.trunc())
+}
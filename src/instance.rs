@@ -1,101 +1,1327 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, OnceLock, PoisonError, RwLock, Weak},
+    time::Duration,
+};
 
 use crate::{
     module::ModuleNode,
-    node::{ExportTypeNode, FunctionNode, FunctionTypeNode},
+    names::NameMap,
+    node::{
+        DataModeNode, ElementInitNode, ElementModeNode, ExportTypeNode, ExpressionNode,
+        FunctionNode, FunctionTypeNode, ImportDescNode, InstructionNode,
+    },
+    runtime::Runtime,
+    shared_memory::{SharedMemory, WaitOutcome},
+    stack::{FuncRef, Number, Reference, Value},
+    trap::Trap,
+    types::{BlockType, GlobalType, Limits, MemoryType, TableType},
 };
 
+/// Size of a linear memory page in bytes.
+pub const PAGE_SIZE: usize = 65536;
+/// Maximum number of pages addressable by a 32-bit linear memory.
+pub const MAX_PAGES: u32 = 65536;
+/// Maximum number of elements in a table. The spec allows up to 2^32 - 1,
+/// which this implementation does not attempt to allocate.
+pub const MAX_TABLE_SIZE: u32 = 10_000_000;
+
 #[derive(Debug, Clone)]
 pub struct Instance {
     pub export_map: HashMap<String, Export>,
+    pub types: Vec<FunctionTypeNode>,
     pub functions: Vec<FunctionInstance>,
+    pub tables: Vec<TableInstance>,
+    pub memories: Vec<MemoryInstance>,
+    pub globals: Vec<GlobalInstance>,
+    pub elems: Vec<ElementInstance>,
+    pub datas: Vec<DataInstance>,
+    pub names: NameMap,
+    home: Home,
+}
+
+/// Where references to the functions of an instance find the instance: a
+/// copy of it, which shares its tables, memories, globals and segments and
+/// so behaves as the instance itself.
+#[derive(Clone)]
+struct Home {
+    address: Weak<OnceLock<Instance>>,
+    /// Keeps the copy alive for as long as the instance is. The copy itself
+    /// leaves this unset, or it would keep itself alive.
+    owner: Option<Arc<OnceLock<Instance>>>,
+}
+
+impl fmt::Debug for Home {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Home({:p})", self.address.as_ptr())
+    }
 }
 
 impl Instance {
-    pub fn new(module: &mut ModuleNode) -> Self {
+    pub fn new(module: &mut ModuleNode, imports: &Imports) -> Result<Self, InstantiationError> {
         module.make();
-        let functions = Instance::instantiate_functions(module);
-        let export_map = Instance::instantiate_exports(module);
+        let home = Arc::new(OnceLock::new());
+        let address = Arc::downgrade(&home);
+        let types = module.types().cloned().unwrap_or_default();
+        let (mut functions, mut tables, mut memories, mut globals) =
+            Instance::resolve_imports(module, imports)?;
+        let instantiated_functions =
+            Instance::instantiate_functions(module, functions.len() as u32)?;
+        functions.extend(instantiated_functions);
+        tables.extend(Instance::instantiate_tables(module));
+        memories.extend(Instance::instantiate_memories(module));
+        let instantiated_globals = Instance::instantiate_globals(module, &globals, &address)?;
+        globals.extend(instantiated_globals);
+        let spaces = IndexSpaces {
+            types: &types,
+            functions: functions.len(),
+            tables: tables.len(),
+            memories: memories.len(),
+            globals: &globals,
+            elems: module.elems().map_or(0, Vec::len),
+            datas: module.datas().map_or(0, Vec::len),
+        };
+        spaces.validate(module, &functions)?;
+        let elems = Instance::instantiate_elems(module, &mut tables, &globals, &address)?;
+        let datas = Instance::instantiate_datas(module, &mut memories, &globals, &address)?;
+        let export_map = Instance::instantiate_exports(module)?;
 
-        Instance {
+        let mut instance = Instance {
             export_map,
+            types,
             functions,
+            tables,
+            memories,
+            globals,
+            elems,
+            datas,
+            names: module.names().cloned().unwrap_or_default(),
+            home: Home {
+                address,
+                owner: None,
+            },
+        };
+        home.get_or_init(|| instance.clone());
+        instance.home.owner = Some(home);
+        match module.start() {
+            Some(function_index) => instance.run_start_function(function_index),
+            None => Ok(instance),
+        }
+    }
+
+    /// Runs the start function as the final step of instantiation.
+    /// The start function must have type `[] -> []`.
+    fn run_start_function(self, function_index: u32) -> Result<Self, InstantiationError> {
+        let function = match self.functions.get(function_index as usize) {
+            Some(function)
+                if function.function_type().params.val_types.is_empty()
+                    && function.function_type().returns.val_types.is_empty() =>
+            {
+                function.clone()
+            }
+            _ => {
+                return Err(InstantiationError::InvalidStartFunction {
+                    index: function_index,
+                })
+            }
+        };
+
+        let mut runtime = Runtime::new(self);
+        runtime
+            .call(function, None)
+            .map_err(InstantiationError::StartFunctionTrap)?;
+        Ok(runtime.into_instance())
+    }
+
+    /// Resolves every import of the module against the host-provided externs.
+    /// The returned instances occupy the front of their index spaces, and
+    /// imported tables, memories and globals are handles to the host's own.
+    #[allow(clippy::type_complexity)]
+    pub fn resolve_imports(
+        module: &ModuleNode,
+        imports: &Imports,
+    ) -> Result<
+        (
+            Vec<FunctionInstance>,
+            Vec<TableInstance>,
+            Vec<MemoryInstance>,
+            Vec<GlobalInstance>,
+        ),
+        InstantiationError,
+    > {
+        let mut functions: Vec<FunctionInstance> = vec![];
+        let mut tables: Vec<TableInstance> = vec![];
+        let mut memories: Vec<MemoryInstance> = vec![];
+        let mut globals: Vec<GlobalInstance> = vec![];
+        let Some(module_imports) = module.imports() else {
+            return Ok((functions, tables, memories, globals));
+        };
+
+        for import in module_imports.iter() {
+            let external = imports.get(&import.module, &import.name).ok_or_else(|| {
+                InstantiationError::UnknownImport {
+                    module: import.module.clone(),
+                    name: import.name.clone(),
+                }
+            })?;
+
+            match (&import.import_desc, external) {
+                (ImportDescNode::Function(type_index), Extern::Function(function)) => {
+                    let function_type = module
+                        .types()
+                        .and_then(|types| types.get(*type_index as usize))
+                        .ok_or(InstantiationError::UnknownType { index: *type_index })?;
+                    if *function_type != function.function_type {
+                        return Err(InstantiationError::IncompatibleImportType {
+                            module: import.module.clone(),
+                            name: import.name.clone(),
+                        });
+                    }
+                    functions.push(FunctionInstance::Host(function.clone()));
+                }
+                (ImportDescNode::Table(table_type), Extern::Table(table)) => {
+                    if table.table_type.element_type != table_type.element_type
+                        || !table.limits().matches(&table_type.limits)
+                    {
+                        return Err(InstantiationError::IncompatibleImportType {
+                            module: import.module.clone(),
+                            name: import.name.clone(),
+                        });
+                    }
+                    tables.push(table.clone());
+                }
+                (ImportDescNode::Memory(memory_type), Extern::Memory(memory)) => {
+                    if memory.is_shared() != memory_type.shared
+                        || !memory.limits().matches(&memory_type.limits)
+                    {
+                        return Err(InstantiationError::IncompatibleImportType {
+                            module: import.module.clone(),
+                            name: import.name.clone(),
+                        });
+                    }
+                    memories.push(memory.clone());
+                }
+                (ImportDescNode::Global(global_type), Extern::Global(global)) => {
+                    if *global_type != global.global_type {
+                        return Err(InstantiationError::IncompatibleImportType {
+                            module: import.module.clone(),
+                            name: import.name.clone(),
+                        });
+                    }
+                    globals.push(global.clone());
+                }
+                _ => {
+                    return Err(InstantiationError::IncompatibleImportType {
+                        module: import.module.clone(),
+                        name: import.name.clone(),
+                    });
+                }
+            }
         }
+        Ok((functions, tables, memories, globals))
     }
 
-    pub fn instantiate_functions(module: &ModuleNode) -> Vec<FunctionInstance> {
+    /// Instantiates the functions defined by the module, whose indices start
+    /// after the `first_index` imported functions.
+    pub fn instantiate_functions(
+        module: &ModuleNode,
+        first_index: u32,
+    ) -> Result<Vec<FunctionInstance>, InstantiationError> {
         let mut functions: Vec<FunctionInstance> = vec![];
-        let function_types = module
-            .type_section()
-            .unwrap_or_else(|| {
-                panic!("Module does not have a type section");
-            })
-            .function_types
-            .clone();
+        let names = module.names().cloned().unwrap_or_default();
         if let Some(funcs) = module.funcs() {
-            for type_index in module
-                .function_section()
-                .unwrap_or_else(|| {
-                    panic!("Module does not have a function section");
-                })
-                .clone()
-                .type_indexes
-            {
-                functions.push(FunctionInstance::new(
-                    &function_types[type_index as usize],
-                    funcs[type_index as usize].clone(),
-                ));
+            for (offset, func) in funcs.iter().enumerate() {
+                let function_type = module
+                    .types()
+                    .and_then(|types| types.get(func.type_index as usize))
+                    .ok_or(InstantiationError::UnknownType {
+                        index: func.type_index,
+                    })?;
+                functions.push(FunctionInstance::Module(ModuleFunction::new(
+                    first_index + offset as u32,
+                    function_type,
+                    func.clone(),
+                    &names,
+                )));
+            }
+        }
+        Ok(functions)
+    }
+
+    pub fn instantiate_tables(module: &ModuleNode) -> Vec<TableInstance> {
+        let mut tables: Vec<TableInstance> = vec![];
+        if let Some(module_tables) = module.tables() {
+            for table_type in module_tables.iter() {
+                tables.push(TableInstance::new(*table_type));
+            }
+        }
+        tables
+    }
+
+    pub fn instantiate_memories(module: &ModuleNode) -> Vec<MemoryInstance> {
+        let mut memories: Vec<MemoryInstance> = vec![];
+        if let Some(mems) = module.mems() {
+            for memory_type in mems.iter() {
+                memories.push(MemoryInstance::new(*memory_type));
+            }
+        }
+        memories
+    }
+
+    /// Writes active element segments into their tables. Passive segments are
+    /// retained for `table.init`, while active and declarative ones are dropped.
+    pub fn instantiate_elems(
+        module: &ModuleNode,
+        tables: &mut [TableInstance],
+        globals: &[GlobalInstance],
+        address: &Weak<OnceLock<Instance>>,
+    ) -> Result<Vec<ElementInstance>, InstantiationError> {
+        let mut elems: Vec<ElementInstance> = vec![];
+        let Some(module_elems) = module.elems() else {
+            return Ok(elems);
+        };
+
+        for (index, elem) in module_elems.iter().enumerate() {
+            let elements = match &elem.init {
+                ElementInitNode::Functions(indexes) => indexes
+                    .iter()
+                    .map(|index| Reference::Func(FuncRef::new(*index, address.clone())))
+                    .collect::<Vec<_>>(),
+                ElementInitNode::Expressions(exprs) => exprs
+                    .iter()
+                    .map(|expr| Instance::evaluate_element_expression(expr, address))
+                    .collect::<Result<Vec<_>, _>>()?,
+            };
+
+            match &elem.mode {
+                ElementModeNode::Passive => {
+                    elems.push(ElementInstance::new(elements));
+                }
+                ElementModeNode::Active {
+                    table_index,
+                    offset,
+                } => {
+                    let offset =
+                        match Instance::evaluate_constant_expression(offset, globals, address)? {
+                            Value::num(Number::i32(offset)) => offset as u32,
+                            _ => return Err(InstantiationError::InvalidConstantExpression),
+                        };
+                    let table = tables.get_mut(*table_index as usize).ok_or(
+                        InstantiationError::UnknownTable {
+                            index: *table_index,
+                        },
+                    )?;
+                    table
+                        .init(offset, &elements)
+                        .map_err(|_| InstantiationError::ElementSegmentOutOfBounds { index })?;
+                    elems.push(ElementInstance::new(vec![]));
+                }
+                ElementModeNode::Declarative => {
+                    elems.push(ElementInstance::new(vec![]));
+                }
+            }
+        }
+        Ok(elems)
+    }
+
+    /// Evaluates an element expression to a reference.
+    fn evaluate_element_expression(
+        expr: &ExpressionNode,
+        address: &Weak<OnceLock<Instance>>,
+    ) -> Result<Reference, InstantiationError> {
+        match expr.instructions.as_slice() {
+            [InstructionNode::RefFunc(node), InstructionNode::End(_)] => Ok(Reference::Func(
+                FuncRef::new(node.function_index, address.clone()),
+            )),
+            [InstructionNode::RefNull(node), InstructionNode::End(_)] => {
+                Ok(Reference::Null(node.ref_type))
+            }
+            _ => Err(InstantiationError::InvalidConstantExpression),
+        }
+    }
+
+    /// Copies active data segments into their memories. Passive segments are
+    /// retained for `memory.init`, while active ones are dropped once applied.
+    pub fn instantiate_datas(
+        module: &ModuleNode,
+        memories: &mut [MemoryInstance],
+        globals: &[GlobalInstance],
+        address: &Weak<OnceLock<Instance>>,
+    ) -> Result<Vec<DataInstance>, InstantiationError> {
+        let mut datas: Vec<DataInstance> = vec![];
+        let Some(module_datas) = module.datas() else {
+            return Ok(datas);
+        };
+
+        for (index, data) in module_datas.iter().enumerate() {
+            match &data.mode {
+                DataModeNode::Passive => {
+                    datas.push(DataInstance::new(data.init.clone()));
+                }
+                DataModeNode::Active {
+                    memory_index,
+                    offset,
+                } => {
+                    let offset =
+                        match Instance::evaluate_constant_expression(offset, globals, address)? {
+                            Value::num(Number::i32(offset)) => offset as u32,
+                            _ => return Err(InstantiationError::InvalidConstantExpression),
+                        };
+                    let memory = memories.get_mut(*memory_index as usize).ok_or(
+                        InstantiationError::UnknownMemory {
+                            index: *memory_index,
+                        },
+                    )?;
+                    memory
+                        .store(offset, 0, &data.init)
+                        .map_err(|_| InstantiationError::DataSegmentOutOfBounds { index })?;
+                    datas.push(DataInstance::new(vec![]));
+                }
+            }
+        }
+        Ok(datas)
+    }
+
+    /// Evaluates the init expression of each global defined by the module.
+    /// Only imported globals are visible to `global.get` in an init expression.
+    pub fn instantiate_globals(
+        module: &ModuleNode,
+        imported_globals: &[GlobalInstance],
+        address: &Weak<OnceLock<Instance>>,
+    ) -> Result<Vec<GlobalInstance>, InstantiationError> {
+        let mut globals: Vec<GlobalInstance> = vec![];
+        if let Some(module_globals) = module.globals() {
+            for global in module_globals.iter() {
+                let value = Instance::evaluate_constant_expression(
+                    &global.init,
+                    imported_globals,
+                    address,
+                )?;
+                globals.push(GlobalInstance::new(global.global_type, value));
+            }
+        }
+        Ok(globals)
+    }
+
+    fn evaluate_constant_expression(
+        expr: &ExpressionNode,
+        globals: &[GlobalInstance],
+        address: &Weak<OnceLock<Instance>>,
+    ) -> Result<Value, InstantiationError> {
+        match expr.instructions.as_slice() {
+            [InstructionNode::I32Const(node), InstructionNode::End(_)] => {
+                Ok(Value::num(Number::i32(node.value)))
+            }
+            [InstructionNode::I64Const(node), InstructionNode::End(_)] => {
+                Ok(Value::num(Number::i64(node.value)))
             }
+            [InstructionNode::F32Const(node), InstructionNode::End(_)] => {
+                Ok(Value::num(Number::f32(node.value)))
+            }
+            [InstructionNode::F64Const(node), InstructionNode::End(_)] => {
+                Ok(Value::num(Number::f64(node.value)))
+            }
+            [InstructionNode::V128Const(node), InstructionNode::End(_)] => {
+                Ok(Value::V128(node.value))
+            }
+            [InstructionNode::RefNull(node), InstructionNode::End(_)] => {
+                Ok(Value::Ref(Reference::Null(node.ref_type)))
+            }
+            [InstructionNode::RefFunc(node), InstructionNode::End(_)] => Ok(Value::Ref(
+                Reference::Func(FuncRef::new(node.function_index, address.clone())),
+            )),
+            [InstructionNode::GetGlobal(node), InstructionNode::End(_)] => globals
+                .get(node.index as usize)
+                .map(GlobalInstance::get)
+                .ok_or(InstantiationError::InvalidConstantExpression),
+            _ => Err(InstantiationError::InvalidConstantExpression),
         }
-        functions
     }
 
-    pub fn instantiate_exports(module: &ModuleNode) -> HashMap<String, Export> {
+    /// Maps each export name to what it exports. Names must be unique.
+    pub fn instantiate_exports(
+        module: &ModuleNode,
+    ) -> Result<HashMap<String, Export>, InstantiationError> {
         let mut exports: HashMap<String, Export> = HashMap::new();
-        for export in module
-            .export_section()
-            .unwrap_or_else(|| panic!(""))
-            .exports
-            .iter()
-        {
-            match &export.export_desc.export_type {
-                ExportTypeNode::Function => {
-                    exports
-                        .entry(export.name.clone())
-                        .and_modify(|_| panic!(""))
-                        .or_insert(Export::Function {
-                            index: export.export_desc.index as usize,
-                            name: export.name.clone(),
-                        });
+        let Some(module_exports) = module.exports() else {
+            return Ok(exports);
+        };
+        for export in module_exports.iter() {
+            let name = export.name.clone();
+            let index = export.export_desc.index as usize;
+            let value = match &export.export_desc.export_type {
+                ExportTypeNode::Function => Export::Function { name, index },
+                ExportTypeNode::Table => Export::Table { name, index },
+                ExportTypeNode::Memory => Export::Memory { name, index },
+                ExportTypeNode::Global => Export::Global { name, index },
+            };
+            if exports.insert(export.name.clone(), value).is_some() {
+                return Err(InstantiationError::DuplicateExport {
+                    name: export.name.clone(),
+                });
+            }
+        }
+        Ok(exports)
+    }
+
+    /// Returns a handle to the table exported as `name`.
+    pub fn exported_table(&self, name: &str) -> Option<TableInstance> {
+        match self.export_map.get(name)? {
+            Export::Table { index, .. } => self.tables.get(*index).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Returns a handle to the memory exported as `name`, through which the
+    /// host sees the module's writes and the module sees the host's.
+    pub fn exported_memory(&self, name: &str) -> Option<MemoryInstance> {
+        match self.export_map.get(name)? {
+            Export::Memory { index, .. } => self.memories.get(*index).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Returns a handle to the global exported as `name`.
+    pub fn exported_global(&self, name: &str) -> Option<GlobalInstance> {
+        match self.export_map.get(name)? {
+            Export::Global { index, .. } => self.globals.get(*index).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Returns a reference to the function at `index`, which calls it in this
+    /// instance from whichever instance it is called.
+    pub fn func_ref(&self, index: u32) -> Option<FuncRef> {
+        self.functions.get(index as usize)?;
+        Some(FuncRef::new(index, self.home.address.clone()))
+    }
+
+    pub(crate) fn address(&self) -> &Weak<OnceLock<Instance>> {
+        &self.home.address
+    }
+}
+
+/// The index spaces of an instance, which its code and segments are checked
+/// against before anything is written to its tables or memories. Once they
+/// pass, running the code can index any of them without checking.
+struct IndexSpaces<'a> {
+    types: &'a [FunctionTypeNode],
+    functions: usize,
+    tables: usize,
+    memories: usize,
+    globals: &'a [GlobalInstance],
+    elems: usize,
+    datas: usize,
+}
+
+impl IndexSpaces<'_> {
+    fn validate(
+        &self,
+        module: &ModuleNode,
+        functions: &[FunctionInstance],
+    ) -> Result<(), InstantiationError> {
+        for function in functions.iter() {
+            if let FunctionInstance::Module(function) = function {
+                self.validate_instructions(&function.code.body)?;
+            }
+        }
+        for global in module.globals().into_iter().flatten() {
+            self.validate_instructions(&global.init.instructions)?;
+        }
+        for elem in module.elems().into_iter().flatten() {
+            match &elem.init {
+                ElementInitNode::Functions(indexes) => {
+                    for index in indexes.iter() {
+                        self.function(*index)?;
+                    }
+                }
+                ElementInitNode::Expressions(exprs) => {
+                    for expr in exprs.iter() {
+                        self.validate_instructions(&expr.instructions)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_instructions(
+        &self,
+        instructions: &[InstructionNode],
+    ) -> Result<(), InstantiationError> {
+        for instruction in instructions.iter() {
+            match instruction {
+                InstructionNode::Block(node) => {
+                    self.block_type(node.block_type)?;
+                    self.validate_instructions(&node.expr.instructions)?
+                }
+                InstructionNode::Loop(node) => {
+                    self.block_type(node.block_type)?;
+                    self.validate_instructions(&node.expr.instructions)?
+                }
+                InstructionNode::If(node) => {
+                    self.block_type(node.block_type)?;
+                    self.validate_instructions(&node.then_expr.instructions)?;
+                    if let Some(else_expr) = &node.else_expr {
+                        self.validate_instructions(&else_expr.instructions)?;
+                    }
+                }
+                InstructionNode::Call(node) => self.function(node.function_index)?,
+                InstructionNode::CallIndirect(node) => {
+                    self.function_type(node.type_index)?;
+                    self.table(node.table_index)?;
+                }
+                InstructionNode::RefFunc(node) => self.function(node.function_index)?,
+                InstructionNode::GetGlobal(node) => {
+                    self.global(node.index)?;
+                }
+                InstructionNode::SetGlobal(node) => {
+                    let global = self.global(node.index)?;
+                    if !global.global_type.mutable {
+                        return Err(InstantiationError::ImmutableGlobal { index: node.index });
+                    }
+                }
+                InstructionNode::MemoryInit(node) => {
+                    self.data(node.data_index)?;
+                    self.memory(node.memory_index)?;
                 }
-                ExportTypeNode::Table => {}
-                ExportTypeNode::Memory => {}
-                ExportTypeNode::Global => {}
+                InstructionNode::DataDrop(node) => self.data(node.data_index)?,
+                InstructionNode::MemoryCopy(node) => {
+                    self.memory(node.dst_memory_index)?;
+                    self.memory(node.src_memory_index)?;
+                }
+                InstructionNode::MemoryFill(node) => self.memory(node.memory_index)?,
+                InstructionNode::TableInit(node) => {
+                    self.elem(node.elem_index)?;
+                    self.table(node.table_index)?;
+                }
+                InstructionNode::ElemDrop(node) => self.elem(node.elem_index)?,
+                InstructionNode::TableCopy(node) => {
+                    self.table(node.dst_table_index)?;
+                    self.table(node.src_table_index)?;
+                }
+                InstructionNode::TableGet(node) => self.table(node.table_index)?,
+                InstructionNode::TableSet(node) => self.table(node.table_index)?,
+                InstructionNode::TableSize(node) => self.table(node.table_index)?,
+                InstructionNode::TableGrow(node) => self.table(node.table_index)?,
+                InstructionNode::TableFill(node) => self.table(node.table_index)?,
+                InstructionNode::MemorySize(node) => self.memory(node.memory_index as u32)?,
+                InstructionNode::MemoryGrow(node) => self.memory(node.memory_index as u32)?,
+                instruction if instruction.memarg().is_some() => self.memory(0)?,
+                _ => {}
             }
         }
-        exports
+        Ok(())
+    }
+
+    fn function(&self, index: u32) -> Result<(), InstantiationError> {
+        if index as usize >= self.functions {
+            return Err(InstantiationError::UnknownFunction { index });
+        }
+        Ok(())
+    }
+
+    fn function_type(&self, index: u32) -> Result<(), InstantiationError> {
+        if index as usize >= self.types.len() {
+            return Err(InstantiationError::UnknownType { index });
+        }
+        Ok(())
+    }
+
+    fn block_type(&self, block_type: BlockType) -> Result<(), InstantiationError> {
+        match block_type {
+            BlockType::TypeIndex(index) => self.function_type(index),
+            BlockType::Empty | BlockType::ValType(_) => Ok(()),
+        }
+    }
+
+    fn global(&self, index: u32) -> Result<&GlobalInstance, InstantiationError> {
+        self.globals
+            .get(index as usize)
+            .ok_or(InstantiationError::UnknownGlobal { index })
+    }
+
+    fn table(&self, index: u32) -> Result<(), InstantiationError> {
+        if index as usize >= self.tables {
+            return Err(InstantiationError::UnknownTable { index });
+        }
+        Ok(())
+    }
+
+    fn memory(&self, index: u32) -> Result<(), InstantiationError> {
+        if index as usize >= self.memories {
+            return Err(InstantiationError::UnknownMemory { index });
+        }
+        Ok(())
+    }
+
+    fn elem(&self, index: u32) -> Result<(), InstantiationError> {
+        if index as usize >= self.elems {
+            return Err(InstantiationError::UnknownElem { index });
+        }
+        Ok(())
+    }
+
+    fn data(&self, index: u32) -> Result<(), InstantiationError> {
+        if index as usize >= self.datas {
+            return Err(InstantiationError::UnknownData { index });
+        }
+        Ok(())
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct FunctionInstance {
-    pub function_type: FunctionTypeNode,
-    pub code: FunctionNode,
+pub enum FunctionInstance {
+    Module(ModuleFunction),
+    Host(HostFunction),
 }
 
 impl FunctionInstance {
-    fn new(function_type: &FunctionTypeNode, code: FunctionNode) -> Self {
-        FunctionInstance {
+    pub fn function_type(&self) -> &FunctionTypeNode {
+        match self {
+            FunctionInstance::Module(function) => &function.function_type,
+            FunctionInstance::Host(function) => &function.function_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ModuleFunction {
+    pub index: u32,
+    /// `$name` from the name section, or the function index when unnamed.
+    pub name: String,
+    /// Display names of the parameters followed by the declared locals.
+    pub local_names: Vec<String>,
+    pub function_type: FunctionTypeNode,
+    /// Shared by the clones made for each call, so a call does not copy the
+    /// body.
+    pub code: Arc<FunctionNode>,
+}
+
+impl ModuleFunction {
+    fn new(
+        index: u32,
+        function_type: &FunctionTypeNode,
+        code: FunctionNode,
+        names: &NameMap,
+    ) -> Self {
+        let local_count = function_type.params.val_types.len() + code.locals.len();
+        ModuleFunction {
+            index,
+            name: names.display_function(index),
+            local_names: (0..local_count as u32)
+                .map(|local_index| names.display_local(index, local_index))
+                .collect(),
             function_type: function_type.clone(),
-            code,
+            code: Arc::new(code),
+        }
+    }
+}
+
+/// A table of references, each either null or of the table's element type.
+/// Clones refer to the same table, so a table imported from the host or
+/// exported to it is one table on both sides.
+#[derive(Clone)]
+pub struct TableInstance {
+    pub table_type: TableType,
+    elements: Arc<RwLock<Vec<Reference>>>,
+}
+
+impl TableInstance {
+    pub fn new(table_type: TableType) -> Self {
+        let null = Reference::Null(table_type.element_type);
+        Self {
+            table_type,
+            elements: Arc::new(RwLock::new(vec![null; table_type.limits.min as usize])),
+        }
+    }
+
+    // a panic while a lock was held leaves the elements no less valid, so a
+    // poisoned lock is used as is
+    fn with_elements<R>(&self, f: impl FnOnce(&Vec<Reference>) -> R) -> R {
+        f(&self.elements.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn with_elements_mut<R>(&mut self, f: impl FnOnce(&mut Vec<Reference>) -> R) -> R {
+        f(&mut self
+            .elements
+            .write()
+            .unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Current limits, with `min` reflecting the current number of elements.
+    pub fn limits(&self) -> Limits {
+        Limits {
+            min: self.size(),
+            max: self.table_type.limits.max,
+        }
+    }
+
+    /// Current size in elements.
+    pub fn size(&self) -> u32 {
+        self.with_elements(|elements| elements.len() as u32)
+    }
+
+    /// Returns the reference at `index`, or an `UndefinedElement` trap when out of bounds.
+    pub fn get(&self, index: u32) -> Result<Reference, Trap> {
+        self.with_elements(|elements| {
+            elements
+                .get(index as usize)
+                .cloned()
+                .ok_or(Trap::UndefinedElement)
+        })
+    }
+
+    pub fn set(&mut self, index: u32, reference: Reference) -> Result<(), Trap> {
+        self.with_elements_mut(|elements| {
+            let element = elements
+                .get_mut(index as usize)
+                .ok_or(Trap::TableOutOfBounds)?;
+            *element = reference;
+            Ok(())
+        })
+    }
+
+    /// Copies `elements` into the table starting at `offset`.
+    pub fn init(&mut self, offset: u32, elements: &[Reference]) -> Result<(), Trap> {
+        self.with_elements_mut(|table| {
+            let start = offset as usize;
+            if start as u64 + elements.len() as u64 > table.len() as u64 {
+                return Err(Trap::TableOutOfBounds);
+            }
+            table[start..start + elements.len()].clone_from_slice(elements);
+            Ok(())
+        })
+    }
+
+    /// Returns a copy of the `len` references starting at `index`.
+    pub fn slice(&self, index: u32, len: u32) -> Result<Vec<Reference>, Trap> {
+        let start = index as usize;
+        self.with_elements(|elements| {
+            elements
+                .get(start..start + len as usize)
+                .map(<[Reference]>::to_vec)
+                .ok_or(Trap::TableOutOfBounds)
+        })
+    }
+
+    /// Sets `len` elements starting at `index` to `reference`.
+    pub fn fill(&mut self, index: u32, reference: Reference, len: u32) -> Result<(), Trap> {
+        let start = index as usize;
+        self.with_elements_mut(|elements| {
+            elements
+                .get_mut(start..start + len as usize)
+                .ok_or(Trap::TableOutOfBounds)?
+                .fill(reference);
+            Ok(())
+        })
+    }
+
+    /// Grows the table by `delta` elements set to `reference` and returns the
+    /// previous size, or `None` when the new size would exceed the maximum.
+    pub fn grow(&mut self, delta: u32, reference: Reference) -> Option<u32> {
+        let max = self
+            .table_type
+            .limits
+            .max
+            .unwrap_or(MAX_TABLE_SIZE)
+            .min(MAX_TABLE_SIZE);
+        self.with_elements_mut(|elements| {
+            let previous = elements.len() as u32;
+            let size = previous.checked_add(delta)?;
+            if size > max {
+                return None;
+            }
+            elements.resize(size as usize, reference);
+            Some(previous)
+        })
+    }
+}
+
+impl fmt::Debug for TableInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with_elements(|elements| {
+            f.debug_struct("TableInstance")
+                .field("table_type", &self.table_type)
+                .field("elements", elements)
+                .finish()
+        })
+    }
+}
+
+/// A linear memory. Clones refer to the same bytes, so a memory imported from
+/// the host or exported to it is one memory on both sides. The bytes of a
+/// shared memory live behind a [`SharedMemory`] handle, which also tracks the
+/// threads waiting on it.
+#[derive(Clone)]
+pub struct MemoryInstance {
+    memory_type: MemoryType,
+    data: MemoryData,
+}
+
+#[derive(Clone)]
+enum MemoryData {
+    Unshared(Arc<RwLock<Vec<u8>>>),
+    Shared(SharedMemory),
+}
+
+impl MemoryInstance {
+    pub fn new(memory_type: MemoryType) -> Self {
+        let data = vec![0; memory_type.limits.min as usize * PAGE_SIZE];
+        Self {
+            memory_type,
+            data: if memory_type.shared {
+                MemoryData::Shared(SharedMemory::new(data))
+            } else {
+                MemoryData::Unshared(Arc::new(RwLock::new(data)))
+            },
+        }
+    }
+
+    pub fn is_shared(&self) -> bool {
+        self.memory_type.shared
+    }
+
+    /// Runs `f` on the bytes, holding the read lock meanwhile.
+    fn with_data<R>(&self, f: impl FnOnce(&Vec<u8>) -> R) -> R {
+        match &self.data {
+            MemoryData::Unshared(data) => f(&data.read().unwrap_or_else(PoisonError::into_inner)),
+            MemoryData::Shared(shared) => f(&shared.read()),
+        }
+    }
+
+    /// Runs `f` on the bytes, holding the write lock so that other holders of
+    /// the memory see all of its changes or none.
+    fn with_data_mut<R>(&mut self, f: impl FnOnce(&mut Vec<u8>) -> R) -> R {
+        match &mut self.data {
+            MemoryData::Unshared(data) => {
+                f(&mut data.write().unwrap_or_else(PoisonError::into_inner))
+            }
+            MemoryData::Shared(shared) => f(&mut shared.write()),
+        }
+    }
+
+    /// Current limits, with `min` reflecting the pages added by `memory.grow`.
+    pub fn limits(&self) -> Limits {
+        Limits {
+            min: self.size(),
+            max: self.memory_type.limits.max,
+        }
+    }
+
+    /// Current size in pages.
+    pub fn size(&self) -> u32 {
+        self.with_data(|data| (data.len() / PAGE_SIZE) as u32)
+    }
+
+    /// Grows the memory by `delta` pages and returns the previous size,
+    /// or `None` when the new size would exceed the maximum.
+    pub fn grow(&mut self, delta: u32) -> Option<u32> {
+        let max = self
+            .memory_type
+            .limits
+            .max
+            .unwrap_or(MAX_PAGES)
+            .min(MAX_PAGES);
+        self.with_data_mut(|data| {
+            let previous = (data.len() / PAGE_SIZE) as u32;
+            let size = previous.checked_add(delta)?;
+            if size > max {
+                return None;
+            }
+            data.resize(size as usize * PAGE_SIZE, 0);
+            Some(previous)
+        })
+    }
+
+    pub fn load<const N: usize>(&self, address: u32, offset: u32) -> Result<[u8; N], Trap> {
+        self.with_data(|data| {
+            let start = Self::effective_address(data, address, offset, N)?;
+            let mut bytes = [0; N];
+            bytes.copy_from_slice(&data[start..start + N]);
+            Ok(bytes)
+        })
+    }
+
+    pub fn store(&mut self, address: u32, offset: u32, bytes: &[u8]) -> Result<(), Trap> {
+        self.with_data_mut(|data| {
+            let start = Self::effective_address(data, address, offset, bytes.len())?;
+            data[start..start + bytes.len()].copy_from_slice(bytes);
+            Ok(())
+        })
+    }
+
+    /// Returns a copy of the `len` bytes starting at `address`.
+    pub fn read(&self, address: u32, len: u32) -> Result<Vec<u8>, Trap> {
+        self.with_data(|data| {
+            let start = Self::effective_address(data, address, 0, len as usize)?;
+            Ok(data[start..start + len as usize].to_vec())
+        })
+    }
+
+    /// Copies `len` bytes from `src` to `dst`, where the two ranges may overlap.
+    pub fn copy(&mut self, dst: u32, src: u32, len: u32) -> Result<(), Trap> {
+        self.with_data_mut(|data| {
+            let src = Self::effective_address(data, src, 0, len as usize)?;
+            let dst = Self::effective_address(data, dst, 0, len as usize)?;
+            data.copy_within(src..src + len as usize, dst);
+            Ok(())
+        })
+    }
+
+    /// Sets `len` bytes starting at `dst` to `value`.
+    pub fn fill(&mut self, dst: u32, value: u8, len: u32) -> Result<(), Trap> {
+        self.with_data_mut(|data| {
+            let dst = Self::effective_address(data, dst, 0, len as usize)?;
+            data[dst..dst + len as usize].fill(value);
+            Ok(())
+        })
+    }
+
+    /// Loads `N` bytes for an atomic access, which must be aligned to `N`.
+    pub fn atomic_load<const N: usize>(&self, address: u32, offset: u32) -> Result<[u8; N], Trap> {
+        Self::check_alignment(address, offset, N)?;
+        self.load(address, offset)
+    }
+
+    pub fn atomic_store(&mut self, address: u32, offset: u32, bytes: &[u8]) -> Result<(), Trap> {
+        Self::check_alignment(address, offset, bytes.len())?;
+        self.store(address, offset, bytes)
+    }
+
+    /// Replaces the `N` bytes at the effective address with `f` of them, in
+    /// a single step that no other thread can interleave with, and returns
+    /// the bytes that were replaced.
+    pub fn atomic_rmw<const N: usize>(
+        &mut self,
+        address: u32,
+        offset: u32,
+        f: impl FnOnce([u8; N]) -> [u8; N],
+    ) -> Result<[u8; N], Trap> {
+        Self::check_alignment(address, offset, N)?;
+        self.with_data_mut(|data| {
+            let start = Self::effective_address(data, address, offset, N)?;
+            let mut bytes = [0; N];
+            bytes.copy_from_slice(&data[start..start + N]);
+            data[start..start + N].copy_from_slice(&f(bytes));
+            Ok(bytes)
+        })
+    }
+
+    /// `memory.atomic.wait32` and `wait64`, which only a shared memory allows.
+    pub fn wait<const N: usize>(
+        &self,
+        address: u32,
+        offset: u32,
+        expected: [u8; N],
+        timeout: Option<Duration>,
+    ) -> Result<WaitOutcome, Trap> {
+        Self::check_alignment(address, offset, N)?;
+        let start = self.with_data(|data| Self::effective_address(data, address, offset, N))?;
+        match &self.data {
+            MemoryData::Shared(shared) => Ok(shared.wait(start, &expected, timeout)),
+            MemoryData::Unshared(_) => Err(Trap::ExpectedSharedMemory),
+        }
+    }
+
+    /// `memory.atomic.notify`, which finds no waiters in an unshared memory.
+    pub fn notify(&self, address: u32, offset: u32, count: u32) -> Result<u32, Trap> {
+        Self::check_alignment(address, offset, 4)?;
+        let start = self.with_data(|data| Self::effective_address(data, address, offset, 4))?;
+        match &self.data {
+            MemoryData::Shared(shared) => Ok(shared.notify(start, count)),
+            MemoryData::Unshared(_) => Ok(0),
+        }
+    }
+
+    fn effective_address(
+        data: &[u8],
+        address: u32,
+        offset: u32,
+        len: usize,
+    ) -> Result<usize, Trap> {
+        let start = address as u64 + offset as u64;
+        if start + len as u64 > data.len() as u64 {
+            return Err(Trap::MemoryOutOfBounds);
+        }
+        Ok(start as usize)
+    }
+
+    // alignment is checked before bounds, as in the reference interpreter
+    fn check_alignment(address: u32, offset: u32, len: usize) -> Result<(), Trap> {
+        if !(address as u64 + offset as u64).is_multiple_of(len as u64) {
+            return Err(Trap::UnalignedAtomic);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for MemoryInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryInstance")
+            .field("memory_type", &self.memory_type)
+            .field("pages", &self.size())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A passive element segment, until `elem.drop` empties it. Clones refer to
+/// the same segment.
+#[derive(Clone)]
+pub struct ElementInstance {
+    elements: Arc<RwLock<Vec<Reference>>>,
+}
+
+impl ElementInstance {
+    pub fn new(elements: Vec<Reference>) -> Self {
+        Self {
+            elements: Arc::new(RwLock::new(elements)),
+        }
+    }
+
+    pub fn elements(&self) -> Vec<Reference> {
+        self.elements
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// `elem.drop`, after which the segment behaves as an empty one.
+    pub fn drop_elements(&self) {
+        self.elements
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+impl fmt::Debug for ElementInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ElementInstance")
+            .field("elements", &self.elements())
+            .finish()
+    }
+}
+
+/// A passive data segment, until `data.drop` empties it. Clones refer to the
+/// same segment.
+#[derive(Clone)]
+pub struct DataInstance {
+    data: Arc<RwLock<Vec<u8>>>,
+}
+
+impl DataInstance {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self {
+            data: Arc::new(RwLock::new(data)),
+        }
+    }
+
+    pub fn data(&self) -> Vec<u8> {
+        self.data
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// `data.drop`, after which the segment behaves as an empty one.
+    pub fn drop_data(&self) {
+        self.data
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+impl fmt::Debug for DataInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataInstance")
+            .field("data", &self.data())
+            .finish()
+    }
+}
+
+/// A global. Clones refer to the same value, so a mutable global imported
+/// from the host or exported to it is one global on both sides.
+#[derive(Clone)]
+pub struct GlobalInstance {
+    pub global_type: GlobalType,
+    value: Arc<RwLock<Value>>,
+}
+
+impl GlobalInstance {
+    pub fn new(global_type: GlobalType, value: Value) -> Self {
+        Self {
+            global_type,
+            value: Arc::new(RwLock::new(value)),
+        }
+    }
+
+    pub fn get(&self) -> Value {
+        self.value
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub fn set(&mut self, value: Value) {
+        *self.value.write().unwrap_or_else(PoisonError::into_inner) = value;
+    }
+}
+
+impl fmt::Debug for GlobalInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GlobalInstance")
+            .field("global_type", &self.global_type)
+            .field("value", &self.get())
+            .finish()
+    }
+}
+
+type HostCallback = dyn Fn(&[Value]) -> Vec<Value> + Send + Sync;
+
+/// A function provided by the embedder and linked to a function import.
+#[derive(Clone)]
+pub struct HostFunction {
+    pub function_type: FunctionTypeNode,
+    callback: Arc<HostCallback>,
+}
+
+impl HostFunction {
+    pub fn new(
+        function_type: FunctionTypeNode,
+        callback: impl Fn(&[Value]) -> Vec<Value> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            function_type,
+            callback: Arc::new(callback),
+        }
+    }
+
+    pub fn call(&self, args: &[Value]) -> Vec<Value> {
+        (self.callback)(args)
+    }
+}
+
+impl fmt::Debug for HostFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostFunction")
+            .field("function_type", &self.function_type)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Extern {
+    Function(HostFunction),
+    Table(TableInstance),
+    Memory(MemoryInstance),
+    Global(GlobalInstance),
+}
+
+/// Host-provided externs, looked up by import module and field name.
+#[derive(Debug, Clone, Default)]
+pub struct Imports {
+    externs: HashMap<(String, String), Extern>,
+}
+
+impl Imports {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn define(&mut self, module: &str, name: &str, value: Extern) {
+        self.externs
+            .insert((module.to_string(), name.to_string()), value);
+    }
+
+    pub fn get(&self, module: &str, name: &str) -> Option<&Extern> {
+        self.externs.get(&(module.to_string(), name.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstantiationError {
+    UnknownImport { module: String, name: String },
+    IncompatibleImportType { module: String, name: String },
+    UnknownType { index: u32 },
+    UnknownFunction { index: u32 },
+    InvalidConstantExpression,
+    UnknownGlobal { index: u32 },
+    ImmutableGlobal { index: u32 },
+    UnknownTable { index: u32 },
+    UnknownMemory { index: u32 },
+    UnknownElem { index: u32 },
+    UnknownData { index: u32 },
+    DuplicateExport { name: String },
+    ElementSegmentOutOfBounds { index: usize },
+    DataSegmentOutOfBounds { index: usize },
+    InvalidStartFunction { index: u32 },
+    StartFunctionTrap(Trap),
+}
+
+impl fmt::Display for InstantiationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstantiationError::UnknownImport { module, name } => {
+                write!(f, "unknown import {}.{}", module, name)
+            }
+            InstantiationError::IncompatibleImportType { module, name } => {
+                write!(f, "incompatible import type for {}.{}", module, name)
+            }
+            InstantiationError::UnknownType { index } => {
+                write!(f, "unknown type {}", index)
+            }
+            InstantiationError::UnknownFunction { index } => {
+                write!(f, "unknown function {}", index)
+            }
+            InstantiationError::InvalidConstantExpression => {
+                write!(f, "constant expression required")
+            }
+            InstantiationError::UnknownGlobal { index } => {
+                write!(f, "unknown global {}", index)
+            }
+            InstantiationError::ImmutableGlobal { index } => {
+                write!(f, "global {} is immutable", index)
+            }
+            InstantiationError::UnknownTable { index } => {
+                write!(f, "unknown table {}", index)
+            }
+            InstantiationError::UnknownMemory { index } => {
+                write!(f, "unknown memory {}", index)
+            }
+            InstantiationError::UnknownElem { index } => {
+                write!(f, "unknown elem segment {}", index)
+            }
+            InstantiationError::UnknownData { index } => {
+                write!(f, "unknown data segment {}", index)
+            }
+            InstantiationError::DuplicateExport { name } => {
+                write!(f, "duplicate export name {}", name)
+            }
+            InstantiationError::ElementSegmentOutOfBounds { index } => {
+                write!(f, "element segment {} does not fit in table", index)
+            }
+            InstantiationError::DataSegmentOutOfBounds { index } => {
+                write!(f, "data segment {} does not fit in memory", index)
+            }
+            InstantiationError::InvalidStartFunction { index } => {
+                write!(f, "start function {} must have type [] -> []", index)
+            }
+            InstantiationError::StartFunctionTrap(trap) => {
+                write!(f, "start function trapped: {}", trap)
+            }
         }
     }
 }
 
+impl std::error::Error for InstantiationError {}
+
 #[derive(Debug, Clone)]
 pub enum Export {
     Function { name: String, index: usize },
-    // Table { name: String, index: usize },
-    // Memory { name: String, index: usize },
-    // Global { name: String, index: usize },
+    Table { name: String, index: usize },
+    Memory { name: String, index: usize },
+    Global { name: String, index: usize },
 }
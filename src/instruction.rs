@@ -2,7 +2,7 @@
 pub enum Instruction {
     Unreachable = 0x00, // trap immediately
     Nop = 0x01,         // no operation
-    Block = 0x02,       //sig : block_type	begin a sequence of bytecodes, yielding 0 or 1 values
+    Block = 0x02,       //sig : block_type	begin a sequence of bytecodes, yielding its results
     Loop = 0x03,        //sig : block_type	begin a block which can also form control flow loops
     If = 0x04,          //sig : block_type	begin if bytecodes
     Else = 0x05,        // begin else bytecodes of if
@@ -19,6 +19,7 @@ pub enum Instruction {
     // Parametric operators https://github.com/WebAssembly/design/blob/main/BinaryEncoding.md#parametric-operators-described-here
     Drop = 0x1a,
     Select = 0x1b,
+    SelectTyped = 0x1c, // select with an explicit vector of value types
 
     // Variable access https://github.com/WebAssembly/design/blob/main/BinaryEncoding.md#variable-access-described-here
     GetLocal = 0x20,
@@ -27,6 +28,10 @@ pub enum Instruction {
     GetGlobal = 0x23,
     SetGlobal = 0x24,
 
+    // Table instructions https://webassembly.github.io/spec/core/binary/instructions.html#table-instructions
+    TableGet = 0x25,
+    TableSet = 0x26,
+
     // Memory-related operators https://github.com/WebAssembly/design/blob/main/BinaryEncoding.md#memory-related-operators-described-here
     I32Load = 0x28,
     I64Load = 0x29,
@@ -160,168 +165,941 @@ pub enum Instruction {
     F64Min = 0xa4,
     F64Max = 0xa5,
     F64Copysign = 0xa6,
+    I32WrapI64 = 0xa7,
+    I32TruncF32S = 0xa8,
+    I32TruncF32U = 0xa9,
+    I32TruncF64S = 0xaa,
+    I32TruncF64U = 0xab,
+    I64ExtendI32S = 0xac,
+    I64ExtendI32U = 0xad,
+    I64TruncF32S = 0xae,
+    I64TruncF32U = 0xaf,
+    I64TruncF64S = 0xb0,
+    I64TruncF64U = 0xb1,
+    F32ConvertI32S = 0xb2,
+    F32ConvertI32U = 0xb3,
+    F32ConvertI64S = 0xb4,
+    F32ConvertI64U = 0xb5,
+    F32DemoteF64 = 0xb6,
+    F64ConvertI32S = 0xb7,
+    F64ConvertI32U = 0xb8,
+    F64ConvertI64S = 0xb9,
+    F64ConvertI64U = 0xba,
+    F64PromoteF32 = 0xbb,
+    I32ReinterpretF32 = 0xbc,
+    I64ReinterpretF64 = 0xbd,
+    F32ReinterpretI32 = 0xbe,
+    F64ReinterpretI64 = 0xbf,
+    I32Extend8S = 0xc0,
+    I32Extend16S = 0xc1,
+    I64Extend8S = 0xc2,
+    I64Extend16S = 0xc3,
+    I64Extend32S = 0xc4,
+
+    // Reference instructions https://webassembly.github.io/spec/core/binary/instructions.html#reference-instructions
+    RefNull = 0xd0,
+    RefIsNull = 0xd1,
+    RefFunc = 0xd2,
+
+    // Prefixed instructions, followed by a u32 sub-opcode
+    MiscPrefix = 0xfc,
+    SimdPrefix = 0xfd,
+    AtomicPrefix = 0xfe,
 }
 
-impl From<u8> for Instruction {
-    fn from(byte: u8) -> Instruction {
+impl TryFrom<u8> for Instruction {
+    type Error = u8;
+
+    fn try_from(byte: u8) -> Result<Instruction, Self::Error> {
         match byte {
-            0x00 => Instruction::Unreachable,
-            0x01 => Instruction::Nop,
-            0x02 => Instruction::Block,
-            0x03 => Instruction::Loop,
-            0x04 => Instruction::If,
-            0x05 => Instruction::Else,
-            0x0b => Instruction::End,
-            0x0c => Instruction::Br,
-            0x0d => Instruction::BrIf,
-            0x0e => Instruction::BrTable,
-            0x0f => Instruction::Return,
+            0x00 => Ok(Instruction::Unreachable),
+            0x01 => Ok(Instruction::Nop),
+            0x02 => Ok(Instruction::Block),
+            0x03 => Ok(Instruction::Loop),
+            0x04 => Ok(Instruction::If),
+            0x05 => Ok(Instruction::Else),
+            0x0b => Ok(Instruction::End),
+            0x0c => Ok(Instruction::Br),
+            0x0d => Ok(Instruction::BrIf),
+            0x0e => Ok(Instruction::BrTable),
+            0x0f => Ok(Instruction::Return),
 
-            0x10 => Instruction::Call,
-            0x11 => Instruction::CallIndirect,
-            0x1a => Instruction::Drop,
-            0x1b => Instruction::Select,
+            0x10 => Ok(Instruction::Call),
+            0x11 => Ok(Instruction::CallIndirect),
+            0x1a => Ok(Instruction::Drop),
+            0x1b => Ok(Instruction::Select),
+            0x1c => Ok(Instruction::SelectTyped),
 
-            0x20 => Instruction::GetLocal,
-            0x21 => Instruction::SetLocal,
-            0x22 => Instruction::TeeLocal,
-            0x23 => Instruction::GetGlobal,
-            0x24 => Instruction::SetGlobal,
+            0x20 => Ok(Instruction::GetLocal),
+            0x21 => Ok(Instruction::SetLocal),
+            0x22 => Ok(Instruction::TeeLocal),
+            0x23 => Ok(Instruction::GetGlobal),
+            0x24 => Ok(Instruction::SetGlobal),
+            0x25 => Ok(Instruction::TableGet),
+            0x26 => Ok(Instruction::TableSet),
 
-            0x28 => Instruction::I32Load,
-            0x29 => Instruction::I64Load,
-            0x2a => Instruction::F32Load,
-            0x2b => Instruction::F64Load,
-            0x2c => Instruction::I32Load8S,
-            0x2d => Instruction::I32Load8U,
-            0x2e => Instruction::I32Load16S,
-            0x2f => Instruction::I32Load16U,
-            0x30 => Instruction::I64Load8S,
-            0x31 => Instruction::I64Load8U,
-            0x32 => Instruction::I64Load16S,
-            0x33 => Instruction::I64Load16U,
-            0x34 => Instruction::I64Load32S,
-            0x35 => Instruction::I64Load32U,
+            0x28 => Ok(Instruction::I32Load),
+            0x29 => Ok(Instruction::I64Load),
+            0x2a => Ok(Instruction::F32Load),
+            0x2b => Ok(Instruction::F64Load),
+            0x2c => Ok(Instruction::I32Load8S),
+            0x2d => Ok(Instruction::I32Load8U),
+            0x2e => Ok(Instruction::I32Load16S),
+            0x2f => Ok(Instruction::I32Load16U),
+            0x30 => Ok(Instruction::I64Load8S),
+            0x31 => Ok(Instruction::I64Load8U),
+            0x32 => Ok(Instruction::I64Load16S),
+            0x33 => Ok(Instruction::I64Load16U),
+            0x34 => Ok(Instruction::I64Load32S),
+            0x35 => Ok(Instruction::I64Load32U),
 
-            0x36 => Instruction::I32Store,
-            0x37 => Instruction::I64Store,
-            0x38 => Instruction::F32Store,
-            0x39 => Instruction::F64Store,
-            0x3a => Instruction::I32Store8,
-            0x3b => Instruction::I32Store16,
-            0x3c => Instruction::I64Store8,
-            0x3d => Instruction::I64Store16,
-            0x3e => Instruction::I64Store32,
-            0x3f => Instruction::CurrentMemory,
-            0x40 => Instruction::GrowMemory,
+            0x36 => Ok(Instruction::I32Store),
+            0x37 => Ok(Instruction::I64Store),
+            0x38 => Ok(Instruction::F32Store),
+            0x39 => Ok(Instruction::F64Store),
+            0x3a => Ok(Instruction::I32Store8),
+            0x3b => Ok(Instruction::I32Store16),
+            0x3c => Ok(Instruction::I64Store8),
+            0x3d => Ok(Instruction::I64Store16),
+            0x3e => Ok(Instruction::I64Store32),
+            0x3f => Ok(Instruction::CurrentMemory),
+            0x40 => Ok(Instruction::GrowMemory),
 
-            0x41 => Instruction::I32Const,
-            0x42 => Instruction::I64Const,
-            0x43 => Instruction::F32Const,
-            0x44 => Instruction::F64Const,
+            0x41 => Ok(Instruction::I32Const),
+            0x42 => Ok(Instruction::I64Const),
+            0x43 => Ok(Instruction::F32Const),
+            0x44 => Ok(Instruction::F64Const),
 
-            0x45 => Instruction::I32Eqz,
-            0x46 => Instruction::I32Eq,
-            0x47 => Instruction::I32Ne,
-            0x48 => Instruction::I32LtS,
-            0x49 => Instruction::I32LtU,
-            0x4a => Instruction::I32GtS,
-            0x4b => Instruction::I32GtU,
-            0x4c => Instruction::I32LeS,
-            0x4d => Instruction::I32LeU,
-            0x4e => Instruction::I32GeS,
-            0x4f => Instruction::I32GeU,
-            0x50 => Instruction::I64Eqz,
-            0x51 => Instruction::I64Eq,
-            0x52 => Instruction::I64Ne,
-            0x53 => Instruction::I64LtS,
-            0x54 => Instruction::I64LtU,
-            0x55 => Instruction::I64GtS,
-            0x56 => Instruction::I64GtU,
-            0x57 => Instruction::I64LeS,
-            0x58 => Instruction::I64LeU,
-            0x59 => Instruction::I64GeS,
-            0x5a => Instruction::I64GeU,
-            0x5b => Instruction::F32Eq,
-            0x5c => Instruction::F32Ne,
-            0x5d => Instruction::F32Lt,
-            0x5e => Instruction::F32Gt,
-            0x5f => Instruction::F32Le,
-            0x60 => Instruction::F32Ge,
-            0x61 => Instruction::F64Eq,
-            0x62 => Instruction::F64Ne,
-            0x63 => Instruction::F64Lt,
-            0x64 => Instruction::F64Gt,
-            0x65 => Instruction::F64Le,
-            0x66 => Instruction::F64Ge,
+            0x45 => Ok(Instruction::I32Eqz),
+            0x46 => Ok(Instruction::I32Eq),
+            0x47 => Ok(Instruction::I32Ne),
+            0x48 => Ok(Instruction::I32LtS),
+            0x49 => Ok(Instruction::I32LtU),
+            0x4a => Ok(Instruction::I32GtS),
+            0x4b => Ok(Instruction::I32GtU),
+            0x4c => Ok(Instruction::I32LeS),
+            0x4d => Ok(Instruction::I32LeU),
+            0x4e => Ok(Instruction::I32GeS),
+            0x4f => Ok(Instruction::I32GeU),
+            0x50 => Ok(Instruction::I64Eqz),
+            0x51 => Ok(Instruction::I64Eq),
+            0x52 => Ok(Instruction::I64Ne),
+            0x53 => Ok(Instruction::I64LtS),
+            0x54 => Ok(Instruction::I64LtU),
+            0x55 => Ok(Instruction::I64GtS),
+            0x56 => Ok(Instruction::I64GtU),
+            0x57 => Ok(Instruction::I64LeS),
+            0x58 => Ok(Instruction::I64LeU),
+            0x59 => Ok(Instruction::I64GeS),
+            0x5a => Ok(Instruction::I64GeU),
+            0x5b => Ok(Instruction::F32Eq),
+            0x5c => Ok(Instruction::F32Ne),
+            0x5d => Ok(Instruction::F32Lt),
+            0x5e => Ok(Instruction::F32Gt),
+            0x5f => Ok(Instruction::F32Le),
+            0x60 => Ok(Instruction::F32Ge),
+            0x61 => Ok(Instruction::F64Eq),
+            0x62 => Ok(Instruction::F64Ne),
+            0x63 => Ok(Instruction::F64Lt),
+            0x64 => Ok(Instruction::F64Gt),
+            0x65 => Ok(Instruction::F64Le),
+            0x66 => Ok(Instruction::F64Ge),
 
             // Conversions https://github.com/WebAssembly/design/blob/main/BinaryEncoding.md#conversions-described-here
-            0x67 => Instruction::I32Clz,
-            0x68 => Instruction::I32Ctz,
-            0x69 => Instruction::I32Popcnt,
-            0x6a => Instruction::I32Add,
-            0x6b => Instruction::I32Sub,
-            0x6c => Instruction::I32Mul,
-            0x6d => Instruction::I32DivS,
-            0x6e => Instruction::I32DivU,
-            0x6f => Instruction::I32RemS,
-            0x70 => Instruction::I32RemU,
-            0x71 => Instruction::I32And,
-            0x72 => Instruction::I32Or,
-            0x73 => Instruction::I32Xor,
-            0x74 => Instruction::I32Shl,
-            0x75 => Instruction::I32ShrS,
-            0x76 => Instruction::I32ShrU,
-            0x77 => Instruction::I32Rotl,
-            0x78 => Instruction::I32Rotr,
-            0x79 => Instruction::I64Clz,
-            0x7a => Instruction::I64Ctz,
-            0x7b => Instruction::I64Popcnt,
-            0x7c => Instruction::I64Add,
-            0x7d => Instruction::I64Sub,
-            0x7e => Instruction::I64Mul,
-            0x7f => Instruction::I64DivS,
-            0x80 => Instruction::I64DivU,
-            0x81 => Instruction::I64RemS,
-            0x82 => Instruction::I64RemU,
-            0x83 => Instruction::I64And,
-            0x84 => Instruction::I64Or,
-            0x85 => Instruction::I64Xor,
-            0x86 => Instruction::I64Shl,
-            0x87 => Instruction::I64ShrS,
-            0x88 => Instruction::I64ShrU,
-            0x89 => Instruction::I64Rotl,
-            0x8a => Instruction::I64Rotr,
-            0x8b => Instruction::F32Abs,
-            0x8c => Instruction::F32Neg,
-            0x8d => Instruction::F32Ceil,
-            0x8e => Instruction::F32Floor,
-            0x8f => Instruction::F32Trunc,
-            0x90 => Instruction::F32Nearest,
-            0x91 => Instruction::F32Sqrt,
-            0x92 => Instruction::F32Add,
-            0x93 => Instruction::F32Sub,
-            0x94 => Instruction::F32Mul,
-            0x95 => Instruction::F32Div,
-            0x96 => Instruction::F32Min,
-            0x97 => Instruction::F32Max,
-            0x98 => Instruction::F32Copysign,
-            0x99 => Instruction::F64Abs,
-            0x9a => Instruction::F64Neg,
-            0x9b => Instruction::F64Ceil,
-            0x9c => Instruction::F64Floor,
-            0x9d => Instruction::F64Trunc,
-            0x9e => Instruction::F64Nearest,
-            0x9f => Instruction::F64Sqrt,
-            0xa0 => Instruction::F64Add,
-            0xa1 => Instruction::F64Sub,
-            0xa2 => Instruction::F64Mul,
-            0xa3 => Instruction::F64Div,
-            0xa4 => Instruction::F64Min,
-            0xa5 => Instruction::F64Max,
-            0xa6 => Instruction::F64Copysign,
+            0x67 => Ok(Instruction::I32Clz),
+            0x68 => Ok(Instruction::I32Ctz),
+            0x69 => Ok(Instruction::I32Popcnt),
+            0x6a => Ok(Instruction::I32Add),
+            0x6b => Ok(Instruction::I32Sub),
+            0x6c => Ok(Instruction::I32Mul),
+            0x6d => Ok(Instruction::I32DivS),
+            0x6e => Ok(Instruction::I32DivU),
+            0x6f => Ok(Instruction::I32RemS),
+            0x70 => Ok(Instruction::I32RemU),
+            0x71 => Ok(Instruction::I32And),
+            0x72 => Ok(Instruction::I32Or),
+            0x73 => Ok(Instruction::I32Xor),
+            0x74 => Ok(Instruction::I32Shl),
+            0x75 => Ok(Instruction::I32ShrS),
+            0x76 => Ok(Instruction::I32ShrU),
+            0x77 => Ok(Instruction::I32Rotl),
+            0x78 => Ok(Instruction::I32Rotr),
+            0x79 => Ok(Instruction::I64Clz),
+            0x7a => Ok(Instruction::I64Ctz),
+            0x7b => Ok(Instruction::I64Popcnt),
+            0x7c => Ok(Instruction::I64Add),
+            0x7d => Ok(Instruction::I64Sub),
+            0x7e => Ok(Instruction::I64Mul),
+            0x7f => Ok(Instruction::I64DivS),
+            0x80 => Ok(Instruction::I64DivU),
+            0x81 => Ok(Instruction::I64RemS),
+            0x82 => Ok(Instruction::I64RemU),
+            0x83 => Ok(Instruction::I64And),
+            0x84 => Ok(Instruction::I64Or),
+            0x85 => Ok(Instruction::I64Xor),
+            0x86 => Ok(Instruction::I64Shl),
+            0x87 => Ok(Instruction::I64ShrS),
+            0x88 => Ok(Instruction::I64ShrU),
+            0x89 => Ok(Instruction::I64Rotl),
+            0x8a => Ok(Instruction::I64Rotr),
+            0x8b => Ok(Instruction::F32Abs),
+            0x8c => Ok(Instruction::F32Neg),
+            0x8d => Ok(Instruction::F32Ceil),
+            0x8e => Ok(Instruction::F32Floor),
+            0x8f => Ok(Instruction::F32Trunc),
+            0x90 => Ok(Instruction::F32Nearest),
+            0x91 => Ok(Instruction::F32Sqrt),
+            0x92 => Ok(Instruction::F32Add),
+            0x93 => Ok(Instruction::F32Sub),
+            0x94 => Ok(Instruction::F32Mul),
+            0x95 => Ok(Instruction::F32Div),
+            0x96 => Ok(Instruction::F32Min),
+            0x97 => Ok(Instruction::F32Max),
+            0x98 => Ok(Instruction::F32Copysign),
+            0x99 => Ok(Instruction::F64Abs),
+            0x9a => Ok(Instruction::F64Neg),
+            0x9b => Ok(Instruction::F64Ceil),
+            0x9c => Ok(Instruction::F64Floor),
+            0x9d => Ok(Instruction::F64Trunc),
+            0x9e => Ok(Instruction::F64Nearest),
+            0x9f => Ok(Instruction::F64Sqrt),
+            0xa0 => Ok(Instruction::F64Add),
+            0xa1 => Ok(Instruction::F64Sub),
+            0xa2 => Ok(Instruction::F64Mul),
+            0xa3 => Ok(Instruction::F64Div),
+            0xa4 => Ok(Instruction::F64Min),
+            0xa5 => Ok(Instruction::F64Max),
+            0xa6 => Ok(Instruction::F64Copysign),
+            0xa7 => Ok(Instruction::I32WrapI64),
+            0xa8 => Ok(Instruction::I32TruncF32S),
+            0xa9 => Ok(Instruction::I32TruncF32U),
+            0xaa => Ok(Instruction::I32TruncF64S),
+            0xab => Ok(Instruction::I32TruncF64U),
+            0xac => Ok(Instruction::I64ExtendI32S),
+            0xad => Ok(Instruction::I64ExtendI32U),
+            0xae => Ok(Instruction::I64TruncF32S),
+            0xaf => Ok(Instruction::I64TruncF32U),
+            0xb0 => Ok(Instruction::I64TruncF64S),
+            0xb1 => Ok(Instruction::I64TruncF64U),
+            0xb2 => Ok(Instruction::F32ConvertI32S),
+            0xb3 => Ok(Instruction::F32ConvertI32U),
+            0xb4 => Ok(Instruction::F32ConvertI64S),
+            0xb5 => Ok(Instruction::F32ConvertI64U),
+            0xb6 => Ok(Instruction::F32DemoteF64),
+            0xb7 => Ok(Instruction::F64ConvertI32S),
+            0xb8 => Ok(Instruction::F64ConvertI32U),
+            0xb9 => Ok(Instruction::F64ConvertI64S),
+            0xba => Ok(Instruction::F64ConvertI64U),
+            0xbb => Ok(Instruction::F64PromoteF32),
+            0xbc => Ok(Instruction::I32ReinterpretF32),
+            0xbd => Ok(Instruction::I64ReinterpretF64),
+            0xbe => Ok(Instruction::F32ReinterpretI32),
+            0xbf => Ok(Instruction::F64ReinterpretI64),
+            0xc0 => Ok(Instruction::I32Extend8S),
+            0xc1 => Ok(Instruction::I32Extend16S),
+            0xc2 => Ok(Instruction::I64Extend8S),
+            0xc3 => Ok(Instruction::I64Extend16S),
+            0xc4 => Ok(Instruction::I64Extend32S),
+
+            0xd0 => Ok(Instruction::RefNull),
+            0xd1 => Ok(Instruction::RefIsNull),
+            0xd2 => Ok(Instruction::RefFunc),
+
+            0xfc => Ok(Instruction::MiscPrefix),
+            0xfd => Ok(Instruction::SimdPrefix),
+            0xfe => Ok(Instruction::AtomicPrefix),
+
+            _ => Err(byte),
+        }
+    }
+}
+
+/// Sub-opcodes of the 0xFC prefix.
+// https://webassembly.github.io/spec/core/binary/instructions.html#numeric-instructions
+#[derive(Debug, Eq, PartialEq)]
+pub enum MiscInstruction {
+    I32TruncSatF32S = 0,
+    I32TruncSatF32U = 1,
+    I32TruncSatF64S = 2,
+    I32TruncSatF64U = 3,
+    I64TruncSatF32S = 4,
+    I64TruncSatF32U = 5,
+    I64TruncSatF64S = 6,
+    I64TruncSatF64U = 7,
+    MemoryInit = 8,
+    DataDrop = 9,
+    MemoryCopy = 10,
+    MemoryFill = 11,
+    TableInit = 12,
+    ElemDrop = 13,
+    TableCopy = 14,
+    TableGrow = 15,
+    TableSize = 16,
+    TableFill = 17,
+}
+
+impl TryFrom<u32> for MiscInstruction {
+    type Error = u32;
+
+    fn try_from(opcode: u32) -> Result<MiscInstruction, Self::Error> {
+        match opcode {
+            0 => Ok(MiscInstruction::I32TruncSatF32S),
+            1 => Ok(MiscInstruction::I32TruncSatF32U),
+            2 => Ok(MiscInstruction::I32TruncSatF64S),
+            3 => Ok(MiscInstruction::I32TruncSatF64U),
+            4 => Ok(MiscInstruction::I64TruncSatF32S),
+            5 => Ok(MiscInstruction::I64TruncSatF32U),
+            6 => Ok(MiscInstruction::I64TruncSatF64S),
+            7 => Ok(MiscInstruction::I64TruncSatF64U),
+            8 => Ok(MiscInstruction::MemoryInit),
+            9 => Ok(MiscInstruction::DataDrop),
+            10 => Ok(MiscInstruction::MemoryCopy),
+            11 => Ok(MiscInstruction::MemoryFill),
+            12 => Ok(MiscInstruction::TableInit),
+            13 => Ok(MiscInstruction::ElemDrop),
+            14 => Ok(MiscInstruction::TableCopy),
+            15 => Ok(MiscInstruction::TableGrow),
+            16 => Ok(MiscInstruction::TableSize),
+            17 => Ok(MiscInstruction::TableFill),
+            _ => Err(opcode),
+        }
+    }
+}
+
+/// Sub-opcodes of the 0xFD prefix.
+// https://webassembly.github.io/spec/core/binary/instructions.html#vector-instructions
+#[derive(Debug, Eq, PartialEq)]
+pub enum SimdInstruction {
+    V128Load = 0x00,
+    V128Load8x8S = 0x01,
+    V128Load8x8U = 0x02,
+    V128Load16x4S = 0x03,
+    V128Load16x4U = 0x04,
+    V128Load32x2S = 0x05,
+    V128Load32x2U = 0x06,
+    V128Load8Splat = 0x07,
+    V128Load16Splat = 0x08,
+    V128Load32Splat = 0x09,
+    V128Load64Splat = 0x0a,
+    V128Store = 0x0b,
+    V128Const = 0x0c,
+    I8x16Shuffle = 0x0d,
+    I8x16Swizzle = 0x0e,
+    I8x16Splat = 0x0f,
+    I16x8Splat = 0x10,
+    I32x4Splat = 0x11,
+    I64x2Splat = 0x12,
+    F32x4Splat = 0x13,
+    F64x2Splat = 0x14,
+    I8x16ExtractLaneS = 0x15,
+    I8x16ExtractLaneU = 0x16,
+    I8x16ReplaceLane = 0x17,
+    I16x8ExtractLaneS = 0x18,
+    I16x8ExtractLaneU = 0x19,
+    I16x8ReplaceLane = 0x1a,
+    I32x4ExtractLane = 0x1b,
+    I32x4ReplaceLane = 0x1c,
+    I64x2ExtractLane = 0x1d,
+    I64x2ReplaceLane = 0x1e,
+    F32x4ExtractLane = 0x1f,
+    F32x4ReplaceLane = 0x20,
+    F64x2ExtractLane = 0x21,
+    F64x2ReplaceLane = 0x22,
+    I8x16Eq = 0x23,
+    I8x16Ne = 0x24,
+    I8x16LtS = 0x25,
+    I8x16LtU = 0x26,
+    I8x16GtS = 0x27,
+    I8x16GtU = 0x28,
+    I8x16LeS = 0x29,
+    I8x16LeU = 0x2a,
+    I8x16GeS = 0x2b,
+    I8x16GeU = 0x2c,
+    I16x8Eq = 0x2d,
+    I16x8Ne = 0x2e,
+    I16x8LtS = 0x2f,
+    I16x8LtU = 0x30,
+    I16x8GtS = 0x31,
+    I16x8GtU = 0x32,
+    I16x8LeS = 0x33,
+    I16x8LeU = 0x34,
+    I16x8GeS = 0x35,
+    I16x8GeU = 0x36,
+    I32x4Eq = 0x37,
+    I32x4Ne = 0x38,
+    I32x4LtS = 0x39,
+    I32x4LtU = 0x3a,
+    I32x4GtS = 0x3b,
+    I32x4GtU = 0x3c,
+    I32x4LeS = 0x3d,
+    I32x4LeU = 0x3e,
+    I32x4GeS = 0x3f,
+    I32x4GeU = 0x40,
+    F32x4Eq = 0x41,
+    F32x4Ne = 0x42,
+    F32x4Lt = 0x43,
+    F32x4Gt = 0x44,
+    F32x4Le = 0x45,
+    F32x4Ge = 0x46,
+    F64x2Eq = 0x47,
+    F64x2Ne = 0x48,
+    F64x2Lt = 0x49,
+    F64x2Gt = 0x4a,
+    F64x2Le = 0x4b,
+    F64x2Ge = 0x4c,
+    V128Not = 0x4d,
+    V128And = 0x4e,
+    V128Andnot = 0x4f,
+    V128Or = 0x50,
+    V128Xor = 0x51,
+    V128Bitselect = 0x52,
+    V128AnyTrue = 0x53,
+    V128Load8Lane = 0x54,
+    V128Load16Lane = 0x55,
+    V128Load32Lane = 0x56,
+    V128Load64Lane = 0x57,
+    V128Store8Lane = 0x58,
+    V128Store16Lane = 0x59,
+    V128Store32Lane = 0x5a,
+    V128Store64Lane = 0x5b,
+    V128Load32Zero = 0x5c,
+    V128Load64Zero = 0x5d,
+    F32x4DemoteF64x2Zero = 0x5e,
+    F64x2PromoteLowF32x4 = 0x5f,
+    I8x16Abs = 0x60,
+    I8x16Neg = 0x61,
+    I8x16Popcnt = 0x62,
+    I8x16AllTrue = 0x63,
+    I8x16Bitmask = 0x64,
+    I8x16NarrowI16x8S = 0x65,
+    I8x16NarrowI16x8U = 0x66,
+    F32x4Ceil = 0x67,
+    F32x4Floor = 0x68,
+    F32x4Trunc = 0x69,
+    F32x4Nearest = 0x6a,
+    I8x16Shl = 0x6b,
+    I8x16ShrS = 0x6c,
+    I8x16ShrU = 0x6d,
+    I8x16Add = 0x6e,
+    I8x16AddSatS = 0x6f,
+    I8x16AddSatU = 0x70,
+    I8x16Sub = 0x71,
+    I8x16SubSatS = 0x72,
+    I8x16SubSatU = 0x73,
+    F64x2Ceil = 0x74,
+    F64x2Floor = 0x75,
+    I8x16MinS = 0x76,
+    I8x16MinU = 0x77,
+    I8x16MaxS = 0x78,
+    I8x16MaxU = 0x79,
+    F64x2Trunc = 0x7a,
+    I8x16AvgrU = 0x7b,
+    I16x8ExtaddPairwiseI8x16S = 0x7c,
+    I16x8ExtaddPairwiseI8x16U = 0x7d,
+    I32x4ExtaddPairwiseI16x8S = 0x7e,
+    I32x4ExtaddPairwiseI16x8U = 0x7f,
+    I16x8Abs = 0x80,
+    I16x8Neg = 0x81,
+    I16x8Q15mulrSatS = 0x82,
+    I16x8AllTrue = 0x83,
+    I16x8Bitmask = 0x84,
+    I16x8NarrowI32x4S = 0x85,
+    I16x8NarrowI32x4U = 0x86,
+    I16x8ExtendLowI8x16S = 0x87,
+    I16x8ExtendHighI8x16S = 0x88,
+    I16x8ExtendLowI8x16U = 0x89,
+    I16x8ExtendHighI8x16U = 0x8a,
+    I16x8Shl = 0x8b,
+    I16x8ShrS = 0x8c,
+    I16x8ShrU = 0x8d,
+    I16x8Add = 0x8e,
+    I16x8AddSatS = 0x8f,
+    I16x8AddSatU = 0x90,
+    I16x8Sub = 0x91,
+    I16x8SubSatS = 0x92,
+    I16x8SubSatU = 0x93,
+    F64x2Nearest = 0x94,
+    I16x8Mul = 0x95,
+    I16x8MinS = 0x96,
+    I16x8MinU = 0x97,
+    I16x8MaxS = 0x98,
+    I16x8MaxU = 0x99,
+    I16x8AvgrU = 0x9b,
+    I16x8ExtmulLowI8x16S = 0x9c,
+    I16x8ExtmulHighI8x16S = 0x9d,
+    I16x8ExtmulLowI8x16U = 0x9e,
+    I16x8ExtmulHighI8x16U = 0x9f,
+    I32x4Abs = 0xa0,
+    I32x4Neg = 0xa1,
+    I32x4AllTrue = 0xa3,
+    I32x4Bitmask = 0xa4,
+    I32x4ExtendLowI16x8S = 0xa7,
+    I32x4ExtendHighI16x8S = 0xa8,
+    I32x4ExtendLowI16x8U = 0xa9,
+    I32x4ExtendHighI16x8U = 0xaa,
+    I32x4Shl = 0xab,
+    I32x4ShrS = 0xac,
+    I32x4ShrU = 0xad,
+    I32x4Add = 0xae,
+    I32x4Sub = 0xb1,
+    I32x4Mul = 0xb5,
+    I32x4MinS = 0xb6,
+    I32x4MinU = 0xb7,
+    I32x4MaxS = 0xb8,
+    I32x4MaxU = 0xb9,
+    I32x4DotI16x8S = 0xba,
+    I32x4ExtmulLowI16x8S = 0xbc,
+    I32x4ExtmulHighI16x8S = 0xbd,
+    I32x4ExtmulLowI16x8U = 0xbe,
+    I32x4ExtmulHighI16x8U = 0xbf,
+    I64x2Abs = 0xc0,
+    I64x2Neg = 0xc1,
+    I64x2AllTrue = 0xc3,
+    I64x2Bitmask = 0xc4,
+    I64x2ExtendLowI32x4S = 0xc7,
+    I64x2ExtendHighI32x4S = 0xc8,
+    I64x2ExtendLowI32x4U = 0xc9,
+    I64x2ExtendHighI32x4U = 0xca,
+    I64x2Shl = 0xcb,
+    I64x2ShrS = 0xcc,
+    I64x2ShrU = 0xcd,
+    I64x2Add = 0xce,
+    I64x2Sub = 0xd1,
+    I64x2Mul = 0xd5,
+    I64x2Eq = 0xd6,
+    I64x2Ne = 0xd7,
+    I64x2LtS = 0xd8,
+    I64x2GtS = 0xd9,
+    I64x2LeS = 0xda,
+    I64x2GeS = 0xdb,
+    I64x2ExtmulLowI32x4S = 0xdc,
+    I64x2ExtmulHighI32x4S = 0xdd,
+    I64x2ExtmulLowI32x4U = 0xde,
+    I64x2ExtmulHighI32x4U = 0xdf,
+    F32x4Abs = 0xe0,
+    F32x4Neg = 0xe1,
+    F32x4Sqrt = 0xe3,
+    F32x4Add = 0xe4,
+    F32x4Sub = 0xe5,
+    F32x4Mul = 0xe6,
+    F32x4Div = 0xe7,
+    F32x4Min = 0xe8,
+    F32x4Max = 0xe9,
+    F32x4Pmin = 0xea,
+    F32x4Pmax = 0xeb,
+    F64x2Abs = 0xec,
+    F64x2Neg = 0xed,
+    F64x2Sqrt = 0xef,
+    F64x2Add = 0xf0,
+    F64x2Sub = 0xf1,
+    F64x2Mul = 0xf2,
+    F64x2Div = 0xf3,
+    F64x2Min = 0xf4,
+    F64x2Max = 0xf5,
+    F64x2Pmin = 0xf6,
+    F64x2Pmax = 0xf7,
+    I32x4TruncSatF32x4S = 0xf8,
+    I32x4TruncSatF32x4U = 0xf9,
+    F32x4ConvertI32x4S = 0xfa,
+    F32x4ConvertI32x4U = 0xfb,
+    I32x4TruncSatF64x2SZero = 0xfc,
+    I32x4TruncSatF64x2UZero = 0xfd,
+    F64x2ConvertLowI32x4S = 0xfe,
+    F64x2ConvertLowI32x4U = 0xff,
+}
+
+impl TryFrom<u32> for SimdInstruction {
+    type Error = u32;
+
+    fn try_from(opcode: u32) -> Result<SimdInstruction, Self::Error> {
+        match opcode {
+            0x00 => Ok(SimdInstruction::V128Load),
+            0x01 => Ok(SimdInstruction::V128Load8x8S),
+            0x02 => Ok(SimdInstruction::V128Load8x8U),
+            0x03 => Ok(SimdInstruction::V128Load16x4S),
+            0x04 => Ok(SimdInstruction::V128Load16x4U),
+            0x05 => Ok(SimdInstruction::V128Load32x2S),
+            0x06 => Ok(SimdInstruction::V128Load32x2U),
+            0x07 => Ok(SimdInstruction::V128Load8Splat),
+            0x08 => Ok(SimdInstruction::V128Load16Splat),
+            0x09 => Ok(SimdInstruction::V128Load32Splat),
+            0x0a => Ok(SimdInstruction::V128Load64Splat),
+            0x0b => Ok(SimdInstruction::V128Store),
+            0x0c => Ok(SimdInstruction::V128Const),
+            0x0d => Ok(SimdInstruction::I8x16Shuffle),
+            0x0e => Ok(SimdInstruction::I8x16Swizzle),
+            0x0f => Ok(SimdInstruction::I8x16Splat),
+            0x10 => Ok(SimdInstruction::I16x8Splat),
+            0x11 => Ok(SimdInstruction::I32x4Splat),
+            0x12 => Ok(SimdInstruction::I64x2Splat),
+            0x13 => Ok(SimdInstruction::F32x4Splat),
+            0x14 => Ok(SimdInstruction::F64x2Splat),
+            0x15 => Ok(SimdInstruction::I8x16ExtractLaneS),
+            0x16 => Ok(SimdInstruction::I8x16ExtractLaneU),
+            0x17 => Ok(SimdInstruction::I8x16ReplaceLane),
+            0x18 => Ok(SimdInstruction::I16x8ExtractLaneS),
+            0x19 => Ok(SimdInstruction::I16x8ExtractLaneU),
+            0x1a => Ok(SimdInstruction::I16x8ReplaceLane),
+            0x1b => Ok(SimdInstruction::I32x4ExtractLane),
+            0x1c => Ok(SimdInstruction::I32x4ReplaceLane),
+            0x1d => Ok(SimdInstruction::I64x2ExtractLane),
+            0x1e => Ok(SimdInstruction::I64x2ReplaceLane),
+            0x1f => Ok(SimdInstruction::F32x4ExtractLane),
+            0x20 => Ok(SimdInstruction::F32x4ReplaceLane),
+            0x21 => Ok(SimdInstruction::F64x2ExtractLane),
+            0x22 => Ok(SimdInstruction::F64x2ReplaceLane),
+            0x23 => Ok(SimdInstruction::I8x16Eq),
+            0x24 => Ok(SimdInstruction::I8x16Ne),
+            0x25 => Ok(SimdInstruction::I8x16LtS),
+            0x26 => Ok(SimdInstruction::I8x16LtU),
+            0x27 => Ok(SimdInstruction::I8x16GtS),
+            0x28 => Ok(SimdInstruction::I8x16GtU),
+            0x29 => Ok(SimdInstruction::I8x16LeS),
+            0x2a => Ok(SimdInstruction::I8x16LeU),
+            0x2b => Ok(SimdInstruction::I8x16GeS),
+            0x2c => Ok(SimdInstruction::I8x16GeU),
+            0x2d => Ok(SimdInstruction::I16x8Eq),
+            0x2e => Ok(SimdInstruction::I16x8Ne),
+            0x2f => Ok(SimdInstruction::I16x8LtS),
+            0x30 => Ok(SimdInstruction::I16x8LtU),
+            0x31 => Ok(SimdInstruction::I16x8GtS),
+            0x32 => Ok(SimdInstruction::I16x8GtU),
+            0x33 => Ok(SimdInstruction::I16x8LeS),
+            0x34 => Ok(SimdInstruction::I16x8LeU),
+            0x35 => Ok(SimdInstruction::I16x8GeS),
+            0x36 => Ok(SimdInstruction::I16x8GeU),
+            0x37 => Ok(SimdInstruction::I32x4Eq),
+            0x38 => Ok(SimdInstruction::I32x4Ne),
+            0x39 => Ok(SimdInstruction::I32x4LtS),
+            0x3a => Ok(SimdInstruction::I32x4LtU),
+            0x3b => Ok(SimdInstruction::I32x4GtS),
+            0x3c => Ok(SimdInstruction::I32x4GtU),
+            0x3d => Ok(SimdInstruction::I32x4LeS),
+            0x3e => Ok(SimdInstruction::I32x4LeU),
+            0x3f => Ok(SimdInstruction::I32x4GeS),
+            0x40 => Ok(SimdInstruction::I32x4GeU),
+            0x41 => Ok(SimdInstruction::F32x4Eq),
+            0x42 => Ok(SimdInstruction::F32x4Ne),
+            0x43 => Ok(SimdInstruction::F32x4Lt),
+            0x44 => Ok(SimdInstruction::F32x4Gt),
+            0x45 => Ok(SimdInstruction::F32x4Le),
+            0x46 => Ok(SimdInstruction::F32x4Ge),
+            0x47 => Ok(SimdInstruction::F64x2Eq),
+            0x48 => Ok(SimdInstruction::F64x2Ne),
+            0x49 => Ok(SimdInstruction::F64x2Lt),
+            0x4a => Ok(SimdInstruction::F64x2Gt),
+            0x4b => Ok(SimdInstruction::F64x2Le),
+            0x4c => Ok(SimdInstruction::F64x2Ge),
+            0x4d => Ok(SimdInstruction::V128Not),
+            0x4e => Ok(SimdInstruction::V128And),
+            0x4f => Ok(SimdInstruction::V128Andnot),
+            0x50 => Ok(SimdInstruction::V128Or),
+            0x51 => Ok(SimdInstruction::V128Xor),
+            0x52 => Ok(SimdInstruction::V128Bitselect),
+            0x53 => Ok(SimdInstruction::V128AnyTrue),
+            0x54 => Ok(SimdInstruction::V128Load8Lane),
+            0x55 => Ok(SimdInstruction::V128Load16Lane),
+            0x56 => Ok(SimdInstruction::V128Load32Lane),
+            0x57 => Ok(SimdInstruction::V128Load64Lane),
+            0x58 => Ok(SimdInstruction::V128Store8Lane),
+            0x59 => Ok(SimdInstruction::V128Store16Lane),
+            0x5a => Ok(SimdInstruction::V128Store32Lane),
+            0x5b => Ok(SimdInstruction::V128Store64Lane),
+            0x5c => Ok(SimdInstruction::V128Load32Zero),
+            0x5d => Ok(SimdInstruction::V128Load64Zero),
+            0x5e => Ok(SimdInstruction::F32x4DemoteF64x2Zero),
+            0x5f => Ok(SimdInstruction::F64x2PromoteLowF32x4),
+            0x60 => Ok(SimdInstruction::I8x16Abs),
+            0x61 => Ok(SimdInstruction::I8x16Neg),
+            0x62 => Ok(SimdInstruction::I8x16Popcnt),
+            0x63 => Ok(SimdInstruction::I8x16AllTrue),
+            0x64 => Ok(SimdInstruction::I8x16Bitmask),
+            0x65 => Ok(SimdInstruction::I8x16NarrowI16x8S),
+            0x66 => Ok(SimdInstruction::I8x16NarrowI16x8U),
+            0x67 => Ok(SimdInstruction::F32x4Ceil),
+            0x68 => Ok(SimdInstruction::F32x4Floor),
+            0x69 => Ok(SimdInstruction::F32x4Trunc),
+            0x6a => Ok(SimdInstruction::F32x4Nearest),
+            0x6b => Ok(SimdInstruction::I8x16Shl),
+            0x6c => Ok(SimdInstruction::I8x16ShrS),
+            0x6d => Ok(SimdInstruction::I8x16ShrU),
+            0x6e => Ok(SimdInstruction::I8x16Add),
+            0x6f => Ok(SimdInstruction::I8x16AddSatS),
+            0x70 => Ok(SimdInstruction::I8x16AddSatU),
+            0x71 => Ok(SimdInstruction::I8x16Sub),
+            0x72 => Ok(SimdInstruction::I8x16SubSatS),
+            0x73 => Ok(SimdInstruction::I8x16SubSatU),
+            0x74 => Ok(SimdInstruction::F64x2Ceil),
+            0x75 => Ok(SimdInstruction::F64x2Floor),
+            0x76 => Ok(SimdInstruction::I8x16MinS),
+            0x77 => Ok(SimdInstruction::I8x16MinU),
+            0x78 => Ok(SimdInstruction::I8x16MaxS),
+            0x79 => Ok(SimdInstruction::I8x16MaxU),
+            0x7a => Ok(SimdInstruction::F64x2Trunc),
+            0x7b => Ok(SimdInstruction::I8x16AvgrU),
+            0x7c => Ok(SimdInstruction::I16x8ExtaddPairwiseI8x16S),
+            0x7d => Ok(SimdInstruction::I16x8ExtaddPairwiseI8x16U),
+            0x7e => Ok(SimdInstruction::I32x4ExtaddPairwiseI16x8S),
+            0x7f => Ok(SimdInstruction::I32x4ExtaddPairwiseI16x8U),
+            0x80 => Ok(SimdInstruction::I16x8Abs),
+            0x81 => Ok(SimdInstruction::I16x8Neg),
+            0x82 => Ok(SimdInstruction::I16x8Q15mulrSatS),
+            0x83 => Ok(SimdInstruction::I16x8AllTrue),
+            0x84 => Ok(SimdInstruction::I16x8Bitmask),
+            0x85 => Ok(SimdInstruction::I16x8NarrowI32x4S),
+            0x86 => Ok(SimdInstruction::I16x8NarrowI32x4U),
+            0x87 => Ok(SimdInstruction::I16x8ExtendLowI8x16S),
+            0x88 => Ok(SimdInstruction::I16x8ExtendHighI8x16S),
+            0x89 => Ok(SimdInstruction::I16x8ExtendLowI8x16U),
+            0x8a => Ok(SimdInstruction::I16x8ExtendHighI8x16U),
+            0x8b => Ok(SimdInstruction::I16x8Shl),
+            0x8c => Ok(SimdInstruction::I16x8ShrS),
+            0x8d => Ok(SimdInstruction::I16x8ShrU),
+            0x8e => Ok(SimdInstruction::I16x8Add),
+            0x8f => Ok(SimdInstruction::I16x8AddSatS),
+            0x90 => Ok(SimdInstruction::I16x8AddSatU),
+            0x91 => Ok(SimdInstruction::I16x8Sub),
+            0x92 => Ok(SimdInstruction::I16x8SubSatS),
+            0x93 => Ok(SimdInstruction::I16x8SubSatU),
+            0x94 => Ok(SimdInstruction::F64x2Nearest),
+            0x95 => Ok(SimdInstruction::I16x8Mul),
+            0x96 => Ok(SimdInstruction::I16x8MinS),
+            0x97 => Ok(SimdInstruction::I16x8MinU),
+            0x98 => Ok(SimdInstruction::I16x8MaxS),
+            0x99 => Ok(SimdInstruction::I16x8MaxU),
+            0x9b => Ok(SimdInstruction::I16x8AvgrU),
+            0x9c => Ok(SimdInstruction::I16x8ExtmulLowI8x16S),
+            0x9d => Ok(SimdInstruction::I16x8ExtmulHighI8x16S),
+            0x9e => Ok(SimdInstruction::I16x8ExtmulLowI8x16U),
+            0x9f => Ok(SimdInstruction::I16x8ExtmulHighI8x16U),
+            0xa0 => Ok(SimdInstruction::I32x4Abs),
+            0xa1 => Ok(SimdInstruction::I32x4Neg),
+            0xa3 => Ok(SimdInstruction::I32x4AllTrue),
+            0xa4 => Ok(SimdInstruction::I32x4Bitmask),
+            0xa7 => Ok(SimdInstruction::I32x4ExtendLowI16x8S),
+            0xa8 => Ok(SimdInstruction::I32x4ExtendHighI16x8S),
+            0xa9 => Ok(SimdInstruction::I32x4ExtendLowI16x8U),
+            0xaa => Ok(SimdInstruction::I32x4ExtendHighI16x8U),
+            0xab => Ok(SimdInstruction::I32x4Shl),
+            0xac => Ok(SimdInstruction::I32x4ShrS),
+            0xad => Ok(SimdInstruction::I32x4ShrU),
+            0xae => Ok(SimdInstruction::I32x4Add),
+            0xb1 => Ok(SimdInstruction::I32x4Sub),
+            0xb5 => Ok(SimdInstruction::I32x4Mul),
+            0xb6 => Ok(SimdInstruction::I32x4MinS),
+            0xb7 => Ok(SimdInstruction::I32x4MinU),
+            0xb8 => Ok(SimdInstruction::I32x4MaxS),
+            0xb9 => Ok(SimdInstruction::I32x4MaxU),
+            0xba => Ok(SimdInstruction::I32x4DotI16x8S),
+            0xbc => Ok(SimdInstruction::I32x4ExtmulLowI16x8S),
+            0xbd => Ok(SimdInstruction::I32x4ExtmulHighI16x8S),
+            0xbe => Ok(SimdInstruction::I32x4ExtmulLowI16x8U),
+            0xbf => Ok(SimdInstruction::I32x4ExtmulHighI16x8U),
+            0xc0 => Ok(SimdInstruction::I64x2Abs),
+            0xc1 => Ok(SimdInstruction::I64x2Neg),
+            0xc3 => Ok(SimdInstruction::I64x2AllTrue),
+            0xc4 => Ok(SimdInstruction::I64x2Bitmask),
+            0xc7 => Ok(SimdInstruction::I64x2ExtendLowI32x4S),
+            0xc8 => Ok(SimdInstruction::I64x2ExtendHighI32x4S),
+            0xc9 => Ok(SimdInstruction::I64x2ExtendLowI32x4U),
+            0xca => Ok(SimdInstruction::I64x2ExtendHighI32x4U),
+            0xcb => Ok(SimdInstruction::I64x2Shl),
+            0xcc => Ok(SimdInstruction::I64x2ShrS),
+            0xcd => Ok(SimdInstruction::I64x2ShrU),
+            0xce => Ok(SimdInstruction::I64x2Add),
+            0xd1 => Ok(SimdInstruction::I64x2Sub),
+            0xd5 => Ok(SimdInstruction::I64x2Mul),
+            0xd6 => Ok(SimdInstruction::I64x2Eq),
+            0xd7 => Ok(SimdInstruction::I64x2Ne),
+            0xd8 => Ok(SimdInstruction::I64x2LtS),
+            0xd9 => Ok(SimdInstruction::I64x2GtS),
+            0xda => Ok(SimdInstruction::I64x2LeS),
+            0xdb => Ok(SimdInstruction::I64x2GeS),
+            0xdc => Ok(SimdInstruction::I64x2ExtmulLowI32x4S),
+            0xdd => Ok(SimdInstruction::I64x2ExtmulHighI32x4S),
+            0xde => Ok(SimdInstruction::I64x2ExtmulLowI32x4U),
+            0xdf => Ok(SimdInstruction::I64x2ExtmulHighI32x4U),
+            0xe0 => Ok(SimdInstruction::F32x4Abs),
+            0xe1 => Ok(SimdInstruction::F32x4Neg),
+            0xe3 => Ok(SimdInstruction::F32x4Sqrt),
+            0xe4 => Ok(SimdInstruction::F32x4Add),
+            0xe5 => Ok(SimdInstruction::F32x4Sub),
+            0xe6 => Ok(SimdInstruction::F32x4Mul),
+            0xe7 => Ok(SimdInstruction::F32x4Div),
+            0xe8 => Ok(SimdInstruction::F32x4Min),
+            0xe9 => Ok(SimdInstruction::F32x4Max),
+            0xea => Ok(SimdInstruction::F32x4Pmin),
+            0xeb => Ok(SimdInstruction::F32x4Pmax),
+            0xec => Ok(SimdInstruction::F64x2Abs),
+            0xed => Ok(SimdInstruction::F64x2Neg),
+            0xef => Ok(SimdInstruction::F64x2Sqrt),
+            0xf0 => Ok(SimdInstruction::F64x2Add),
+            0xf1 => Ok(SimdInstruction::F64x2Sub),
+            0xf2 => Ok(SimdInstruction::F64x2Mul),
+            0xf3 => Ok(SimdInstruction::F64x2Div),
+            0xf4 => Ok(SimdInstruction::F64x2Min),
+            0xf5 => Ok(SimdInstruction::F64x2Max),
+            0xf6 => Ok(SimdInstruction::F64x2Pmin),
+            0xf7 => Ok(SimdInstruction::F64x2Pmax),
+            0xf8 => Ok(SimdInstruction::I32x4TruncSatF32x4S),
+            0xf9 => Ok(SimdInstruction::I32x4TruncSatF32x4U),
+            0xfa => Ok(SimdInstruction::F32x4ConvertI32x4S),
+            0xfb => Ok(SimdInstruction::F32x4ConvertI32x4U),
+            0xfc => Ok(SimdInstruction::I32x4TruncSatF64x2SZero),
+            0xfd => Ok(SimdInstruction::I32x4TruncSatF64x2UZero),
+            0xfe => Ok(SimdInstruction::F64x2ConvertLowI32x4S),
+            0xff => Ok(SimdInstruction::F64x2ConvertLowI32x4U),
+            _ => Err(opcode),
+        }
+    }
+}
+
+/// Sub-opcodes of the 0xFE prefix.
+// https://github.com/WebAssembly/threads/blob/main/proposals/threads/Overview.md#instruction-encoding
+#[derive(Debug, Eq, PartialEq)]
+pub enum AtomicInstruction {
+    MemoryAtomicNotify = 0x00,
+    MemoryAtomicWait32 = 0x01,
+    MemoryAtomicWait64 = 0x02,
+    AtomicFence = 0x03,
+    I32AtomicLoad = 0x10,
+    I64AtomicLoad = 0x11,
+    I32AtomicLoad8U = 0x12,
+    I32AtomicLoad16U = 0x13,
+    I64AtomicLoad8U = 0x14,
+    I64AtomicLoad16U = 0x15,
+    I64AtomicLoad32U = 0x16,
+    I32AtomicStore = 0x17,
+    I64AtomicStore = 0x18,
+    I32AtomicStore8 = 0x19,
+    I32AtomicStore16 = 0x1a,
+    I64AtomicStore8 = 0x1b,
+    I64AtomicStore16 = 0x1c,
+    I64AtomicStore32 = 0x1d,
+    I32AtomicRmwAdd = 0x1e,
+    I64AtomicRmwAdd = 0x1f,
+    I32AtomicRmw8AddU = 0x20,
+    I32AtomicRmw16AddU = 0x21,
+    I64AtomicRmw8AddU = 0x22,
+    I64AtomicRmw16AddU = 0x23,
+    I64AtomicRmw32AddU = 0x24,
+    I32AtomicRmwSub = 0x25,
+    I64AtomicRmwSub = 0x26,
+    I32AtomicRmw8SubU = 0x27,
+    I32AtomicRmw16SubU = 0x28,
+    I64AtomicRmw8SubU = 0x29,
+    I64AtomicRmw16SubU = 0x2a,
+    I64AtomicRmw32SubU = 0x2b,
+    I32AtomicRmwAnd = 0x2c,
+    I64AtomicRmwAnd = 0x2d,
+    I32AtomicRmw8AndU = 0x2e,
+    I32AtomicRmw16AndU = 0x2f,
+    I64AtomicRmw8AndU = 0x30,
+    I64AtomicRmw16AndU = 0x31,
+    I64AtomicRmw32AndU = 0x32,
+    I32AtomicRmwOr = 0x33,
+    I64AtomicRmwOr = 0x34,
+    I32AtomicRmw8OrU = 0x35,
+    I32AtomicRmw16OrU = 0x36,
+    I64AtomicRmw8OrU = 0x37,
+    I64AtomicRmw16OrU = 0x38,
+    I64AtomicRmw32OrU = 0x39,
+    I32AtomicRmwXor = 0x3a,
+    I64AtomicRmwXor = 0x3b,
+    I32AtomicRmw8XorU = 0x3c,
+    I32AtomicRmw16XorU = 0x3d,
+    I64AtomicRmw8XorU = 0x3e,
+    I64AtomicRmw16XorU = 0x3f,
+    I64AtomicRmw32XorU = 0x40,
+    I32AtomicRmwXchg = 0x41,
+    I64AtomicRmwXchg = 0x42,
+    I32AtomicRmw8XchgU = 0x43,
+    I32AtomicRmw16XchgU = 0x44,
+    I64AtomicRmw8XchgU = 0x45,
+    I64AtomicRmw16XchgU = 0x46,
+    I64AtomicRmw32XchgU = 0x47,
+    I32AtomicRmwCmpxchg = 0x48,
+    I64AtomicRmwCmpxchg = 0x49,
+    I32AtomicRmw8CmpxchgU = 0x4a,
+    I32AtomicRmw16CmpxchgU = 0x4b,
+    I64AtomicRmw8CmpxchgU = 0x4c,
+    I64AtomicRmw16CmpxchgU = 0x4d,
+    I64AtomicRmw32CmpxchgU = 0x4e,
+}
+
+impl TryFrom<u32> for AtomicInstruction {
+    type Error = u32;
 
-            _ => panic!("Invalid byte OpCode {} hex:{:x}", byte, byte),
+    fn try_from(opcode: u32) -> Result<AtomicInstruction, Self::Error> {
+        match opcode {
+            0x00 => Ok(AtomicInstruction::MemoryAtomicNotify),
+            0x01 => Ok(AtomicInstruction::MemoryAtomicWait32),
+            0x02 => Ok(AtomicInstruction::MemoryAtomicWait64),
+            0x03 => Ok(AtomicInstruction::AtomicFence),
+            0x10 => Ok(AtomicInstruction::I32AtomicLoad),
+            0x11 => Ok(AtomicInstruction::I64AtomicLoad),
+            0x12 => Ok(AtomicInstruction::I32AtomicLoad8U),
+            0x13 => Ok(AtomicInstruction::I32AtomicLoad16U),
+            0x14 => Ok(AtomicInstruction::I64AtomicLoad8U),
+            0x15 => Ok(AtomicInstruction::I64AtomicLoad16U),
+            0x16 => Ok(AtomicInstruction::I64AtomicLoad32U),
+            0x17 => Ok(AtomicInstruction::I32AtomicStore),
+            0x18 => Ok(AtomicInstruction::I64AtomicStore),
+            0x19 => Ok(AtomicInstruction::I32AtomicStore8),
+            0x1a => Ok(AtomicInstruction::I32AtomicStore16),
+            0x1b => Ok(AtomicInstruction::I64AtomicStore8),
+            0x1c => Ok(AtomicInstruction::I64AtomicStore16),
+            0x1d => Ok(AtomicInstruction::I64AtomicStore32),
+            0x1e => Ok(AtomicInstruction::I32AtomicRmwAdd),
+            0x1f => Ok(AtomicInstruction::I64AtomicRmwAdd),
+            0x20 => Ok(AtomicInstruction::I32AtomicRmw8AddU),
+            0x21 => Ok(AtomicInstruction::I32AtomicRmw16AddU),
+            0x22 => Ok(AtomicInstruction::I64AtomicRmw8AddU),
+            0x23 => Ok(AtomicInstruction::I64AtomicRmw16AddU),
+            0x24 => Ok(AtomicInstruction::I64AtomicRmw32AddU),
+            0x25 => Ok(AtomicInstruction::I32AtomicRmwSub),
+            0x26 => Ok(AtomicInstruction::I64AtomicRmwSub),
+            0x27 => Ok(AtomicInstruction::I32AtomicRmw8SubU),
+            0x28 => Ok(AtomicInstruction::I32AtomicRmw16SubU),
+            0x29 => Ok(AtomicInstruction::I64AtomicRmw8SubU),
+            0x2a => Ok(AtomicInstruction::I64AtomicRmw16SubU),
+            0x2b => Ok(AtomicInstruction::I64AtomicRmw32SubU),
+            0x2c => Ok(AtomicInstruction::I32AtomicRmwAnd),
+            0x2d => Ok(AtomicInstruction::I64AtomicRmwAnd),
+            0x2e => Ok(AtomicInstruction::I32AtomicRmw8AndU),
+            0x2f => Ok(AtomicInstruction::I32AtomicRmw16AndU),
+            0x30 => Ok(AtomicInstruction::I64AtomicRmw8AndU),
+            0x31 => Ok(AtomicInstruction::I64AtomicRmw16AndU),
+            0x32 => Ok(AtomicInstruction::I64AtomicRmw32AndU),
+            0x33 => Ok(AtomicInstruction::I32AtomicRmwOr),
+            0x34 => Ok(AtomicInstruction::I64AtomicRmwOr),
+            0x35 => Ok(AtomicInstruction::I32AtomicRmw8OrU),
+            0x36 => Ok(AtomicInstruction::I32AtomicRmw16OrU),
+            0x37 => Ok(AtomicInstruction::I64AtomicRmw8OrU),
+            0x38 => Ok(AtomicInstruction::I64AtomicRmw16OrU),
+            0x39 => Ok(AtomicInstruction::I64AtomicRmw32OrU),
+            0x3a => Ok(AtomicInstruction::I32AtomicRmwXor),
+            0x3b => Ok(AtomicInstruction::I64AtomicRmwXor),
+            0x3c => Ok(AtomicInstruction::I32AtomicRmw8XorU),
+            0x3d => Ok(AtomicInstruction::I32AtomicRmw16XorU),
+            0x3e => Ok(AtomicInstruction::I64AtomicRmw8XorU),
+            0x3f => Ok(AtomicInstruction::I64AtomicRmw16XorU),
+            0x40 => Ok(AtomicInstruction::I64AtomicRmw32XorU),
+            0x41 => Ok(AtomicInstruction::I32AtomicRmwXchg),
+            0x42 => Ok(AtomicInstruction::I64AtomicRmwXchg),
+            0x43 => Ok(AtomicInstruction::I32AtomicRmw8XchgU),
+            0x44 => Ok(AtomicInstruction::I32AtomicRmw16XchgU),
+            0x45 => Ok(AtomicInstruction::I64AtomicRmw8XchgU),
+            0x46 => Ok(AtomicInstruction::I64AtomicRmw16XchgU),
+            0x47 => Ok(AtomicInstruction::I64AtomicRmw32XchgU),
+            0x48 => Ok(AtomicInstruction::I32AtomicRmwCmpxchg),
+            0x49 => Ok(AtomicInstruction::I64AtomicRmwCmpxchg),
+            0x4a => Ok(AtomicInstruction::I32AtomicRmw8CmpxchgU),
+            0x4b => Ok(AtomicInstruction::I32AtomicRmw16CmpxchgU),
+            0x4c => Ok(AtomicInstruction::I64AtomicRmw8CmpxchgU),
+            0x4d => Ok(AtomicInstruction::I64AtomicRmw16CmpxchgU),
+            0x4e => Ok(AtomicInstruction::I64AtomicRmw32CmpxchgU),
+            _ => Err(opcode),
         }
     }
 }
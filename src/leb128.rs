@@ -1,50 +1,111 @@
-use std::error::Error;
+use std::fmt;
 
 pub const LEB128_MAX_BITS: u32 = 32;
+/// Maximum number of bytes of a 32-bit LEB128 value.
+const LEB128_MAX_BYTES: u32 = LEB128_MAX_BITS.div_ceil(7);
+pub const LEB128_64_MAX_BITS: u32 = 64;
+/// Maximum number of bytes of a 64-bit LEB128 value.
+const LEB128_64_MAX_BYTES: u32 = LEB128_64_MAX_BITS.div_ceil(7);
 
-pub fn decode_unsigned_leb128(bytes: &mut Vec<u8>) -> Result<(u32, u32), Box<dyn Error>> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Leb128Error {
+    UnexpectedEof,
+    Overflow,
+}
+
+impl fmt::Display for Leb128Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Leb128Error::UnexpectedEof => write!(f, "unexpected end of LEB128"),
+            Leb128Error::Overflow => write!(f, "LEB128 overflow"),
+        }
+    }
+}
+
+impl std::error::Error for Leb128Error {}
+
+/// Decodes an unsigned LEB128 value from the front of `bytes`,
+/// returning the value and the number of bytes it occupies.
+pub fn decode_unsigned_leb128(bytes: &[u8]) -> Result<(u32, u32), Leb128Error> {
     let mut value: u32 = 0;
     let mut shift: u32 = 0;
     let mut byte_count: u32 = 0;
 
     loop {
-        let byte = bytes[0];
-        (*bytes).drain(0..1);
+        let byte = *bytes
+            .get(byte_count as usize)
+            .ok_or(Leb128Error::UnexpectedEof)?;
+        byte_count += 1;
+        // the last byte may only carry the remaining 4 bits
+        if byte_count == LEB128_MAX_BYTES && byte & 0xf0 != 0 {
+            return Err(Leb128Error::Overflow);
+        }
         value |= u32::from(byte & 0x7f) << shift;
         shift += 7;
-        byte_count += 1;
 
         if ((byte >> 7) & 1) != 1 {
             break;
         }
-        if shift > LEB128_MAX_BITS {
-            panic!("unsigned LEB128 overflow");
-        }
     }
     Ok((value, byte_count))
 }
 
-pub fn decode_signed_leb128(bytes: &mut Vec<u8>) -> Result<(i32, u32), Box<dyn Error>> {
+/// Decodes a signed LEB128 value from the front of `bytes`,
+/// returning the value and the number of bytes it occupies.
+pub fn decode_signed_leb128(bytes: &[u8]) -> Result<(i32, u32), Leb128Error> {
     let mut value: i32 = 0;
     let mut shift: u32 = 0;
     let mut byte_count: u32 = 0;
 
     loop {
-        let byte = bytes[0];
-        (*bytes).drain(0..1);
+        let byte = *bytes
+            .get(byte_count as usize)
+            .ok_or(Leb128Error::UnexpectedEof)?;
+        byte_count += 1;
+        // the last byte carries the remaining 4 bits, and its unused bits
+        // must be a sign extension of them
+        if byte_count == LEB128_MAX_BYTES && byte & 0xf8 != 0 && byte & 0xf8 != 0x78 {
+            return Err(Leb128Error::Overflow);
+        }
         value |= i32::from(byte & 0x7F) << shift;
         shift += 7;
-        byte_count += 1;
 
         if ((byte >> 7) & 1) != 1 {
+            if shift < LEB128_MAX_BITS && byte & 0x40 != 0 {
+                value |= !0 << shift;
+            }
             break;
         }
-        if shift > LEB128_MAX_BITS {
-            panic!("signed LEB128 overflow");
-        }
     }
-    if (value >> (shift - 1)) & 1 == 1 {
-        value |= !0 << shift;
+    Ok((value, byte_count))
+}
+
+/// Decodes a signed 64-bit LEB128 value from the front of `bytes`,
+/// returning the value and the number of bytes it occupies.
+pub fn decode_signed_leb128_64(bytes: &[u8]) -> Result<(i64, u32), Leb128Error> {
+    let mut value: i64 = 0;
+    let mut shift: u32 = 0;
+    let mut byte_count: u32 = 0;
+
+    loop {
+        let byte = *bytes
+            .get(byte_count as usize)
+            .ok_or(Leb128Error::UnexpectedEof)?;
+        byte_count += 1;
+        // the last byte carries the remaining bit, and its unused bits
+        // must be a sign extension of it
+        if byte_count == LEB128_64_MAX_BYTES && byte != 0x00 && byte != 0x7f {
+            return Err(Leb128Error::Overflow);
+        }
+        value |= i64::from(byte & 0x7F) << shift;
+        shift += 7;
+
+        if ((byte >> 7) & 1) != 1 {
+            if shift < LEB128_64_MAX_BITS && byte & 0x40 != 0 {
+                value |= !0 << shift;
+            }
+            break;
+        }
     }
     Ok((value, byte_count))
 }
@@ -81,3 +142,20 @@ pub fn encode_i32_to_leb128(mut value: i32) -> Vec<u8> {
     }
     result
 }
+
+pub fn encode_i64_to_leb128(mut value: i64) -> Vec<u8> {
+    // signed leb128
+    let mut result: Vec<u8> = vec![];
+    loop {
+        let byte = value & 0b01111111;
+        value >>= 7;
+
+        if (value == 0 && (byte & 0b01000000) == 0) || (value == -1 && (byte & 0b01000000) != 0) {
+            result.push(byte as u8);
+            break;
+        } else {
+            result.push((byte | 0b10000000) as u8);
+        }
+    }
+    result
+}
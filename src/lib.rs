@@ -0,0 +1,17 @@
+pub mod buffer;
+pub mod float;
+pub mod instance;
+pub mod instruction;
+pub mod leb128;
+pub mod module;
+pub mod names;
+pub mod node;
+pub mod parse_error;
+pub mod parser;
+pub mod reader;
+pub mod runtime;
+pub mod shared_memory;
+pub mod simd;
+pub mod stack;
+pub mod trap;
+pub mod types;
@@ -1,40 +1,32 @@
 use std::env;
 
-use crate::{
+use wasm_runtime::{
+    instance, parser,
     runtime::Runtime,
     stack::{Number, Value},
 };
 
-mod buffer;
-mod instance;
-mod instruction;
-mod leb128;
-mod module;
-mod node;
-mod parser;
-mod runtime;
-mod stack;
-mod types;
-
 fn main() {
     let args: Vec<String> = env::args().collect();
     let file_path = &args[1];
     println!("file path: {:?}", *file_path);
 
-    let mut bytes = std::fs::read(file_path).expect("file not found");
+    let bytes = std::fs::read(file_path).expect("file not found");
 
     let parser = parser::Parser::new().unwrap();
-    let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+    let mut module = parser.parse(&bytes).expect("Failed to parse");
 
     module.emit();
     // println!("Successfully parse module\n{:#?}", module);
     // println!("emit wasm module\n{:#?}", module.buffer);
 
-    let instance = instance::Instance::new(&mut module);
+    let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+        .expect("Failed to instantiate");
     let keys = instance
         .export_map
-        .keys()
-        .map(|k| k.to_string())
+        .iter()
+        .filter(|(_, export)| matches!(export, instance::Export::Function { .. }))
+        .map(|(k, _)| k.to_string())
         .collect::<Vec<String>>();
 
     let module_args = args[2..]
@@ -42,6 +34,17 @@ fn main() {
         .map(|s| Value::num(Number::i32(s.parse::<i32>().unwrap())))
         .collect::<Vec<Value>>();
 
+    if let Some(name) = &instance.names.module {
+        println!("module: ${}", name);
+    }
+    if let Some(instance::Export::Function { index, .. }) = instance.export_map.get(&keys[0]) {
+        println!(
+            "invoke: {} ({})",
+            keys[0],
+            instance.names.display_function(*index as u32)
+        );
+    }
+
     let mut runtime = Runtime::new(instance);
     let result = runtime.execute(&keys[0], Some(module_args));
     println!("{:#?}", runtime);
@@ -50,16 +53,16 @@ fn main() {
 
 #[cfg(test)]
 mod parser_tests {
-    use crate::node::ExportTypeNode;
+    use wasm_runtime::{module::section::SectionId, node, node::ExportTypeNode, types};
 
     use super::*;
 
     #[test]
     fn parse_const_i32_module() {
         let file_path = "test/fixtures/const_i32.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let module = parser.parse(&mut bytes).expect("Failed to parse");
+        let module = parser.parse(&bytes).expect("Failed to parse");
 
         let type_section_function_types = &module.type_section().unwrap().function_types;
         assert_eq!(type_section_function_types[0].params.val_types.len(), 0);
@@ -75,12 +78,52 @@ mod parser_tests {
         assert_eq!(code_section_bodies[0].expr.instructions.len(), 2);
     }
 
+    #[test]
+    fn parse_offsets() {
+        let file_path = "test/fixtures/recursion.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let module = parser.parse(&bytes).expect("Failed to parse");
+
+        // sections start just past their id and size
+        assert_eq!(module.type_section().unwrap().offset, 0x0A);
+        assert_eq!(module.function_section().unwrap().offset, 0x16);
+        assert_eq!(module.table_section().unwrap().offset, 0x1C);
+        assert_eq!(module.export_section().unwrap().offset, 0x22);
+        assert_eq!(module.element_section().unwrap().offset, 0x4E);
+        assert_eq!(module.code_section().unwrap().offset, 0x57);
+
+        let bodies = &module.code_section().unwrap().bodies;
+        let body_offsets: Vec<usize> = bodies.iter().map(|body| body.offset).collect();
+        assert_eq!(body_offsets, vec![0x59, 0x6B, 0x70]);
+
+        // countdown: local.get, if and end, with the if split at its else
+        let expr = &bodies[0].expr;
+        assert_eq!(expr.offsets, vec![1, 3, 16]);
+        let node::InstructionNode::If(if_node) = &expr.instructions[1] else {
+            panic!("expected an if");
+        };
+        assert_eq!(if_node.then_expr.offsets, vec![5, 7, 9, 10]);
+        assert_eq!(
+            if_node.else_expr.as_ref().unwrap().offsets,
+            vec![12, 13, 15]
+        );
+        assert_eq!(bodies[2].expr.offsets, vec![1, 3, 6]);
+
+        // a constant expression counts from its own start
+        let element = &module.element_section().unwrap().elements[0];
+        let node::ElementModeNode::Active { offset, .. } = &element.mode else {
+            panic!("expected an active segment");
+        };
+        assert_eq!(offset.offsets, vec![0, 2]);
+    }
+
     #[test]
     fn parse_local_i32_get_set_module() {
         let file_path = "test/fixtures/local_i32_var.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let module = parser.parse(&mut bytes).expect("Failed to parse");
+        let module = parser.parse(&bytes).expect("Failed to parse");
 
         let type_section_function_types = &module.type_section().unwrap().function_types;
         assert_eq!(type_section_function_types[0].params.val_types.len(), 0);
@@ -100,9 +143,9 @@ mod parser_tests {
     #[test]
     fn parse_local_i32_add_module() {
         let file_path = "test/fixtures/i32_add.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let module = parser.parse(&mut bytes).expect("Failed to parse");
+        let module = parser.parse(&bytes).expect("Failed to parse");
 
         let type_section_function_types = &module.type_section().unwrap().function_types;
         assert_eq!(type_section_function_types[0].params.val_types.len(), 2);
@@ -130,9 +173,9 @@ mod parser_tests {
     #[test]
     fn parse_if_else_module() {
         let file_path = "test/fixtures/if_i32_ge_s.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let module = parser.parse(&mut bytes).expect("Failed to parse");
+        let module = parser.parse(&bytes).expect("Failed to parse");
 
         let type_section_function_types = &module.type_section().unwrap().function_types;
         assert_eq!(type_section_function_types[0].params.val_types.len(), 1);
@@ -160,9 +203,9 @@ mod parser_tests {
     #[test]
     fn parse_loop_module() {
         let file_path = "test/fixtures/loop.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let module = parser.parse(&mut bytes).expect("Failed to parse");
+        let module = parser.parse(&bytes).expect("Failed to parse");
 
         let type_section_function_types = &module.type_section().unwrap().function_types;
         assert_eq!(type_section_function_types[0].params.val_types.len(), 0);
@@ -204,9 +247,9 @@ mod parser_tests {
     #[test]
     fn parse_increment_module() {
         let file_path = "test/fixtures/increment.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let module = parser.parse(&mut bytes).expect("Failed to parse");
+        let module = parser.parse(&bytes).expect("Failed to parse");
 
         let type_section_function_types = &module.type_section().unwrap().function_types;
         assert_eq!(type_section_function_types.len(), 2);
@@ -238,16 +281,271 @@ mod parser_tests {
         assert_eq!(code_section_bodies[1].expr.instructions.len(), 4);
     }
 
+    #[test]
+    fn parse_import_module() {
+        let file_path = "test/fixtures/import.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let module = parser.parse(&bytes).expect("Failed to parse");
+
+        let imports = &module.import_section().unwrap().imports;
+        assert_eq!(imports.len(), 4);
+        assert_eq!(imports[0].module, "env");
+        assert_eq!(imports[0].name, "print");
+        assert_eq!(imports[0].import_desc, node::ImportDescNode::Function(0));
+        assert_eq!(
+            imports[1].import_desc,
+            node::ImportDescNode::Table(types::TableType {
+                element_type: types::ReferenceTypeNode::FunctionRef,
+                limits: types::Limits { min: 1, max: None },
+            })
+        );
+        assert_eq!(
+            imports[2].import_desc,
+            node::ImportDescNode::Memory(types::MemoryType {
+                limits: types::Limits {
+                    min: 1,
+                    max: Some(2)
+                },
+                shared: false,
+            })
+        );
+        assert_eq!(
+            imports[3].import_desc,
+            node::ImportDescNode::Global(types::GlobalType {
+                value_type: types::ValueType::Number(types::NumberType::I32),
+                mutable: true,
+            })
+        );
+
+        let export_section_exports = &module.export_section().unwrap().exports;
+        assert_eq!(export_section_exports[0].name, "noop");
+        assert_eq!(export_section_exports[0].export_desc.index, 1);
+    }
+
+    #[test]
+    fn parse_global_module() {
+        let file_path = "test/fixtures/global.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let module = parser.parse(&bytes).expect("Failed to parse");
+
+        let globals = &module.global_section().unwrap().globals;
+        assert_eq!(globals.len(), 2);
+        assert!(globals[0].global_type.mutable);
+        assert!(!globals[1].global_type.mutable);
+        assert_eq!(globals[0].init.instructions.len(), 2);
+        match &globals[1].init.instructions[0] {
+            node::InstructionNode::GetGlobal(node) => assert_eq!(node.index, 0),
+            _ => panic!("Expected global.get node"),
+        }
+    }
+
+    #[test]
+    fn parse_memory_module() {
+        let file_path = "test/fixtures/memory.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let module = parser.parse(&bytes).expect("Failed to parse");
+
+        let memories = &module.memory_section().unwrap().memories;
+        assert_eq!(memories.len(), 1);
+        assert_eq!(
+            memories[0].limits,
+            types::Limits {
+                min: 1,
+                max: Some(2)
+            }
+        );
+
+        let code_section_bodies = &module.code_section().unwrap().bodies;
+        match &code_section_bodies[0].expr.instructions[2] {
+            node::InstructionNode::I32Store(node) => {
+                assert_eq!(
+                    node.memarg,
+                    node::MemArgNode {
+                        align: 2,
+                        offset: 4
+                    }
+                );
+            }
+            _ => panic!("Expected i32.store node"),
+        }
+    }
+
+    #[test]
+    fn parse_shared_memory_import() {
+        let file_path = "test/fixtures/threads.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let module = parser.parse(&bytes).expect("Failed to parse");
+
+        let imports = &module.import_section().unwrap().imports;
+        assert_eq!(
+            imports[0].import_desc,
+            node::ImportDescNode::Memory(types::MemoryType {
+                limits: types::Limits {
+                    min: 1,
+                    max: Some(1)
+                },
+                shared: true,
+            })
+        );
+
+        let code_section_bodies = &module.code_section().unwrap().bodies;
+        match &code_section_bodies[0].expr.instructions[2] {
+            node::InstructionNode::MemoryAtomicNotify(node) => {
+                assert_eq!(
+                    node.memarg,
+                    node::MemArgNode {
+                        align: 2,
+                        offset: 0
+                    }
+                );
+            }
+            _ => panic!("Expected memory.atomic.notify node"),
+        }
+    }
+
+    #[test]
+    fn parse_data_module() {
+        let file_path = "test/fixtures/data.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let module = parser.parse(&bytes).expect("Failed to parse");
+
+        let data = &module.data_section().unwrap().data;
+        assert_eq!(data.len(), 2);
+        match &data[0].mode {
+            node::DataModeNode::Active {
+                memory_index,
+                offset,
+            } => {
+                assert_eq!(*memory_index, 0);
+                assert_eq!(offset.instructions.len(), 2);
+            }
+            _ => panic!("Expected active data segment"),
+        }
+        assert_eq!(data[0].init, b"hello");
+        assert!(matches!(data[1].mode, node::DataModeNode::Passive));
+        assert_eq!(data[1].init, b"world");
+    }
+
+    #[test]
+    fn parse_table_module() {
+        let file_path = "test/fixtures/table.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let module = parser.parse(&bytes).expect("Failed to parse");
+
+        let tables = &module.table_section().unwrap().tables;
+        assert_eq!(tables.len(), 1);
+        assert_eq!(
+            tables[0].element_type,
+            types::ReferenceTypeNode::FunctionRef
+        );
+        assert_eq!(tables[0].limits.min, 4);
+
+        let elements = &module.element_section().unwrap().elements;
+        assert_eq!(elements.len(), 4);
+        match (&elements[0].mode, &elements[0].init) {
+            (
+                node::ElementModeNode::Active { table_index, .. },
+                node::ElementInitNode::Functions(indexes),
+            ) => {
+                assert_eq!(*table_index, 0);
+                assert_eq!(indexes, &vec![0, 1, 2]);
+            }
+            _ => panic!("Expected active element segment"),
+        }
+        assert!(matches!(elements[1].mode, node::ElementModeNode::Passive));
+        assert!(matches!(
+            elements[2].mode,
+            node::ElementModeNode::Declarative
+        ));
+        match &elements[3].init {
+            node::ElementInitNode::Expressions(exprs) => assert_eq!(exprs.len(), 2),
+            _ => panic!("Expected element expressions"),
+        }
+
+        let code_section = module.code_section().unwrap();
+        match &code_section.bodies[3].expr.instructions[3] {
+            node::InstructionNode::CallIndirect(node) => {
+                assert_eq!(node.type_index, 0);
+                assert_eq!(node.table_index, 0);
+            }
+            _ => panic!("Expected call_indirect node"),
+        }
+    }
+
+    #[test]
+    fn parse_start_module() {
+        let file_path = "test/fixtures/start.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        assert_eq!(module.start_section().unwrap().function_index, 0);
+        module.make();
+        assert_eq!(module.start(), Some(0));
+    }
+
+    #[test]
+    fn parse_custom_sections() {
+        let file_path = "test/fixtures/custom.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let module = parser.parse(&bytes).expect("Failed to parse");
+
+        let custom_sections = module.custom_sections();
+        assert_eq!(custom_sections.len(), 3);
+
+        let (after, section) = &custom_sections[0];
+        assert_eq!(*after, None);
+        assert_eq!(section.name, "first");
+        assert_eq!(section.payload, vec![1, 2, 3]);
+
+        let (after, section) = &custom_sections[1];
+        assert_eq!(*after, Some(SectionId::FunctionSectionId));
+        assert_eq!(section.name, "between");
+        assert!(section.payload.is_empty());
+
+        let (after, section) = &custom_sections[2];
+        assert_eq!(*after, Some(SectionId::CodeSectionId));
+        assert_eq!(section.name, "last");
+        assert_eq!(section.payload, b"payload");
+    }
+
+    #[test]
+    fn parse_name_section() {
+        let file_path = "test/fixtures/gcd_names.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let module = parser.parse(&bytes).expect("Failed to parse");
+
+        let names = module.names().unwrap();
+        assert_eq!(names.module, Some("euclid".to_string()));
+        assert_eq!(names.function_name(0), Some("gcd"));
+        assert_eq!(names.local_name(0, 2), Some("rem"));
+        assert_eq!(names.display_function(0), "$gcd");
+        assert_eq!(names.display_local(0, 3), "$tmp");
+        assert_eq!(names.display_function(1), "1");
+
+        // the raw section is kept so that the module round-trips
+        let (_, section) = &module.custom_sections()[0];
+        assert_eq!(section.name, "name");
+    }
+
     #[test]
     fn emit_module() {
         let dir = "test/fixtures";
         for file in std::fs::read_dir(dir).unwrap() {
             let file_path = file.unwrap().path().to_str().unwrap().to_string();
             if (&file_path).ends_with(".wasm") {
-                let mut bytes = std::fs::read(&file_path).expect("file not found");
+                let bytes = std::fs::read(&file_path).expect("file not found");
                 let original_bytes = bytes.clone();
                 let parser = parser::Parser::new().unwrap();
-                let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+                let mut module = parser.parse(&bytes).expect("Failed to parse");
                 module.emit();
 
                 assert_eq!(module.buffer.bytes, original_bytes);
@@ -258,17 +556,17 @@ mod parser_tests {
 
 #[cfg(test)]
 mod module_node_convert_tests {
-    use crate::{
+    use wasm_runtime::{
         node::{I32SubInstructionNode, InstructionNode},
         parser,
     };
 
     #[test]
     fn convert_add_instruction_to_sub() {
-        let mut bytes = std::fs::read("test/fixtures/i32_add.wasm").expect("file not found");
+        let bytes = std::fs::read("test/fixtures/i32_add.wasm").expect("file not found");
         let sub_bytes = std::fs::read("test/fixtures/i32_sub.wasm").expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
 
         let mut export_section = module
             .export_section()
@@ -296,21 +594,35 @@ mod module_node_convert_tests {
 
 #[cfg(test)]
 mod runtime_tests {
-    use crate::{
-        instance, parser,
-        runtime::Runtime,
-        stack::{Number, Value},
+    use std::time::Duration;
+    use wasm_runtime::{
+        float::{F32, F64},
+        instance::{
+            self, Extern, GlobalInstance, HostFunction, Instance, InstantiationError,
+            MemoryInstance, TableInstance, MAX_TABLE_SIZE,
+        },
+        leb128::encode_u32_to_leb128,
+        node::{FunctionTypeNode, ResultTypeNode},
+        parser,
+        runtime::{Runtime, MAX_DEPTH},
+        simd::{Lane, V128},
+        stack::{ExternRef, Number, Reference, Value},
+        trap::Trap,
+        types::{
+            GlobalType, Limits, MemoryType, NumberType, ReferenceTypeNode, TableType, ValueType,
+        },
     };
 
     #[test]
     fn run_i32_const() {
         let file_path = "test/fixtures/const_i32.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
         module.make();
 
-        let instance = instance::Instance::new(&mut module);
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
         let keys = instance
             .export_map
             .keys()
@@ -320,18 +632,19 @@ mod runtime_tests {
         let mut runtime = Runtime::new(instance);
         let result = runtime.execute(&keys[0], None);
 
-        assert_eq!(result, Some(Number::i32(42)));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(42))]));
     }
 
     #[test]
     fn run_i32_local_get_set() {
         let file_path = "test/fixtures/local_i32_var.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
         module.make();
 
-        let instance = instance::Instance::new(&mut module);
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
         let keys = instance
             .export_map
             .keys()
@@ -341,18 +654,19 @@ mod runtime_tests {
         let mut runtime = Runtime::new(instance);
         let result = runtime.execute(&keys[0], None);
 
-        assert_eq!(result, Some(Number::i32(55)));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(55))]));
     }
 
     #[test]
     fn run_i32_add() {
         let file_path = "test/fixtures/i32_add.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
         module.make();
 
-        let instance = instance::Instance::new(&mut module);
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
         let keys = instance
             .export_map
             .keys()
@@ -367,18 +681,19 @@ mod runtime_tests {
         let mut runtime = Runtime::new(instance);
         let result = runtime.execute(&keys[0], Some(args));
 
-        assert_eq!(result, Some(Number::i32(3)));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(3))]));
     }
 
     #[test]
     fn run_i32_sub() {
         let file_path = "test/fixtures/i32_sub.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
         module.make();
 
-        let instance = instance::Instance::new(&mut module);
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
         let keys = instance
             .export_map
             .keys()
@@ -393,18 +708,19 @@ mod runtime_tests {
         let mut runtime = Runtime::new(instance);
         let result = runtime.execute(&keys[0], Some(args));
 
-        assert_eq!(result, Some(Number::i32(-1)));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(-1))]));
     }
 
     #[test]
     fn run_if_then_i32_ge_s() {
         let file_path = "test/fixtures/if_i32_ge_s.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
         module.make();
 
-        let instance = instance::Instance::new(&mut module);
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
         let keys = instance
             .export_map
             .keys()
@@ -419,18 +735,19 @@ mod runtime_tests {
         let mut runtime = Runtime::new(instance);
         let result = runtime.execute(&keys[0], Some(args));
 
-        assert_eq!(result, Some(Number::i32(1)));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(1))]));
     }
 
     #[test]
     fn run_if_else_i32_ge_s() {
         let file_path = "test/fixtures/if_i32_ge_s.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
         module.make();
 
-        let instance = instance::Instance::new(&mut module);
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
         let keys = instance
             .export_map
             .keys()
@@ -445,18 +762,19 @@ mod runtime_tests {
         let mut runtime = Runtime::new(instance);
         let result = runtime.execute(&keys[0], Some(args));
 
-        assert_eq!(result, Some(Number::i32(0)));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(0))]));
     }
 
     #[test]
     fn run_block() {
         let file_path = "test/fixtures/block.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
         module.make();
 
-        let instance = instance::Instance::new(&mut module);
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
         let keys = instance
             .export_map
             .keys()
@@ -466,18 +784,19 @@ mod runtime_tests {
         let mut runtime = Runtime::new(instance);
         let result = runtime.execute(&keys[0], None);
 
-        assert_eq!(result, Some(Number::i32(14)));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(14))]));
     }
 
     #[test]
     fn run_block_no_result() {
         let file_path = "test/fixtures/block_no_result.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
         module.make();
 
-        let instance = instance::Instance::new(&mut module);
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
         let keys = instance
             .export_map
             .keys()
@@ -487,18 +806,19 @@ mod runtime_tests {
         let mut runtime = Runtime::new(instance);
         let result = runtime.execute(&keys[0], None);
 
-        assert_eq!(result, None);
+        assert_eq!(result, Ok(vec![]));
     }
 
     #[test]
     fn run_loop() {
         let file_path = "test/fixtures/loop.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
         module.make();
 
-        let instance = instance::Instance::new(&mut module);
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
         let keys = instance
             .export_map
             .keys()
@@ -508,18 +828,19 @@ mod runtime_tests {
         let mut runtime = Runtime::new(instance);
         let result = runtime.execute(&keys[0], None);
 
-        assert_eq!(result, Some(Number::i32(33)));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(33))]));
     }
 
     #[test]
     fn run_increment_with_call_add_function() {
         let file_path = "test/fixtures/increment.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
         module.make();
 
-        let instance = instance::Instance::new(&mut module);
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
         let keys = instance
             .export_map
             .keys()
@@ -534,18 +855,45 @@ mod runtime_tests {
         let mut runtime = Runtime::new(instance);
         let result = runtime.execute(&keys[0], Some(args));
 
-        assert_eq!(result, Some(Number::i32(6)));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(6))]));
+    }
+
+    #[test]
+    fn run_gcd_with_names() {
+        let file_path = "test/fixtures/gcd_names.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        match &instance.functions[0] {
+            instance::FunctionInstance::Module(function) => {
+                assert_eq!(function.name, "$gcd");
+                assert_eq!(
+                    function.local_names,
+                    vec!["$small", "$large", "$rem", "$tmp"]
+                );
+            }
+            _ => panic!("Expected module function"),
+        }
+
+        let mut runtime = Runtime::new(instance);
+        let args = vec![Value::num(Number::i32(12)), Value::num(Number::i32(18))];
+        let result = runtime.execute(&"gcd".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(6))]));
     }
 
     #[test]
     fn run_gcd() {
         let file_path = "test/fixtures/gcd.wasm";
-        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let bytes = std::fs::read(file_path).expect("file not found");
         let parser = parser::Parser::new().unwrap();
-        let mut module = parser.parse(&mut bytes).expect("Failed to parse");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
         module.make();
 
-        let instance = instance::Instance::new(&mut module);
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
         let keys = instance
             .export_map
             .keys()
@@ -570,7 +918,2887 @@ mod runtime_tests {
 
             let result = runtime.execute(&keys[0], Some(args[0..2].to_vec()));
 
-            assert_eq!(result, Some(Number::i32(tmp[2].parse::<i32>().unwrap())));
+            assert_eq!(
+                result,
+                Ok(vec![Value::num(Number::i32(
+                    tmp[2].parse::<i32>().unwrap()
+                ))])
+            );
+        }
+    }
+
+    #[test]
+    fn run_imported_host_function() {
+        let file_path = "test/fixtures/import_function.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let i32_type = ValueType::Number(NumberType::I32);
+        let function_type = FunctionTypeNode::new(
+            ResultTypeNode {
+                val_types: vec![i32_type, i32_type],
+            },
+            ResultTypeNode {
+                val_types: vec![i32_type],
+            },
+        );
+        let mut imports = instance::Imports::new();
+        imports.define(
+            "env",
+            "sub",
+            Extern::Function(HostFunction::new(function_type, |args| {
+                match (&args[0], &args[1]) {
+                    (Value::num(Number::i32(lhs)), Value::num(Number::i32(rhs))) => {
+                        vec![Value::num(Number::i32(lhs - rhs))]
+                    }
+                    _ => panic!("env.sub expects two i32 values"),
+                }
+            })),
+        );
+
+        let instance =
+            instance::Instance::new(&mut module, &imports).expect("Failed to instantiate");
+        let args = vec![Value::num(Number::i32(10))];
+
+        let mut runtime = Runtime::new(instance);
+        let result = runtime.execute(&"decrement".to_string(), Some(args));
+
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(9))]));
+    }
+
+    #[test]
+    fn instantiate_with_unknown_import() {
+        let file_path = "test/fixtures/import_function.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+        assert_eq!(
+            result.err(),
+            Some(InstantiationError::UnknownImport {
+                module: "env".to_string(),
+                name: "sub".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn instantiate_with_unknown_import_type() {
+        let file_path = "test/fixtures/import_function.wasm";
+        let mut bytes = std::fs::read(file_path).expect("file not found");
+        // point env.sub at type 7 of the two declared
+        assert_eq!(bytes[0x22], 0x00);
+        bytes[0x22] = 0x07;
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let mut imports = instance::Imports::new();
+        let function_type = FunctionTypeNode::new(
+            ResultTypeNode { val_types: vec![] },
+            ResultTypeNode { val_types: vec![] },
+        );
+        imports.define(
+            "env",
+            "sub",
+            Extern::Function(HostFunction::new(function_type, |_| vec![])),
+        );
+        let result = instance::Instance::new(&mut module, &imports);
+
+        assert_eq!(
+            result.err(),
+            Some(InstantiationError::UnknownType { index: 7 })
+        );
+    }
+
+    #[test]
+    fn instantiate_with_unknown_function_type() {
+        let file_path = "test/fixtures/const_i32.wasm";
+        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        // declare the function with type 3 of the one in the type section
+        assert_eq!(bytes[0x12], 0x00);
+        bytes[0x12] = 0x03;
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+        assert_eq!(
+            result.err(),
+            Some(InstantiationError::UnknownType { index: 3 })
+        );
+
+        // or without a type section at all
+        bytes[0x12] = 0x00;
+        assert_eq!(&bytes[0x08..0x0A], [0x01, 0x05]);
+        bytes.drain(0x08..0x0F);
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+        assert_eq!(
+            result.err(),
+            Some(InstantiationError::UnknownType { index: 0 })
+        );
+    }
+
+    #[test]
+    fn run_global_get_set() {
+        let file_path = "test/fixtures/global.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let mut imports = instance::Imports::new();
+        imports.define(
+            "env",
+            "step",
+            Extern::Global(GlobalInstance::new(
+                GlobalType {
+                    value_type: ValueType::Number(NumberType::I32),
+                    mutable: false,
+                },
+                Value::num(Number::i32(5)),
+            )),
+        );
+
+        let instance =
+            instance::Instance::new(&mut module, &imports).expect("Failed to instantiate");
+
+        let mut runtime = Runtime::new(instance);
+        let result = runtime.execute(&"count".to_string(), None);
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(5))]));
+        let result = runtime.execute(&"count".to_string(), None);
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(10))]));
+    }
+
+    #[test]
+    fn run_with_imports_shared_with_host() {
+        let file_path = "test/fixtures/linked_externs.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let mut table = TableInstance::new(TableType {
+            element_type: ReferenceTypeNode::FunctionRef,
+            limits: Limits { min: 2, max: None },
+        });
+        let mut memory = MemoryInstance::new(MemoryType {
+            limits: Limits { min: 1, max: None },
+            shared: false,
+        });
+        let mut counter = GlobalInstance::new(
+            GlobalType {
+                value_type: ValueType::Number(NumberType::I32),
+                mutable: true,
+            },
+            Value::num(Number::i32(0)),
+        );
+        let mut imports = instance::Imports::new();
+        imports.define("env", "table", Extern::Table(table.clone()));
+        imports.define("env", "memory", Extern::Memory(memory.clone()));
+        imports.define("env", "counter", Extern::Global(counter.clone()));
+
+        let instance =
+            instance::Instance::new(&mut module, &imports).expect("Failed to instantiate");
+        let exported_memory = instance.exported_memory("memory").unwrap();
+        let exported_counter = instance.exported_global("counter").unwrap();
+        let exported_table = instance.exported_table("table").unwrap();
+        assert!(instance.exported_memory("counter").is_none());
+        let answer = Reference::Func(instance.func_ref(0).unwrap());
+        let mut runtime = Runtime::new(instance);
+
+        // the module sees the host's writes
+        memory.store(0, 0, &7i32.to_le_bytes()).unwrap();
+        let args = vec![Value::num(Number::i32(0))];
+        let result = runtime.execute(&"load".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(7))]));
+
+        // and the host sees the module's, through the import and the export
+        let args = vec![Value::num(Number::i32(4)), Value::num(Number::i32(99))];
+        runtime.execute(&"store".to_string(), Some(args)).unwrap();
+        assert_eq!(memory.load::<4>(4, 0), Ok(99i32.to_le_bytes()));
+        assert_eq!(exported_memory.load::<4>(4, 0), Ok(99i32.to_le_bytes()));
+
+        counter.set(Value::num(Number::i32(10)));
+        runtime.execute(&"bump".to_string(), None).unwrap();
+        assert_eq!(counter.get(), Value::num(Number::i32(11)));
+        assert_eq!(exported_counter.get(), Value::num(Number::i32(11)));
+
+        let args = vec![Value::num(Number::i32(1))];
+        runtime.execute(&"install".to_string(), Some(args)).unwrap();
+        assert_eq!(table.get(1), Ok(answer.clone()));
+        assert_eq!(exported_table.get(1), Ok(answer));
+        table
+            .set(1, Reference::Null(ReferenceTypeNode::FunctionRef))
+            .unwrap();
+        assert_eq!(
+            exported_table.get(1),
+            Ok(Reference::Null(ReferenceTypeNode::FunctionRef))
+        );
+    }
+
+    #[test]
+    fn run_deepest_nesting() {
+        // "deep" nests the most blocks the parser accepts, each of type
+        // [] -> [i32], around an i32.const 7
+        let depth = parser::MAX_NESTING_DEPTH as usize;
+        let mut body = vec![0x00];
+        body.extend([0x02, 0x7F].repeat(depth));
+        body.extend([0x41, 0x07]);
+        body.extend([0x0B].repeat(depth + 1));
+        let mut code = vec![0x01];
+        code.extend(encode_u32_to_leb128(body.len() as u32));
+        code.extend(body);
+        let mut bytes = vec![0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+        bytes.extend([0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7F]);
+        bytes.extend([0x03, 0x02, 0x01, 0x00]);
+        bytes.extend([0x07, 0x08, 0x01, 0x04, b'd', b'e', b'e', b'p', 0x00, 0x00]);
+        bytes.push(0x0A);
+        bytes.extend(encode_u32_to_leb128(code.len() as u32));
+        bytes.extend(code);
+
+        // on a spawned thread, with the default stack size
+        let result = std::thread::spawn(move || {
+            let parser = parser::Parser::new().unwrap();
+            let mut module = parser.parse(&bytes).expect("Failed to parse");
+            let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+                .expect("Failed to instantiate");
+            let mut runtime = Runtime::new(instance);
+            runtime.execute(&"deep".to_string(), None)
+        })
+        .join()
+        .expect("overflowed the stack");
+
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(7))]));
+    }
+
+    #[test]
+    fn run_unbounded_recursion() {
+        let result = std::thread::spawn(|| {
+            let file_path = "test/fixtures/recursion.wasm";
+            let bytes = std::fs::read(file_path).expect("file not found");
+            let parser = parser::Parser::new().unwrap();
+            let mut module = parser.parse(&bytes).expect("Failed to parse");
+            let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+                .expect("Failed to instantiate");
+            let mut runtime = Runtime::new(instance);
+
+            let forever = runtime.execute(&"forever".to_string(), None);
+            let forever_indirect = runtime.execute(&"forever_indirect".to_string(), None);
+            // each level takes a call and the if around it
+            let levels = (MAX_DEPTH / 2 - 1) as i32;
+            let countdown = runtime.execute(
+                &"countdown".to_string(),
+                Some(vec![Value::num(Number::i32(levels))]),
+            );
+            (forever, forever_indirect, countdown)
+        })
+        .join()
+        .expect("overflowed the stack");
+
+        assert_eq!(
+            result,
+            (
+                Err(Trap::CallStackExhausted),
+                Err(Trap::CallStackExhausted),
+                Ok(vec![Value::num(Number::i32(0))])
+            )
+        );
+    }
+
+    #[test]
+    fn run_call_indirect_across_instances() {
+        let parser = parser::Parser::new().unwrap();
+        let bytes = std::fs::read("test/fixtures/funcref_table.wasm").expect("file not found");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+        let exporter = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        let table = exporter.exported_table("table").unwrap();
+
+        let bytes = std::fs::read("test/fixtures/funcref_import.wasm").expect("file not found");
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+        let mut imports = instance::Imports::new();
+        imports.define("env", "table", Extern::Table(table));
+        let importer =
+            instance::Instance::new(&mut module, &imports).expect("Failed to instantiate");
+
+        let mut exporter = Runtime::new(exporter);
+        let mut importer = Runtime::new(importer);
+
+        // the exporter's functions run in the exporter, whose global they read
+        let args = vec![Value::num(Number::i32(0))];
+        let result = importer.execute(&"call".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(100))]));
+        let args = vec![Value::num(Number::i32(1))];
+        let result = importer.execute(&"call".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(2))]));
+
+        // and the importer's function runs in the importer
+        let args = vec![Value::num(Number::i32(1))];
+        importer
+            .execute(&"install".to_string(), Some(args))
+            .unwrap();
+        let args = vec![Value::num(Number::i32(1))];
+        let result = exporter.execute(&"call".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(-1))]));
+        let args = vec![Value::num(Number::i32(0))];
+        let result = exporter.execute(&"call".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(100))]));
+    }
+
+    #[test]
+    fn instantiate_with_duplicate_export() {
+        let file_path = "test/fixtures/linked_externs.wasm";
+        let mut bytes = std::fs::read(file_path).expect("file not found");
+        // rename the "bump" export to "load", which is already taken
+        assert_eq!(&bytes[0x69..0x6D], b"bump");
+        bytes[0x69..0x6D].copy_from_slice(b"load");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let mut imports = instance::Imports::new();
+        imports.define(
+            "env",
+            "table",
+            Extern::Table(TableInstance::new(TableType {
+                element_type: ReferenceTypeNode::FunctionRef,
+                limits: Limits { min: 2, max: None },
+            })),
+        );
+        imports.define(
+            "env",
+            "memory",
+            Extern::Memory(MemoryInstance::new(MemoryType {
+                limits: Limits { min: 1, max: None },
+                shared: false,
+            })),
+        );
+        imports.define(
+            "env",
+            "counter",
+            Extern::Global(GlobalInstance::new(
+                GlobalType {
+                    value_type: ValueType::Number(NumberType::I32),
+                    mutable: true,
+                },
+                Value::num(Number::i32(0)),
+            )),
+        );
+        let result = instance::Instance::new(&mut module, &imports);
+
+        assert_eq!(
+            result.err(),
+            Some(InstantiationError::DuplicateExport {
+                name: "load".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn instantiate_with_immutable_global_set() {
+        let file_path = "test/fixtures/global_immutable_set.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+        assert_eq!(
+            result.err(),
+            Some(InstantiationError::ImmutableGlobal { index: 0 })
+        );
+    }
+
+    #[test]
+    fn instantiate_with_unknown_global() {
+        let parser = parser::Parser::new().unwrap();
+        // global.set of global 5 in a module with one global, and global.get
+        // of global 7 in a module with three
+        let cases = [
+            (
+                "test/fixtures/global_immutable_set.wasm",
+                0x34,
+                0x00,
+                0x05,
+                5,
+            ),
+            ("test/fixtures/global.wasm", 0x47, 0x01, 0x07, 7),
+        ];
+        for (file_path, offset, original, patched, index) in cases {
+            let mut bytes = std::fs::read(file_path).expect("file not found");
+            assert_eq!(bytes[offset], original);
+            bytes[offset] = patched;
+            let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+            let mut imports = instance::Imports::new();
+            let step = GlobalInstance::new(
+                GlobalType {
+                    value_type: ValueType::Number(NumberType::I32),
+                    mutable: false,
+                },
+                Value::num(Number::i32(1)),
+            );
+            imports.define("env", "step", Extern::Global(step));
+            let result = instance::Instance::new(&mut module, &imports);
+
+            assert_eq!(
+                result.err(),
+                Some(InstantiationError::UnknownGlobal { index })
+            );
+        }
+    }
+
+    #[test]
+    fn run_memory_load_store() {
+        let file_path = "test/fixtures/memory.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        let mut runtime = Runtime::new(instance);
+
+        let args = vec![Value::num(Number::i32(8)), Value::num(Number::i32(42))];
+        let result = runtime.execute(&"store_load".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(42))]));
+
+        let args = vec![Value::num(Number::i32(0))];
+        let result = runtime.execute(&"load8_s".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(-1))]));
+
+        let args = vec![Value::num(Number::i32(0))];
+        let result = runtime.execute(&"load16_u".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(65535))]));
+
+        let args = vec![Value::num(Number::i32(0))];
+        let result = runtime.execute(&"load32_u".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i64(4294967295))]));
+
+        // the effective address (65532 + offset 4) is past the end of the single page
+        let args = vec![Value::num(Number::i32(65532)), Value::num(Number::i32(1))];
+        let result = runtime.execute(&"store_load".to_string(), Some(args));
+        assert_eq!(result, Err(Trap::MemoryOutOfBounds));
+    }
+
+    #[test]
+    fn run_memory_size_and_grow() {
+        let file_path = "test/fixtures/memory.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        let mut runtime = Runtime::new(instance);
+
+        let result = runtime.execute(&"size".to_string(), None);
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(1))]));
+
+        let args = vec![Value::num(Number::i32(1))];
+        let result = runtime.execute(&"grow".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(1))]));
+
+        let result = runtime.execute(&"size".to_string(), None);
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(2))]));
+
+        // the maximum is 2 pages
+        let args = vec![Value::num(Number::i32(1))];
+        let result = runtime.execute(&"grow".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(-1))]));
+
+        // the page past the old end is now addressable
+        let args = vec![Value::num(Number::i32(65532)), Value::num(Number::i32(7))];
+        let result = runtime.execute(&"store_load".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(7))]));
+    }
+
+    #[test]
+    fn run_active_data_segment() {
+        let file_path = "test/fixtures/data.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        assert_eq!(instance.datas[0].data(), b"");
+        assert_eq!(instance.datas[1].data(), b"world");
+
+        let mut runtime = Runtime::new(instance);
+        for (addr, expected) in [(16, b'h'), (20, b'o'), (21, 0)] {
+            let args = vec![Value::num(Number::i32(addr))];
+            let result = runtime.execute(&"load_byte".to_string(), Some(args));
+            assert_eq!(result, Ok(vec![Value::num(Number::i32(expected as i32))]));
         }
     }
+
+    #[test]
+    fn instantiate_with_data_segment_out_of_bounds() {
+        let file_path = "test/fixtures/data_out_of_bounds.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+        assert_eq!(
+            result.err(),
+            Some(InstantiationError::DataSegmentOutOfBounds { index: 0 })
+        );
+    }
+
+    #[test]
+    fn run_call_indirect() {
+        let file_path = "test/fixtures/table.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        let null = Reference::Null(ReferenceTypeNode::FunctionRef);
+        let func = |index| Reference::Func(instance.func_ref(index).unwrap());
+        assert_eq!(
+            instance.tables[0].slice(0, 4),
+            Ok(vec![func(0), func(1), func(2), null.clone()])
+        );
+        assert_eq!(instance.elems[1].elements(), vec![func(0)]);
+        assert_eq!(instance.elems[3].elements(), vec![null, func(2)]);
+
+        let mut runtime = Runtime::new(instance);
+        let cases = [
+            (0, Ok(Some(Number::i32(10)))),
+            (1, Ok(Some(Number::i32(4)))),
+            (2, Err(Trap::IndirectCallTypeMismatch)),
+            (3, Err(Trap::UninitializedElement)),
+            (4, Err(Trap::UndefinedElement)),
+        ];
+        for (index, expected) in cases {
+            let args = vec![
+                Value::num(Number::i32(index)),
+                Value::num(Number::i32(7)),
+                Value::num(Number::i32(3)),
+            ];
+            let result = runtime.execute(&"call_binary".to_string(), Some(args));
+            assert_eq!(result, values(&expected));
+        }
+    }
+
+    #[test]
+    fn instantiate_with_unknown_call_indirect_indices() {
+        let file_path = "test/fixtures/table.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        // an element naming function 9, call_indirect naming type 7 and
+        // call_indirect naming table 1, of the four functions, three types
+        // and one table declared
+        let cases = [
+            (
+                0x45,
+                0x02,
+                0x09,
+                InstantiationError::UnknownFunction { index: 9 },
+            ),
+            (
+                0x7B,
+                0x00,
+                0x07,
+                InstantiationError::UnknownType { index: 7 },
+            ),
+            (
+                0x7C,
+                0x00,
+                0x01,
+                InstantiationError::UnknownTable { index: 1 },
+            ),
+        ];
+        for (offset, original, patched, expected) in cases {
+            let mut bytes = bytes.clone();
+            assert_eq!(bytes[offset], original);
+            bytes[offset] = patched;
+            let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+            let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+            assert_eq!(result.err(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn instantiate_with_unknown_block_type() {
+        let file_path = "test/fixtures/multi_value.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        // a block, a loop and an if naming type 32, of the seven types
+        // declared
+        for (offset, original) in [(0x102, 0x03), (0x12D, 0x03), (0x14A, 0x03)] {
+            let mut bytes = bytes.clone();
+            assert_eq!(bytes[offset], original);
+            bytes[offset] = 0x20;
+            let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+            let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+            assert_eq!(
+                result.err(),
+                Some(InstantiationError::UnknownType { index: 32 })
+            );
+        }
+    }
+
+    #[test]
+    fn instantiate_with_unknown_segment_indices() {
+        let file_path = "test/fixtures/bulk_memory.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        // memory.fill naming memory 1, data.drop naming segment 5, table.init
+        // naming table 1 and elem.drop naming segment 4, of the one memory,
+        // two data segments, one table and one elem segment declared
+        let cases = [
+            (
+                0xD1,
+                0x00,
+                0x01,
+                InstantiationError::UnknownMemory { index: 1 },
+            ),
+            (
+                0xFE,
+                0x00,
+                0x05,
+                InstantiationError::UnknownData { index: 5 },
+            ),
+            (
+                0x10B,
+                0x00,
+                0x01,
+                InstantiationError::UnknownTable { index: 1 },
+            ),
+            (
+                0x11E,
+                0x00,
+                0x04,
+                InstantiationError::UnknownElem { index: 4 },
+            ),
+        ];
+        for (offset, original, patched, expected) in cases {
+            let mut bytes = bytes.clone();
+            assert_eq!(bytes[offset], original);
+            bytes[offset] = patched;
+            let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+            let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+            assert_eq!(result.err(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn instantiate_with_memory_instructions_without_memory() {
+        let file_path = "test/fixtures/memory.wasm";
+        let mut bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        // the loads, stores, memory.size and memory.grow all address memory 0
+        assert_eq!(bytes[0x28..0x2E], [0x05, 0x04, 0x01, 0x01, 0x01, 0x02]);
+        bytes.drain(0x28..0x2E);
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+        assert_eq!(
+            result.err(),
+            Some(InstantiationError::UnknownMemory { index: 0 })
+        );
+    }
+
+    #[test]
+    fn instantiate_with_element_segment_out_of_bounds() {
+        let file_path = "test/fixtures/elem_out_of_bounds.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+        assert_eq!(
+            result.err(),
+            Some(InstantiationError::ElementSegmentOutOfBounds { index: 0 })
+        );
+    }
+
+    #[test]
+    fn run_start_function_at_instantiation() {
+        let file_path = "test/fixtures/start.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        let mut runtime = Runtime::new(instance);
+        let result = runtime.execute(&"get".to_string(), None);
+
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(42))]));
+    }
+
+    #[test]
+    fn instantiate_with_recursive_start_function() {
+        let file_path = "test/fixtures/start_recursive.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+        assert_eq!(
+            result.err(),
+            Some(InstantiationError::StartFunctionTrap(
+                Trap::CallStackExhausted
+            ))
+        );
+    }
+
+    #[test]
+    fn instantiate_with_trapping_start_function() {
+        let file_path = "test/fixtures/start_trap.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+        assert_eq!(
+            result.err(),
+            Some(InstantiationError::StartFunctionTrap(
+                Trap::MemoryOutOfBounds
+            ))
+        );
+    }
+
+    #[test]
+    fn instantiate_with_invalid_start_function_type() {
+        let file_path = "test/fixtures/start_invalid_type.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let result = instance::Instance::new(&mut module, &instance::Imports::new());
+
+        assert_eq!(
+            result.err(),
+            Some(InstantiationError::InvalidStartFunction { index: 0 })
+        );
+    }
+
+    /// An export name, its arguments and the expected result.
+    type Case<'a, T> = (&'a str, &'a [T], Result<Option<Number>, Trap>);
+
+    fn run_i32_ops(cases: &[Case<i32>]) {
+        let file_path = "test/fixtures/i32_ops.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        let mut runtime = Runtime::new(instance);
+
+        for (name, args, expected) in cases {
+            let args = args.iter().map(|v| Value::num(Number::i32(*v))).collect();
+            let result = runtime.execute(&name.to_string(), Some(args));
+            assert_eq!(result, values(expected), "i32.{}", name);
+        }
+    }
+
+    #[test]
+    fn run_i32_arithmetic_and_bitwise() {
+        run_i32_ops(&[
+            ("add", &[i32::MAX, 1], Ok(Some(Number::i32(i32::MIN)))),
+            ("sub", &[i32::MIN, 1], Ok(Some(Number::i32(i32::MAX)))),
+            ("mul", &[0x10000, 0x10000], Ok(Some(Number::i32(0)))),
+            ("mul", &[-3, 7], Ok(Some(Number::i32(-21)))),
+            ("div_s", &[-7, 2], Ok(Some(Number::i32(-3)))),
+            ("div_u", &[-7, 2], Ok(Some(Number::i32(0x7FFFFFFC)))),
+            ("rem_s", &[-7, 2], Ok(Some(Number::i32(-1)))),
+            ("rem_s", &[i32::MIN, -1], Ok(Some(Number::i32(0)))),
+            ("rem_u", &[-7, 2], Ok(Some(Number::i32(1)))),
+            ("and", &[0b1100, 0b1010], Ok(Some(Number::i32(0b1000)))),
+            ("or", &[0b1100, 0b1010], Ok(Some(Number::i32(0b1110)))),
+            ("xor", &[0b1100, 0b1010], Ok(Some(Number::i32(0b0110)))),
+            ("shl", &[1, 33], Ok(Some(Number::i32(2)))),
+            ("shr_s", &[i32::MIN, 31], Ok(Some(Number::i32(-1)))),
+            ("shr_u", &[i32::MIN, 31], Ok(Some(Number::i32(1)))),
+            ("shr_u", &[-1, 32], Ok(Some(Number::i32(-1)))),
+            ("rotl", &[0x80000001u32 as i32, 1], Ok(Some(Number::i32(3)))),
+            ("rotr", &[3, 1], Ok(Some(Number::i32(0x80000001u32 as i32)))),
+            (
+                "rotr",
+                &[3, 33],
+                Ok(Some(Number::i32(0x80000001u32 as i32))),
+            ),
+            ("clz", &[1], Ok(Some(Number::i32(31)))),
+            ("clz", &[0], Ok(Some(Number::i32(32)))),
+            ("ctz", &[i32::MIN], Ok(Some(Number::i32(31)))),
+            ("ctz", &[0], Ok(Some(Number::i32(32)))),
+            ("popcnt", &[-1], Ok(Some(Number::i32(32)))),
+        ]);
+    }
+
+    #[test]
+    fn run_i32_comparisons() {
+        run_i32_ops(&[
+            ("eqz", &[0], Ok(Some(Number::i32(1)))),
+            ("eqz", &[5], Ok(Some(Number::i32(0)))),
+            ("eq", &[-1, -1], Ok(Some(Number::i32(1)))),
+            ("ne", &[-1, -1], Ok(Some(Number::i32(0)))),
+            ("lt_s", &[-1, 1], Ok(Some(Number::i32(1)))),
+            ("lt_u", &[-1, 1], Ok(Some(Number::i32(0)))),
+            ("gt_s", &[-1, 1], Ok(Some(Number::i32(0)))),
+            ("gt_u", &[-1, 1], Ok(Some(Number::i32(1)))),
+            ("le_s", &[1, 1], Ok(Some(Number::i32(1)))),
+            ("le_u", &[-1, 1], Ok(Some(Number::i32(0)))),
+            ("ge_s", &[-1, 1], Ok(Some(Number::i32(0)))),
+            ("ge_u", &[-1, -1], Ok(Some(Number::i32(1)))),
+        ]);
+    }
+
+    #[test]
+    fn run_i32_division_traps() {
+        run_i32_ops(&[
+            ("div_s", &[1, 0], Err(Trap::IntegerDivideByZero)),
+            ("div_u", &[1, 0], Err(Trap::IntegerDivideByZero)),
+            ("rem_s", &[1, 0], Err(Trap::IntegerDivideByZero)),
+            ("rem_u", &[1, 0], Err(Trap::IntegerDivideByZero)),
+            ("div_s", &[i32::MIN, -1], Err(Trap::IntegerOverflow)),
+            // the runtime is still usable after a trap
+            ("div_s", &[6, -3], Ok(Some(Number::i32(-2)))),
+        ]);
+    }
+
+    fn run_i64_ops(cases: &[Case<i64>]) {
+        let file_path = "test/fixtures/i64_ops.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        let mut runtime = Runtime::new(instance);
+
+        for (name, args, expected) in cases {
+            let args = args.iter().map(|v| Value::num(Number::i64(*v))).collect();
+            let result = runtime.execute(&name.to_string(), Some(args));
+            assert_eq!(result, values(expected), "i64.{}", name);
+        }
+    }
+
+    #[test]
+    fn run_i64_arithmetic_and_bitwise() {
+        run_i64_ops(&[
+            ("add", &[i64::MAX, 1], Ok(Some(Number::i64(i64::MIN)))),
+            ("sub", &[0, 1], Ok(Some(Number::i64(-1)))),
+            ("mul", &[1 << 32, 1 << 32], Ok(Some(Number::i64(0)))),
+            ("div_s", &[-7, 2], Ok(Some(Number::i64(-3)))),
+            ("div_u", &[-1, 2], Ok(Some(Number::i64(i64::MAX)))),
+            ("rem_s", &[i64::MIN, -1], Ok(Some(Number::i64(0)))),
+            ("rem_u", &[-7, 2], Ok(Some(Number::i64(1)))),
+            ("and", &[0xF0F0, 0xFF00], Ok(Some(Number::i64(0xF000)))),
+            ("or", &[0xF0F0, 0xFF00], Ok(Some(Number::i64(0xFFF0)))),
+            ("xor", &[0xF0F0, 0xFF00], Ok(Some(Number::i64(0x0FF0)))),
+            ("shl", &[1, 63], Ok(Some(Number::i64(i64::MIN)))),
+            ("shl", &[1, 65], Ok(Some(Number::i64(2)))),
+            ("shr_s", &[i64::MIN, 63], Ok(Some(Number::i64(-1)))),
+            ("shr_u", &[i64::MIN, 63], Ok(Some(Number::i64(1)))),
+            ("rotl", &[i64::MIN | 1, 1], Ok(Some(Number::i64(3)))),
+            ("rotr", &[3, -63], Ok(Some(Number::i64(i64::MIN | 1)))),
+            ("clz", &[1], Ok(Some(Number::i64(63)))),
+            ("ctz", &[0], Ok(Some(Number::i64(64)))),
+            ("popcnt", &[-1], Ok(Some(Number::i64(64)))),
+        ]);
+    }
+
+    #[test]
+    fn run_i64_comparisons() {
+        run_i64_ops(&[
+            ("eqz", &[0], Ok(Some(Number::i32(1)))),
+            ("eqz", &[1 << 32], Ok(Some(Number::i32(0)))),
+            ("eq", &[1 << 32, 0], Ok(Some(Number::i32(0)))),
+            ("ne", &[1 << 32, 0], Ok(Some(Number::i32(1)))),
+            ("lt_s", &[-1, 1], Ok(Some(Number::i32(1)))),
+            ("lt_u", &[-1, 1], Ok(Some(Number::i32(0)))),
+            ("gt_s", &[-1, 1], Ok(Some(Number::i32(0)))),
+            ("gt_u", &[-1, 1], Ok(Some(Number::i32(1)))),
+            ("le_s", &[i64::MIN, i64::MAX], Ok(Some(Number::i32(1)))),
+            ("le_u", &[i64::MIN, i64::MAX], Ok(Some(Number::i32(0)))),
+            ("ge_s", &[2, 2], Ok(Some(Number::i32(1)))),
+            ("ge_u", &[1, -1], Ok(Some(Number::i32(0)))),
+        ]);
+    }
+
+    #[test]
+    fn run_i64_division_traps() {
+        run_i64_ops(&[
+            ("div_s", &[1, 0], Err(Trap::IntegerDivideByZero)),
+            ("div_u", &[1, 0], Err(Trap::IntegerDivideByZero)),
+            ("rem_s", &[1, 0], Err(Trap::IntegerDivideByZero)),
+            ("rem_u", &[1, 0], Err(Trap::IntegerDivideByZero)),
+            ("div_s", &[i64::MIN, -1], Err(Trap::IntegerOverflow)),
+        ]);
+    }
+
+    #[test]
+    fn run_i64_fnv1a_with_local_and_block_result() {
+        let offset = 0xcbf29ce484222325u64 as i64;
+        let expected = "a".bytes().fold(offset, |hash, byte| {
+            (hash ^ byte as i64).wrapping_mul(0x100000001b3)
+        });
+        run_i64_ops(&[
+            ("fnv1a_offset", &[], Ok(Some(Number::i64(offset)))),
+            (
+                "fnv1a_step",
+                &[offset, b'a' as i64],
+                Ok(Some(Number::i64(expected))),
+            ),
+        ]);
+        // FNV-1a of "a"
+        assert_eq!(expected as u64, 0xaf63dc4c8601ec8c);
+    }
+
+    /// The result a case expects, in the form `execute` returns it.
+    fn values(expected: &Result<Option<Number>, Trap>) -> Result<Vec<Value>, Trap> {
+        expected
+            .clone()
+            .map(|number| number.into_iter().map(Value::num).collect())
+    }
+
+    fn run_cases(file_path: &str, cases: &[Case<Number>]) {
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        let mut runtime = Runtime::new(instance);
+
+        for (name, args, expected) in cases {
+            let args = args.iter().cloned().map(Value::num).collect();
+            let result = runtime.execute(&name.to_string(), Some(args));
+            assert_eq!(result, values(expected), "{}", name);
+        }
+    }
+
+    fn run_float_ops(cases: &[Case<Number>]) {
+        run_cases("test/fixtures/float_ops.wasm", cases);
+    }
+
+    fn f32(value: f32) -> Number {
+        Number::f32(F32::from(value))
+    }
+
+    fn f64(value: f64) -> Number {
+        Number::f64(F64::from(value))
+    }
+
+    #[test]
+    fn run_float_arithmetic() {
+        run_float_ops(&[
+            ("f32.add", &[f32(0.1), f32(0.2)], Ok(Some(f32(0.1 + 0.2)))),
+            (
+                "f64.add",
+                &[f64(0.1), f64(0.2)],
+                Ok(Some(f64(0.30000000000000004))),
+            ),
+            ("f32.sub", &[f32(1.0), f32(1.0)], Ok(Some(f32(0.0)))),
+            (
+                "f64.mul",
+                &[f64(1e308), f64(10.0)],
+                Ok(Some(f64(f64::INFINITY))),
+            ),
+            (
+                "f32.div",
+                &[f32(1.0), f32(-0.0)],
+                Ok(Some(f32(f32::NEG_INFINITY))),
+            ),
+            (
+                "f64.sqrt",
+                &[f64(2.0)],
+                Ok(Some(f64(std::f64::consts::SQRT_2))),
+            ),
+            ("f32.sqrt", &[f32(-0.0)], Ok(Some(f32(-0.0)))),
+            ("f32.abs", &[f32(-1.5)], Ok(Some(f32(1.5)))),
+            ("f64.neg", &[f64(0.0)], Ok(Some(f64(-0.0)))),
+            ("f32.copysign", &[f32(2.0), f32(-0.0)], Ok(Some(f32(-2.0)))),
+            ("f64.copysign", &[f64(-2.0), f64(1.0)], Ok(Some(f64(2.0)))),
+        ]);
+    }
+
+    #[test]
+    fn run_float_rounding() {
+        run_float_ops(&[
+            ("f32.ceil", &[f32(-0.5)], Ok(Some(f32(-0.0)))),
+            ("f64.floor", &[f64(-0.5)], Ok(Some(f64(-1.0)))),
+            ("f32.trunc", &[f32(-1.9)], Ok(Some(f32(-1.0)))),
+            // nearest rounds half-way cases to even
+            ("f32.nearest", &[f32(2.5)], Ok(Some(f32(2.0)))),
+            ("f32.nearest", &[f32(3.5)], Ok(Some(f32(4.0)))),
+            ("f64.nearest", &[f64(-0.5)], Ok(Some(f64(-0.0)))),
+            (
+                "f64.nearest",
+                &[f64(4503599627370497.0)],
+                Ok(Some(f64(4503599627370497.0))),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn run_float_min_max() {
+        run_float_ops(&[
+            ("f32.min", &[f32(0.0), f32(-0.0)], Ok(Some(f32(-0.0)))),
+            ("f32.min", &[f32(-0.0), f32(0.0)], Ok(Some(f32(-0.0)))),
+            ("f32.max", &[f32(-0.0), f32(0.0)], Ok(Some(f32(0.0)))),
+            ("f64.max", &[f64(0.0), f64(-0.0)], Ok(Some(f64(0.0)))),
+            ("f64.min", &[f64(1.0), f64(-3.0)], Ok(Some(f64(-3.0)))),
+            ("f64.max", &[f64(1.0), f64(-3.0)], Ok(Some(f64(1.0)))),
+        ]);
+
+        // unlike f32::min, a NaN operand is propagated
+        for name in ["f32.min", "f32.max"] {
+            let file_path = "test/fixtures/float_ops.wasm";
+            let bytes = std::fs::read(file_path).expect("file not found");
+            let mut module = parser::Parser::new().unwrap().parse(&bytes).unwrap();
+            let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+                .expect("Failed to instantiate");
+            let mut runtime = Runtime::new(instance);
+            let args = vec![Value::num(f32(f32::NAN)), Value::num(f32(1.0))];
+            match runtime.execute(&name.to_string(), Some(args)).as_deref() {
+                Ok([Value::num(Number::f32(value))]) => assert!(value.is_nan(), "{}", name),
+                result => panic!("{}: unexpected result {:?}", name, result),
+            }
+        }
+    }
+
+    #[test]
+    fn run_float_comparisons() {
+        run_float_ops(&[
+            ("f32.eq", &[f32(0.0), f32(-0.0)], Ok(Some(Number::i32(1)))),
+            (
+                "f32.eq",
+                &[f32(f32::NAN), f32(f32::NAN)],
+                Ok(Some(Number::i32(0))),
+            ),
+            (
+                "f32.ne",
+                &[f32(f32::NAN), f32(f32::NAN)],
+                Ok(Some(Number::i32(1))),
+            ),
+            (
+                "f32.lt",
+                &[f32(1.0), f32(f32::NAN)],
+                Ok(Some(Number::i32(0))),
+            ),
+            ("f32.ge", &[f32(1.0), f32(1.0)], Ok(Some(Number::i32(1)))),
+            (
+                "f64.gt",
+                &[f64(1.0), f64(f64::NEG_INFINITY)],
+                Ok(Some(Number::i32(1))),
+            ),
+            (
+                "f64.le",
+                &[f64(f64::NAN), f64(1.0)],
+                Ok(Some(Number::i32(0))),
+            ),
+            ("f64.lt", &[f64(-0.0), f64(0.0)], Ok(Some(Number::i32(0)))),
+        ]);
+    }
+
+    #[test]
+    fn run_float_nan_payloads_are_preserved() {
+        let f32_snan = Number::f32(F32::from_bits(0x7fa00001));
+        let f64_snan = Number::f64(F64::from_bits(0xfff4000000000001));
+        run_float_ops(&[
+            ("f32.snan", &[], Ok(Some(f32_snan.clone()))),
+            ("f64.snan", &[], Ok(Some(f64_snan.clone()))),
+            // sign operations only flip the sign bit, even for NaNs
+            (
+                "f32.neg",
+                &[f32_snan],
+                Ok(Some(Number::f32(F32::from_bits(0xffa00001)))),
+            ),
+            (
+                "f64.abs",
+                &[f64_snan],
+                Ok(Some(Number::f64(F64::from_bits(0x7ff4000000000001)))),
+            ),
+        ]);
+    }
+
+    fn run_conversions(cases: &[Case<Number>]) {
+        run_cases("test/fixtures/conversions.wasm", cases);
+    }
+
+    #[test]
+    fn run_integer_conversions() {
+        run_conversions(&[
+            (
+                "i32.wrap_i64",
+                &[Number::i64(0x1_2345_6789)],
+                Ok(Some(Number::i32(0x2345_6789))),
+            ),
+            (
+                "i32.wrap_i64",
+                &[Number::i64(-1)],
+                Ok(Some(Number::i32(-1))),
+            ),
+            (
+                "i64.extend_i32_s",
+                &[Number::i32(-1)],
+                Ok(Some(Number::i64(-1))),
+            ),
+            (
+                "i64.extend_i32_u",
+                &[Number::i32(-1)],
+                Ok(Some(Number::i64(0xFFFF_FFFF))),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn run_float_to_integer_truncation() {
+        run_conversions(&[
+            ("i32.trunc_f32_s", &[f32(-1.9)], Ok(Some(Number::i32(-1)))),
+            (
+                "i32.trunc_f32_s",
+                &[f32(-2147483648.0)],
+                Ok(Some(Number::i32(i32::MIN))),
+            ),
+            ("i32.trunc_f32_u", &[f32(-0.9)], Ok(Some(Number::i32(0)))),
+            (
+                "i32.trunc_f32_u",
+                &[f32(4294967040.0)],
+                Ok(Some(Number::i32(-256))),
+            ),
+            (
+                "i32.trunc_f64_s",
+                &[f64(2147483647.9)],
+                Ok(Some(Number::i32(i32::MAX))),
+            ),
+            (
+                "i32.trunc_f64_s",
+                &[f64(-2147483648.9)],
+                Ok(Some(Number::i32(i32::MIN))),
+            ),
+            (
+                "i32.trunc_f64_u",
+                &[f64(4294967295.9)],
+                Ok(Some(Number::i32(-1))),
+            ),
+            (
+                "i64.trunc_f32_s",
+                &[f32(-9223372036854775808.0)],
+                Ok(Some(Number::i64(i64::MIN))),
+            ),
+            (
+                "i64.trunc_f32_u",
+                &[f32(9223372036854775808.0)],
+                Ok(Some(Number::i64(i64::MIN))),
+            ),
+            ("i64.trunc_f64_s", &[f64(-0.5)], Ok(Some(Number::i64(0)))),
+            (
+                "i64.trunc_f64_u",
+                &[f64(18446744073709549568.0)],
+                Ok(Some(Number::i64(-2048))),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn run_float_to_integer_truncation_traps() {
+        run_conversions(&[
+            (
+                "i32.trunc_f32_s",
+                &[f32(f32::NAN)],
+                Err(Trap::InvalidConversionToInteger),
+            ),
+            (
+                "i64.trunc_f64_u",
+                &[f64(-f64::NAN)],
+                Err(Trap::InvalidConversionToInteger),
+            ),
+            (
+                "i32.trunc_f32_s",
+                &[f32(2147483648.0)],
+                Err(Trap::IntegerOverflow),
+            ),
+            (
+                "i32.trunc_f64_s",
+                &[f64(-2147483649.0)],
+                Err(Trap::IntegerOverflow),
+            ),
+            ("i32.trunc_f32_u", &[f32(-1.0)], Err(Trap::IntegerOverflow)),
+            (
+                "i32.trunc_f64_u",
+                &[f64(4294967296.0)],
+                Err(Trap::IntegerOverflow),
+            ),
+            (
+                "i64.trunc_f32_s",
+                &[f32(f32::INFINITY)],
+                Err(Trap::IntegerOverflow),
+            ),
+            (
+                "i64.trunc_f64_s",
+                &[f64(9223372036854775808.0)],
+                Err(Trap::IntegerOverflow),
+            ),
+            (
+                "i64.trunc_f32_u",
+                &[f32(18446744073709551616.0)],
+                Err(Trap::IntegerOverflow),
+            ),
+            (
+                "i64.trunc_f64_u",
+                &[f64(f64::NEG_INFINITY)],
+                Err(Trap::IntegerOverflow),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn run_integer_to_float_conversions() {
+        run_conversions(&[
+            ("f32.convert_i32_s", &[Number::i32(-1)], Ok(Some(f32(-1.0)))),
+            (
+                "f32.convert_i32_u",
+                &[Number::i32(-1)],
+                Ok(Some(f32(4294967296.0))),
+            ),
+            // 2^24 + 1 rounds to the even neighbour 2^24
+            (
+                "f32.convert_i32_s",
+                &[Number::i32(16777217)],
+                Ok(Some(f32(16777216.0))),
+            ),
+            (
+                "f32.convert_i64_s",
+                &[Number::i64(i64::MIN)],
+                Ok(Some(f32(-9223372036854775808.0))),
+            ),
+            (
+                "f32.convert_i64_u",
+                &[Number::i64(-1)],
+                Ok(Some(f32(18446744073709551616.0))),
+            ),
+            (
+                "f64.convert_i32_s",
+                &[Number::i32(i32::MIN)],
+                Ok(Some(f64(-2147483648.0))),
+            ),
+            (
+                "f64.convert_i32_u",
+                &[Number::i32(-1)],
+                Ok(Some(f64(4294967295.0))),
+            ),
+            (
+                "f64.convert_i64_s",
+                &[Number::i64(9007199254740993)],
+                Ok(Some(f64(9007199254740992.0))),
+            ),
+            (
+                "f64.convert_i64_u",
+                &[Number::i64(-1)],
+                Ok(Some(f64(18446744073709551616.0))),
+            ),
+            ("f32.demote_f64", &[f64(0.1)], Ok(Some(f32(0.1)))),
+            (
+                "f32.demote_f64",
+                &[f64(1e300)],
+                Ok(Some(f32(f32::INFINITY))),
+            ),
+            ("f64.promote_f32", &[f32(0.1)], Ok(Some(f64(0.1f32 as f64)))),
+        ]);
+    }
+
+    #[test]
+    fn run_reinterpretations() {
+        run_conversions(&[
+            (
+                "i32.reinterpret_f32",
+                &[f32(-0.0)],
+                Ok(Some(Number::i32(i32::MIN))),
+            ),
+            (
+                "i64.reinterpret_f64",
+                &[f64(1.0)],
+                Ok(Some(Number::i64(0x3FF0_0000_0000_0000))),
+            ),
+            // NaN payloads are kept intact in both directions
+            (
+                "f32.reinterpret_i32",
+                &[Number::i32(0x7FA0_0001)],
+                Ok(Some(Number::f32(F32::from_bits(0x7FA0_0001)))),
+            ),
+            (
+                "f64.reinterpret_i64",
+                &[Number::i64(-1)],
+                Ok(Some(Number::f64(F64::from_bits(u64::MAX)))),
+            ),
+            (
+                "i32.reinterpret_f32",
+                &[Number::f32(F32::from_bits(0xFFA0_0001))],
+                Ok(Some(Number::i32(0xFFA0_0001u32 as i32))),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn run_saturating_truncation() {
+        run_cases(
+            "test/fixtures/trunc_sat.wasm",
+            &[
+                (
+                    "i32.trunc_sat_f32_s",
+                    &[f32(-1.9)],
+                    Ok(Some(Number::i32(-1))),
+                ),
+                (
+                    "i32.trunc_sat_f32_s",
+                    &[f32(f32::NAN)],
+                    Ok(Some(Number::i32(0))),
+                ),
+                (
+                    "i32.trunc_sat_f32_s",
+                    &[f32(3e9)],
+                    Ok(Some(Number::i32(i32::MAX))),
+                ),
+                (
+                    "i32.trunc_sat_f32_u",
+                    &[f32(-3e9)],
+                    Ok(Some(Number::i32(0))),
+                ),
+                (
+                    "i32.trunc_sat_f32_u",
+                    &[f32(f32::INFINITY)],
+                    Ok(Some(Number::i32(-1))),
+                ),
+                (
+                    "i32.trunc_sat_f64_s",
+                    &[f64(f64::NEG_INFINITY)],
+                    Ok(Some(Number::i32(i32::MIN))),
+                ),
+                (
+                    "i32.trunc_sat_f64_u",
+                    &[f64(4294967295.9)],
+                    Ok(Some(Number::i32(-1))),
+                ),
+                (
+                    "i64.trunc_sat_f32_s",
+                    &[f32(-1e30)],
+                    Ok(Some(Number::i64(i64::MIN))),
+                ),
+                (
+                    "i64.trunc_sat_f32_u",
+                    &[f32(-f32::NAN)],
+                    Ok(Some(Number::i64(0))),
+                ),
+                (
+                    "i64.trunc_sat_f64_s",
+                    &[f64(1e30)],
+                    Ok(Some(Number::i64(i64::MAX))),
+                ),
+                (
+                    "i64.trunc_sat_f64_s",
+                    &[f64(-0.5)],
+                    Ok(Some(Number::i64(0))),
+                ),
+                (
+                    "i64.trunc_sat_f64_u",
+                    &[f64(1e30)],
+                    Ok(Some(Number::i64(-1))),
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn run_nested_branches() {
+        run_cases(
+            "test/fixtures/branch.wasm",
+            &[
+                ("collatz", &[Number::i32(1)], Ok(Some(Number::i32(0)))),
+                ("collatz", &[Number::i32(6)], Ok(Some(Number::i32(8)))),
+                ("collatz", &[Number::i32(27)], Ok(Some(Number::i32(111)))),
+                ("count_pairs", &[Number::i32(0)], Ok(Some(Number::i32(0)))),
+                ("count_pairs", &[Number::i32(5)], Ok(Some(Number::i32(10)))),
+                (
+                    "find_factor",
+                    &[Number::i32(15)],
+                    Ok(Some(Number::i32(305))),
+                ),
+                (
+                    "find_factor",
+                    &[Number::i32(49)],
+                    Ok(Some(Number::i32(707))),
+                ),
+                ("find_factor", &[Number::i32(13)], Ok(Some(Number::i32(-1)))),
+            ],
+        );
+    }
+
+    #[test]
+    fn run_branches_with_values() {
+        run_cases(
+            "test/fixtures/branch.wasm",
+            &[
+                ("br_value", &[], Ok(Some(Number::i32(42)))),
+                ("br_nested_value", &[], Ok(Some(Number::i32(9)))),
+                ("br_if_value", &[Number::i32(1)], Ok(Some(Number::i32(10)))),
+                ("br_if_value", &[Number::i32(0)], Ok(Some(Number::i32(11)))),
+                ("br_from_if", &[Number::i32(1)], Ok(Some(Number::i32(5)))),
+                ("br_from_if", &[Number::i32(0)], Ok(Some(Number::i32(6)))),
+                ("br_function", &[Number::i32(3)], Ok(Some(Number::i32(3)))),
+                ("call_in_block", &[Number::i32(3)], Ok(Some(Number::i32(4)))),
+                ("loop_result", &[], Ok(Some(Number::i32(4)))),
+            ],
+        );
+    }
+
+    #[test]
+    fn run_br_table_and_return() {
+        run_cases(
+            "test/fixtures/br_table.wasm",
+            &[
+                ("switch", &[Number::i32(0)], Ok(Some(Number::i32(100)))),
+                ("switch", &[Number::i32(1)], Ok(Some(Number::i32(101)))),
+                ("switch", &[Number::i32(2)], Ok(Some(Number::i32(102)))),
+                ("switch", &[Number::i32(3)], Ok(Some(Number::i32(99)))),
+                // the operand is unsigned, so negative indices take the default
+                ("switch", &[Number::i32(-1)], Ok(Some(Number::i32(99)))),
+                (
+                    "br_table_value",
+                    &[Number::i32(5)],
+                    Ok(Some(Number::i32(7))),
+                ),
+                (
+                    "next_multiple",
+                    &[Number::i32(15)],
+                    Ok(Some(Number::i32(21))),
+                ),
+                (
+                    "next_multiple",
+                    &[Number::i32(14)],
+                    Ok(Some(Number::i32(14))),
+                ),
+                (
+                    "call_returning",
+                    &[Number::i32(8)],
+                    Ok(Some(Number::i32(1014))),
+                ),
+                ("early_return", &[Number::i32(1)], Ok(Some(Number::i32(1)))),
+                ("early_return", &[Number::i32(0)], Ok(Some(Number::i32(2)))),
+                ("return_void", &[], Ok(None)),
+            ],
+        );
+    }
+
+    #[test]
+    fn run_parametric_instructions() {
+        run_cases(
+            "test/fixtures/parametric.wasm",
+            &[
+                ("select_i32", &[Number::i32(1)], Ok(Some(Number::i32(10)))),
+                ("select_i32", &[Number::i32(0)], Ok(Some(Number::i32(20)))),
+                ("select_i64", &[Number::i32(-1)], Ok(Some(Number::i64(-1)))),
+                (
+                    "select_i64",
+                    &[Number::i32(0)],
+                    Ok(Some(Number::i64(1 << 32))),
+                ),
+                ("select_typed_f64", &[Number::i32(1)], Ok(Some(f64(1.5)))),
+                ("select_typed_f64", &[Number::i32(0)], Ok(Some(f64(-2.5)))),
+                ("drop", &[], Ok(Some(Number::i32(1)))),
+                ("tee", &[Number::i32(5)], Ok(Some(Number::i32(20)))),
+                ("nop", &[], Ok(Some(Number::i32(3)))),
+                ("unreachable", &[], Err(Trap::Unreachable)),
+                (
+                    "unreachable_in_loop",
+                    &[Number::i32(3)],
+                    Err(Trap::Unreachable),
+                ),
+                // the runtime is usable again after the trap
+                ("select_i32", &[Number::i32(0)], Ok(Some(Number::i32(20)))),
+            ],
+        );
+    }
+
+    #[test]
+    fn run_memory_copy_and_fill() {
+        let i32 = Number::i32;
+        run_cases(
+            "test/fixtures/bulk_memory.wasm",
+            &[
+                // the active segment wrote 1 2 3 4 5 at address 0
+                ("load8_u", &[i32(4)], Ok(Some(i32(5)))),
+                // overlapping copy to a higher address: 1 1 2 3 4
+                ("copy", &[i32(1), i32(0), i32(4)], Ok(None)),
+                ("load8_u", &[i32(1)], Ok(Some(i32(1)))),
+                ("load8_u", &[i32(4)], Ok(Some(i32(4)))),
+                // and back to a lower one: 1 2 3 4 4
+                ("copy", &[i32(0), i32(1), i32(4)], Ok(None)),
+                ("load8_u", &[i32(0)], Ok(Some(i32(1)))),
+                ("load8_u", &[i32(3)], Ok(Some(i32(4)))),
+                ("fill", &[i32(10), i32(0x1AA), i32(3)], Ok(None)),
+                ("load8_u", &[i32(12)], Ok(Some(i32(0xAA)))),
+                ("load8_u", &[i32(13)], Ok(Some(i32(0)))),
+                (
+                    "fill",
+                    &[i32(65535), i32(1), i32(2)],
+                    Err(Trap::MemoryOutOfBounds),
+                ),
+                // nothing is written when the range is out of bounds
+                ("load8_u", &[i32(65535)], Ok(Some(i32(0)))),
+                ("fill", &[i32(65536), i32(1), i32(0)], Ok(None)),
+                (
+                    "fill",
+                    &[i32(65537), i32(1), i32(0)],
+                    Err(Trap::MemoryOutOfBounds),
+                ),
+                (
+                    "copy",
+                    &[i32(0), i32(65535), i32(2)],
+                    Err(Trap::MemoryOutOfBounds),
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn run_memory_init_and_data_drop() {
+        let i32 = Number::i32;
+        run_cases(
+            "test/fixtures/bulk_memory.wasm",
+            &[
+                ("init", &[i32(100), i32(1), i32(3)], Ok(None)),
+                ("load8_u", &[i32(100)], Ok(Some(i32(b'e' as i32)))),
+                ("load8_u", &[i32(102)], Ok(Some(i32(b'l' as i32)))),
+                (
+                    "init",
+                    &[i32(0), i32(4), i32(2)],
+                    Err(Trap::MemoryOutOfBounds),
+                ),
+                (
+                    "init",
+                    &[i32(65535), i32(0), i32(2)],
+                    Err(Trap::MemoryOutOfBounds),
+                ),
+                // active segments are dropped once instantiated
+                (
+                    "init_active",
+                    &[i32(0), i32(0), i32(1)],
+                    Err(Trap::MemoryOutOfBounds),
+                ),
+                ("init_active", &[i32(0), i32(0), i32(0)], Ok(None)),
+                ("drop_data", &[], Ok(None)),
+                ("init", &[i32(0), i32(0), i32(0)], Ok(None)),
+                (
+                    "init",
+                    &[i32(0), i32(0), i32(1)],
+                    Err(Trap::MemoryOutOfBounds),
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn run_table_init_copy_and_elem_drop() {
+        let i32 = Number::i32;
+        run_cases(
+            "test/fixtures/bulk_memory.wasm",
+            &[
+                ("call", &[i32(0)], Err(Trap::UninitializedElement)),
+                ("table_init", &[i32(0), i32(0), i32(3)], Ok(None)),
+                ("call", &[i32(2)], Ok(Some(i32(2)))),
+                ("call", &[i32(3)], Err(Trap::UninitializedElement)),
+                // overlapping copy leaves f0 f0 f1 f2
+                ("table_copy", &[i32(1), i32(0), i32(3)], Ok(None)),
+                ("call", &[i32(1)], Ok(Some(i32(0)))),
+                ("call", &[i32(3)], Ok(Some(i32(2)))),
+                (
+                    "table_copy",
+                    &[i32(2), i32(0), i32(3)],
+                    Err(Trap::TableOutOfBounds),
+                ),
+                (
+                    "table_init",
+                    &[i32(2), i32(1), i32(3)],
+                    Err(Trap::TableOutOfBounds),
+                ),
+                ("elem_drop", &[], Ok(None)),
+                ("table_init", &[i32(0), i32(0), i32(0)], Ok(None)),
+                (
+                    "table_init",
+                    &[i32(0), i32(0), i32(1)],
+                    Err(Trap::TableOutOfBounds),
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn run_multi_value() {
+        let i32 = |value| Value::num(Number::i32(value));
+        let i64 = |value| Value::num(Number::i64(value));
+        let cases = [
+            ("swap", vec![i32(1), i32(2)], vec![i32(2), i32(1)]),
+            ("swap_twice", vec![i32(1), i32(2)], vec![i32(1), i32(2)]),
+            ("divmod", vec![i32(17), i32(5)], vec![i32(3), i32(2)]),
+            ("swap_sub", vec![i32(10), i32(3)], vec![i32(-7)]),
+            ("block_params", vec![i32(10), i32(3)], vec![i32(7)]),
+            ("block_results", vec![], vec![i32(1), i32(2)]),
+            ("br_values", vec![i32(1)], vec![i32(7), i32(8)]),
+            ("br_values", vec![i32(0)], vec![i32(9), i32(10)]),
+            ("loop_sum", vec![i32(1)], vec![i32(1)]),
+            ("loop_sum", vec![i32(4)], vec![i32(10)]),
+            ("if_params", vec![i32(1), i32(10), i32(3)], vec![i32(13)]),
+            ("if_params", vec![i32(0), i32(10), i32(3)], vec![i32(7)]),
+            ("if_results", vec![i32(1)], vec![i32(1), i32(2)]),
+            ("if_results", vec![i32(0)], vec![i32(3), i32(4)]),
+            ("return_values", vec![i32(1)], vec![i32(1), i64(2)]),
+            ("return_values", vec![i32(0)], vec![i32(3), i64(4)]),
+        ];
+
+        let file_path = "test/fixtures/multi_value.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        let mut runtime = Runtime::new(instance);
+
+        for (name, args, expected) in cases {
+            let result = runtime.execute(&name.to_string(), Some(args));
+            assert_eq!(result, Ok(expected), "{}", name);
+        }
+    }
+
+    fn reference_types_runtime() -> Runtime {
+        let file_path = "test/fixtures/reference_types.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+
+        let externref = ValueType::Reference(ReferenceTypeNode::ExternRef);
+        let i32_type = ValueType::Number(NumberType::I32);
+        let mut imports = instance::Imports::new();
+        imports.define(
+            "env",
+            "describe",
+            Extern::Function(HostFunction::new(
+                FunctionTypeNode::new(
+                    ResultTypeNode {
+                        val_types: vec![externref],
+                    },
+                    ResultTypeNode {
+                        val_types: vec![i32_type],
+                    },
+                ),
+                |args| {
+                    let length = match &args[0] {
+                        Value::Ref(Reference::Extern(object)) => object
+                            .downcast_ref::<String>()
+                            .map_or(-1, |text| text.len() as i32),
+                        Value::Ref(Reference::Null(_)) => 0,
+                        _ => panic!("env.describe expects an externref"),
+                    };
+                    vec![Value::num(Number::i32(length))]
+                },
+            )),
+        );
+        imports.define(
+            "env",
+            "make",
+            Extern::Function(HostFunction::new(
+                FunctionTypeNode::new(
+                    ResultTypeNode {
+                        val_types: vec![i32_type],
+                    },
+                    ResultTypeNode {
+                        val_types: vec![externref],
+                    },
+                ),
+                |args| match &args[0] {
+                    Value::num(Number::i32(n)) => {
+                        vec![Value::Ref(Reference::Extern(ExternRef::new(
+                            "x".repeat(*n as usize),
+                        )))]
+                    }
+                    _ => panic!("env.make expects an i32"),
+                },
+            )),
+        );
+
+        let instance =
+            instance::Instance::new(&mut module, &imports).expect("Failed to instantiate");
+        Runtime::new(instance)
+    }
+
+    fn extern_value(object: &ExternRef) -> Value {
+        Value::Ref(Reference::Extern(object.clone()))
+    }
+
+    #[test]
+    fn run_ref_func_and_ref_is_null() {
+        let mut runtime = reference_types_runtime();
+        let i32 = Number::i32;
+        let cases = [
+            ("is_null_func", vec![], Ok(Some(Number::i32(1)))),
+            ("is_null_answer", vec![], Ok(Some(Number::i32(0)))),
+            ("call_slot", vec![i32(1)], Err(Trap::UninitializedElement)),
+            ("store_answer", vec![i32(1)], Ok(None)),
+            ("call_slot", vec![i32(1)], Ok(Some(Number::i32(42)))),
+            ("store_answer", vec![i32(3)], Err(Trap::TableOutOfBounds)),
+        ];
+        for (name, args, expected) in cases {
+            let args = args.into_iter().map(Value::num).collect();
+            let result = runtime.execute(&name.to_string(), Some(args));
+            assert_eq!(result, values(&expected), "{}", name);
+        }
+    }
+
+    #[test]
+    fn run_externref_table_instructions() {
+        let mut runtime = reference_types_runtime();
+        let i32 = Number::i32;
+        let null = Value::Ref(Reference::Null(ReferenceTypeNode::ExternRef));
+        let first = ExternRef::new(1u8);
+        let second = ExternRef::new("second".to_string());
+
+        let result = runtime.execute(&"get_object".to_string(), Some(vec![Value::num(i32(0))]));
+        assert_eq!(result, Ok(vec![null.clone()]));
+
+        let args = vec![Value::num(i32(1)), extern_value(&first)];
+        let result = runtime.execute(&"set_object".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![]));
+        let result = runtime.execute(&"get_object".to_string(), Some(vec![Value::num(i32(1))]));
+        assert_eq!(result, Ok(vec![extern_value(&first)]));
+
+        let args = vec![extern_value(&second), Value::num(i32(3))];
+        let result = runtime.execute(&"grow_objects".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(i32(2))]));
+        let result = runtime.execute(&"objects_size".to_string(), None);
+        assert_eq!(result, Ok(vec![Value::num(i32(5))]));
+        let result = runtime.execute(&"get_object".to_string(), Some(vec![Value::num(i32(4))]));
+        assert_eq!(result, Ok(vec![extern_value(&second)]));
+
+        // the maximum of 10 elements is not exceeded
+        let args = vec![null.clone(), Value::num(i32(6))];
+        let result = runtime.execute(&"grow_objects".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(i32(-1))]));
+
+        let args = vec![Value::num(i32(0)), null.clone(), Value::num(i32(5))];
+        let result = runtime.execute(&"fill_objects".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![]));
+        let result = runtime.execute(&"get_object".to_string(), Some(vec![Value::num(i32(1))]));
+        assert_eq!(result, Ok(vec![null.clone()]));
+
+        let args = vec![Value::num(i32(4)), null, Value::num(i32(2))];
+        let result = runtime.execute(&"fill_objects".to_string(), Some(args));
+        assert_eq!(result, Err(Trap::TableOutOfBounds));
+        let result = runtime.execute(&"get_object".to_string(), Some(vec![Value::num(i32(5))]));
+        assert_eq!(result, Err(Trap::TableOutOfBounds));
+    }
+
+    #[test]
+    fn grow_table_past_implementation_limit() {
+        let null = Reference::Null(ReferenceTypeNode::FunctionRef);
+        let mut table = TableInstance::new(TableType {
+            element_type: ReferenceTypeNode::FunctionRef,
+            limits: Limits { min: 1, max: None },
+        });
+
+        // without a maximum the implementation limit still applies
+        assert_eq!(table.grow(u32::MAX, null.clone()), None);
+        assert_eq!(table.grow(MAX_TABLE_SIZE, null.clone()), None);
+        assert_eq!(table.size(), 1);
+        assert_eq!(table.grow(1, null), Some(1));
+    }
+
+    #[test]
+    fn run_externref_host_values() {
+        let mut runtime = reference_types_runtime();
+        let i32 = Number::i32;
+        let text = ExternRef::new("hello".to_string());
+
+        let result = runtime.execute(&"describe".to_string(), Some(vec![extern_value(&text)]));
+        assert_eq!(result, Ok(vec![Value::num(i32(5))]));
+        let null = Value::Ref(Reference::Null(ReferenceTypeNode::ExternRef));
+        let result = runtime.execute(&"describe".to_string(), Some(vec![null.clone()]));
+        assert_eq!(result, Ok(vec![Value::num(i32(0))]));
+
+        let result = runtime.execute(&"make".to_string(), Some(vec![Value::num(i32(3))]));
+        match result.as_deref() {
+            Ok([Value::Ref(Reference::Extern(object))]) => {
+                assert_eq!(object.downcast_ref::<String>(), Some(&"xxx".to_string()));
+                assert_eq!(object.downcast_ref::<u8>(), None);
+            }
+            other => panic!("expected an externref, got {:?}", other),
+        }
+
+        // the global keeps the very same object, not a copy
+        let result = runtime.execute(&"remember".to_string(), Some(vec![extern_value(&text)]));
+        assert_eq!(result, Ok(vec![null]));
+        let other = ExternRef::new("hello".to_string());
+        let result = runtime.execute(&"remember".to_string(), Some(vec![extern_value(&other)]));
+        assert_eq!(result, Ok(vec![extern_value(&text)]));
+        assert_ne!(result, Ok(vec![extern_value(&other)]));
+    }
+
+    #[test]
+    fn run_sign_extension() {
+        run_cases(
+            "test/fixtures/sign_extension.wasm",
+            &[
+                (
+                    "i32.extend8_s",
+                    &[Number::i32(0x7F)],
+                    Ok(Some(Number::i32(127))),
+                ),
+                (
+                    "i32.extend8_s",
+                    &[Number::i32(0x80)],
+                    Ok(Some(Number::i32(-128))),
+                ),
+                // only the low bits take part
+                (
+                    "i32.extend8_s",
+                    &[Number::i32(0x1234_56FF)],
+                    Ok(Some(Number::i32(-1))),
+                ),
+                (
+                    "i32.extend16_s",
+                    &[Number::i32(0x8000)],
+                    Ok(Some(Number::i32(-32768))),
+                ),
+                (
+                    "i32.extend16_s",
+                    &[Number::i32(0xFFFF_7FFFu32 as i32)],
+                    Ok(Some(Number::i32(0x7FFF))),
+                ),
+                (
+                    "i64.extend8_s",
+                    &[Number::i64(0xFE)],
+                    Ok(Some(Number::i64(-2))),
+                ),
+                (
+                    "i64.extend16_s",
+                    &[Number::i64(0x1_0000_8000)],
+                    Ok(Some(Number::i64(-32768))),
+                ),
+                (
+                    "i64.extend32_s",
+                    &[Number::i64(0x8000_0000)],
+                    Ok(Some(Number::i64(i32::MIN as i64))),
+                ),
+                (
+                    "i64.extend32_s",
+                    &[Number::i64(-1 << 32)],
+                    Ok(Some(Number::i64(0))),
+                ),
+            ],
+        );
+    }
+
+    fn simd_runtime() -> Runtime {
+        let file_path = "test/fixtures/simd.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        Runtime::new(instance)
+    }
+
+    fn vector<T: Lane>(lanes: impl IntoIterator<Item = T>) -> Value {
+        Value::V128(V128::from_lanes(lanes))
+    }
+
+    fn f32x4(lanes: [f32; 4]) -> Value {
+        vector(lanes.map(F32::from))
+    }
+
+    fn f64x2(lanes: [f64; 2]) -> Value {
+        vector(lanes.map(F64::from))
+    }
+
+    // expectations are taken from the spec's simd_*.wast test vectors
+    #[test]
+    fn run_simd_integer_lanes() {
+        let i32 = |value| Value::num(Number::i32(value));
+        let i64 = |value| Value::num(Number::i64(value));
+        let bits = |bits| Value::V128(V128::from_bits(bits));
+        let cases = [
+            (
+                "i8x16.add_sat_s",
+                vec![vector([127i8; 16]), vector([1i8; 16])],
+                vector([127i8; 16]),
+            ),
+            (
+                "i8x16.add_sat_s",
+                vec![vector([-128i8; 16]), vector([-1i8; 16])],
+                vector([-128i8; 16]),
+            ),
+            (
+                "i8x16.add_sat_u",
+                vec![vector([255u8; 16]), vector([1u8; 16])],
+                vector([255u8; 16]),
+            ),
+            (
+                "i8x16.sub_sat_u",
+                vec![vector([0u8; 16]), vector([1u8; 16])],
+                vector([0u8; 16]),
+            ),
+            (
+                "i8x16.avgr_u",
+                vec![vector([255u8; 16]), vector([0u8; 16])],
+                vector([128u8; 16]),
+            ),
+            (
+                "i8x16.avgr_u",
+                vec![vector([1u8; 16]), vector([2u8; 16])],
+                vector([2u8; 16]),
+            ),
+            (
+                "i8x16.min_u",
+                vec![vector([-1i8; 16]), vector([1i8; 16])],
+                vector([1i8; 16]),
+            ),
+            (
+                "i8x16.narrow_i16x8_s",
+                vec![vector([128i16; 8]), vector([-129i16; 8])],
+                vector([[127i8; 8], [-128; 8]].concat()),
+            ),
+            (
+                "i8x16.narrow_i16x8_u",
+                vec![vector([-1i16; 8]), vector([256i16; 8])],
+                vector([[0u8; 8], [255; 8]].concat()),
+            ),
+            (
+                "i8x16.swizzle",
+                vec![vector(100u8..116), vector((0u8..16).rev())],
+                vector((100u8..116).rev()),
+            ),
+            // indexes past the 16th byte select 0
+            (
+                "i8x16.swizzle",
+                vec![vector(100u8..116), vector((0u8..16).map(|i| i * 2))],
+                vector((0u8..16).map(|i| if i < 8 { 100 + i * 2 } else { 0 })),
+            ),
+            (
+                "i8x16.shuffle",
+                vec![vector(0u8..16), vector(16u8..32)],
+                vector((0u8..8).flat_map(|i| [i, i + 16])),
+            ),
+            (
+                "i16x8.q15mulr_sat_s",
+                vec![vector([-32768i16; 8]), vector([-32768i16; 8])],
+                vector([32767i16; 8]),
+            ),
+            (
+                "i16x8.q15mulr_sat_s",
+                vec![vector([16384i16; 8]), vector([16384i16; 8])],
+                vector([8192i16; 8]),
+            ),
+            (
+                "i16x8.mul",
+                vec![vector([-32768i16; 8]), vector([-1i16; 8])],
+                vector([-32768i16; 8]),
+            ),
+            (
+                "i16x8.extmul_low_i8x16_s",
+                vec![vector([-128i8; 16]), vector([-128i8; 16])],
+                vector([16384i16; 8]),
+            ),
+            (
+                "i16x8.extmul_high_i8x16_u",
+                vec![vector([[0u8; 8], [255; 8]].concat()), vector([255u8; 16])],
+                vector([65025u16; 8]),
+            ),
+            (
+                "i16x8.lt_s",
+                vec![vector([-1i16; 8]), vector([0i16; 8])],
+                vector([-1i16; 8]),
+            ),
+            (
+                "i16x8.lt_u",
+                vec![vector([-1i16; 8]), vector([0i16; 8])],
+                vector([0i16; 8]),
+            ),
+            // -32768 * -32768 twice overflows
+            (
+                "i32x4.dot_i16x8_s",
+                vec![vector([-32768i16; 8]), vector([-32768i16; 8])],
+                vector([i32::MIN; 4]),
+            ),
+            (
+                "i32x4.dot_i16x8_s",
+                vec![vector(1i16..9), vector([1i16; 8])],
+                vector([3i32, 7, 11, 15]),
+            ),
+            (
+                "i32x4.min_s",
+                vec![vector([-1, 1, i32::MIN, i32::MAX]), vector([0i32; 4])],
+                vector([-1, 0, i32::MIN, 0]),
+            ),
+            (
+                "i32x4.max_u",
+                vec![vector([-1, 1, i32::MIN, i32::MAX]), vector([-2i32; 4])],
+                vector([-1i32, -2, -2, -2]),
+            ),
+            (
+                "i32x4.gt_u",
+                vec![vector([-1, 1, i32::MIN, i32::MAX]), vector([1i32; 4])],
+                vector([-1i32, 0, -1, -1]),
+            ),
+            (
+                "i64x2.mul",
+                vec![vector([i64::MAX, -1]), vector([2i64, -1])],
+                vector([-2i64, 1]),
+            ),
+            (
+                "i64x2.lt_s",
+                vec![vector([-1i64, 1]), vector([0i64; 2])],
+                vector([-1i64, 0]),
+            ),
+            (
+                "i64x2.extmul_high_i32x4_u",
+                vec![vector([0i32, 0, -1, 2]), vector([0i32, 0, -1, 3])],
+                vector([0xFFFF_FFFE_0000_0001u64, 6]),
+            ),
+            (
+                "v128.and",
+                vec![
+                    bits(0x0123_4567_89AB_CDEF_0123_4567_89AB_CDEF),
+                    bits(0xFFFF_FFFF_0000_0000_FFFF_0000_FFFF_0000),
+                ],
+                bits(0x0123_4567_0000_0000_0123_0000_89AB_0000),
+            ),
+            (
+                "v128.andnot",
+                vec![
+                    bits(0x0123_4567_89AB_CDEF_0123_4567_89AB_CDEF),
+                    bits(0xFFFF_FFFF_0000_0000_FFFF_0000_FFFF_0000),
+                ],
+                bits(0x0000_0000_89AB_CDEF_0000_4567_0000_CDEF),
+            ),
+            (
+                "v128.bitselect",
+                vec![
+                    bits(0x1111_1111_1111_1111_1111_1111_1111_1111),
+                    bits(0x2222_2222_2222_2222_2222_2222_2222_2222),
+                    bits(0xFFFF_FFFF_FFFF_FFFF_0000_0000_0000_0000),
+                ],
+                bits(0x1111_1111_1111_1111_2222_2222_2222_2222),
+            ),
+            ("v128.not", vec![bits(0)], bits(u128::MAX)),
+            (
+                "i8x16.abs",
+                vec![vector([-128i8; 16])],
+                vector([-128i8; 16]),
+            ),
+            ("i8x16.abs", vec![vector([-5i8; 16])], vector([5i8; 16])),
+            (
+                "i8x16.neg",
+                vec![vector([-128i8; 16])],
+                vector([-128i8; 16]),
+            ),
+            (
+                "i8x16.popcnt",
+                vec![vector([0xFFu8; 16])],
+                vector([8u8; 16]),
+            ),
+            (
+                "i8x16.popcnt",
+                vec![vector([0x55u8; 16])],
+                vector([4u8; 16]),
+            ),
+            (
+                "i16x8.extadd_pairwise_i8x16_u",
+                vec![vector([255u8; 16])],
+                vector([510i16; 8]),
+            ),
+            (
+                "i32x4.extadd_pairwise_i16x8_s",
+                vec![vector([-32768i16; 8])],
+                vector([-65536i32; 4]),
+            ),
+            (
+                "i16x8.extend_high_i8x16_s",
+                vec![vector([[0i8; 8], [-128; 8]].concat())],
+                vector([-128i16; 8]),
+            ),
+            (
+                "i32x4.extend_low_i16x8_u",
+                vec![vector([-1i16, 1, 2, 3, 9, 9, 9, 9])],
+                vector([65535i32, 1, 2, 3]),
+            ),
+            (
+                "i64x2.extend_low_i32x4_s",
+                vec![vector([-1, i32::MIN, 5, 5])],
+                vector([-1, i32::MIN as i64]),
+            ),
+            (
+                "i64x2.abs",
+                vec![vector([i64::MIN, -1])],
+                vector([i64::MIN, 1]),
+            ),
+            // shift counts are taken modulo the lane width
+            (
+                "i8x16.shl",
+                vec![vector([1i8; 16]), i32(9)],
+                vector([2i8; 16]),
+            ),
+            (
+                "i8x16.shl",
+                vec![vector([0x81u8; 16]), i32(1)],
+                vector([2u8; 16]),
+            ),
+            (
+                "i8x16.shr_u",
+                vec![vector([0x80u8; 16]), i32(7)],
+                vector([1u8; 16]),
+            ),
+            (
+                "i8x16.shr_u",
+                vec![vector([0xFFu8; 16]), i32(8)],
+                vector([0xFFu8; 16]),
+            ),
+            (
+                "i16x8.shr_s",
+                vec![vector([-32768i16; 8]), i32(15)],
+                vector([-1i16; 8]),
+            ),
+            (
+                "i16x8.shr_s",
+                vec![vector([-32768i16; 8]), i32(16)],
+                vector([-32768i16; 8]),
+            ),
+            (
+                "i64x2.shr_s",
+                vec![vector([i64::MIN, 4]), i32(65)],
+                vector([i64::MIN >> 1, 2]),
+            ),
+            ("i8x16.splat", vec![i32(0x1FF)], vector([-1i8; 16])),
+            ("i16x8.splat", vec![i32(0x12345)], vector([0x2345i16; 8])),
+            ("i64x2.splat", vec![i64(-2)], vector([-2i64; 2])),
+            (
+                "i8x16.replace_lane",
+                vec![vector([0i8; 16]), i32(0x1FF)],
+                bits(0xFF),
+            ),
+            (
+                "i32x4.replace_lane",
+                vec![vector([1i32, 2, 3, 4]), i32(-1)],
+                vector([1i32, 2, 3, -1]),
+            ),
+            (
+                "i64x2.replace_lane",
+                vec![vector([1i64, 2]), i64(-5)],
+                vector([-5i64, 2]),
+            ),
+            ("v128.const", vec![], vector([1i32, 2, 3, 4])),
+            ("ones", vec![], bits(u128::MAX)),
+        ];
+
+        let mut runtime = simd_runtime();
+        for (name, args, expected) in cases {
+            let result = runtime.execute(&name.to_string(), Some(args));
+            assert_eq!(result, Ok(vec![expected]), "{}", name);
+        }
+
+        let cases = [
+            ("v128.any_true", vec![bits(0)], i32(0)),
+            ("v128.any_true", vec![bits(1 << 127)], i32(1)),
+            ("i8x16.all_true", vec![vector([1i8; 16])], i32(1)),
+            ("i8x16.all_true", vec![vector(0u8..16)], i32(0)),
+            ("i32x4.all_true", vec![vector([1i32, 1, 1, 0x100])], i32(1)),
+            ("i32x4.all_true", vec![bits(0xFF)], i32(0)),
+            (
+                "i8x16.bitmask",
+                vec![vector((0..16).map(|i| if i % 2 == 0 { -1i8 } else { 0 }))],
+                i32(0x5555),
+            ),
+            (
+                "i16x8.bitmask",
+                vec![vector([-1i16, 0, 0, 0, 0, 0, 0, -32768])],
+                i32(0x81),
+            ),
+            ("i64x2.bitmask", vec![vector([0i64, -1])], i32(2)),
+            (
+                "i8x16.extract_lane_s",
+                vec![vector((0u8..16).map(|i| i * 17))],
+                i32(-1),
+            ),
+            (
+                "i8x16.extract_lane_u",
+                vec![vector((0u8..16).map(|i| i * 17))],
+                i32(255),
+            ),
+            (
+                "i16x8.extract_lane_s",
+                vec![vector([-32768i16; 8])],
+                i32(-32768),
+            ),
+            ("i32x4.extract_lane", vec![vector([1i32, 2, 3, 4])], i32(4)),
+            (
+                "i64x2.extract_lane",
+                vec![vector([1, i64::MIN])],
+                i64(i64::MIN),
+            ),
+        ];
+
+        for (name, args, expected) in cases {
+            let result = runtime.execute(&name.to_string(), Some(args));
+            assert_eq!(result, Ok(vec![expected]), "{}", name);
+        }
+    }
+
+    #[test]
+    fn run_simd_float_lanes() {
+        let nan = f32::NAN;
+        let inf = f32::INFINITY;
+        let cases = [
+            (
+                "f32x4.add",
+                vec![
+                    f32x4([1.5, 3e38, -0.0, 0.5]),
+                    f32x4([2.25, 3e38, -0.0, 0.25]),
+                ],
+                f32x4([3.75, inf, -0.0, 0.75]),
+            ),
+            (
+                "f32x4.div",
+                vec![f32x4([1.0, -1.0, 1.0, 1.0]), f32x4([0.0, 0.0, -0.0, 4.0])],
+                f32x4([inf, -inf, -inf, 0.25]),
+            ),
+            // unlike the pseudo-min/max, NaN wins and -0 is less than +0
+            (
+                "f32x4.min",
+                vec![f32x4([-0.0, 0.0, nan, 1.0]), f32x4([0.0, -0.0, 1.0, 2.0])],
+                f32x4([-0.0, -0.0, nan, 1.0]),
+            ),
+            (
+                "f32x4.max",
+                vec![f32x4([-0.0, 0.0, nan, 1.0]), f32x4([0.0, -0.0, 1.0, 2.0])],
+                f32x4([0.0, 0.0, nan, 2.0]),
+            ),
+            (
+                "f32x4.pmin",
+                vec![f32x4([-0.0, nan, 1.0, 2.0]), f32x4([0.0, 1.0, nan, 1.0])],
+                f32x4([-0.0, nan, 1.0, 1.0]),
+            ),
+            (
+                "f32x4.pmax",
+                vec![f32x4([-0.0, nan, 1.0, 2.0]), f32x4([0.0, 1.0, nan, 1.0])],
+                f32x4([-0.0, nan, 1.0, 2.0]),
+            ),
+            (
+                "f32x4.eq",
+                vec![f32x4([nan, 0.0, 1.0, 2.0]), f32x4([nan, -0.0, 1.0, 3.0])],
+                vector([0i32, -1, -1, 0]),
+            ),
+            (
+                "f32x4.lt",
+                vec![f32x4([-0.0, 1.0, nan, -inf]), f32x4([0.0, 2.0, 1.0, inf])],
+                vector([0i32, -1, 0, -1]),
+            ),
+            (
+                "f64x2.sub",
+                vec![f64x2([1.0, f64::INFINITY]), f64x2([0.5, 1.0])],
+                f64x2([0.5, f64::INFINITY]),
+            ),
+            (
+                "f64x2.max",
+                vec![f64x2([-0.0, f64::NAN]), f64x2([0.0, 1.0])],
+                f64x2([0.0, f64::NAN]),
+            ),
+            (
+                "f64x2.ne",
+                vec![f64x2([f64::NAN, 1.0]), f64x2([f64::NAN, 1.0])],
+                vector([-1i64, 0]),
+            ),
+            // ties round to even
+            (
+                "f32x4.nearest",
+                vec![f32x4([2.5, 3.5, -0.5, -1.5])],
+                f32x4([2.0, 4.0, -0.0, -2.0]),
+            ),
+            (
+                "f32x4.sqrt",
+                vec![f32x4([4.0, 2.25, -0.0, inf])],
+                f32x4([2.0, 1.5, -0.0, inf]),
+            ),
+            (
+                "f32x4.neg",
+                vec![f32x4([0.0, -1.0, inf, nan])],
+                f32x4([-0.0, 1.0, -inf, -nan]),
+            ),
+            ("f64x2.floor", vec![f64x2([-0.5, 1.5])], f64x2([-1.0, 1.0])),
+            (
+                "i32x4.trunc_sat_f32x4_s",
+                vec![f32x4([nan, 3e9, -3e9, -1.9])],
+                vector([0, i32::MAX, i32::MIN, -1]),
+            ),
+            (
+                "i32x4.trunc_sat_f32x4_u",
+                vec![f32x4([-1.0, 5e9, 1.9, nan])],
+                vector([0, u32::MAX, 1, 0]),
+            ),
+            (
+                "i32x4.trunc_sat_f64x2_s_zero",
+                vec![f64x2([1e10, -2.5])],
+                vector([i32::MAX, -2, 0, 0]),
+            ),
+            // 16777217 has no f32 and rounds to even
+            (
+                "f32x4.convert_i32x4_u",
+                vec![vector([u32::MAX, 0, 1, 16777217])],
+                f32x4([4294967296.0, 0.0, 1.0, 16777216.0]),
+            ),
+            (
+                "f64x2.convert_low_i32x4_s",
+                vec![vector([-1, i32::MIN, 7, 7])],
+                f64x2([-1.0, -2147483648.0]),
+            ),
+            (
+                "f32x4.demote_f64x2_zero",
+                vec![f64x2([1.5, 1e300])],
+                f32x4([1.5, inf, 0.0, 0.0]),
+            ),
+            (
+                "f64x2.promote_low_f32x4",
+                vec![f32x4([1.5, -0.0, 9.0, 9.0])],
+                f64x2([1.5, -0.0]),
+            ),
+            (
+                "f32x4.replace_lane",
+                vec![f32x4([0.0; 4]), Value::num(Number::f32(F32::from(2.5)))],
+                f32x4([0.0, 2.5, 0.0, 0.0]),
+            ),
+            (
+                "f32x4.splat",
+                vec![Value::num(Number::f32(F32::from(nan)))],
+                f32x4([nan; 4]),
+            ),
+            (
+                "f64x2.splat",
+                vec![Value::num(Number::f64(F64::from(-0.0)))],
+                f64x2([-0.0; 2]),
+            ),
+            (
+                "f32x4.extract_lane",
+                vec![f32x4([0.0, 0.0, -1.5, 0.0])],
+                Value::num(Number::f32(F32::from(-1.5))),
+            ),
+            (
+                "f64x2.extract_lane",
+                vec![f64x2([0.0, 2.5])],
+                Value::num(Number::f64(F64::from(2.5))),
+            ),
+        ];
+
+        let mut runtime = simd_runtime();
+        for (name, args, expected) in cases {
+            let result = runtime.execute(&name.to_string(), Some(args));
+            assert_eq!(result, Ok(vec![expected]), "{}", name);
+        }
+    }
+
+    #[test]
+    fn run_simd_memory() {
+        let i32 = |value| Value::num(Number::i32(value));
+        // the fixture stores the bytes 0x00..=0x0F at 0 followed by 0x80..=0x8F
+        let cases = [
+            ("v128.load", vec![i32(0)], vector(0u8..16)),
+            (
+                "v128.load",
+                vec![i32(8)],
+                vector((8u8..16).chain(0x80..0x88)),
+            ),
+            (
+                "v128.load8x8_s",
+                vec![i32(12)],
+                vector([12i16, 13, 14, 15, -128, -127, -126, -125]),
+            ),
+            (
+                "v128.load16x4_u",
+                vec![i32(16)],
+                vector([0x8180u32, 0x8382, 0x8584, 0x8786]),
+            ),
+            (
+                "v128.load32x2_s",
+                vec![i32(16)],
+                vector([0x8382_8180u32 as i32 as i64, 0x8786_8584u32 as i32 as i64]),
+            ),
+            ("v128.load8_splat", vec![i32(17)], vector([0x81u8; 16])),
+            (
+                "v128.load64_splat",
+                vec![i32(0)],
+                vector([0x0706_0504_0302_0100u64; 2]),
+            ),
+            (
+                "v128.load32_zero",
+                vec![i32(4)],
+                vector([0x0706_0504u32, 0, 0, 0]),
+            ),
+            (
+                "v128.load8_lane",
+                vec![i32(16), vector([0u8; 16])],
+                Value::V128(V128::from_bits(0x80 << 120)),
+            ),
+            (
+                "v128.load32_lane",
+                vec![i32(0), vector([-1i32; 4])],
+                vector([-1, 0x0302_0100, -1, -1]),
+            ),
+        ];
+
+        let mut runtime = simd_runtime();
+        for (name, args, expected) in cases {
+            let result = runtime.execute(&name.to_string(), Some(args));
+            assert_eq!(result, Ok(vec![expected]), "{}", name);
+        }
+
+        let args = vec![i32(32), vector([1i32, 2, 3, 4])];
+        assert_eq!(
+            runtime.execute(&"v128.store".to_string(), Some(args)),
+            Ok(vec![])
+        );
+        let result = runtime.execute(&"v128.load".to_string(), Some(vec![i32(32)]));
+        assert_eq!(result, Ok(vec![vector([1i32, 2, 3, 4])]));
+
+        let args = vec![i32(48), vector([0i16, 0x1234, -1, -1, -1, -1, -1, -1])];
+        let result = runtime.execute(&"v128.store16_lane".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![]));
+        let result = runtime.execute(&"v128.load".to_string(), Some(vec![i32(48)]));
+        assert_eq!(result, Ok(vec![Value::V128(V128::from_bits(0x1234))]));
+
+        // all 16 bytes must be in bounds
+        let result = runtime.execute(&"v128.load".to_string(), Some(vec![i32(65528)]));
+        assert_eq!(result, Err(Trap::MemoryOutOfBounds));
+    }
+
+    #[test]
+    fn run_simd_brighten() {
+        let i32 = |value| Value::num(Number::i32(value));
+        let mut runtime = simd_runtime();
+
+        let result = runtime.execute(&"brighten".to_string(), Some(vec![i32(0), i32(250)]));
+        assert_eq!(result, Ok(vec![]));
+        let result = runtime.execute(&"brighten".to_string(), Some(vec![i32(16), i32(100)]));
+        assert_eq!(result, Ok(vec![]));
+
+        let result = runtime.execute(&"v128.load".to_string(), Some(vec![i32(0)]));
+        assert_eq!(
+            result,
+            Ok(vec![vector([
+                250u8, 251, 252, 253, 254, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255
+            ])])
+        );
+        let result = runtime.execute(&"v128.load".to_string(), Some(vec![i32(16)]));
+        assert_eq!(result, Ok(vec![vector(228u8..244)]));
+    }
+
+    fn shared_memory() -> MemoryInstance {
+        MemoryInstance::new(MemoryType {
+            limits: Limits {
+                min: 1,
+                max: Some(1),
+            },
+            shared: true,
+        })
+    }
+
+    /// Instantiates the threads fixture over `memory`, imported as env.memory.
+    fn threads_instance(memory: MemoryInstance) -> Result<Instance, InstantiationError> {
+        let file_path = "test/fixtures/threads.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+        let mut imports = instance::Imports::new();
+        imports.define("env", "memory", Extern::Memory(memory));
+        Instance::new(&mut module, &imports)
+    }
+
+    // expectations are taken from the threads proposal's atomic.wast, and
+    // each case runs on the memory left behind by the previous ones
+    #[test]
+    fn run_atomic_instructions() {
+        let i32 = |value: u32| Value::num(Number::i32(value as i32));
+        let i64 = |value: u64| Value::num(Number::i64(value as i64));
+        let cases = [
+            (
+                "i64.atomic.store",
+                vec![i32(0), i64(0x0706050403020100)],
+                Ok(vec![]),
+            ),
+            ("i32.atomic.load", vec![i32(0)], Ok(vec![i32(0x03020100)])),
+            ("i32.atomic.load", vec![i32(4)], Ok(vec![i32(0x07060504)])),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x0706050403020100)]),
+            ),
+            ("i32.atomic.load8_u", vec![i32(5)], Ok(vec![i32(0x05)])),
+            ("i32.atomic.load16_u", vec![i32(6)], Ok(vec![i32(0x0706)])),
+            ("i64.atomic.load8_u", vec![i32(3)], Ok(vec![i64(0x03)])),
+            ("i64.atomic.load16_u", vec![i32(2)], Ok(vec![i64(0x0302)])),
+            (
+                "i64.atomic.load32_u",
+                vec![i32(4)],
+                Ok(vec![i64(0x07060504)]),
+            ),
+            (
+                "i32.atomic.store",
+                vec![i32(0), i32(0xffeeddcc)],
+                Ok(vec![]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x07060504ffeeddcc)]),
+            ),
+            ("i32.atomic.store8", vec![i32(1), i32(0x1234)], Ok(vec![])),
+            ("i64.atomic.store16", vec![i32(6), i64(0x5678)], Ok(vec![])),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x56780504ffee34cc)]),
+            ),
+            (
+                "i64.atomic.store",
+                vec![i32(0), i64(0x1111111111111111)],
+                Ok(vec![]),
+            ),
+            (
+                "i32.atomic.rmw.add",
+                vec![i32(0), i32(0x12345678)],
+                Ok(vec![i32(0x11111111)]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x1111111123456789)]),
+            ),
+            (
+                "i64.atomic.rmw8.add_u",
+                vec![i32(0), i64(0x4242424242424242)],
+                Ok(vec![i64(0x89)]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x11111111234567cb)]),
+            ),
+            (
+                "i64.atomic.store",
+                vec![i32(0), i64(0x1111111111111111)],
+                Ok(vec![]),
+            ),
+            (
+                "i32.atomic.rmw8.sub_u",
+                vec![i32(0), i32(0xcdcdcdcd)],
+                Ok(vec![i32(0x11)]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x1111111111111144)]),
+            ),
+            (
+                "i64.atomic.rmw.sub",
+                vec![i32(0), i64(0x0101010102020202)],
+                Ok(vec![i64(0x1111111111111144)]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x101010100f0f0f42)]),
+            ),
+            (
+                "i64.atomic.store",
+                vec![i32(0), i64(0x1111111111111111)],
+                Ok(vec![]),
+            ),
+            (
+                "i32.atomic.rmw.and",
+                vec![i32(0), i32(0x12345678)],
+                Ok(vec![i32(0x11111111)]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x1111111110101010)]),
+            ),
+            (
+                "i64.atomic.rmw.or",
+                vec![i32(0), i64(0x0101010102020202)],
+                Ok(vec![i64(0x1111111110101010)]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x1111111112121212)]),
+            ),
+            (
+                "i32.atomic.rmw16.xor_u",
+                vec![i32(0), i32(0xcafecafe)],
+                Ok(vec![i32(0x1212)]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x111111111212d8ec)]),
+            ),
+            (
+                "i64.atomic.rmw32.xchg_u",
+                vec![i32(0), i64(0xcafecafecafecafe)],
+                Ok(vec![i64(0x1212d8ec)]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x11111111cafecafe)]),
+            ),
+            // a value other than the expected one is left as it is
+            (
+                "i64.atomic.store",
+                vec![i32(0), i64(0x1111111111111111)],
+                Ok(vec![]),
+            ),
+            (
+                "i32.atomic.rmw.cmpxchg",
+                vec![i32(0), i32(0), i32(0x12345678)],
+                Ok(vec![i32(0x11111111)]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x1111111111111111)]),
+            ),
+            (
+                "i32.atomic.rmw.cmpxchg",
+                vec![i32(0), i32(0x11111111), i32(0x12345678)],
+                Ok(vec![i32(0x11111111)]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x1111111112345678)]),
+            ),
+            // the expected value is wrapped to the width of the access
+            (
+                "i32.atomic.rmw8.cmpxchg_u",
+                vec![i32(4), i32(0x11111111), i32(0xcdcdcdcd)],
+                Ok(vec![i32(0x11)]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x111111cd12345678)]),
+            ),
+            (
+                "i64.atomic.rmw.cmpxchg",
+                vec![i32(0), i64(0x111111cd12345678), i64(0x0123456789abcdef)],
+                Ok(vec![i64(0x111111cd12345678)]),
+            ),
+            (
+                "i64.atomic.load",
+                vec![i32(0)],
+                Ok(vec![i64(0x0123456789abcdef)]),
+            ),
+            ("atomic.fence", vec![], Ok(vec![])),
+            // every atomic access must be aligned to its width
+            ("i32.atomic.load", vec![i32(1)], Err(Trap::UnalignedAtomic)),
+            (
+                "i32.atomic.load16_u",
+                vec![i32(1)],
+                Err(Trap::UnalignedAtomic),
+            ),
+            (
+                "i64.atomic.store",
+                vec![i32(4), i64(0)],
+                Err(Trap::UnalignedAtomic),
+            ),
+            (
+                "i64.atomic.rmw32.add_u",
+                vec![i32(2), i64(0)],
+                Err(Trap::UnalignedAtomic),
+            ),
+            (
+                "memory.atomic.notify",
+                vec![i32(2), i32(1)],
+                Err(Trap::UnalignedAtomic),
+            ),
+            ("i32.atomic.load8_u", vec![i32(65535)], Ok(vec![i32(0)])),
+            (
+                "i32.atomic.load",
+                vec![i32(65536)],
+                Err(Trap::MemoryOutOfBounds),
+            ),
+            (
+                "memory.atomic.wait64",
+                vec![i32(65536), i64(0), i64(0)],
+                Err(Trap::MemoryOutOfBounds),
+            ),
+            // the value differs, or nobody notifies before the timeout
+            (
+                "memory.atomic.wait32",
+                vec![i32(0), i32(0), i64(u64::MAX)],
+                Ok(vec![i32(1)]),
+            ),
+            (
+                "memory.atomic.wait64",
+                vec![i32(0), i64(0x0123456789abcdef), i64(0)],
+                Ok(vec![i32(2)]),
+            ),
+            (
+                "memory.atomic.wait32",
+                vec![i32(8), i32(0), i64(1_000_000)],
+                Ok(vec![i32(2)]),
+            ),
+            (
+                "memory.atomic.notify",
+                vec![i32(8), i32(1)],
+                Ok(vec![i32(0)]),
+            ),
+        ];
+
+        let instance = threads_instance(shared_memory()).expect("Failed to instantiate");
+        let mut runtime = Runtime::new(instance);
+        for (name, args, expected) in cases {
+            let result = runtime.execute(&name.to_string(), Some(args));
+            assert_eq!(result, expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn run_atomics_on_unshared_memory() {
+        let i32 = |value| Value::num(Number::i32(value));
+        let cases = [
+            ("i32.atomic.rmw.add", vec![i32(0), i32(5)], Ok(vec![i32(0)])),
+            ("i32.atomic.load", vec![i32(0)], Ok(vec![i32(5)])),
+            (
+                "memory.atomic.notify",
+                vec![i32(0), i32(1)],
+                Ok(vec![i32(0)]),
+            ),
+            (
+                "memory.atomic.wait32",
+                vec![i32(0), i32(5), Value::num(Number::i64(0))],
+                Err(Trap::ExpectedSharedMemory),
+            ),
+        ];
+
+        let file_path = "test/fixtures/atomics_unshared.wasm";
+        let bytes = std::fs::read(file_path).expect("file not found");
+        let parser = parser::Parser::new().unwrap();
+        let mut module = parser.parse(&bytes).expect("Failed to parse");
+        let instance = instance::Instance::new(&mut module, &instance::Imports::new())
+            .expect("Failed to instantiate");
+        let mut runtime = Runtime::new(instance);
+
+        for (name, args, expected) in cases {
+            let result = runtime.execute(&name.to_string(), Some(args));
+            assert_eq!(result, expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn instantiate_with_unshared_memory_for_shared_import() {
+        let memory = MemoryInstance::new(MemoryType {
+            limits: Limits {
+                min: 1,
+                max: Some(1),
+            },
+            shared: false,
+        });
+
+        assert_eq!(
+            threads_instance(memory).err(),
+            Some(InstantiationError::IncompatibleImportType {
+                module: "env".to_string(),
+                name: "memory".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn run_atomic_increments_from_threads() {
+        let memory = shared_memory();
+        let instance = threads_instance(memory.clone()).expect("Failed to instantiate");
+
+        // each thread runs its own clone of the instance over the same memory
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                let mut runtime = Runtime::new(instance.clone());
+                scope.spawn(move || {
+                    let args = vec![Value::num(Number::i32(16)), Value::num(Number::i32(1000))];
+                    let result = runtime.execute(&"increment".to_string(), Some(args));
+                    assert_eq!(result, Ok(vec![]));
+                });
+            }
+        });
+
+        // as does a separate instance importing the memory
+        let instance = threads_instance(memory).expect("Failed to instantiate");
+        let mut runtime = Runtime::new(instance);
+        let args = vec![Value::num(Number::i32(16))];
+        let result = runtime.execute(&"i32.atomic.load".to_string(), Some(args));
+        assert_eq!(result, Ok(vec![Value::num(Number::i32(4000))]));
+    }
+
+    #[test]
+    fn run_wait_and_notify_across_threads() {
+        let instance = threads_instance(shared_memory()).expect("Failed to instantiate");
+        let mut runtime = Runtime::new(instance.clone());
+
+        std::thread::scope(|scope| {
+            let waiters: Vec<_> = (0..2)
+                .map(|_| {
+                    let mut runtime = Runtime::new(instance.clone());
+                    scope.spawn(move || {
+                        // a negative timeout waits until notified
+                        let args = vec![
+                            Value::num(Number::i32(8)),
+                            Value::num(Number::i32(0)),
+                            Value::num(Number::i64(-1)),
+                        ];
+                        runtime.execute(&"memory.atomic.wait32".to_string(), Some(args))
+                    })
+                })
+                .collect();
+
+            // the waiters may not have parked yet, so notify until both woke
+            let mut woken = 0;
+            while woken < 2 {
+                let args = vec![Value::num(Number::i32(8)), Value::num(Number::i32(2))];
+                match runtime.execute(&"memory.atomic.notify".to_string(), Some(args)) {
+                    Ok(results) => match results[..] {
+                        [Value::num(Number::i32(count))] => woken += count,
+                        _ => panic!("memory.atomic.notify must return an i32"),
+                    },
+                    Err(trap) => panic!("{}", trap),
+                }
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            assert_eq!(woken, 2);
+
+            for waiter in waiters {
+                let result = waiter.join().unwrap();
+                assert_eq!(result, Ok(vec![Value::num(Number::i32(0))]));
+            }
+        });
+    }
 }
@@ -4,25 +4,51 @@ use std::error::Error;
 
 use crate::{
     buffer::Buffer,
-    node::{ExportNode, FunctionNode, FunctionTypeNode, Node},
-    types::ValueType,
+    names::NameMap,
+    node::{
+        DataNode, ElementNode, ExportNode, FunctionNode, FunctionTypeNode, GlobalNode, ImportNode,
+        Node,
+    },
+    types::{MemoryType, TableType, ValueType},
 };
 
-use self::section::{CodeSectionNode, ExportSectionNode, FunctionSectionNode, TypeSectionNode};
+use self::section::{
+    CodeSectionNode, CustomSectionNode, DataCountSectionNode, DataSectionNode, ElementSectionNode,
+    ExportSectionNode, FunctionSectionNode, GlobalSectionNode, ImportSectionNode,
+    MemorySectionNode, SectionId, StartSectionNode, TableSectionNode, TypeSectionNode,
+};
 
 #[derive(Debug)]
 pub struct ModuleNode {
     magic: [u8; 4],
     version: [u8; 4],
     type_section: Option<TypeSectionNode>,
+    import_section: Option<ImportSectionNode>,
     function_section: Option<FunctionSectionNode>,
+    table_section: Option<TableSectionNode>,
+    memory_section: Option<MemorySectionNode>,
+    global_section: Option<GlobalSectionNode>,
     export_section: Option<ExportSectionNode>,
+    start_section: Option<StartSectionNode>,
+    element_section: Option<ElementSectionNode>,
+    data_count_section: Option<DataCountSectionNode>,
     code_section: Option<CodeSectionNode>,
+    data_section: Option<DataSectionNode>,
+    /// Custom sections, each paired with the known section it followed
+    /// (`None` when it appeared before any known section).
+    custom_sections: Vec<(Option<SectionId>, CustomSectionNode)>,
+    names: Option<NameMap>,
     pub buffer: Buffer,
     types: Option<Vec<FunctionTypeNode>>,
+    imports: Option<Vec<ImportNode>>,
     funcs: Option<Vec<FunctionNode>>,
+    tables: Option<Vec<TableType>>,
+    mems: Option<Vec<MemoryType>>,
+    globals: Option<Vec<GlobalNode>>,
     start: Option<u32>,
     exports: Option<Vec<ExportNode>>,
+    elems: Option<Vec<ElementNode>>,
+    datas: Option<Vec<DataNode>>,
 }
 impl ModuleNode {
     pub fn new(magic: [u8; 4], version: [u8; 4]) -> Result<Self, Box<dyn Error>> {
@@ -32,14 +58,30 @@ impl ModuleNode {
             magic,
             version,
             type_section: None,
+            import_section: None,
             function_section: None,
+            table_section: None,
+            memory_section: None,
+            global_section: None,
             export_section: None,
+            start_section: None,
+            element_section: None,
+            data_count_section: None,
             code_section: None,
+            data_section: None,
+            custom_sections: vec![],
+            names: None,
             buffer: Buffer::new(),
             types: None,
+            imports: None,
             funcs: None,
+            tables: None,
+            mems: None,
+            globals: None,
             start: None,
             exports: None,
+            elems: None,
+            datas: None,
         })
     }
 
@@ -67,6 +109,14 @@ impl ModuleNode {
         self.type_section = Some(type_section);
     }
 
+    pub fn import_section(&self) -> Option<&ImportSectionNode> {
+        self.import_section.as_ref()
+    }
+
+    pub fn set_import_section(&mut self, import_section: ImportSectionNode) {
+        self.import_section = Some(import_section);
+    }
+
     pub fn function_section(&self) -> Option<&FunctionSectionNode> {
         self.function_section.as_ref()
     }
@@ -75,6 +125,30 @@ impl ModuleNode {
         self.function_section = Some(function_section);
     }
 
+    pub fn table_section(&self) -> Option<&TableSectionNode> {
+        self.table_section.as_ref()
+    }
+
+    pub fn set_table_section(&mut self, table_section: TableSectionNode) {
+        self.table_section = Some(table_section);
+    }
+
+    pub fn memory_section(&self) -> Option<&MemorySectionNode> {
+        self.memory_section.as_ref()
+    }
+
+    pub fn set_memory_section(&mut self, memory_section: MemorySectionNode) {
+        self.memory_section = Some(memory_section);
+    }
+
+    pub fn global_section(&self) -> Option<&GlobalSectionNode> {
+        self.global_section.as_ref()
+    }
+
+    pub fn set_global_section(&mut self, global_section: GlobalSectionNode) {
+        self.global_section = Some(global_section);
+    }
+
     pub fn export_section(&self) -> Option<&ExportSectionNode> {
         self.export_section.as_ref()
     }
@@ -83,6 +157,30 @@ impl ModuleNode {
         self.export_section = Some(export_section);
     }
 
+    pub fn start_section(&self) -> Option<&StartSectionNode> {
+        self.start_section.as_ref()
+    }
+
+    pub fn set_start_section(&mut self, start_section: StartSectionNode) {
+        self.start_section = Some(start_section);
+    }
+
+    pub fn element_section(&self) -> Option<&ElementSectionNode> {
+        self.element_section.as_ref()
+    }
+
+    pub fn set_element_section(&mut self, element_section: ElementSectionNode) {
+        self.element_section = Some(element_section);
+    }
+
+    pub fn data_count_section(&self) -> Option<&DataCountSectionNode> {
+        self.data_count_section.as_ref()
+    }
+
+    pub fn set_data_count_section(&mut self, data_count_section: DataCountSectionNode) {
+        self.data_count_section = Some(data_count_section);
+    }
+
     pub fn code_section(&self) -> Option<&CodeSectionNode> {
         self.code_section.as_ref()
     }
@@ -91,10 +189,44 @@ impl ModuleNode {
         self.code_section = Some(code_section);
     }
 
+    pub fn data_section(&self) -> Option<&DataSectionNode> {
+        self.data_section.as_ref()
+    }
+
+    pub fn set_data_section(&mut self, data_section: DataSectionNode) {
+        self.data_section = Some(data_section);
+    }
+
+    pub fn custom_sections(&self) -> &[(Option<SectionId>, CustomSectionNode)] {
+        &self.custom_sections
+    }
+
+    /// Records a custom section placed right after the known section `after`.
+    pub fn add_custom_section(&mut self, after: Option<SectionId>, section: CustomSectionNode) {
+        self.custom_sections.push((after, section));
+    }
+
+    pub fn names(&self) -> Option<&NameMap> {
+        self.names.as_ref()
+    }
+
+    pub fn set_names(&mut self, names: NameMap) {
+        self.names = Some(names);
+    }
+
     pub fn make(&mut self) {
         self.make_types();
+        self.make_imports();
         self.make_funcs();
+        self.make_tables();
+        self.make_mems();
+        self.make_globals();
+        if let Some(start_section) = self.start_section() {
+            self.make_start_function(start_section.function_index);
+        }
         self.make_exports();
+        self.make_elems();
+        self.make_datas();
     }
 
     fn make_types(&mut self) {
@@ -103,16 +235,26 @@ impl ModuleNode {
         }
     }
 
+    fn make_imports(&mut self) {
+        if let Some(import_section) = self.import_section() {
+            self.imports = Some(import_section.imports.clone());
+        }
+    }
+
+    /// Pairs each function with its body. The parser rejects a module whose
+    /// function and code sections differ in length.
     fn make_funcs(&mut self) {
         if let Some(function_section) = self.function_section() {
+            let bodies = self
+                .code_section()
+                .into_iter()
+                .flat_map(|code_section| code_section.bodies.iter());
             let funcs = function_section
                 .type_indexes
                 .iter()
-                .map(|type_index| {
-                    let code_section = self
-                        .code_section()
-                        .unwrap_or_else(|| panic!("Module does not have a code section"));
-                    let code = code_section.bodies[type_index.clone() as usize].clone();
+                .zip(bodies)
+                .map(|(type_index, code)| {
+                    let code = code.clone();
                     let mut locals: Vec<ValueType> = vec![];
                     for local in code.locals.iter() {
                         for _ in 0..local.count {
@@ -130,6 +272,24 @@ impl ModuleNode {
         }
     }
 
+    fn make_tables(&mut self) {
+        if let Some(table_section) = self.table_section() {
+            self.tables = Some(table_section.tables.clone());
+        }
+    }
+
+    fn make_mems(&mut self) {
+        if let Some(memory_section) = self.memory_section() {
+            self.mems = Some(memory_section.memories.clone());
+        }
+    }
+
+    fn make_globals(&mut self) {
+        if let Some(global_section) = self.global_section() {
+            self.globals = Some(global_section.globals.clone());
+        }
+    }
+
     fn make_start_function(&mut self, func_index: u32) {
         self.start = Some(func_index);
     }
@@ -140,34 +300,117 @@ impl ModuleNode {
         }
     }
 
+    fn make_elems(&mut self) {
+        if let Some(element_section) = self.element_section() {
+            self.elems = Some(element_section.elements.clone());
+        }
+    }
+
+    fn make_datas(&mut self) {
+        if let Some(data_section) = self.data_section() {
+            self.datas = Some(data_section.data.clone());
+        }
+    }
+
     pub fn types(&self) -> Option<&Vec<FunctionTypeNode>> {
         self.types.as_ref()
     }
 
+    pub fn imports(&self) -> Option<&Vec<ImportNode>> {
+        self.imports.as_ref()
+    }
+
     pub fn funcs(&self) -> Option<&Vec<FunctionNode>> {
         self.funcs.as_ref()
     }
 
+    pub fn tables(&self) -> Option<&Vec<TableType>> {
+        self.tables.as_ref()
+    }
+
+    pub fn mems(&self) -> Option<&Vec<MemoryType>> {
+        self.mems.as_ref()
+    }
+
+    pub fn globals(&self) -> Option<&Vec<GlobalNode>> {
+        self.globals.as_ref()
+    }
+
+    pub fn start(&self) -> Option<u32> {
+        self.start
+    }
+
     pub fn exports(&self) -> Option<&Vec<ExportNode>> {
         self.exports.as_ref()
     }
 
+    pub fn elems(&self) -> Option<&Vec<ElementNode>> {
+        self.elems.as_ref()
+    }
+
+    pub fn datas(&self) -> Option<&Vec<DataNode>> {
+        self.datas.as_ref()
+    }
+
     pub fn emit(&mut self) {
         self.buffer.write_bytes(self.magic.to_vec());
         self.buffer.write_bytes(self.version.to_vec());
+        self.emit_custom_sections(None);
 
         if let Some(type_section) = &self.type_section {
             self.buffer.write_bytes(type_section.encode());
         }
+        self.emit_custom_sections(Some(SectionId::TypeSectionId));
+        if let Some(import_section) = &self.import_section {
+            self.buffer.write_bytes(import_section.encode());
+        }
+        self.emit_custom_sections(Some(SectionId::ImportSectionId));
         if let Some(function_section) = &self.function_section {
             self.buffer.write_bytes(function_section.encode());
         }
+        self.emit_custom_sections(Some(SectionId::FunctionSectionId));
+        if let Some(table_section) = &self.table_section {
+            self.buffer.write_bytes(table_section.encode());
+        }
+        self.emit_custom_sections(Some(SectionId::TableSectionId));
+        if let Some(memory_section) = &self.memory_section {
+            self.buffer.write_bytes(memory_section.encode());
+        }
+        self.emit_custom_sections(Some(SectionId::MemorySectionId));
+        if let Some(global_section) = &self.global_section {
+            self.buffer.write_bytes(global_section.encode());
+        }
+        self.emit_custom_sections(Some(SectionId::GlobalSectionId));
         if let Some(export_section) = &self.export_section {
             self.buffer.write_bytes(export_section.encode());
         }
+        self.emit_custom_sections(Some(SectionId::ExportSectionId));
+        if let Some(start_section) = &self.start_section {
+            self.buffer.write_bytes(start_section.encode());
+        }
+        self.emit_custom_sections(Some(SectionId::StartSectionId));
+        if let Some(element_section) = &self.element_section {
+            self.buffer.write_bytes(element_section.encode());
+        }
+        self.emit_custom_sections(Some(SectionId::ElementSectionId));
+        if let Some(data_count_section) = &self.data_count_section {
+            self.buffer.write_bytes(data_count_section.encode());
+        }
+        self.emit_custom_sections(Some(SectionId::DataCountSectionId));
         if let Some(code_section) = &self.code_section {
             self.buffer.write_bytes(code_section.encode());
         }
+        self.emit_custom_sections(Some(SectionId::CodeSectionId));
+        if let Some(data_section) = &self.data_section {
+            self.buffer.write_bytes(data_section.encode());
+        }
+        self.emit_custom_sections(Some(SectionId::DataSectionId));
+    }
+
+    fn emit_custom_sections(&mut self, after: Option<SectionId>) {
+        for (_, section) in self.custom_sections.iter().filter(|(id, _)| *id == after) {
+            self.buffer.write_bytes(section.encode());
+        }
     }
 
     pub fn write_to_file(&self, path: &str) -> Result<(), Box<dyn Error>> {
@@ -1,47 +1,117 @@
 use crate::{
     leb128::encode_u32_to_leb128,
-    node::{CodeNode, ExportNode, FunctionTypeNode, Node},
+    node::{
+        CodeNode, DataNode, ElementNode, ExportNode, FunctionTypeNode, GlobalNode, ImportNode, Node,
+    },
+    types::{MemoryType, TableType},
 };
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SectionId {
     CustomSectionId = 0x0,
     TypeSectionId = 0x1,
     ImportSectionId = 0x2,
     FunctionSectionId = 0x3,
+    TableSectionId = 0x4,
+    MemorySectionId = 0x5,
     GlobalSectionId = 0x6,
     ExportSectionId = 0x7,
     StartSectionId = 0x8,
     ElementSectionId = 0x9,
     CodeSectionId = 0xA,
     DataSectionId = 0xB,
+    DataCountSectionId = 0xC,
 }
 
-impl From<u8> for SectionId {
-    fn from(x: u8) -> SectionId {
+impl TryFrom<u8> for SectionId {
+    type Error = u8;
+
+    fn try_from(x: u8) -> Result<SectionId, Self::Error> {
         use self::SectionId::*;
         match x {
-            0x0 => CustomSectionId,
-            0x1 => TypeSectionId,
-            0x2 => ImportSectionId,
-            0x3 => FunctionSectionId,
-            0x6 => GlobalSectionId,
-            0x7 => ExportSectionId,
-            0x8 => StartSectionId,
-            0x9 => ElementSectionId,
-            0xA => CodeSectionId,
-            0xB => DataSectionId,
-            _ => todo!("{} is not supported", x),
+            0x0 => Ok(CustomSectionId),
+            0x1 => Ok(TypeSectionId),
+            0x2 => Ok(ImportSectionId),
+            0x3 => Ok(FunctionSectionId),
+            0x4 => Ok(TableSectionId),
+            0x5 => Ok(MemorySectionId),
+            0x6 => Ok(GlobalSectionId),
+            0x7 => Ok(ExportSectionId),
+            0x8 => Ok(StartSectionId),
+            0x9 => Ok(ElementSectionId),
+            0xA => Ok(CodeSectionId),
+            0xB => Ok(DataSectionId),
+            0xC => Ok(DataCountSectionId),
+            _ => Err(x),
+        }
+    }
+}
+
+impl SectionId {
+    pub fn name(&self) -> &'static str {
+        use self::SectionId::*;
+        match self {
+            CustomSectionId => "custom",
+            TypeSectionId => "type",
+            ImportSectionId => "import",
+            FunctionSectionId => "function",
+            TableSectionId => "table",
+            MemorySectionId => "memory",
+            GlobalSectionId => "global",
+            ExportSectionId => "export",
+            StartSectionId => "start",
+            ElementSectionId => "element",
+            CodeSectionId => "code",
+            DataSectionId => "data",
+            DataCountSectionId => "data count",
         }
     }
 }
 
+/// Each section node also records in `offset` where its contents start in
+/// the module it was parsed from, just past its id and size.
 trait Section {
     fn id(&self) -> SectionId;
 }
 
+// https://webassembly.github.io/spec/core/binary/modules.html#custom-section
+#[derive(Debug, Clone)]
+pub struct CustomSectionNode {
+    pub name: String,
+    pub payload: Vec<u8>,
+    pub offset: usize,
+}
+
+impl Section for CustomSectionNode {
+    fn id(&self) -> SectionId {
+        SectionId::CustomSectionId
+    }
+}
+
+impl Node for CustomSectionNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += encode_u32_to_leb128(self.name.len() as u32).len() as u32; // size of name
+        size += self.name.len() as u32;
+        size += self.payload.len() as u32;
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.push(self.id() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.name.len() as u32));
+        bytes.extend(self.name.as_bytes());
+        bytes.extend(&self.payload);
+        bytes
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TypeSectionNode {
     pub function_types: Vec<FunctionTypeNode>,
+    pub offset: usize,
 }
 
 impl Section for TypeSectionNode {
@@ -53,7 +123,7 @@ impl Section for TypeSectionNode {
 impl Node for TypeSectionNode {
     fn size(&self) -> u32 {
         let mut size = 0;
-        size += 1; // count of function types
+        size += encode_u32_to_leb128(self.function_types.len() as u32).len() as u32; // count of function types
         for function_type in &self.function_types {
             size += function_type.size();
         }
@@ -63,8 +133,8 @@ impl Node for TypeSectionNode {
     fn encode(&self) -> Vec<u8> {
         let mut bytes = vec![];
         bytes.push(self.id() as u8);
-        bytes.push(self.size() as u8);
-        bytes.push(self.function_types.len() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.function_types.len() as u32));
         for function_type in &self.function_types {
             bytes.extend(function_type.encode());
         }
@@ -72,9 +142,44 @@ impl Node for TypeSectionNode {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct ImportSectionNode {
+    pub imports: Vec<ImportNode>,
+    pub offset: usize,
+}
+
+impl Section for ImportSectionNode {
+    fn id(&self) -> SectionId {
+        SectionId::ImportSectionId
+    }
+}
+
+impl Node for ImportSectionNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += encode_u32_to_leb128(self.imports.len() as u32).len() as u32; // count of imports
+        for import in &self.imports {
+            size += import.size();
+        }
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.push(self.id() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.imports.len() as u32));
+        for import in &self.imports {
+            bytes.extend(import.encode());
+        }
+        bytes
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FunctionSectionNode {
     pub type_indexes: Vec<u32>,
+    pub offset: usize,
 }
 
 impl Section for FunctionSectionNode {
@@ -86,16 +191,18 @@ impl Section for FunctionSectionNode {
 impl Node for FunctionSectionNode {
     fn size(&self) -> u32 {
         let mut size = 0;
-        size += 1; // count of type_indexes
-        size += (&self.type_indexes).len() as u32;
+        size += encode_u32_to_leb128(self.type_indexes.len() as u32).len() as u32; // count of type_indexes
+        for type_index in &self.type_indexes {
+            size += encode_u32_to_leb128(*type_index).len() as u32;
+        }
         size
     }
 
     fn encode(&self) -> Vec<u8> {
         let mut bytes = vec![];
         bytes.push(self.id() as u8);
-        bytes.push(self.size() as u8);
-        bytes.push(self.type_indexes.len() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.type_indexes.len() as u32));
         for type_index in &self.type_indexes {
             bytes.extend(encode_u32_to_leb128(*type_index));
         }
@@ -103,9 +210,112 @@ impl Node for FunctionSectionNode {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct TableSectionNode {
+    pub tables: Vec<TableType>,
+    pub offset: usize,
+}
+
+impl Section for TableSectionNode {
+    fn id(&self) -> SectionId {
+        SectionId::TableSectionId
+    }
+}
+
+impl Node for TableSectionNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += encode_u32_to_leb128(self.tables.len() as u32).len() as u32; // count of tables
+        for table in &self.tables {
+            size += table.size();
+        }
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.push(self.id() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.tables.len() as u32));
+        for table in &self.tables {
+            bytes.extend(table.encode());
+        }
+        bytes
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MemorySectionNode {
+    pub memories: Vec<MemoryType>,
+    pub offset: usize,
+}
+
+impl Section for MemorySectionNode {
+    fn id(&self) -> SectionId {
+        SectionId::MemorySectionId
+    }
+}
+
+impl Node for MemorySectionNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += encode_u32_to_leb128(self.memories.len() as u32).len() as u32; // count of memories
+        for memory in &self.memories {
+            size += memory.size();
+        }
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.push(self.id() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.memories.len() as u32));
+        for memory in &self.memories {
+            bytes.extend(memory.encode());
+        }
+        bytes
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GlobalSectionNode {
+    pub globals: Vec<GlobalNode>,
+    pub offset: usize,
+}
+
+impl Section for GlobalSectionNode {
+    fn id(&self) -> SectionId {
+        SectionId::GlobalSectionId
+    }
+}
+
+impl Node for GlobalSectionNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += encode_u32_to_leb128(self.globals.len() as u32).len() as u32; // count of globals
+        for global in &self.globals {
+            size += global.size();
+        }
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.push(self.id() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.globals.len() as u32));
+        for global in &self.globals {
+            bytes.extend(global.encode());
+        }
+        bytes
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ExportSectionNode {
     pub exports: Vec<ExportNode>,
+    pub offset: usize,
 }
 
 impl Section for ExportSectionNode {
@@ -117,7 +327,7 @@ impl Section for ExportSectionNode {
 impl Node for ExportSectionNode {
     fn size(&self) -> u32 {
         let mut size = 0;
-        size += 1; // count of exports
+        size += encode_u32_to_leb128(self.exports.len() as u32).len() as u32; // count of exports
         for export in &self.exports {
             size += export.size();
         }
@@ -127,8 +337,8 @@ impl Node for ExportSectionNode {
     fn encode(&self) -> Vec<u8> {
         let mut bytes = vec![];
         bytes.push(self.id() as u8);
-        bytes.push(self.size() as u8);
-        bytes.push(self.exports.len() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.exports.len() as u32));
         for export in &self.exports {
             bytes.extend(export.encode());
         }
@@ -144,9 +354,98 @@ impl ExportSectionNode {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct StartSectionNode {
+    pub function_index: u32,
+    pub offset: usize,
+}
+
+impl Section for StartSectionNode {
+    fn id(&self) -> SectionId {
+        SectionId::StartSectionId
+    }
+}
+
+impl Node for StartSectionNode {
+    fn size(&self) -> u32 {
+        encode_u32_to_leb128(self.function_index).len() as u32
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.push(self.id() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.function_index));
+        bytes
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ElementSectionNode {
+    pub elements: Vec<ElementNode>,
+    pub offset: usize,
+}
+
+impl Section for ElementSectionNode {
+    fn id(&self) -> SectionId {
+        SectionId::ElementSectionId
+    }
+}
+
+impl Node for ElementSectionNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += encode_u32_to_leb128(self.elements.len() as u32).len() as u32; // count of element segments
+        for element in &self.elements {
+            size += element.size();
+        }
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.push(self.id() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.elements.len() as u32));
+        for element in &self.elements {
+            bytes.extend(element.encode());
+        }
+        bytes
+    }
+}
+
+/// Declares the number of data segments ahead of the code section, so that
+/// `memory.init` and `data.drop` can be checked in a single pass.
+#[derive(Debug, Clone)]
+pub struct DataCountSectionNode {
+    pub count: u32,
+    pub offset: usize,
+}
+
+impl Section for DataCountSectionNode {
+    fn id(&self) -> SectionId {
+        SectionId::DataCountSectionId
+    }
+}
+
+impl Node for DataCountSectionNode {
+    fn size(&self) -> u32 {
+        encode_u32_to_leb128(self.count).len() as u32
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.push(self.id() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.count));
+        bytes
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CodeSectionNode {
     pub bodies: Vec<CodeNode>,
+    pub offset: usize,
 }
 
 impl Section for CodeSectionNode {
@@ -158,7 +457,7 @@ impl Section for CodeSectionNode {
 impl Node for CodeSectionNode {
     fn size(&self) -> u32 {
         let mut size = 0;
-        size += 1; // count of bodies
+        size += encode_u32_to_leb128(self.bodies.len() as u32).len() as u32; // count of bodies
         for body in &self.bodies {
             size += body.size();
         }
@@ -168,11 +467,45 @@ impl Node for CodeSectionNode {
     fn encode(&self) -> Vec<u8> {
         let mut bytes = vec![];
         bytes.push(self.id() as u8);
-        bytes.push(self.size() as u8);
-        bytes.push(self.bodies.len() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.bodies.len() as u32));
         for body in &self.bodies {
             bytes.extend(body.encode());
         }
         bytes
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct DataSectionNode {
+    pub data: Vec<DataNode>,
+    pub offset: usize,
+}
+
+impl Section for DataSectionNode {
+    fn id(&self) -> SectionId {
+        SectionId::DataSectionId
+    }
+}
+
+impl Node for DataSectionNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += encode_u32_to_leb128(self.data.len() as u32).len() as u32; // count of data segments
+        for data in &self.data {
+            size += data.size();
+        }
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.push(self.id() as u8);
+        bytes.extend(encode_u32_to_leb128(self.size()));
+        bytes.extend(encode_u32_to_leb128(self.data.len() as u32));
+        for data in &self.data {
+            bytes.extend(data.encode());
+        }
+        bytes
+    }
+}
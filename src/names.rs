@@ -0,0 +1,40 @@
+use std::collections::BTreeMap;
+
+/// Debug names decoded from the `name` custom section.
+// https://webassembly.github.io/spec/core/appendix/custom.html#name-section
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NameMap {
+    pub module: Option<String>,
+    pub functions: BTreeMap<u32, String>,
+    pub locals: BTreeMap<u32, BTreeMap<u32, String>>,
+}
+
+impl NameMap {
+    pub fn function_name(&self, function_index: u32) -> Option<&str> {
+        self.functions.get(&function_index).map(String::as_str)
+    }
+
+    pub fn local_name(&self, function_index: u32, local_index: u32) -> Option<&str> {
+        self.locals
+            .get(&function_index)
+            .and_then(|locals| locals.get(&local_index))
+            .map(String::as_str)
+    }
+
+    /// Formats a function as `$name`, falling back to its index when unnamed.
+    pub fn display_function(&self, function_index: u32) -> String {
+        display(self.function_name(function_index), function_index)
+    }
+
+    /// Formats a local as `$name`, falling back to its index when unnamed.
+    pub fn display_local(&self, function_index: u32, local_index: u32) -> String {
+        display(self.local_name(function_index, local_index), local_index)
+    }
+}
+
+fn display(name: Option<&str>, index: u32) -> String {
+    match name {
+        Some(name) => format!("${}", name),
+        None => index.to_string(),
+    }
+}
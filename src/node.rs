@@ -1,6 +1,8 @@
 use crate::{
-    leb128::{encode_i32_to_leb128, encode_u32_to_leb128},
-    types::{BlockType, ValueType},
+    float::{F32, F64},
+    leb128::{encode_i32_to_leb128, encode_i64_to_leb128, encode_u32_to_leb128},
+    simd::V128,
+    types::{BlockType, GlobalType, MemoryType, ReferenceTypeNode, TableType, ValueType},
 };
 
 pub trait Node {
@@ -9,7 +11,7 @@ pub trait Node {
 }
 
 // https://webassembly.github.io/spec/core/binary/types.html#function-types
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FunctionTypeNode {
     pub header: u8,
     pub params: ResultTypeNode,
@@ -62,7 +64,7 @@ pub struct FunctionNode {
 }
 
 // https://webassembly.github.io/spec/core/binary/types.html#result-types
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ResultTypeNode {
     // TODO: replace to Value Types
     pub val_types: Vec<ValueType>,
@@ -78,11 +80,7 @@ impl Node for ResultTypeNode {
     fn encode(&self) -> Vec<u8> {
         let mut buffer = vec![];
         for val_type in self.val_types.iter() {
-            match val_type {
-                ValueType::Number(num) => {
-                    buffer.extend(num.encode());
-                }
-            }
+            buffer.extend(val_type.encode());
         }
         buffer
     }
@@ -94,6 +92,9 @@ pub struct CodeNode {
     pub local_count: u32,
     pub locals: Vec<LocalEntryNode>,
     pub expr: ExpressionNode,
+    /// Absolute offset of the function body, just past its size, which the
+    /// offsets of its instructions count from.
+    pub offset: usize,
 }
 
 impl Node for CodeNode {
@@ -145,12 +146,16 @@ impl Node for LocalEntryNode {
 #[derive(Debug, Clone)]
 pub struct ExpressionNode {
     pub instructions: Vec<InstructionNode>,
+    /// Where each of `instructions` starts, counted from the start of the
+    /// function body in code, and of the expression itself elsewhere.
+    pub offsets: Vec<usize>,
 }
 
 impl Default for ExpressionNode {
     fn default() -> Self {
         Self {
             instructions: vec![],
+            offsets: vec![],
         }
     }
 }
@@ -179,6 +184,238 @@ impl ExpressionNode {
     }
 }
 
+// https://webassembly.github.io/spec/core/binary/modules.html#import-section
+#[derive(Debug, Clone)]
+pub struct ImportNode {
+    pub module: String,
+    pub name: String,
+    pub import_desc: ImportDescNode,
+}
+
+impl Node for ImportNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += encode_u32_to_leb128(self.module.len() as u32).len() as u32;
+        size += self.module.len() as u32;
+        size += encode_u32_to_leb128(self.name.len() as u32).len() as u32;
+        size += self.name.len() as u32;
+        size += self.import_desc.size();
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        buffer.extend(encode_u32_to_leb128(self.module.len() as u32));
+        buffer.extend(self.module.as_bytes());
+        buffer.extend(encode_u32_to_leb128(self.name.len() as u32));
+        buffer.extend(self.name.as_bytes());
+        buffer.extend(self.import_desc.encode());
+        buffer
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ImportDescNode {
+    Function(u32),
+    Table(TableType),
+    Memory(MemoryType),
+    Global(GlobalType),
+}
+
+impl Node for ImportDescNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += 1; // import type
+        size += match self {
+            ImportDescNode::Function(type_index) => encode_u32_to_leb128(*type_index).len() as u32,
+            ImportDescNode::Table(table_type) => table_type.size(),
+            ImportDescNode::Memory(memory_type) => memory_type.size(),
+            ImportDescNode::Global(global_type) => global_type.size(),
+        };
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        match self {
+            ImportDescNode::Function(type_index) => {
+                buffer.push(0x00);
+                buffer.extend(encode_u32_to_leb128(*type_index));
+            }
+            ImportDescNode::Table(table_type) => {
+                buffer.push(0x01);
+                buffer.extend(table_type.encode());
+            }
+            ImportDescNode::Memory(memory_type) => {
+                buffer.push(0x02);
+                buffer.extend(memory_type.encode());
+            }
+            ImportDescNode::Global(global_type) => {
+                buffer.push(0x03);
+                buffer.extend(global_type.encode());
+            }
+        }
+        buffer
+    }
+}
+
+// https://webassembly.github.io/spec/core/binary/modules.html#global-section
+#[derive(Debug, Clone)]
+pub struct GlobalNode {
+    pub global_type: GlobalType,
+    pub init: ExpressionNode,
+}
+
+impl Node for GlobalNode {
+    fn size(&self) -> u32 {
+        self.global_type.size() + self.init.size()
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        buffer.extend(self.global_type.encode());
+        buffer.extend(self.init.encode());
+        buffer
+    }
+}
+
+// https://webassembly.github.io/spec/core/binary/modules.html#data-section
+#[derive(Debug, Clone)]
+pub struct DataNode {
+    pub mode: DataModeNode,
+    pub init: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub enum DataModeNode {
+    Passive,
+    Active {
+        memory_index: u32,
+        offset: ExpressionNode,
+    },
+}
+
+impl Node for DataNode {
+    fn size(&self) -> u32 {
+        self.encode().len() as u32
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        match &self.mode {
+            DataModeNode::Active {
+                memory_index: 0,
+                offset,
+            } => {
+                buffer.extend(encode_u32_to_leb128(0x00));
+                buffer.extend(offset.encode());
+            }
+            DataModeNode::Passive => {
+                buffer.extend(encode_u32_to_leb128(0x01));
+            }
+            DataModeNode::Active {
+                memory_index,
+                offset,
+            } => {
+                buffer.extend(encode_u32_to_leb128(0x02));
+                buffer.extend(encode_u32_to_leb128(*memory_index));
+                buffer.extend(offset.encode());
+            }
+        }
+        buffer.extend(encode_u32_to_leb128(self.init.len() as u32));
+        buffer.extend(&self.init);
+        buffer
+    }
+}
+
+// https://webassembly.github.io/spec/core/binary/modules.html#element-section
+#[derive(Debug, Clone)]
+pub struct ElementNode {
+    pub mode: ElementModeNode,
+    pub element_type: ReferenceTypeNode,
+    pub init: ElementInitNode,
+}
+
+#[derive(Debug, Clone)]
+pub enum ElementModeNode {
+    Passive,
+    Active {
+        table_index: u32,
+        offset: ExpressionNode,
+    },
+    Declarative,
+}
+
+/// Elements are encoded either as plain function indices or as constant expressions.
+#[derive(Debug, Clone)]
+pub enum ElementInitNode {
+    Functions(Vec<u32>),
+    Expressions(Vec<ExpressionNode>),
+}
+
+impl ElementNode {
+    fn flag(&self) -> u32 {
+        let mut flag = match &self.mode {
+            ElementModeNode::Active { table_index: 0, .. }
+                if self.element_type == ReferenceTypeNode::FunctionRef =>
+            {
+                0b000
+            }
+            ElementModeNode::Active { .. } => 0b010,
+            ElementModeNode::Passive => 0b001,
+            ElementModeNode::Declarative => 0b011,
+        };
+        if let ElementInitNode::Expressions(_) = self.init {
+            flag |= 0b100;
+        }
+        flag
+    }
+}
+
+impl Node for ElementNode {
+    fn size(&self) -> u32 {
+        self.encode().len() as u32
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        let flag = self.flag();
+        buffer.extend(encode_u32_to_leb128(flag));
+        if let ElementModeNode::Active {
+            table_index,
+            offset,
+        } = &self.mode
+        {
+            if flag & 0b010 != 0 {
+                buffer.extend(encode_u32_to_leb128(*table_index));
+            }
+            buffer.extend(offset.encode());
+        }
+        if flag & 0b011 != 0 {
+            match self.init {
+                // elemkind 0x00 stands for funcref
+                ElementInitNode::Functions(_) => buffer.push(0x00),
+                ElementInitNode::Expressions(_) => buffer.extend(self.element_type.encode()),
+            }
+        }
+        match &self.init {
+            ElementInitNode::Functions(indexes) => {
+                buffer.extend(encode_u32_to_leb128(indexes.len() as u32));
+                for index in indexes {
+                    buffer.extend(encode_u32_to_leb128(*index));
+                }
+            }
+            ElementInitNode::Expressions(exprs) => {
+                buffer.extend(encode_u32_to_leb128(exprs.len() as u32));
+                for expr in exprs {
+                    buffer.extend(expr.encode());
+                }
+            }
+        }
+        buffer
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ExportNode {
     pub name: String,
@@ -233,14 +470,16 @@ pub enum ExportTypeNode {
     Global = 0x03,
 }
 
-impl From<u8> for ExportTypeNode {
-    fn from(x: u8) -> Self {
+impl TryFrom<u8> for ExportTypeNode {
+    type Error = u8;
+
+    fn try_from(x: u8) -> Result<Self, Self::Error> {
         match x {
-            0x00 => ExportTypeNode::Function,
-            0x01 => ExportTypeNode::Table,
-            0x02 => ExportTypeNode::Memory,
-            0x03 => ExportTypeNode::Global,
-            _ => unreachable!("{} is an invalid value in ExportType", x),
+            0x00 => Ok(ExportTypeNode::Function),
+            0x01 => Ok(ExportTypeNode::Table),
+            0x02 => Ok(ExportTypeNode::Memory),
+            0x03 => Ok(ExportTypeNode::Global),
+            _ => Err(x),
         }
     }
 }
@@ -278,30 +517,624 @@ pub enum InstructionNode {
     Else(ElseInstructionNode),
     Br(BrInstructionNode),
     BrIf(BrIfInstructionNode),
+    BrTable(BrTableInstructionNode),
+    Return(ReturnInstructionNode),
+    Unreachable(UnreachableInstructionNode),
+    Nop(NopInstructionNode),
     Call(CallInstructionNode),
+    CallIndirect(CallIndirectInstructionNode),
+    Drop(DropInstructionNode),
+    Select(SelectInstructionNode),
     End(EndInstructionNode),
     I32Const(I32ConstInstructionNode),
+    I64Const(I64ConstInstructionNode),
+    F32Const(F32ConstInstructionNode),
+    F64Const(F64ConstInstructionNode),
     GetLocal(GetLocalInstructionNode),
     SetLocal(SetLocalInstructionNode),
-    I32Add(I32AddInstructionNode),
-    I32Sub(I32SubInstructionNode),
-    I32RemS(I32RemSInstructionNode),
-    I32RemU(I32RemUInstructionNode),
-    // I32Shl(I32ShlInstructionNode),
+    TeeLocal(TeeLocalInstructionNode),
+    GetGlobal(GetGlobalInstructionNode),
+    SetGlobal(SetGlobalInstructionNode),
+    I32Load(MemoryAccessInstructionNode),
+    I64Load(MemoryAccessInstructionNode),
+    F32Load(MemoryAccessInstructionNode),
+    F64Load(MemoryAccessInstructionNode),
+    I32Load8S(MemoryAccessInstructionNode),
+    I32Load8U(MemoryAccessInstructionNode),
+    I32Load16S(MemoryAccessInstructionNode),
+    I32Load16U(MemoryAccessInstructionNode),
+    I64Load8S(MemoryAccessInstructionNode),
+    I64Load8U(MemoryAccessInstructionNode),
+    I64Load16S(MemoryAccessInstructionNode),
+    I64Load16U(MemoryAccessInstructionNode),
+    I64Load32S(MemoryAccessInstructionNode),
+    I64Load32U(MemoryAccessInstructionNode),
+    I32Store(MemoryAccessInstructionNode),
+    I64Store(MemoryAccessInstructionNode),
+    F32Store(MemoryAccessInstructionNode),
+    F64Store(MemoryAccessInstructionNode),
+    I32Store8(MemoryAccessInstructionNode),
+    I32Store16(MemoryAccessInstructionNode),
+    I64Store8(MemoryAccessInstructionNode),
+    I64Store16(MemoryAccessInstructionNode),
+    I64Store32(MemoryAccessInstructionNode),
+    MemorySize(MemorySizeInstructionNode),
+    MemoryGrow(MemoryGrowInstructionNode),
+    RefNull(RefNullInstructionNode),
+    RefIsNull(RefIsNullInstructionNode),
+    RefFunc(RefFuncInstructionNode),
+    TableGet(TableGetInstructionNode),
+    TableSet(TableSetInstructionNode),
     I32Eqz(I32EqzInstructionNode),
     I32Eq(I32EqInstructionNode),
+    I32Ne(I32NeInstructionNode),
     I32LtS(I32LtSInstructionNode),
     I32LtU(I32LtUInstructionNode),
+    I32GtS(I32GtSInstructionNode),
+    I32GtU(I32GtUInstructionNode),
+    I32LeS(I32LeSInstructionNode),
+    I32LeU(I32LeUInstructionNode),
     I32GeS(I32GeSInstructionNode),
-    // I32GeU(I32GeUInstructionNode),
-    // I32GtS(I32GtSInstructionNode),
-    // I32GtU(I32GtUInstructionNode),
-    // I64Add(I32AddInstructionNode),
-    // I64Sub(I32SubInstructionNode),
-    // Unreachable,
-    // Nop,
-    // BrTable(Vec<u32>, u32),
-    // Return,
+    I32GeU(I32GeUInstructionNode),
+    I32Clz(I32ClzInstructionNode),
+    I32Ctz(I32CtzInstructionNode),
+    I32Popcnt(I32PopcntInstructionNode),
+    I32Add(I32AddInstructionNode),
+    I32Sub(I32SubInstructionNode),
+    I32Mul(I32MulInstructionNode),
+    I32DivS(I32DivSInstructionNode),
+    I32DivU(I32DivUInstructionNode),
+    I32RemS(I32RemSInstructionNode),
+    I32RemU(I32RemUInstructionNode),
+    I32And(I32AndInstructionNode),
+    I32Or(I32OrInstructionNode),
+    I32Xor(I32XorInstructionNode),
+    I32Shl(I32ShlInstructionNode),
+    I32ShrS(I32ShrSInstructionNode),
+    I32ShrU(I32ShrUInstructionNode),
+    I32Rotl(I32RotlInstructionNode),
+    I32Rotr(I32RotrInstructionNode),
+    I64Eqz(I64EqzInstructionNode),
+    I64Eq(I64EqInstructionNode),
+    I64Ne(I64NeInstructionNode),
+    I64LtS(I64LtSInstructionNode),
+    I64LtU(I64LtUInstructionNode),
+    I64GtS(I64GtSInstructionNode),
+    I64GtU(I64GtUInstructionNode),
+    I64LeS(I64LeSInstructionNode),
+    I64LeU(I64LeUInstructionNode),
+    I64GeS(I64GeSInstructionNode),
+    I64GeU(I64GeUInstructionNode),
+    I64Clz(I64ClzInstructionNode),
+    I64Ctz(I64CtzInstructionNode),
+    I64Popcnt(I64PopcntInstructionNode),
+    I64Add(I64AddInstructionNode),
+    I64Sub(I64SubInstructionNode),
+    I64Mul(I64MulInstructionNode),
+    I64DivS(I64DivSInstructionNode),
+    I64DivU(I64DivUInstructionNode),
+    I64RemS(I64RemSInstructionNode),
+    I64RemU(I64RemUInstructionNode),
+    I64And(I64AndInstructionNode),
+    I64Or(I64OrInstructionNode),
+    I64Xor(I64XorInstructionNode),
+    I64Shl(I64ShlInstructionNode),
+    I64ShrS(I64ShrSInstructionNode),
+    I64ShrU(I64ShrUInstructionNode),
+    I64Rotl(I64RotlInstructionNode),
+    I64Rotr(I64RotrInstructionNode),
+    F32Eq(F32EqInstructionNode),
+    F32Ne(F32NeInstructionNode),
+    F32Lt(F32LtInstructionNode),
+    F32Gt(F32GtInstructionNode),
+    F32Le(F32LeInstructionNode),
+    F32Ge(F32GeInstructionNode),
+    F64Eq(F64EqInstructionNode),
+    F64Ne(F64NeInstructionNode),
+    F64Lt(F64LtInstructionNode),
+    F64Gt(F64GtInstructionNode),
+    F64Le(F64LeInstructionNode),
+    F64Ge(F64GeInstructionNode),
+    F32Abs(F32AbsInstructionNode),
+    F32Neg(F32NegInstructionNode),
+    F32Ceil(F32CeilInstructionNode),
+    F32Floor(F32FloorInstructionNode),
+    F32Trunc(F32TruncInstructionNode),
+    F32Nearest(F32NearestInstructionNode),
+    F32Sqrt(F32SqrtInstructionNode),
+    F32Add(F32AddInstructionNode),
+    F32Sub(F32SubInstructionNode),
+    F32Mul(F32MulInstructionNode),
+    F32Div(F32DivInstructionNode),
+    F32Min(F32MinInstructionNode),
+    F32Max(F32MaxInstructionNode),
+    F32Copysign(F32CopysignInstructionNode),
+    F64Abs(F64AbsInstructionNode),
+    F64Neg(F64NegInstructionNode),
+    F64Ceil(F64CeilInstructionNode),
+    F64Floor(F64FloorInstructionNode),
+    F64Trunc(F64TruncInstructionNode),
+    F64Nearest(F64NearestInstructionNode),
+    F64Sqrt(F64SqrtInstructionNode),
+    F64Add(F64AddInstructionNode),
+    F64Sub(F64SubInstructionNode),
+    F64Mul(F64MulInstructionNode),
+    F64Div(F64DivInstructionNode),
+    F64Min(F64MinInstructionNode),
+    F64Max(F64MaxInstructionNode),
+    F64Copysign(F64CopysignInstructionNode),
+    I32WrapI64(I32WrapI64InstructionNode),
+    I32TruncF32S(I32TruncF32SInstructionNode),
+    I32TruncF32U(I32TruncF32UInstructionNode),
+    I32TruncF64S(I32TruncF64SInstructionNode),
+    I32TruncF64U(I32TruncF64UInstructionNode),
+    I64ExtendI32S(I64ExtendI32SInstructionNode),
+    I64ExtendI32U(I64ExtendI32UInstructionNode),
+    I64TruncF32S(I64TruncF32SInstructionNode),
+    I64TruncF32U(I64TruncF32UInstructionNode),
+    I64TruncF64S(I64TruncF64SInstructionNode),
+    I64TruncF64U(I64TruncF64UInstructionNode),
+    F32ConvertI32S(F32ConvertI32SInstructionNode),
+    F32ConvertI32U(F32ConvertI32UInstructionNode),
+    F32ConvertI64S(F32ConvertI64SInstructionNode),
+    F32ConvertI64U(F32ConvertI64UInstructionNode),
+    F32DemoteF64(F32DemoteF64InstructionNode),
+    F64ConvertI32S(F64ConvertI32SInstructionNode),
+    F64ConvertI32U(F64ConvertI32UInstructionNode),
+    F64ConvertI64S(F64ConvertI64SInstructionNode),
+    F64ConvertI64U(F64ConvertI64UInstructionNode),
+    F64PromoteF32(F64PromoteF32InstructionNode),
+    I32ReinterpretF32(I32ReinterpretF32InstructionNode),
+    I64ReinterpretF64(I64ReinterpretF64InstructionNode),
+    F32ReinterpretI32(F32ReinterpretI32InstructionNode),
+    F64ReinterpretI64(F64ReinterpretI64InstructionNode),
+    I32Extend8S(I32Extend8SInstructionNode),
+    I32Extend16S(I32Extend16SInstructionNode),
+    I64Extend8S(I64Extend8SInstructionNode),
+    I64Extend16S(I64Extend16SInstructionNode),
+    I64Extend32S(I64Extend32SInstructionNode),
+    I32TruncSatF32S(I32TruncSatF32SInstructionNode),
+    I32TruncSatF32U(I32TruncSatF32UInstructionNode),
+    I32TruncSatF64S(I32TruncSatF64SInstructionNode),
+    I32TruncSatF64U(I32TruncSatF64UInstructionNode),
+    I64TruncSatF32S(I64TruncSatF32SInstructionNode),
+    I64TruncSatF32U(I64TruncSatF32UInstructionNode),
+    I64TruncSatF64S(I64TruncSatF64SInstructionNode),
+    I64TruncSatF64U(I64TruncSatF64UInstructionNode),
+    MemoryInit(MemoryInitInstructionNode),
+    DataDrop(DataDropInstructionNode),
+    MemoryCopy(MemoryCopyInstructionNode),
+    MemoryFill(MemoryFillInstructionNode),
+    TableInit(TableInitInstructionNode),
+    ElemDrop(ElemDropInstructionNode),
+    TableCopy(TableCopyInstructionNode),
+    TableGrow(TableGrowInstructionNode),
+    TableSize(TableSizeInstructionNode),
+    TableFill(TableFillInstructionNode),
+    V128Load(SimdMemoryAccessInstructionNode),
+    V128Load8x8S(SimdMemoryAccessInstructionNode),
+    V128Load8x8U(SimdMemoryAccessInstructionNode),
+    V128Load16x4S(SimdMemoryAccessInstructionNode),
+    V128Load16x4U(SimdMemoryAccessInstructionNode),
+    V128Load32x2S(SimdMemoryAccessInstructionNode),
+    V128Load32x2U(SimdMemoryAccessInstructionNode),
+    V128Load8Splat(SimdMemoryAccessInstructionNode),
+    V128Load16Splat(SimdMemoryAccessInstructionNode),
+    V128Load32Splat(SimdMemoryAccessInstructionNode),
+    V128Load64Splat(SimdMemoryAccessInstructionNode),
+    V128Store(SimdMemoryAccessInstructionNode),
+    V128Const(V128ConstInstructionNode),
+    I8x16Shuffle(I8x16ShuffleInstructionNode),
+    I8x16Swizzle(I8x16SwizzleInstructionNode),
+    I8x16Splat(I8x16SplatInstructionNode),
+    I16x8Splat(I16x8SplatInstructionNode),
+    I32x4Splat(I32x4SplatInstructionNode),
+    I64x2Splat(I64x2SplatInstructionNode),
+    F32x4Splat(F32x4SplatInstructionNode),
+    F64x2Splat(F64x2SplatInstructionNode),
+    I8x16ExtractLaneS(SimdLaneInstructionNode),
+    I8x16ExtractLaneU(SimdLaneInstructionNode),
+    I8x16ReplaceLane(SimdLaneInstructionNode),
+    I16x8ExtractLaneS(SimdLaneInstructionNode),
+    I16x8ExtractLaneU(SimdLaneInstructionNode),
+    I16x8ReplaceLane(SimdLaneInstructionNode),
+    I32x4ExtractLane(SimdLaneInstructionNode),
+    I32x4ReplaceLane(SimdLaneInstructionNode),
+    I64x2ExtractLane(SimdLaneInstructionNode),
+    I64x2ReplaceLane(SimdLaneInstructionNode),
+    F32x4ExtractLane(SimdLaneInstructionNode),
+    F32x4ReplaceLane(SimdLaneInstructionNode),
+    F64x2ExtractLane(SimdLaneInstructionNode),
+    F64x2ReplaceLane(SimdLaneInstructionNode),
+    I8x16Eq(I8x16EqInstructionNode),
+    I8x16Ne(I8x16NeInstructionNode),
+    I8x16LtS(I8x16LtSInstructionNode),
+    I8x16LtU(I8x16LtUInstructionNode),
+    I8x16GtS(I8x16GtSInstructionNode),
+    I8x16GtU(I8x16GtUInstructionNode),
+    I8x16LeS(I8x16LeSInstructionNode),
+    I8x16LeU(I8x16LeUInstructionNode),
+    I8x16GeS(I8x16GeSInstructionNode),
+    I8x16GeU(I8x16GeUInstructionNode),
+    I16x8Eq(I16x8EqInstructionNode),
+    I16x8Ne(I16x8NeInstructionNode),
+    I16x8LtS(I16x8LtSInstructionNode),
+    I16x8LtU(I16x8LtUInstructionNode),
+    I16x8GtS(I16x8GtSInstructionNode),
+    I16x8GtU(I16x8GtUInstructionNode),
+    I16x8LeS(I16x8LeSInstructionNode),
+    I16x8LeU(I16x8LeUInstructionNode),
+    I16x8GeS(I16x8GeSInstructionNode),
+    I16x8GeU(I16x8GeUInstructionNode),
+    I32x4Eq(I32x4EqInstructionNode),
+    I32x4Ne(I32x4NeInstructionNode),
+    I32x4LtS(I32x4LtSInstructionNode),
+    I32x4LtU(I32x4LtUInstructionNode),
+    I32x4GtS(I32x4GtSInstructionNode),
+    I32x4GtU(I32x4GtUInstructionNode),
+    I32x4LeS(I32x4LeSInstructionNode),
+    I32x4LeU(I32x4LeUInstructionNode),
+    I32x4GeS(I32x4GeSInstructionNode),
+    I32x4GeU(I32x4GeUInstructionNode),
+    F32x4Eq(F32x4EqInstructionNode),
+    F32x4Ne(F32x4NeInstructionNode),
+    F32x4Lt(F32x4LtInstructionNode),
+    F32x4Gt(F32x4GtInstructionNode),
+    F32x4Le(F32x4LeInstructionNode),
+    F32x4Ge(F32x4GeInstructionNode),
+    F64x2Eq(F64x2EqInstructionNode),
+    F64x2Ne(F64x2NeInstructionNode),
+    F64x2Lt(F64x2LtInstructionNode),
+    F64x2Gt(F64x2GtInstructionNode),
+    F64x2Le(F64x2LeInstructionNode),
+    F64x2Ge(F64x2GeInstructionNode),
+    V128Not(V128NotInstructionNode),
+    V128And(V128AndInstructionNode),
+    V128Andnot(V128AndnotInstructionNode),
+    V128Or(V128OrInstructionNode),
+    V128Xor(V128XorInstructionNode),
+    V128Bitselect(V128BitselectInstructionNode),
+    V128AnyTrue(V128AnyTrueInstructionNode),
+    V128Load8Lane(SimdMemoryLaneInstructionNode),
+    V128Load16Lane(SimdMemoryLaneInstructionNode),
+    V128Load32Lane(SimdMemoryLaneInstructionNode),
+    V128Load64Lane(SimdMemoryLaneInstructionNode),
+    V128Store8Lane(SimdMemoryLaneInstructionNode),
+    V128Store16Lane(SimdMemoryLaneInstructionNode),
+    V128Store32Lane(SimdMemoryLaneInstructionNode),
+    V128Store64Lane(SimdMemoryLaneInstructionNode),
+    V128Load32Zero(SimdMemoryAccessInstructionNode),
+    V128Load64Zero(SimdMemoryAccessInstructionNode),
+    F32x4DemoteF64x2Zero(F32x4DemoteF64x2ZeroInstructionNode),
+    F64x2PromoteLowF32x4(F64x2PromoteLowF32x4InstructionNode),
+    I8x16Abs(I8x16AbsInstructionNode),
+    I8x16Neg(I8x16NegInstructionNode),
+    I8x16Popcnt(I8x16PopcntInstructionNode),
+    I8x16AllTrue(I8x16AllTrueInstructionNode),
+    I8x16Bitmask(I8x16BitmaskInstructionNode),
+    I8x16NarrowI16x8S(I8x16NarrowI16x8SInstructionNode),
+    I8x16NarrowI16x8U(I8x16NarrowI16x8UInstructionNode),
+    F32x4Ceil(F32x4CeilInstructionNode),
+    F32x4Floor(F32x4FloorInstructionNode),
+    F32x4Trunc(F32x4TruncInstructionNode),
+    F32x4Nearest(F32x4NearestInstructionNode),
+    I8x16Shl(I8x16ShlInstructionNode),
+    I8x16ShrS(I8x16ShrSInstructionNode),
+    I8x16ShrU(I8x16ShrUInstructionNode),
+    I8x16Add(I8x16AddInstructionNode),
+    I8x16AddSatS(I8x16AddSatSInstructionNode),
+    I8x16AddSatU(I8x16AddSatUInstructionNode),
+    I8x16Sub(I8x16SubInstructionNode),
+    I8x16SubSatS(I8x16SubSatSInstructionNode),
+    I8x16SubSatU(I8x16SubSatUInstructionNode),
+    F64x2Ceil(F64x2CeilInstructionNode),
+    F64x2Floor(F64x2FloorInstructionNode),
+    I8x16MinS(I8x16MinSInstructionNode),
+    I8x16MinU(I8x16MinUInstructionNode),
+    I8x16MaxS(I8x16MaxSInstructionNode),
+    I8x16MaxU(I8x16MaxUInstructionNode),
+    F64x2Trunc(F64x2TruncInstructionNode),
+    I8x16AvgrU(I8x16AvgrUInstructionNode),
+    I16x8ExtaddPairwiseI8x16S(I16x8ExtaddPairwiseI8x16SInstructionNode),
+    I16x8ExtaddPairwiseI8x16U(I16x8ExtaddPairwiseI8x16UInstructionNode),
+    I32x4ExtaddPairwiseI16x8S(I32x4ExtaddPairwiseI16x8SInstructionNode),
+    I32x4ExtaddPairwiseI16x8U(I32x4ExtaddPairwiseI16x8UInstructionNode),
+    I16x8Abs(I16x8AbsInstructionNode),
+    I16x8Neg(I16x8NegInstructionNode),
+    I16x8Q15mulrSatS(I16x8Q15mulrSatSInstructionNode),
+    I16x8AllTrue(I16x8AllTrueInstructionNode),
+    I16x8Bitmask(I16x8BitmaskInstructionNode),
+    I16x8NarrowI32x4S(I16x8NarrowI32x4SInstructionNode),
+    I16x8NarrowI32x4U(I16x8NarrowI32x4UInstructionNode),
+    I16x8ExtendLowI8x16S(I16x8ExtendLowI8x16SInstructionNode),
+    I16x8ExtendHighI8x16S(I16x8ExtendHighI8x16SInstructionNode),
+    I16x8ExtendLowI8x16U(I16x8ExtendLowI8x16UInstructionNode),
+    I16x8ExtendHighI8x16U(I16x8ExtendHighI8x16UInstructionNode),
+    I16x8Shl(I16x8ShlInstructionNode),
+    I16x8ShrS(I16x8ShrSInstructionNode),
+    I16x8ShrU(I16x8ShrUInstructionNode),
+    I16x8Add(I16x8AddInstructionNode),
+    I16x8AddSatS(I16x8AddSatSInstructionNode),
+    I16x8AddSatU(I16x8AddSatUInstructionNode),
+    I16x8Sub(I16x8SubInstructionNode),
+    I16x8SubSatS(I16x8SubSatSInstructionNode),
+    I16x8SubSatU(I16x8SubSatUInstructionNode),
+    F64x2Nearest(F64x2NearestInstructionNode),
+    I16x8Mul(I16x8MulInstructionNode),
+    I16x8MinS(I16x8MinSInstructionNode),
+    I16x8MinU(I16x8MinUInstructionNode),
+    I16x8MaxS(I16x8MaxSInstructionNode),
+    I16x8MaxU(I16x8MaxUInstructionNode),
+    I16x8AvgrU(I16x8AvgrUInstructionNode),
+    I16x8ExtmulLowI8x16S(I16x8ExtmulLowI8x16SInstructionNode),
+    I16x8ExtmulHighI8x16S(I16x8ExtmulHighI8x16SInstructionNode),
+    I16x8ExtmulLowI8x16U(I16x8ExtmulLowI8x16UInstructionNode),
+    I16x8ExtmulHighI8x16U(I16x8ExtmulHighI8x16UInstructionNode),
+    I32x4Abs(I32x4AbsInstructionNode),
+    I32x4Neg(I32x4NegInstructionNode),
+    I32x4AllTrue(I32x4AllTrueInstructionNode),
+    I32x4Bitmask(I32x4BitmaskInstructionNode),
+    I32x4ExtendLowI16x8S(I32x4ExtendLowI16x8SInstructionNode),
+    I32x4ExtendHighI16x8S(I32x4ExtendHighI16x8SInstructionNode),
+    I32x4ExtendLowI16x8U(I32x4ExtendLowI16x8UInstructionNode),
+    I32x4ExtendHighI16x8U(I32x4ExtendHighI16x8UInstructionNode),
+    I32x4Shl(I32x4ShlInstructionNode),
+    I32x4ShrS(I32x4ShrSInstructionNode),
+    I32x4ShrU(I32x4ShrUInstructionNode),
+    I32x4Add(I32x4AddInstructionNode),
+    I32x4Sub(I32x4SubInstructionNode),
+    I32x4Mul(I32x4MulInstructionNode),
+    I32x4MinS(I32x4MinSInstructionNode),
+    I32x4MinU(I32x4MinUInstructionNode),
+    I32x4MaxS(I32x4MaxSInstructionNode),
+    I32x4MaxU(I32x4MaxUInstructionNode),
+    I32x4DotI16x8S(I32x4DotI16x8SInstructionNode),
+    I32x4ExtmulLowI16x8S(I32x4ExtmulLowI16x8SInstructionNode),
+    I32x4ExtmulHighI16x8S(I32x4ExtmulHighI16x8SInstructionNode),
+    I32x4ExtmulLowI16x8U(I32x4ExtmulLowI16x8UInstructionNode),
+    I32x4ExtmulHighI16x8U(I32x4ExtmulHighI16x8UInstructionNode),
+    I64x2Abs(I64x2AbsInstructionNode),
+    I64x2Neg(I64x2NegInstructionNode),
+    I64x2AllTrue(I64x2AllTrueInstructionNode),
+    I64x2Bitmask(I64x2BitmaskInstructionNode),
+    I64x2ExtendLowI32x4S(I64x2ExtendLowI32x4SInstructionNode),
+    I64x2ExtendHighI32x4S(I64x2ExtendHighI32x4SInstructionNode),
+    I64x2ExtendLowI32x4U(I64x2ExtendLowI32x4UInstructionNode),
+    I64x2ExtendHighI32x4U(I64x2ExtendHighI32x4UInstructionNode),
+    I64x2Shl(I64x2ShlInstructionNode),
+    I64x2ShrS(I64x2ShrSInstructionNode),
+    I64x2ShrU(I64x2ShrUInstructionNode),
+    I64x2Add(I64x2AddInstructionNode),
+    I64x2Sub(I64x2SubInstructionNode),
+    I64x2Mul(I64x2MulInstructionNode),
+    I64x2Eq(I64x2EqInstructionNode),
+    I64x2Ne(I64x2NeInstructionNode),
+    I64x2LtS(I64x2LtSInstructionNode),
+    I64x2GtS(I64x2GtSInstructionNode),
+    I64x2LeS(I64x2LeSInstructionNode),
+    I64x2GeS(I64x2GeSInstructionNode),
+    I64x2ExtmulLowI32x4S(I64x2ExtmulLowI32x4SInstructionNode),
+    I64x2ExtmulHighI32x4S(I64x2ExtmulHighI32x4SInstructionNode),
+    I64x2ExtmulLowI32x4U(I64x2ExtmulLowI32x4UInstructionNode),
+    I64x2ExtmulHighI32x4U(I64x2ExtmulHighI32x4UInstructionNode),
+    F32x4Abs(F32x4AbsInstructionNode),
+    F32x4Neg(F32x4NegInstructionNode),
+    F32x4Sqrt(F32x4SqrtInstructionNode),
+    F32x4Add(F32x4AddInstructionNode),
+    F32x4Sub(F32x4SubInstructionNode),
+    F32x4Mul(F32x4MulInstructionNode),
+    F32x4Div(F32x4DivInstructionNode),
+    F32x4Min(F32x4MinInstructionNode),
+    F32x4Max(F32x4MaxInstructionNode),
+    F32x4Pmin(F32x4PminInstructionNode),
+    F32x4Pmax(F32x4PmaxInstructionNode),
+    F64x2Abs(F64x2AbsInstructionNode),
+    F64x2Neg(F64x2NegInstructionNode),
+    F64x2Sqrt(F64x2SqrtInstructionNode),
+    F64x2Add(F64x2AddInstructionNode),
+    F64x2Sub(F64x2SubInstructionNode),
+    F64x2Mul(F64x2MulInstructionNode),
+    F64x2Div(F64x2DivInstructionNode),
+    F64x2Min(F64x2MinInstructionNode),
+    F64x2Max(F64x2MaxInstructionNode),
+    F64x2Pmin(F64x2PminInstructionNode),
+    F64x2Pmax(F64x2PmaxInstructionNode),
+    I32x4TruncSatF32x4S(I32x4TruncSatF32x4SInstructionNode),
+    I32x4TruncSatF32x4U(I32x4TruncSatF32x4UInstructionNode),
+    F32x4ConvertI32x4S(F32x4ConvertI32x4SInstructionNode),
+    F32x4ConvertI32x4U(F32x4ConvertI32x4UInstructionNode),
+    I32x4TruncSatF64x2SZero(I32x4TruncSatF64x2SZeroInstructionNode),
+    I32x4TruncSatF64x2UZero(I32x4TruncSatF64x2UZeroInstructionNode),
+    F64x2ConvertLowI32x4S(F64x2ConvertLowI32x4SInstructionNode),
+    F64x2ConvertLowI32x4U(F64x2ConvertLowI32x4UInstructionNode),
+    MemoryAtomicNotify(AtomicMemoryAccessInstructionNode),
+    MemoryAtomicWait32(AtomicMemoryAccessInstructionNode),
+    MemoryAtomicWait64(AtomicMemoryAccessInstructionNode),
+    AtomicFence(AtomicFenceInstructionNode),
+    I32AtomicLoad(AtomicMemoryAccessInstructionNode),
+    I64AtomicLoad(AtomicMemoryAccessInstructionNode),
+    I32AtomicLoad8U(AtomicMemoryAccessInstructionNode),
+    I32AtomicLoad16U(AtomicMemoryAccessInstructionNode),
+    I64AtomicLoad8U(AtomicMemoryAccessInstructionNode),
+    I64AtomicLoad16U(AtomicMemoryAccessInstructionNode),
+    I64AtomicLoad32U(AtomicMemoryAccessInstructionNode),
+    I32AtomicStore(AtomicMemoryAccessInstructionNode),
+    I64AtomicStore(AtomicMemoryAccessInstructionNode),
+    I32AtomicStore8(AtomicMemoryAccessInstructionNode),
+    I32AtomicStore16(AtomicMemoryAccessInstructionNode),
+    I64AtomicStore8(AtomicMemoryAccessInstructionNode),
+    I64AtomicStore16(AtomicMemoryAccessInstructionNode),
+    I64AtomicStore32(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmwAdd(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmwAdd(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw8AddU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw16AddU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw8AddU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw16AddU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw32AddU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmwSub(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmwSub(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw8SubU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw16SubU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw8SubU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw16SubU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw32SubU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmwAnd(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmwAnd(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw8AndU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw16AndU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw8AndU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw16AndU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw32AndU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmwOr(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmwOr(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw8OrU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw16OrU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw8OrU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw16OrU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw32OrU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmwXor(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmwXor(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw8XorU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw16XorU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw8XorU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw16XorU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw32XorU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmwXchg(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmwXchg(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw8XchgU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw16XchgU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw8XchgU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw16XchgU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw32XchgU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmwCmpxchg(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmwCmpxchg(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw8CmpxchgU(AtomicMemoryAccessInstructionNode),
+    I32AtomicRmw16CmpxchgU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw8CmpxchgU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw16CmpxchgU(AtomicMemoryAccessInstructionNode),
+    I64AtomicRmw32CmpxchgU(AtomicMemoryAccessInstructionNode),
+}
+
+impl InstructionNode {
+    /// The memarg of a load, store or atomic access, all of which address
+    /// memory 0.
+    pub fn memarg(&self) -> Option<&MemArgNode> {
+        match self {
+            InstructionNode::I32Load(x)
+            | InstructionNode::I64Load(x)
+            | InstructionNode::F32Load(x)
+            | InstructionNode::F64Load(x)
+            | InstructionNode::I32Load8S(x)
+            | InstructionNode::I32Load8U(x)
+            | InstructionNode::I32Load16S(x)
+            | InstructionNode::I32Load16U(x)
+            | InstructionNode::I64Load8S(x)
+            | InstructionNode::I64Load8U(x)
+            | InstructionNode::I64Load16S(x)
+            | InstructionNode::I64Load16U(x)
+            | InstructionNode::I64Load32S(x)
+            | InstructionNode::I64Load32U(x)
+            | InstructionNode::I32Store(x)
+            | InstructionNode::I64Store(x)
+            | InstructionNode::F32Store(x)
+            | InstructionNode::F64Store(x)
+            | InstructionNode::I32Store8(x)
+            | InstructionNode::I32Store16(x)
+            | InstructionNode::I64Store8(x)
+            | InstructionNode::I64Store16(x)
+            | InstructionNode::I64Store32(x) => Some(&x.memarg),
+            InstructionNode::V128Load(x)
+            | InstructionNode::V128Load8x8S(x)
+            | InstructionNode::V128Load8x8U(x)
+            | InstructionNode::V128Load16x4S(x)
+            | InstructionNode::V128Load16x4U(x)
+            | InstructionNode::V128Load32x2S(x)
+            | InstructionNode::V128Load32x2U(x)
+            | InstructionNode::V128Load8Splat(x)
+            | InstructionNode::V128Load16Splat(x)
+            | InstructionNode::V128Load32Splat(x)
+            | InstructionNode::V128Load64Splat(x)
+            | InstructionNode::V128Store(x)
+            | InstructionNode::V128Load32Zero(x)
+            | InstructionNode::V128Load64Zero(x) => Some(&x.memarg),
+            InstructionNode::V128Load8Lane(x)
+            | InstructionNode::V128Load16Lane(x)
+            | InstructionNode::V128Load32Lane(x)
+            | InstructionNode::V128Load64Lane(x)
+            | InstructionNode::V128Store8Lane(x)
+            | InstructionNode::V128Store16Lane(x)
+            | InstructionNode::V128Store32Lane(x)
+            | InstructionNode::V128Store64Lane(x) => Some(&x.memarg),
+            InstructionNode::MemoryAtomicNotify(x)
+            | InstructionNode::MemoryAtomicWait32(x)
+            | InstructionNode::MemoryAtomicWait64(x)
+            | InstructionNode::I32AtomicLoad(x)
+            | InstructionNode::I64AtomicLoad(x)
+            | InstructionNode::I32AtomicLoad8U(x)
+            | InstructionNode::I32AtomicLoad16U(x)
+            | InstructionNode::I64AtomicLoad8U(x)
+            | InstructionNode::I64AtomicLoad16U(x)
+            | InstructionNode::I64AtomicLoad32U(x)
+            | InstructionNode::I32AtomicStore(x)
+            | InstructionNode::I64AtomicStore(x)
+            | InstructionNode::I32AtomicStore8(x)
+            | InstructionNode::I32AtomicStore16(x)
+            | InstructionNode::I64AtomicStore8(x)
+            | InstructionNode::I64AtomicStore16(x)
+            | InstructionNode::I64AtomicStore32(x)
+            | InstructionNode::I32AtomicRmwAdd(x)
+            | InstructionNode::I64AtomicRmwAdd(x)
+            | InstructionNode::I32AtomicRmw8AddU(x)
+            | InstructionNode::I32AtomicRmw16AddU(x)
+            | InstructionNode::I64AtomicRmw8AddU(x)
+            | InstructionNode::I64AtomicRmw16AddU(x)
+            | InstructionNode::I64AtomicRmw32AddU(x)
+            | InstructionNode::I32AtomicRmwSub(x)
+            | InstructionNode::I64AtomicRmwSub(x)
+            | InstructionNode::I32AtomicRmw8SubU(x)
+            | InstructionNode::I32AtomicRmw16SubU(x)
+            | InstructionNode::I64AtomicRmw8SubU(x)
+            | InstructionNode::I64AtomicRmw16SubU(x)
+            | InstructionNode::I64AtomicRmw32SubU(x)
+            | InstructionNode::I32AtomicRmwAnd(x)
+            | InstructionNode::I64AtomicRmwAnd(x)
+            | InstructionNode::I32AtomicRmw8AndU(x)
+            | InstructionNode::I32AtomicRmw16AndU(x)
+            | InstructionNode::I64AtomicRmw8AndU(x)
+            | InstructionNode::I64AtomicRmw16AndU(x)
+            | InstructionNode::I64AtomicRmw32AndU(x)
+            | InstructionNode::I32AtomicRmwOr(x)
+            | InstructionNode::I64AtomicRmwOr(x)
+            | InstructionNode::I32AtomicRmw8OrU(x)
+            | InstructionNode::I32AtomicRmw16OrU(x)
+            | InstructionNode::I64AtomicRmw8OrU(x)
+            | InstructionNode::I64AtomicRmw16OrU(x)
+            | InstructionNode::I64AtomicRmw32OrU(x)
+            | InstructionNode::I32AtomicRmwXor(x)
+            | InstructionNode::I64AtomicRmwXor(x)
+            | InstructionNode::I32AtomicRmw8XorU(x)
+            | InstructionNode::I32AtomicRmw16XorU(x)
+            | InstructionNode::I64AtomicRmw8XorU(x)
+            | InstructionNode::I64AtomicRmw16XorU(x)
+            | InstructionNode::I64AtomicRmw32XorU(x)
+            | InstructionNode::I32AtomicRmwXchg(x)
+            | InstructionNode::I64AtomicRmwXchg(x)
+            | InstructionNode::I32AtomicRmw8XchgU(x)
+            | InstructionNode::I32AtomicRmw16XchgU(x)
+            | InstructionNode::I64AtomicRmw8XchgU(x)
+            | InstructionNode::I64AtomicRmw16XchgU(x)
+            | InstructionNode::I64AtomicRmw32XchgU(x)
+            | InstructionNode::I32AtomicRmwCmpxchg(x)
+            | InstructionNode::I64AtomicRmwCmpxchg(x)
+            | InstructionNode::I32AtomicRmw8CmpxchgU(x)
+            | InstructionNode::I32AtomicRmw16CmpxchgU(x)
+            | InstructionNode::I64AtomicRmw8CmpxchgU(x)
+            | InstructionNode::I64AtomicRmw16CmpxchgU(x)
+            | InstructionNode::I64AtomicRmw32CmpxchgU(x) => Some(&x.memarg),
+            _ => None,
+        }
+    }
 }
 
 impl Node for InstructionNode {
@@ -313,31 +1146,503 @@ impl Node for InstructionNode {
             InstructionNode::Else(x) => x.size(),
             InstructionNode::Br(x) => x.size(),
             InstructionNode::BrIf(x) => x.size(),
+            InstructionNode::BrTable(x) => x.size(),
+            InstructionNode::Return(x) => x.size(),
+            InstructionNode::Unreachable(x) => x.size(),
+            InstructionNode::Nop(x) => x.size(),
             InstructionNode::Call(x) => x.size(),
+            InstructionNode::CallIndirect(x) => x.size(),
+            InstructionNode::Drop(x) => x.size(),
+            InstructionNode::Select(x) => x.size(),
             InstructionNode::End(x) => x.size(),
             InstructionNode::I32Const(x) => x.size(),
+            InstructionNode::I64Const(x) => x.size(),
+            InstructionNode::F32Const(x) => x.size(),
+            InstructionNode::F64Const(x) => x.size(),
             InstructionNode::GetLocal(x) => x.size(),
             InstructionNode::SetLocal(x) => x.size(),
-            InstructionNode::I32Add(x) => x.size(),
-            // InstructionNode::I32Sub(x) => x.size(),
-            InstructionNode::I32RemS(x) => x.size(),
-            InstructionNode::I32RemU(x) => x.size(),
-            // InstructionNode::I32Shl(x) => x.size(),
+            InstructionNode::TeeLocal(x) => x.size(),
+            InstructionNode::GetGlobal(x) => x.size(),
+            InstructionNode::SetGlobal(x) => x.size(),
+            InstructionNode::I32Load(x) => x.size(),
+            InstructionNode::I64Load(x) => x.size(),
+            InstructionNode::F32Load(x) => x.size(),
+            InstructionNode::F64Load(x) => x.size(),
+            InstructionNode::I32Load8S(x) => x.size(),
+            InstructionNode::I32Load8U(x) => x.size(),
+            InstructionNode::I32Load16S(x) => x.size(),
+            InstructionNode::I32Load16U(x) => x.size(),
+            InstructionNode::I64Load8S(x) => x.size(),
+            InstructionNode::I64Load8U(x) => x.size(),
+            InstructionNode::I64Load16S(x) => x.size(),
+            InstructionNode::I64Load16U(x) => x.size(),
+            InstructionNode::I64Load32S(x) => x.size(),
+            InstructionNode::I64Load32U(x) => x.size(),
+            InstructionNode::I32Store(x) => x.size(),
+            InstructionNode::I64Store(x) => x.size(),
+            InstructionNode::F32Store(x) => x.size(),
+            InstructionNode::F64Store(x) => x.size(),
+            InstructionNode::I32Store8(x) => x.size(),
+            InstructionNode::I32Store16(x) => x.size(),
+            InstructionNode::I64Store8(x) => x.size(),
+            InstructionNode::I64Store16(x) => x.size(),
+            InstructionNode::I64Store32(x) => x.size(),
+            InstructionNode::MemorySize(x) => x.size(),
+            InstructionNode::MemoryGrow(x) => x.size(),
+            InstructionNode::RefNull(x) => x.size(),
+            InstructionNode::RefIsNull(x) => x.size(),
+            InstructionNode::RefFunc(x) => x.size(),
+            InstructionNode::TableGet(x) => x.size(),
+            InstructionNode::TableSet(x) => x.size(),
             InstructionNode::I32Eqz(x) => x.size(),
             InstructionNode::I32Eq(x) => x.size(),
+            InstructionNode::I32Ne(x) => x.size(),
             InstructionNode::I32LtS(x) => x.size(),
             InstructionNode::I32LtU(x) => x.size(),
+            InstructionNode::I32GtS(x) => x.size(),
+            InstructionNode::I32GtU(x) => x.size(),
+            InstructionNode::I32LeS(x) => x.size(),
+            InstructionNode::I32LeU(x) => x.size(),
             InstructionNode::I32GeS(x) => x.size(),
+            InstructionNode::I32GeU(x) => x.size(),
+            InstructionNode::I32Clz(x) => x.size(),
+            InstructionNode::I32Ctz(x) => x.size(),
+            InstructionNode::I32Popcnt(x) => x.size(),
+            InstructionNode::I32Add(x) => x.size(),
             InstructionNode::I32Sub(x) => x.size(),
-            // InstructionNode::I32GeU(x) => x.size(),
-            // InstructionNode::I32GtS(x) => x.size(),
-            // InstructionNode::I32GtU(x) => x.size(),
-            // InstructionNode::I64Add(x) => x.size(),
-            // InstructionNode::I64Sub(x) => x.size(),
-            // InstructionNode::Unreachable => 1,
-            // InstructionNode::Nop => 1,
-            // InstructionNode::BrTable(x, y) => 1 + encode_u32_to_leb128(x.len() as u32).len() as u32 + (x.len() as u32 * 4) + 4,
-            // InstructionNode::Return => 1,
+            InstructionNode::I32Mul(x) => x.size(),
+            InstructionNode::I32DivS(x) => x.size(),
+            InstructionNode::I32DivU(x) => x.size(),
+            InstructionNode::I32RemS(x) => x.size(),
+            InstructionNode::I32RemU(x) => x.size(),
+            InstructionNode::I32And(x) => x.size(),
+            InstructionNode::I32Or(x) => x.size(),
+            InstructionNode::I32Xor(x) => x.size(),
+            InstructionNode::I32Shl(x) => x.size(),
+            InstructionNode::I32ShrS(x) => x.size(),
+            InstructionNode::I32ShrU(x) => x.size(),
+            InstructionNode::I32Rotl(x) => x.size(),
+            InstructionNode::I32Rotr(x) => x.size(),
+            InstructionNode::I64Eqz(x) => x.size(),
+            InstructionNode::I64Eq(x) => x.size(),
+            InstructionNode::I64Ne(x) => x.size(),
+            InstructionNode::I64LtS(x) => x.size(),
+            InstructionNode::I64LtU(x) => x.size(),
+            InstructionNode::I64GtS(x) => x.size(),
+            InstructionNode::I64GtU(x) => x.size(),
+            InstructionNode::I64LeS(x) => x.size(),
+            InstructionNode::I64LeU(x) => x.size(),
+            InstructionNode::I64GeS(x) => x.size(),
+            InstructionNode::I64GeU(x) => x.size(),
+            InstructionNode::I64Clz(x) => x.size(),
+            InstructionNode::I64Ctz(x) => x.size(),
+            InstructionNode::I64Popcnt(x) => x.size(),
+            InstructionNode::I64Add(x) => x.size(),
+            InstructionNode::I64Sub(x) => x.size(),
+            InstructionNode::I64Mul(x) => x.size(),
+            InstructionNode::I64DivS(x) => x.size(),
+            InstructionNode::I64DivU(x) => x.size(),
+            InstructionNode::I64RemS(x) => x.size(),
+            InstructionNode::I64RemU(x) => x.size(),
+            InstructionNode::I64And(x) => x.size(),
+            InstructionNode::I64Or(x) => x.size(),
+            InstructionNode::I64Xor(x) => x.size(),
+            InstructionNode::I64Shl(x) => x.size(),
+            InstructionNode::I64ShrS(x) => x.size(),
+            InstructionNode::I64ShrU(x) => x.size(),
+            InstructionNode::I64Rotl(x) => x.size(),
+            InstructionNode::I64Rotr(x) => x.size(),
+            InstructionNode::F32Eq(x) => x.size(),
+            InstructionNode::F32Ne(x) => x.size(),
+            InstructionNode::F32Lt(x) => x.size(),
+            InstructionNode::F32Gt(x) => x.size(),
+            InstructionNode::F32Le(x) => x.size(),
+            InstructionNode::F32Ge(x) => x.size(),
+            InstructionNode::F64Eq(x) => x.size(),
+            InstructionNode::F64Ne(x) => x.size(),
+            InstructionNode::F64Lt(x) => x.size(),
+            InstructionNode::F64Gt(x) => x.size(),
+            InstructionNode::F64Le(x) => x.size(),
+            InstructionNode::F64Ge(x) => x.size(),
+            InstructionNode::F32Abs(x) => x.size(),
+            InstructionNode::F32Neg(x) => x.size(),
+            InstructionNode::F32Ceil(x) => x.size(),
+            InstructionNode::F32Floor(x) => x.size(),
+            InstructionNode::F32Trunc(x) => x.size(),
+            InstructionNode::F32Nearest(x) => x.size(),
+            InstructionNode::F32Sqrt(x) => x.size(),
+            InstructionNode::F32Add(x) => x.size(),
+            InstructionNode::F32Sub(x) => x.size(),
+            InstructionNode::F32Mul(x) => x.size(),
+            InstructionNode::F32Div(x) => x.size(),
+            InstructionNode::F32Min(x) => x.size(),
+            InstructionNode::F32Max(x) => x.size(),
+            InstructionNode::F32Copysign(x) => x.size(),
+            InstructionNode::F64Abs(x) => x.size(),
+            InstructionNode::F64Neg(x) => x.size(),
+            InstructionNode::F64Ceil(x) => x.size(),
+            InstructionNode::F64Floor(x) => x.size(),
+            InstructionNode::F64Trunc(x) => x.size(),
+            InstructionNode::F64Nearest(x) => x.size(),
+            InstructionNode::F64Sqrt(x) => x.size(),
+            InstructionNode::F64Add(x) => x.size(),
+            InstructionNode::F64Sub(x) => x.size(),
+            InstructionNode::F64Mul(x) => x.size(),
+            InstructionNode::F64Div(x) => x.size(),
+            InstructionNode::F64Min(x) => x.size(),
+            InstructionNode::F64Max(x) => x.size(),
+            InstructionNode::F64Copysign(x) => x.size(),
+            InstructionNode::I32WrapI64(x) => x.size(),
+            InstructionNode::I32TruncF32S(x) => x.size(),
+            InstructionNode::I32TruncF32U(x) => x.size(),
+            InstructionNode::I32TruncF64S(x) => x.size(),
+            InstructionNode::I32TruncF64U(x) => x.size(),
+            InstructionNode::I64ExtendI32S(x) => x.size(),
+            InstructionNode::I64ExtendI32U(x) => x.size(),
+            InstructionNode::I64TruncF32S(x) => x.size(),
+            InstructionNode::I64TruncF32U(x) => x.size(),
+            InstructionNode::I64TruncF64S(x) => x.size(),
+            InstructionNode::I64TruncF64U(x) => x.size(),
+            InstructionNode::F32ConvertI32S(x) => x.size(),
+            InstructionNode::F32ConvertI32U(x) => x.size(),
+            InstructionNode::F32ConvertI64S(x) => x.size(),
+            InstructionNode::F32ConvertI64U(x) => x.size(),
+            InstructionNode::F32DemoteF64(x) => x.size(),
+            InstructionNode::F64ConvertI32S(x) => x.size(),
+            InstructionNode::F64ConvertI32U(x) => x.size(),
+            InstructionNode::F64ConvertI64S(x) => x.size(),
+            InstructionNode::F64ConvertI64U(x) => x.size(),
+            InstructionNode::F64PromoteF32(x) => x.size(),
+            InstructionNode::I32ReinterpretF32(x) => x.size(),
+            InstructionNode::I64ReinterpretF64(x) => x.size(),
+            InstructionNode::F32ReinterpretI32(x) => x.size(),
+            InstructionNode::F64ReinterpretI64(x) => x.size(),
+            InstructionNode::I32Extend8S(x) => x.size(),
+            InstructionNode::I32Extend16S(x) => x.size(),
+            InstructionNode::I64Extend8S(x) => x.size(),
+            InstructionNode::I64Extend16S(x) => x.size(),
+            InstructionNode::I64Extend32S(x) => x.size(),
+            InstructionNode::I32TruncSatF32S(x) => x.size(),
+            InstructionNode::I32TruncSatF32U(x) => x.size(),
+            InstructionNode::I32TruncSatF64S(x) => x.size(),
+            InstructionNode::I32TruncSatF64U(x) => x.size(),
+            InstructionNode::I64TruncSatF32S(x) => x.size(),
+            InstructionNode::I64TruncSatF32U(x) => x.size(),
+            InstructionNode::I64TruncSatF64S(x) => x.size(),
+            InstructionNode::I64TruncSatF64U(x) => x.size(),
+            InstructionNode::MemoryInit(x) => x.size(),
+            InstructionNode::DataDrop(x) => x.size(),
+            InstructionNode::MemoryCopy(x) => x.size(),
+            InstructionNode::MemoryFill(x) => x.size(),
+            InstructionNode::TableInit(x) => x.size(),
+            InstructionNode::ElemDrop(x) => x.size(),
+            InstructionNode::TableCopy(x) => x.size(),
+            InstructionNode::TableGrow(x) => x.size(),
+            InstructionNode::TableSize(x) => x.size(),
+            InstructionNode::TableFill(x) => x.size(),
+            InstructionNode::V128Load(x) => x.size(),
+            InstructionNode::V128Load8x8S(x) => x.size(),
+            InstructionNode::V128Load8x8U(x) => x.size(),
+            InstructionNode::V128Load16x4S(x) => x.size(),
+            InstructionNode::V128Load16x4U(x) => x.size(),
+            InstructionNode::V128Load32x2S(x) => x.size(),
+            InstructionNode::V128Load32x2U(x) => x.size(),
+            InstructionNode::V128Load8Splat(x) => x.size(),
+            InstructionNode::V128Load16Splat(x) => x.size(),
+            InstructionNode::V128Load32Splat(x) => x.size(),
+            InstructionNode::V128Load64Splat(x) => x.size(),
+            InstructionNode::V128Store(x) => x.size(),
+            InstructionNode::V128Const(x) => x.size(),
+            InstructionNode::I8x16Shuffle(x) => x.size(),
+            InstructionNode::I8x16Swizzle(x) => x.size(),
+            InstructionNode::I8x16Splat(x) => x.size(),
+            InstructionNode::I16x8Splat(x) => x.size(),
+            InstructionNode::I32x4Splat(x) => x.size(),
+            InstructionNode::I64x2Splat(x) => x.size(),
+            InstructionNode::F32x4Splat(x) => x.size(),
+            InstructionNode::F64x2Splat(x) => x.size(),
+            InstructionNode::I8x16ExtractLaneS(x) => x.size(),
+            InstructionNode::I8x16ExtractLaneU(x) => x.size(),
+            InstructionNode::I8x16ReplaceLane(x) => x.size(),
+            InstructionNode::I16x8ExtractLaneS(x) => x.size(),
+            InstructionNode::I16x8ExtractLaneU(x) => x.size(),
+            InstructionNode::I16x8ReplaceLane(x) => x.size(),
+            InstructionNode::I32x4ExtractLane(x) => x.size(),
+            InstructionNode::I32x4ReplaceLane(x) => x.size(),
+            InstructionNode::I64x2ExtractLane(x) => x.size(),
+            InstructionNode::I64x2ReplaceLane(x) => x.size(),
+            InstructionNode::F32x4ExtractLane(x) => x.size(),
+            InstructionNode::F32x4ReplaceLane(x) => x.size(),
+            InstructionNode::F64x2ExtractLane(x) => x.size(),
+            InstructionNode::F64x2ReplaceLane(x) => x.size(),
+            InstructionNode::I8x16Eq(x) => x.size(),
+            InstructionNode::I8x16Ne(x) => x.size(),
+            InstructionNode::I8x16LtS(x) => x.size(),
+            InstructionNode::I8x16LtU(x) => x.size(),
+            InstructionNode::I8x16GtS(x) => x.size(),
+            InstructionNode::I8x16GtU(x) => x.size(),
+            InstructionNode::I8x16LeS(x) => x.size(),
+            InstructionNode::I8x16LeU(x) => x.size(),
+            InstructionNode::I8x16GeS(x) => x.size(),
+            InstructionNode::I8x16GeU(x) => x.size(),
+            InstructionNode::I16x8Eq(x) => x.size(),
+            InstructionNode::I16x8Ne(x) => x.size(),
+            InstructionNode::I16x8LtS(x) => x.size(),
+            InstructionNode::I16x8LtU(x) => x.size(),
+            InstructionNode::I16x8GtS(x) => x.size(),
+            InstructionNode::I16x8GtU(x) => x.size(),
+            InstructionNode::I16x8LeS(x) => x.size(),
+            InstructionNode::I16x8LeU(x) => x.size(),
+            InstructionNode::I16x8GeS(x) => x.size(),
+            InstructionNode::I16x8GeU(x) => x.size(),
+            InstructionNode::I32x4Eq(x) => x.size(),
+            InstructionNode::I32x4Ne(x) => x.size(),
+            InstructionNode::I32x4LtS(x) => x.size(),
+            InstructionNode::I32x4LtU(x) => x.size(),
+            InstructionNode::I32x4GtS(x) => x.size(),
+            InstructionNode::I32x4GtU(x) => x.size(),
+            InstructionNode::I32x4LeS(x) => x.size(),
+            InstructionNode::I32x4LeU(x) => x.size(),
+            InstructionNode::I32x4GeS(x) => x.size(),
+            InstructionNode::I32x4GeU(x) => x.size(),
+            InstructionNode::F32x4Eq(x) => x.size(),
+            InstructionNode::F32x4Ne(x) => x.size(),
+            InstructionNode::F32x4Lt(x) => x.size(),
+            InstructionNode::F32x4Gt(x) => x.size(),
+            InstructionNode::F32x4Le(x) => x.size(),
+            InstructionNode::F32x4Ge(x) => x.size(),
+            InstructionNode::F64x2Eq(x) => x.size(),
+            InstructionNode::F64x2Ne(x) => x.size(),
+            InstructionNode::F64x2Lt(x) => x.size(),
+            InstructionNode::F64x2Gt(x) => x.size(),
+            InstructionNode::F64x2Le(x) => x.size(),
+            InstructionNode::F64x2Ge(x) => x.size(),
+            InstructionNode::V128Not(x) => x.size(),
+            InstructionNode::V128And(x) => x.size(),
+            InstructionNode::V128Andnot(x) => x.size(),
+            InstructionNode::V128Or(x) => x.size(),
+            InstructionNode::V128Xor(x) => x.size(),
+            InstructionNode::V128Bitselect(x) => x.size(),
+            InstructionNode::V128AnyTrue(x) => x.size(),
+            InstructionNode::V128Load8Lane(x) => x.size(),
+            InstructionNode::V128Load16Lane(x) => x.size(),
+            InstructionNode::V128Load32Lane(x) => x.size(),
+            InstructionNode::V128Load64Lane(x) => x.size(),
+            InstructionNode::V128Store8Lane(x) => x.size(),
+            InstructionNode::V128Store16Lane(x) => x.size(),
+            InstructionNode::V128Store32Lane(x) => x.size(),
+            InstructionNode::V128Store64Lane(x) => x.size(),
+            InstructionNode::V128Load32Zero(x) => x.size(),
+            InstructionNode::V128Load64Zero(x) => x.size(),
+            InstructionNode::F32x4DemoteF64x2Zero(x) => x.size(),
+            InstructionNode::F64x2PromoteLowF32x4(x) => x.size(),
+            InstructionNode::I8x16Abs(x) => x.size(),
+            InstructionNode::I8x16Neg(x) => x.size(),
+            InstructionNode::I8x16Popcnt(x) => x.size(),
+            InstructionNode::I8x16AllTrue(x) => x.size(),
+            InstructionNode::I8x16Bitmask(x) => x.size(),
+            InstructionNode::I8x16NarrowI16x8S(x) => x.size(),
+            InstructionNode::I8x16NarrowI16x8U(x) => x.size(),
+            InstructionNode::F32x4Ceil(x) => x.size(),
+            InstructionNode::F32x4Floor(x) => x.size(),
+            InstructionNode::F32x4Trunc(x) => x.size(),
+            InstructionNode::F32x4Nearest(x) => x.size(),
+            InstructionNode::I8x16Shl(x) => x.size(),
+            InstructionNode::I8x16ShrS(x) => x.size(),
+            InstructionNode::I8x16ShrU(x) => x.size(),
+            InstructionNode::I8x16Add(x) => x.size(),
+            InstructionNode::I8x16AddSatS(x) => x.size(),
+            InstructionNode::I8x16AddSatU(x) => x.size(),
+            InstructionNode::I8x16Sub(x) => x.size(),
+            InstructionNode::I8x16SubSatS(x) => x.size(),
+            InstructionNode::I8x16SubSatU(x) => x.size(),
+            InstructionNode::F64x2Ceil(x) => x.size(),
+            InstructionNode::F64x2Floor(x) => x.size(),
+            InstructionNode::I8x16MinS(x) => x.size(),
+            InstructionNode::I8x16MinU(x) => x.size(),
+            InstructionNode::I8x16MaxS(x) => x.size(),
+            InstructionNode::I8x16MaxU(x) => x.size(),
+            InstructionNode::F64x2Trunc(x) => x.size(),
+            InstructionNode::I8x16AvgrU(x) => x.size(),
+            InstructionNode::I16x8ExtaddPairwiseI8x16S(x) => x.size(),
+            InstructionNode::I16x8ExtaddPairwiseI8x16U(x) => x.size(),
+            InstructionNode::I32x4ExtaddPairwiseI16x8S(x) => x.size(),
+            InstructionNode::I32x4ExtaddPairwiseI16x8U(x) => x.size(),
+            InstructionNode::I16x8Abs(x) => x.size(),
+            InstructionNode::I16x8Neg(x) => x.size(),
+            InstructionNode::I16x8Q15mulrSatS(x) => x.size(),
+            InstructionNode::I16x8AllTrue(x) => x.size(),
+            InstructionNode::I16x8Bitmask(x) => x.size(),
+            InstructionNode::I16x8NarrowI32x4S(x) => x.size(),
+            InstructionNode::I16x8NarrowI32x4U(x) => x.size(),
+            InstructionNode::I16x8ExtendLowI8x16S(x) => x.size(),
+            InstructionNode::I16x8ExtendHighI8x16S(x) => x.size(),
+            InstructionNode::I16x8ExtendLowI8x16U(x) => x.size(),
+            InstructionNode::I16x8ExtendHighI8x16U(x) => x.size(),
+            InstructionNode::I16x8Shl(x) => x.size(),
+            InstructionNode::I16x8ShrS(x) => x.size(),
+            InstructionNode::I16x8ShrU(x) => x.size(),
+            InstructionNode::I16x8Add(x) => x.size(),
+            InstructionNode::I16x8AddSatS(x) => x.size(),
+            InstructionNode::I16x8AddSatU(x) => x.size(),
+            InstructionNode::I16x8Sub(x) => x.size(),
+            InstructionNode::I16x8SubSatS(x) => x.size(),
+            InstructionNode::I16x8SubSatU(x) => x.size(),
+            InstructionNode::F64x2Nearest(x) => x.size(),
+            InstructionNode::I16x8Mul(x) => x.size(),
+            InstructionNode::I16x8MinS(x) => x.size(),
+            InstructionNode::I16x8MinU(x) => x.size(),
+            InstructionNode::I16x8MaxS(x) => x.size(),
+            InstructionNode::I16x8MaxU(x) => x.size(),
+            InstructionNode::I16x8AvgrU(x) => x.size(),
+            InstructionNode::I16x8ExtmulLowI8x16S(x) => x.size(),
+            InstructionNode::I16x8ExtmulHighI8x16S(x) => x.size(),
+            InstructionNode::I16x8ExtmulLowI8x16U(x) => x.size(),
+            InstructionNode::I16x8ExtmulHighI8x16U(x) => x.size(),
+            InstructionNode::I32x4Abs(x) => x.size(),
+            InstructionNode::I32x4Neg(x) => x.size(),
+            InstructionNode::I32x4AllTrue(x) => x.size(),
+            InstructionNode::I32x4Bitmask(x) => x.size(),
+            InstructionNode::I32x4ExtendLowI16x8S(x) => x.size(),
+            InstructionNode::I32x4ExtendHighI16x8S(x) => x.size(),
+            InstructionNode::I32x4ExtendLowI16x8U(x) => x.size(),
+            InstructionNode::I32x4ExtendHighI16x8U(x) => x.size(),
+            InstructionNode::I32x4Shl(x) => x.size(),
+            InstructionNode::I32x4ShrS(x) => x.size(),
+            InstructionNode::I32x4ShrU(x) => x.size(),
+            InstructionNode::I32x4Add(x) => x.size(),
+            InstructionNode::I32x4Sub(x) => x.size(),
+            InstructionNode::I32x4Mul(x) => x.size(),
+            InstructionNode::I32x4MinS(x) => x.size(),
+            InstructionNode::I32x4MinU(x) => x.size(),
+            InstructionNode::I32x4MaxS(x) => x.size(),
+            InstructionNode::I32x4MaxU(x) => x.size(),
+            InstructionNode::I32x4DotI16x8S(x) => x.size(),
+            InstructionNode::I32x4ExtmulLowI16x8S(x) => x.size(),
+            InstructionNode::I32x4ExtmulHighI16x8S(x) => x.size(),
+            InstructionNode::I32x4ExtmulLowI16x8U(x) => x.size(),
+            InstructionNode::I32x4ExtmulHighI16x8U(x) => x.size(),
+            InstructionNode::I64x2Abs(x) => x.size(),
+            InstructionNode::I64x2Neg(x) => x.size(),
+            InstructionNode::I64x2AllTrue(x) => x.size(),
+            InstructionNode::I64x2Bitmask(x) => x.size(),
+            InstructionNode::I64x2ExtendLowI32x4S(x) => x.size(),
+            InstructionNode::I64x2ExtendHighI32x4S(x) => x.size(),
+            InstructionNode::I64x2ExtendLowI32x4U(x) => x.size(),
+            InstructionNode::I64x2ExtendHighI32x4U(x) => x.size(),
+            InstructionNode::I64x2Shl(x) => x.size(),
+            InstructionNode::I64x2ShrS(x) => x.size(),
+            InstructionNode::I64x2ShrU(x) => x.size(),
+            InstructionNode::I64x2Add(x) => x.size(),
+            InstructionNode::I64x2Sub(x) => x.size(),
+            InstructionNode::I64x2Mul(x) => x.size(),
+            InstructionNode::I64x2Eq(x) => x.size(),
+            InstructionNode::I64x2Ne(x) => x.size(),
+            InstructionNode::I64x2LtS(x) => x.size(),
+            InstructionNode::I64x2GtS(x) => x.size(),
+            InstructionNode::I64x2LeS(x) => x.size(),
+            InstructionNode::I64x2GeS(x) => x.size(),
+            InstructionNode::I64x2ExtmulLowI32x4S(x) => x.size(),
+            InstructionNode::I64x2ExtmulHighI32x4S(x) => x.size(),
+            InstructionNode::I64x2ExtmulLowI32x4U(x) => x.size(),
+            InstructionNode::I64x2ExtmulHighI32x4U(x) => x.size(),
+            InstructionNode::F32x4Abs(x) => x.size(),
+            InstructionNode::F32x4Neg(x) => x.size(),
+            InstructionNode::F32x4Sqrt(x) => x.size(),
+            InstructionNode::F32x4Add(x) => x.size(),
+            InstructionNode::F32x4Sub(x) => x.size(),
+            InstructionNode::F32x4Mul(x) => x.size(),
+            InstructionNode::F32x4Div(x) => x.size(),
+            InstructionNode::F32x4Min(x) => x.size(),
+            InstructionNode::F32x4Max(x) => x.size(),
+            InstructionNode::F32x4Pmin(x) => x.size(),
+            InstructionNode::F32x4Pmax(x) => x.size(),
+            InstructionNode::F64x2Abs(x) => x.size(),
+            InstructionNode::F64x2Neg(x) => x.size(),
+            InstructionNode::F64x2Sqrt(x) => x.size(),
+            InstructionNode::F64x2Add(x) => x.size(),
+            InstructionNode::F64x2Sub(x) => x.size(),
+            InstructionNode::F64x2Mul(x) => x.size(),
+            InstructionNode::F64x2Div(x) => x.size(),
+            InstructionNode::F64x2Min(x) => x.size(),
+            InstructionNode::F64x2Max(x) => x.size(),
+            InstructionNode::F64x2Pmin(x) => x.size(),
+            InstructionNode::F64x2Pmax(x) => x.size(),
+            InstructionNode::I32x4TruncSatF32x4S(x) => x.size(),
+            InstructionNode::I32x4TruncSatF32x4U(x) => x.size(),
+            InstructionNode::F32x4ConvertI32x4S(x) => x.size(),
+            InstructionNode::F32x4ConvertI32x4U(x) => x.size(),
+            InstructionNode::I32x4TruncSatF64x2SZero(x) => x.size(),
+            InstructionNode::I32x4TruncSatF64x2UZero(x) => x.size(),
+            InstructionNode::F64x2ConvertLowI32x4S(x) => x.size(),
+            InstructionNode::F64x2ConvertLowI32x4U(x) => x.size(),
+            InstructionNode::MemoryAtomicNotify(x) => x.size(),
+            InstructionNode::MemoryAtomicWait32(x) => x.size(),
+            InstructionNode::MemoryAtomicWait64(x) => x.size(),
+            InstructionNode::AtomicFence(x) => x.size(),
+            InstructionNode::I32AtomicLoad(x) => x.size(),
+            InstructionNode::I64AtomicLoad(x) => x.size(),
+            InstructionNode::I32AtomicLoad8U(x) => x.size(),
+            InstructionNode::I32AtomicLoad16U(x) => x.size(),
+            InstructionNode::I64AtomicLoad8U(x) => x.size(),
+            InstructionNode::I64AtomicLoad16U(x) => x.size(),
+            InstructionNode::I64AtomicLoad32U(x) => x.size(),
+            InstructionNode::I32AtomicStore(x) => x.size(),
+            InstructionNode::I64AtomicStore(x) => x.size(),
+            InstructionNode::I32AtomicStore8(x) => x.size(),
+            InstructionNode::I32AtomicStore16(x) => x.size(),
+            InstructionNode::I64AtomicStore8(x) => x.size(),
+            InstructionNode::I64AtomicStore16(x) => x.size(),
+            InstructionNode::I64AtomicStore32(x) => x.size(),
+            InstructionNode::I32AtomicRmwAdd(x) => x.size(),
+            InstructionNode::I64AtomicRmwAdd(x) => x.size(),
+            InstructionNode::I32AtomicRmw8AddU(x) => x.size(),
+            InstructionNode::I32AtomicRmw16AddU(x) => x.size(),
+            InstructionNode::I64AtomicRmw8AddU(x) => x.size(),
+            InstructionNode::I64AtomicRmw16AddU(x) => x.size(),
+            InstructionNode::I64AtomicRmw32AddU(x) => x.size(),
+            InstructionNode::I32AtomicRmwSub(x) => x.size(),
+            InstructionNode::I64AtomicRmwSub(x) => x.size(),
+            InstructionNode::I32AtomicRmw8SubU(x) => x.size(),
+            InstructionNode::I32AtomicRmw16SubU(x) => x.size(),
+            InstructionNode::I64AtomicRmw8SubU(x) => x.size(),
+            InstructionNode::I64AtomicRmw16SubU(x) => x.size(),
+            InstructionNode::I64AtomicRmw32SubU(x) => x.size(),
+            InstructionNode::I32AtomicRmwAnd(x) => x.size(),
+            InstructionNode::I64AtomicRmwAnd(x) => x.size(),
+            InstructionNode::I32AtomicRmw8AndU(x) => x.size(),
+            InstructionNode::I32AtomicRmw16AndU(x) => x.size(),
+            InstructionNode::I64AtomicRmw8AndU(x) => x.size(),
+            InstructionNode::I64AtomicRmw16AndU(x) => x.size(),
+            InstructionNode::I64AtomicRmw32AndU(x) => x.size(),
+            InstructionNode::I32AtomicRmwOr(x) => x.size(),
+            InstructionNode::I64AtomicRmwOr(x) => x.size(),
+            InstructionNode::I32AtomicRmw8OrU(x) => x.size(),
+            InstructionNode::I32AtomicRmw16OrU(x) => x.size(),
+            InstructionNode::I64AtomicRmw8OrU(x) => x.size(),
+            InstructionNode::I64AtomicRmw16OrU(x) => x.size(),
+            InstructionNode::I64AtomicRmw32OrU(x) => x.size(),
+            InstructionNode::I32AtomicRmwXor(x) => x.size(),
+            InstructionNode::I64AtomicRmwXor(x) => x.size(),
+            InstructionNode::I32AtomicRmw8XorU(x) => x.size(),
+            InstructionNode::I32AtomicRmw16XorU(x) => x.size(),
+            InstructionNode::I64AtomicRmw8XorU(x) => x.size(),
+            InstructionNode::I64AtomicRmw16XorU(x) => x.size(),
+            InstructionNode::I64AtomicRmw32XorU(x) => x.size(),
+            InstructionNode::I32AtomicRmwXchg(x) => x.size(),
+            InstructionNode::I64AtomicRmwXchg(x) => x.size(),
+            InstructionNode::I32AtomicRmw8XchgU(x) => x.size(),
+            InstructionNode::I32AtomicRmw16XchgU(x) => x.size(),
+            InstructionNode::I64AtomicRmw8XchgU(x) => x.size(),
+            InstructionNode::I64AtomicRmw16XchgU(x) => x.size(),
+            InstructionNode::I64AtomicRmw32XchgU(x) => x.size(),
+            InstructionNode::I32AtomicRmwCmpxchg(x) => x.size(),
+            InstructionNode::I64AtomicRmwCmpxchg(x) => x.size(),
+            InstructionNode::I32AtomicRmw8CmpxchgU(x) => x.size(),
+            InstructionNode::I32AtomicRmw16CmpxchgU(x) => x.size(),
+            InstructionNode::I64AtomicRmw8CmpxchgU(x) => x.size(),
+            InstructionNode::I64AtomicRmw16CmpxchgU(x) => x.size(),
+            InstructionNode::I64AtomicRmw32CmpxchgU(x) => x.size(),
         }
     }
 
@@ -349,39 +1654,503 @@ impl Node for InstructionNode {
             InstructionNode::Else(x) => x.encode(),
             InstructionNode::Br(x) => x.encode(),
             InstructionNode::BrIf(x) => x.encode(),
+            InstructionNode::BrTable(x) => x.encode(),
+            InstructionNode::Return(x) => x.encode(),
+            InstructionNode::Unreachable(x) => x.encode(),
+            InstructionNode::Nop(x) => x.encode(),
             InstructionNode::Call(x) => x.encode(),
+            InstructionNode::CallIndirect(x) => x.encode(),
+            InstructionNode::Drop(x) => x.encode(),
+            InstructionNode::Select(x) => x.encode(),
             InstructionNode::End(x) => x.encode(),
             InstructionNode::I32Const(x) => x.encode(),
+            InstructionNode::I64Const(x) => x.encode(),
+            InstructionNode::F32Const(x) => x.encode(),
+            InstructionNode::F64Const(x) => x.encode(),
             InstructionNode::GetLocal(x) => x.encode(),
             InstructionNode::SetLocal(x) => x.encode(),
-            InstructionNode::I32Add(x) => x.encode(),
-            // InstructionNode::I32Sub(x) => x.encode(),
-            InstructionNode::I32RemS(x) => x.encode(),
-            InstructionNode::I32RemU(x) => x.encode(),
-            // InstructionNode::I32Shl(x) => x.encode(),
+            InstructionNode::TeeLocal(x) => x.encode(),
+            InstructionNode::GetGlobal(x) => x.encode(),
+            InstructionNode::SetGlobal(x) => x.encode(),
+            InstructionNode::I32Load(x) => x.encode(),
+            InstructionNode::I64Load(x) => x.encode(),
+            InstructionNode::F32Load(x) => x.encode(),
+            InstructionNode::F64Load(x) => x.encode(),
+            InstructionNode::I32Load8S(x) => x.encode(),
+            InstructionNode::I32Load8U(x) => x.encode(),
+            InstructionNode::I32Load16S(x) => x.encode(),
+            InstructionNode::I32Load16U(x) => x.encode(),
+            InstructionNode::I64Load8S(x) => x.encode(),
+            InstructionNode::I64Load8U(x) => x.encode(),
+            InstructionNode::I64Load16S(x) => x.encode(),
+            InstructionNode::I64Load16U(x) => x.encode(),
+            InstructionNode::I64Load32S(x) => x.encode(),
+            InstructionNode::I64Load32U(x) => x.encode(),
+            InstructionNode::I32Store(x) => x.encode(),
+            InstructionNode::I64Store(x) => x.encode(),
+            InstructionNode::F32Store(x) => x.encode(),
+            InstructionNode::F64Store(x) => x.encode(),
+            InstructionNode::I32Store8(x) => x.encode(),
+            InstructionNode::I32Store16(x) => x.encode(),
+            InstructionNode::I64Store8(x) => x.encode(),
+            InstructionNode::I64Store16(x) => x.encode(),
+            InstructionNode::I64Store32(x) => x.encode(),
+            InstructionNode::MemorySize(x) => x.encode(),
+            InstructionNode::MemoryGrow(x) => x.encode(),
+            InstructionNode::RefNull(x) => x.encode(),
+            InstructionNode::RefIsNull(x) => x.encode(),
+            InstructionNode::RefFunc(x) => x.encode(),
+            InstructionNode::TableGet(x) => x.encode(),
+            InstructionNode::TableSet(x) => x.encode(),
             InstructionNode::I32Eqz(x) => x.encode(),
             InstructionNode::I32Eq(x) => x.encode(),
+            InstructionNode::I32Ne(x) => x.encode(),
             InstructionNode::I32LtS(x) => x.encode(),
             InstructionNode::I32LtU(x) => x.encode(),
+            InstructionNode::I32GtS(x) => x.encode(),
+            InstructionNode::I32GtU(x) => x.encode(),
+            InstructionNode::I32LeS(x) => x.encode(),
+            InstructionNode::I32LeU(x) => x.encode(),
             InstructionNode::I32GeS(x) => x.encode(),
+            InstructionNode::I32GeU(x) => x.encode(),
+            InstructionNode::I32Clz(x) => x.encode(),
+            InstructionNode::I32Ctz(x) => x.encode(),
+            InstructionNode::I32Popcnt(x) => x.encode(),
+            InstructionNode::I32Add(x) => x.encode(),
             InstructionNode::I32Sub(x) => x.encode(),
-            // InstructionNode::I32GeU(x) => x.encode(),
-            // InstructionNode::I32GtS(x) => x.encode(),
-            // InstructionNode::I32GtU(x) => x.encode(),
-            // InstructionNode::I64Add(x) => x.encode(),
-            // InstructionNode::I64Sub(x) => x.encode(),
-            // InstructionNode::Unreachable => vec![0x00],
-            // InstructionNode::Nop => vec![0x01],
-            // InstructionNode::BrTable(x, y) => {
-            //     let mut buffer = vec![0x0e];
-            //     buffer.extend(encode_u32_to_leb128(x.len() as u32));
-            //     for i in x {
-            //         buffer.extend(encode_u32_to_leb128(*i));
-            //     }
-            //     buffer.extend(encode_u32_to_leb128(*y));
-            //     buffer
-            // },
-            // InstructionNode::Return => vec![0x0f],
+            InstructionNode::I32Mul(x) => x.encode(),
+            InstructionNode::I32DivS(x) => x.encode(),
+            InstructionNode::I32DivU(x) => x.encode(),
+            InstructionNode::I32RemS(x) => x.encode(),
+            InstructionNode::I32RemU(x) => x.encode(),
+            InstructionNode::I32And(x) => x.encode(),
+            InstructionNode::I32Or(x) => x.encode(),
+            InstructionNode::I32Xor(x) => x.encode(),
+            InstructionNode::I32Shl(x) => x.encode(),
+            InstructionNode::I32ShrS(x) => x.encode(),
+            InstructionNode::I32ShrU(x) => x.encode(),
+            InstructionNode::I32Rotl(x) => x.encode(),
+            InstructionNode::I32Rotr(x) => x.encode(),
+            InstructionNode::I64Eqz(x) => x.encode(),
+            InstructionNode::I64Eq(x) => x.encode(),
+            InstructionNode::I64Ne(x) => x.encode(),
+            InstructionNode::I64LtS(x) => x.encode(),
+            InstructionNode::I64LtU(x) => x.encode(),
+            InstructionNode::I64GtS(x) => x.encode(),
+            InstructionNode::I64GtU(x) => x.encode(),
+            InstructionNode::I64LeS(x) => x.encode(),
+            InstructionNode::I64LeU(x) => x.encode(),
+            InstructionNode::I64GeS(x) => x.encode(),
+            InstructionNode::I64GeU(x) => x.encode(),
+            InstructionNode::I64Clz(x) => x.encode(),
+            InstructionNode::I64Ctz(x) => x.encode(),
+            InstructionNode::I64Popcnt(x) => x.encode(),
+            InstructionNode::I64Add(x) => x.encode(),
+            InstructionNode::I64Sub(x) => x.encode(),
+            InstructionNode::I64Mul(x) => x.encode(),
+            InstructionNode::I64DivS(x) => x.encode(),
+            InstructionNode::I64DivU(x) => x.encode(),
+            InstructionNode::I64RemS(x) => x.encode(),
+            InstructionNode::I64RemU(x) => x.encode(),
+            InstructionNode::I64And(x) => x.encode(),
+            InstructionNode::I64Or(x) => x.encode(),
+            InstructionNode::I64Xor(x) => x.encode(),
+            InstructionNode::I64Shl(x) => x.encode(),
+            InstructionNode::I64ShrS(x) => x.encode(),
+            InstructionNode::I64ShrU(x) => x.encode(),
+            InstructionNode::I64Rotl(x) => x.encode(),
+            InstructionNode::I64Rotr(x) => x.encode(),
+            InstructionNode::F32Eq(x) => x.encode(),
+            InstructionNode::F32Ne(x) => x.encode(),
+            InstructionNode::F32Lt(x) => x.encode(),
+            InstructionNode::F32Gt(x) => x.encode(),
+            InstructionNode::F32Le(x) => x.encode(),
+            InstructionNode::F32Ge(x) => x.encode(),
+            InstructionNode::F64Eq(x) => x.encode(),
+            InstructionNode::F64Ne(x) => x.encode(),
+            InstructionNode::F64Lt(x) => x.encode(),
+            InstructionNode::F64Gt(x) => x.encode(),
+            InstructionNode::F64Le(x) => x.encode(),
+            InstructionNode::F64Ge(x) => x.encode(),
+            InstructionNode::F32Abs(x) => x.encode(),
+            InstructionNode::F32Neg(x) => x.encode(),
+            InstructionNode::F32Ceil(x) => x.encode(),
+            InstructionNode::F32Floor(x) => x.encode(),
+            InstructionNode::F32Trunc(x) => x.encode(),
+            InstructionNode::F32Nearest(x) => x.encode(),
+            InstructionNode::F32Sqrt(x) => x.encode(),
+            InstructionNode::F32Add(x) => x.encode(),
+            InstructionNode::F32Sub(x) => x.encode(),
+            InstructionNode::F32Mul(x) => x.encode(),
+            InstructionNode::F32Div(x) => x.encode(),
+            InstructionNode::F32Min(x) => x.encode(),
+            InstructionNode::F32Max(x) => x.encode(),
+            InstructionNode::F32Copysign(x) => x.encode(),
+            InstructionNode::F64Abs(x) => x.encode(),
+            InstructionNode::F64Neg(x) => x.encode(),
+            InstructionNode::F64Ceil(x) => x.encode(),
+            InstructionNode::F64Floor(x) => x.encode(),
+            InstructionNode::F64Trunc(x) => x.encode(),
+            InstructionNode::F64Nearest(x) => x.encode(),
+            InstructionNode::F64Sqrt(x) => x.encode(),
+            InstructionNode::F64Add(x) => x.encode(),
+            InstructionNode::F64Sub(x) => x.encode(),
+            InstructionNode::F64Mul(x) => x.encode(),
+            InstructionNode::F64Div(x) => x.encode(),
+            InstructionNode::F64Min(x) => x.encode(),
+            InstructionNode::F64Max(x) => x.encode(),
+            InstructionNode::F64Copysign(x) => x.encode(),
+            InstructionNode::I32WrapI64(x) => x.encode(),
+            InstructionNode::I32TruncF32S(x) => x.encode(),
+            InstructionNode::I32TruncF32U(x) => x.encode(),
+            InstructionNode::I32TruncF64S(x) => x.encode(),
+            InstructionNode::I32TruncF64U(x) => x.encode(),
+            InstructionNode::I64ExtendI32S(x) => x.encode(),
+            InstructionNode::I64ExtendI32U(x) => x.encode(),
+            InstructionNode::I64TruncF32S(x) => x.encode(),
+            InstructionNode::I64TruncF32U(x) => x.encode(),
+            InstructionNode::I64TruncF64S(x) => x.encode(),
+            InstructionNode::I64TruncF64U(x) => x.encode(),
+            InstructionNode::F32ConvertI32S(x) => x.encode(),
+            InstructionNode::F32ConvertI32U(x) => x.encode(),
+            InstructionNode::F32ConvertI64S(x) => x.encode(),
+            InstructionNode::F32ConvertI64U(x) => x.encode(),
+            InstructionNode::F32DemoteF64(x) => x.encode(),
+            InstructionNode::F64ConvertI32S(x) => x.encode(),
+            InstructionNode::F64ConvertI32U(x) => x.encode(),
+            InstructionNode::F64ConvertI64S(x) => x.encode(),
+            InstructionNode::F64ConvertI64U(x) => x.encode(),
+            InstructionNode::F64PromoteF32(x) => x.encode(),
+            InstructionNode::I32ReinterpretF32(x) => x.encode(),
+            InstructionNode::I64ReinterpretF64(x) => x.encode(),
+            InstructionNode::F32ReinterpretI32(x) => x.encode(),
+            InstructionNode::F64ReinterpretI64(x) => x.encode(),
+            InstructionNode::I32Extend8S(x) => x.encode(),
+            InstructionNode::I32Extend16S(x) => x.encode(),
+            InstructionNode::I64Extend8S(x) => x.encode(),
+            InstructionNode::I64Extend16S(x) => x.encode(),
+            InstructionNode::I64Extend32S(x) => x.encode(),
+            InstructionNode::I32TruncSatF32S(x) => x.encode(),
+            InstructionNode::I32TruncSatF32U(x) => x.encode(),
+            InstructionNode::I32TruncSatF64S(x) => x.encode(),
+            InstructionNode::I32TruncSatF64U(x) => x.encode(),
+            InstructionNode::I64TruncSatF32S(x) => x.encode(),
+            InstructionNode::I64TruncSatF32U(x) => x.encode(),
+            InstructionNode::I64TruncSatF64S(x) => x.encode(),
+            InstructionNode::I64TruncSatF64U(x) => x.encode(),
+            InstructionNode::MemoryInit(x) => x.encode(),
+            InstructionNode::DataDrop(x) => x.encode(),
+            InstructionNode::MemoryCopy(x) => x.encode(),
+            InstructionNode::MemoryFill(x) => x.encode(),
+            InstructionNode::TableInit(x) => x.encode(),
+            InstructionNode::ElemDrop(x) => x.encode(),
+            InstructionNode::TableCopy(x) => x.encode(),
+            InstructionNode::TableGrow(x) => x.encode(),
+            InstructionNode::TableSize(x) => x.encode(),
+            InstructionNode::TableFill(x) => x.encode(),
+            InstructionNode::V128Load(x) => x.encode(),
+            InstructionNode::V128Load8x8S(x) => x.encode(),
+            InstructionNode::V128Load8x8U(x) => x.encode(),
+            InstructionNode::V128Load16x4S(x) => x.encode(),
+            InstructionNode::V128Load16x4U(x) => x.encode(),
+            InstructionNode::V128Load32x2S(x) => x.encode(),
+            InstructionNode::V128Load32x2U(x) => x.encode(),
+            InstructionNode::V128Load8Splat(x) => x.encode(),
+            InstructionNode::V128Load16Splat(x) => x.encode(),
+            InstructionNode::V128Load32Splat(x) => x.encode(),
+            InstructionNode::V128Load64Splat(x) => x.encode(),
+            InstructionNode::V128Store(x) => x.encode(),
+            InstructionNode::V128Const(x) => x.encode(),
+            InstructionNode::I8x16Shuffle(x) => x.encode(),
+            InstructionNode::I8x16Swizzle(x) => x.encode(),
+            InstructionNode::I8x16Splat(x) => x.encode(),
+            InstructionNode::I16x8Splat(x) => x.encode(),
+            InstructionNode::I32x4Splat(x) => x.encode(),
+            InstructionNode::I64x2Splat(x) => x.encode(),
+            InstructionNode::F32x4Splat(x) => x.encode(),
+            InstructionNode::F64x2Splat(x) => x.encode(),
+            InstructionNode::I8x16ExtractLaneS(x) => x.encode(),
+            InstructionNode::I8x16ExtractLaneU(x) => x.encode(),
+            InstructionNode::I8x16ReplaceLane(x) => x.encode(),
+            InstructionNode::I16x8ExtractLaneS(x) => x.encode(),
+            InstructionNode::I16x8ExtractLaneU(x) => x.encode(),
+            InstructionNode::I16x8ReplaceLane(x) => x.encode(),
+            InstructionNode::I32x4ExtractLane(x) => x.encode(),
+            InstructionNode::I32x4ReplaceLane(x) => x.encode(),
+            InstructionNode::I64x2ExtractLane(x) => x.encode(),
+            InstructionNode::I64x2ReplaceLane(x) => x.encode(),
+            InstructionNode::F32x4ExtractLane(x) => x.encode(),
+            InstructionNode::F32x4ReplaceLane(x) => x.encode(),
+            InstructionNode::F64x2ExtractLane(x) => x.encode(),
+            InstructionNode::F64x2ReplaceLane(x) => x.encode(),
+            InstructionNode::I8x16Eq(x) => x.encode(),
+            InstructionNode::I8x16Ne(x) => x.encode(),
+            InstructionNode::I8x16LtS(x) => x.encode(),
+            InstructionNode::I8x16LtU(x) => x.encode(),
+            InstructionNode::I8x16GtS(x) => x.encode(),
+            InstructionNode::I8x16GtU(x) => x.encode(),
+            InstructionNode::I8x16LeS(x) => x.encode(),
+            InstructionNode::I8x16LeU(x) => x.encode(),
+            InstructionNode::I8x16GeS(x) => x.encode(),
+            InstructionNode::I8x16GeU(x) => x.encode(),
+            InstructionNode::I16x8Eq(x) => x.encode(),
+            InstructionNode::I16x8Ne(x) => x.encode(),
+            InstructionNode::I16x8LtS(x) => x.encode(),
+            InstructionNode::I16x8LtU(x) => x.encode(),
+            InstructionNode::I16x8GtS(x) => x.encode(),
+            InstructionNode::I16x8GtU(x) => x.encode(),
+            InstructionNode::I16x8LeS(x) => x.encode(),
+            InstructionNode::I16x8LeU(x) => x.encode(),
+            InstructionNode::I16x8GeS(x) => x.encode(),
+            InstructionNode::I16x8GeU(x) => x.encode(),
+            InstructionNode::I32x4Eq(x) => x.encode(),
+            InstructionNode::I32x4Ne(x) => x.encode(),
+            InstructionNode::I32x4LtS(x) => x.encode(),
+            InstructionNode::I32x4LtU(x) => x.encode(),
+            InstructionNode::I32x4GtS(x) => x.encode(),
+            InstructionNode::I32x4GtU(x) => x.encode(),
+            InstructionNode::I32x4LeS(x) => x.encode(),
+            InstructionNode::I32x4LeU(x) => x.encode(),
+            InstructionNode::I32x4GeS(x) => x.encode(),
+            InstructionNode::I32x4GeU(x) => x.encode(),
+            InstructionNode::F32x4Eq(x) => x.encode(),
+            InstructionNode::F32x4Ne(x) => x.encode(),
+            InstructionNode::F32x4Lt(x) => x.encode(),
+            InstructionNode::F32x4Gt(x) => x.encode(),
+            InstructionNode::F32x4Le(x) => x.encode(),
+            InstructionNode::F32x4Ge(x) => x.encode(),
+            InstructionNode::F64x2Eq(x) => x.encode(),
+            InstructionNode::F64x2Ne(x) => x.encode(),
+            InstructionNode::F64x2Lt(x) => x.encode(),
+            InstructionNode::F64x2Gt(x) => x.encode(),
+            InstructionNode::F64x2Le(x) => x.encode(),
+            InstructionNode::F64x2Ge(x) => x.encode(),
+            InstructionNode::V128Not(x) => x.encode(),
+            InstructionNode::V128And(x) => x.encode(),
+            InstructionNode::V128Andnot(x) => x.encode(),
+            InstructionNode::V128Or(x) => x.encode(),
+            InstructionNode::V128Xor(x) => x.encode(),
+            InstructionNode::V128Bitselect(x) => x.encode(),
+            InstructionNode::V128AnyTrue(x) => x.encode(),
+            InstructionNode::V128Load8Lane(x) => x.encode(),
+            InstructionNode::V128Load16Lane(x) => x.encode(),
+            InstructionNode::V128Load32Lane(x) => x.encode(),
+            InstructionNode::V128Load64Lane(x) => x.encode(),
+            InstructionNode::V128Store8Lane(x) => x.encode(),
+            InstructionNode::V128Store16Lane(x) => x.encode(),
+            InstructionNode::V128Store32Lane(x) => x.encode(),
+            InstructionNode::V128Store64Lane(x) => x.encode(),
+            InstructionNode::V128Load32Zero(x) => x.encode(),
+            InstructionNode::V128Load64Zero(x) => x.encode(),
+            InstructionNode::F32x4DemoteF64x2Zero(x) => x.encode(),
+            InstructionNode::F64x2PromoteLowF32x4(x) => x.encode(),
+            InstructionNode::I8x16Abs(x) => x.encode(),
+            InstructionNode::I8x16Neg(x) => x.encode(),
+            InstructionNode::I8x16Popcnt(x) => x.encode(),
+            InstructionNode::I8x16AllTrue(x) => x.encode(),
+            InstructionNode::I8x16Bitmask(x) => x.encode(),
+            InstructionNode::I8x16NarrowI16x8S(x) => x.encode(),
+            InstructionNode::I8x16NarrowI16x8U(x) => x.encode(),
+            InstructionNode::F32x4Ceil(x) => x.encode(),
+            InstructionNode::F32x4Floor(x) => x.encode(),
+            InstructionNode::F32x4Trunc(x) => x.encode(),
+            InstructionNode::F32x4Nearest(x) => x.encode(),
+            InstructionNode::I8x16Shl(x) => x.encode(),
+            InstructionNode::I8x16ShrS(x) => x.encode(),
+            InstructionNode::I8x16ShrU(x) => x.encode(),
+            InstructionNode::I8x16Add(x) => x.encode(),
+            InstructionNode::I8x16AddSatS(x) => x.encode(),
+            InstructionNode::I8x16AddSatU(x) => x.encode(),
+            InstructionNode::I8x16Sub(x) => x.encode(),
+            InstructionNode::I8x16SubSatS(x) => x.encode(),
+            InstructionNode::I8x16SubSatU(x) => x.encode(),
+            InstructionNode::F64x2Ceil(x) => x.encode(),
+            InstructionNode::F64x2Floor(x) => x.encode(),
+            InstructionNode::I8x16MinS(x) => x.encode(),
+            InstructionNode::I8x16MinU(x) => x.encode(),
+            InstructionNode::I8x16MaxS(x) => x.encode(),
+            InstructionNode::I8x16MaxU(x) => x.encode(),
+            InstructionNode::F64x2Trunc(x) => x.encode(),
+            InstructionNode::I8x16AvgrU(x) => x.encode(),
+            InstructionNode::I16x8ExtaddPairwiseI8x16S(x) => x.encode(),
+            InstructionNode::I16x8ExtaddPairwiseI8x16U(x) => x.encode(),
+            InstructionNode::I32x4ExtaddPairwiseI16x8S(x) => x.encode(),
+            InstructionNode::I32x4ExtaddPairwiseI16x8U(x) => x.encode(),
+            InstructionNode::I16x8Abs(x) => x.encode(),
+            InstructionNode::I16x8Neg(x) => x.encode(),
+            InstructionNode::I16x8Q15mulrSatS(x) => x.encode(),
+            InstructionNode::I16x8AllTrue(x) => x.encode(),
+            InstructionNode::I16x8Bitmask(x) => x.encode(),
+            InstructionNode::I16x8NarrowI32x4S(x) => x.encode(),
+            InstructionNode::I16x8NarrowI32x4U(x) => x.encode(),
+            InstructionNode::I16x8ExtendLowI8x16S(x) => x.encode(),
+            InstructionNode::I16x8ExtendHighI8x16S(x) => x.encode(),
+            InstructionNode::I16x8ExtendLowI8x16U(x) => x.encode(),
+            InstructionNode::I16x8ExtendHighI8x16U(x) => x.encode(),
+            InstructionNode::I16x8Shl(x) => x.encode(),
+            InstructionNode::I16x8ShrS(x) => x.encode(),
+            InstructionNode::I16x8ShrU(x) => x.encode(),
+            InstructionNode::I16x8Add(x) => x.encode(),
+            InstructionNode::I16x8AddSatS(x) => x.encode(),
+            InstructionNode::I16x8AddSatU(x) => x.encode(),
+            InstructionNode::I16x8Sub(x) => x.encode(),
+            InstructionNode::I16x8SubSatS(x) => x.encode(),
+            InstructionNode::I16x8SubSatU(x) => x.encode(),
+            InstructionNode::F64x2Nearest(x) => x.encode(),
+            InstructionNode::I16x8Mul(x) => x.encode(),
+            InstructionNode::I16x8MinS(x) => x.encode(),
+            InstructionNode::I16x8MinU(x) => x.encode(),
+            InstructionNode::I16x8MaxS(x) => x.encode(),
+            InstructionNode::I16x8MaxU(x) => x.encode(),
+            InstructionNode::I16x8AvgrU(x) => x.encode(),
+            InstructionNode::I16x8ExtmulLowI8x16S(x) => x.encode(),
+            InstructionNode::I16x8ExtmulHighI8x16S(x) => x.encode(),
+            InstructionNode::I16x8ExtmulLowI8x16U(x) => x.encode(),
+            InstructionNode::I16x8ExtmulHighI8x16U(x) => x.encode(),
+            InstructionNode::I32x4Abs(x) => x.encode(),
+            InstructionNode::I32x4Neg(x) => x.encode(),
+            InstructionNode::I32x4AllTrue(x) => x.encode(),
+            InstructionNode::I32x4Bitmask(x) => x.encode(),
+            InstructionNode::I32x4ExtendLowI16x8S(x) => x.encode(),
+            InstructionNode::I32x4ExtendHighI16x8S(x) => x.encode(),
+            InstructionNode::I32x4ExtendLowI16x8U(x) => x.encode(),
+            InstructionNode::I32x4ExtendHighI16x8U(x) => x.encode(),
+            InstructionNode::I32x4Shl(x) => x.encode(),
+            InstructionNode::I32x4ShrS(x) => x.encode(),
+            InstructionNode::I32x4ShrU(x) => x.encode(),
+            InstructionNode::I32x4Add(x) => x.encode(),
+            InstructionNode::I32x4Sub(x) => x.encode(),
+            InstructionNode::I32x4Mul(x) => x.encode(),
+            InstructionNode::I32x4MinS(x) => x.encode(),
+            InstructionNode::I32x4MinU(x) => x.encode(),
+            InstructionNode::I32x4MaxS(x) => x.encode(),
+            InstructionNode::I32x4MaxU(x) => x.encode(),
+            InstructionNode::I32x4DotI16x8S(x) => x.encode(),
+            InstructionNode::I32x4ExtmulLowI16x8S(x) => x.encode(),
+            InstructionNode::I32x4ExtmulHighI16x8S(x) => x.encode(),
+            InstructionNode::I32x4ExtmulLowI16x8U(x) => x.encode(),
+            InstructionNode::I32x4ExtmulHighI16x8U(x) => x.encode(),
+            InstructionNode::I64x2Abs(x) => x.encode(),
+            InstructionNode::I64x2Neg(x) => x.encode(),
+            InstructionNode::I64x2AllTrue(x) => x.encode(),
+            InstructionNode::I64x2Bitmask(x) => x.encode(),
+            InstructionNode::I64x2ExtendLowI32x4S(x) => x.encode(),
+            InstructionNode::I64x2ExtendHighI32x4S(x) => x.encode(),
+            InstructionNode::I64x2ExtendLowI32x4U(x) => x.encode(),
+            InstructionNode::I64x2ExtendHighI32x4U(x) => x.encode(),
+            InstructionNode::I64x2Shl(x) => x.encode(),
+            InstructionNode::I64x2ShrS(x) => x.encode(),
+            InstructionNode::I64x2ShrU(x) => x.encode(),
+            InstructionNode::I64x2Add(x) => x.encode(),
+            InstructionNode::I64x2Sub(x) => x.encode(),
+            InstructionNode::I64x2Mul(x) => x.encode(),
+            InstructionNode::I64x2Eq(x) => x.encode(),
+            InstructionNode::I64x2Ne(x) => x.encode(),
+            InstructionNode::I64x2LtS(x) => x.encode(),
+            InstructionNode::I64x2GtS(x) => x.encode(),
+            InstructionNode::I64x2LeS(x) => x.encode(),
+            InstructionNode::I64x2GeS(x) => x.encode(),
+            InstructionNode::I64x2ExtmulLowI32x4S(x) => x.encode(),
+            InstructionNode::I64x2ExtmulHighI32x4S(x) => x.encode(),
+            InstructionNode::I64x2ExtmulLowI32x4U(x) => x.encode(),
+            InstructionNode::I64x2ExtmulHighI32x4U(x) => x.encode(),
+            InstructionNode::F32x4Abs(x) => x.encode(),
+            InstructionNode::F32x4Neg(x) => x.encode(),
+            InstructionNode::F32x4Sqrt(x) => x.encode(),
+            InstructionNode::F32x4Add(x) => x.encode(),
+            InstructionNode::F32x4Sub(x) => x.encode(),
+            InstructionNode::F32x4Mul(x) => x.encode(),
+            InstructionNode::F32x4Div(x) => x.encode(),
+            InstructionNode::F32x4Min(x) => x.encode(),
+            InstructionNode::F32x4Max(x) => x.encode(),
+            InstructionNode::F32x4Pmin(x) => x.encode(),
+            InstructionNode::F32x4Pmax(x) => x.encode(),
+            InstructionNode::F64x2Abs(x) => x.encode(),
+            InstructionNode::F64x2Neg(x) => x.encode(),
+            InstructionNode::F64x2Sqrt(x) => x.encode(),
+            InstructionNode::F64x2Add(x) => x.encode(),
+            InstructionNode::F64x2Sub(x) => x.encode(),
+            InstructionNode::F64x2Mul(x) => x.encode(),
+            InstructionNode::F64x2Div(x) => x.encode(),
+            InstructionNode::F64x2Min(x) => x.encode(),
+            InstructionNode::F64x2Max(x) => x.encode(),
+            InstructionNode::F64x2Pmin(x) => x.encode(),
+            InstructionNode::F64x2Pmax(x) => x.encode(),
+            InstructionNode::I32x4TruncSatF32x4S(x) => x.encode(),
+            InstructionNode::I32x4TruncSatF32x4U(x) => x.encode(),
+            InstructionNode::F32x4ConvertI32x4S(x) => x.encode(),
+            InstructionNode::F32x4ConvertI32x4U(x) => x.encode(),
+            InstructionNode::I32x4TruncSatF64x2SZero(x) => x.encode(),
+            InstructionNode::I32x4TruncSatF64x2UZero(x) => x.encode(),
+            InstructionNode::F64x2ConvertLowI32x4S(x) => x.encode(),
+            InstructionNode::F64x2ConvertLowI32x4U(x) => x.encode(),
+            InstructionNode::MemoryAtomicNotify(x) => x.encode(),
+            InstructionNode::MemoryAtomicWait32(x) => x.encode(),
+            InstructionNode::MemoryAtomicWait64(x) => x.encode(),
+            InstructionNode::AtomicFence(x) => x.encode(),
+            InstructionNode::I32AtomicLoad(x) => x.encode(),
+            InstructionNode::I64AtomicLoad(x) => x.encode(),
+            InstructionNode::I32AtomicLoad8U(x) => x.encode(),
+            InstructionNode::I32AtomicLoad16U(x) => x.encode(),
+            InstructionNode::I64AtomicLoad8U(x) => x.encode(),
+            InstructionNode::I64AtomicLoad16U(x) => x.encode(),
+            InstructionNode::I64AtomicLoad32U(x) => x.encode(),
+            InstructionNode::I32AtomicStore(x) => x.encode(),
+            InstructionNode::I64AtomicStore(x) => x.encode(),
+            InstructionNode::I32AtomicStore8(x) => x.encode(),
+            InstructionNode::I32AtomicStore16(x) => x.encode(),
+            InstructionNode::I64AtomicStore8(x) => x.encode(),
+            InstructionNode::I64AtomicStore16(x) => x.encode(),
+            InstructionNode::I64AtomicStore32(x) => x.encode(),
+            InstructionNode::I32AtomicRmwAdd(x) => x.encode(),
+            InstructionNode::I64AtomicRmwAdd(x) => x.encode(),
+            InstructionNode::I32AtomicRmw8AddU(x) => x.encode(),
+            InstructionNode::I32AtomicRmw16AddU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw8AddU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw16AddU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw32AddU(x) => x.encode(),
+            InstructionNode::I32AtomicRmwSub(x) => x.encode(),
+            InstructionNode::I64AtomicRmwSub(x) => x.encode(),
+            InstructionNode::I32AtomicRmw8SubU(x) => x.encode(),
+            InstructionNode::I32AtomicRmw16SubU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw8SubU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw16SubU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw32SubU(x) => x.encode(),
+            InstructionNode::I32AtomicRmwAnd(x) => x.encode(),
+            InstructionNode::I64AtomicRmwAnd(x) => x.encode(),
+            InstructionNode::I32AtomicRmw8AndU(x) => x.encode(),
+            InstructionNode::I32AtomicRmw16AndU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw8AndU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw16AndU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw32AndU(x) => x.encode(),
+            InstructionNode::I32AtomicRmwOr(x) => x.encode(),
+            InstructionNode::I64AtomicRmwOr(x) => x.encode(),
+            InstructionNode::I32AtomicRmw8OrU(x) => x.encode(),
+            InstructionNode::I32AtomicRmw16OrU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw8OrU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw16OrU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw32OrU(x) => x.encode(),
+            InstructionNode::I32AtomicRmwXor(x) => x.encode(),
+            InstructionNode::I64AtomicRmwXor(x) => x.encode(),
+            InstructionNode::I32AtomicRmw8XorU(x) => x.encode(),
+            InstructionNode::I32AtomicRmw16XorU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw8XorU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw16XorU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw32XorU(x) => x.encode(),
+            InstructionNode::I32AtomicRmwXchg(x) => x.encode(),
+            InstructionNode::I64AtomicRmwXchg(x) => x.encode(),
+            InstructionNode::I32AtomicRmw8XchgU(x) => x.encode(),
+            InstructionNode::I32AtomicRmw16XchgU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw8XchgU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw16XchgU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw32XchgU(x) => x.encode(),
+            InstructionNode::I32AtomicRmwCmpxchg(x) => x.encode(),
+            InstructionNode::I64AtomicRmwCmpxchg(x) => x.encode(),
+            InstructionNode::I32AtomicRmw8CmpxchgU(x) => x.encode(),
+            InstructionNode::I32AtomicRmw16CmpxchgU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw8CmpxchgU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw16CmpxchgU(x) => x.encode(),
+            InstructionNode::I64AtomicRmw32CmpxchgU(x) => x.encode(),
         }
     }
 }
@@ -418,163 +2187,159 @@ impl Node for I32ConstInstructionNode {
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct EndInstructionNode {
-    opcode: u8,
-}
-
-impl Node for EndInstructionNode {
-    fn size(&self) -> u32 {
-        1
-    }
-
-    fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
-    }
-}
-
-impl Default for EndInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x0b }
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-pub struct GetLocalInstructionNode {
+pub struct I64ConstInstructionNode {
     opcode: u8,
-    pub index: u32,
+    pub value: i64,
 }
 
-impl GetLocalInstructionNode {
-    pub fn new(index: u32) -> Self {
+impl I64ConstInstructionNode {
+    pub fn new(value: i64) -> Self {
         Self {
-            opcode: 0x20,
-            index,
+            opcode: 0x42,
+            value,
         }
     }
 }
 
-impl Node for GetLocalInstructionNode {
+impl Node for I64ConstInstructionNode {
     fn size(&self) -> u32 {
         let mut size = 0;
         size += 1; // opcode
-        size += encode_u32_to_leb128(self.index).len() as u32;
+        size += encode_i64_to_leb128(self.value).len() as u32;
         size
     }
 
     fn encode(&self) -> Vec<u8> {
         let mut buffer = vec![];
         buffer.push(self.opcode);
-        buffer.extend(encode_u32_to_leb128(self.index));
+        buffer.extend(encode_i64_to_leb128(self.value));
         buffer
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct SetLocalInstructionNode {
+pub struct F32ConstInstructionNode {
     opcode: u8,
-    pub index: u32,
+    pub value: F32,
 }
 
-impl SetLocalInstructionNode {
-    pub fn new(index: u32) -> Self {
+impl F32ConstInstructionNode {
+    pub fn new(value: F32) -> Self {
         Self {
-            opcode: 0x21,
-            index,
+            opcode: 0x43,
+            value,
         }
     }
 }
 
-impl Node for SetLocalInstructionNode {
+impl Node for F32ConstInstructionNode {
     fn size(&self) -> u32 {
-        let mut size = 0;
-        size += 1; // opcode
-        size += encode_u32_to_leb128(self.index).len() as u32;
-        size
+        1 + 4 // opcode and little-endian bits
     }
 
     fn encode(&self) -> Vec<u8> {
         let mut buffer = vec![];
         buffer.push(self.opcode);
-        buffer.extend(encode_u32_to_leb128(self.index));
+        buffer.extend(self.value.to_bits().to_le_bytes());
         buffer
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct I32AddInstructionNode {
+pub struct F64ConstInstructionNode {
     opcode: u8,
+    pub value: F64,
 }
 
-impl Default for I32AddInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x6a }
+impl F64ConstInstructionNode {
+    pub fn new(value: F64) -> Self {
+        Self {
+            opcode: 0x44,
+            value,
+        }
     }
 }
 
-impl Node for I32AddInstructionNode {
+impl Node for F64ConstInstructionNode {
     fn size(&self) -> u32 {
-        1
+        1 + 8 // opcode and little-endian bits
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(self.value.to_bits().to_le_bytes());
+        buffer
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct I32SubInstructionNode {
-    opcode: u8,
+pub struct V128ConstInstructionNode {
+    prefix: u8,
+    opcode: u32,
+    pub value: V128,
 }
 
-impl Default for I32SubInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x6b }
+impl V128ConstInstructionNode {
+    pub fn new(value: V128) -> Self {
+        Self {
+            prefix: 0xfd,
+            opcode: 0x0c,
+            value,
+        }
     }
 }
 
-impl Node for I32SubInstructionNode {
+impl Node for V128ConstInstructionNode {
     fn size(&self) -> u32 {
-        1
+        1 + encode_u32_to_leb128(self.opcode).len() as u32 + 16 // little-endian bytes
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        let mut buffer = vec![self.prefix];
+        buffer.extend(encode_u32_to_leb128(self.opcode));
+        buffer.extend(self.value.to_le_bytes());
+        buffer
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct I64AddInstructionNode {
-    opcode: u8,
+pub struct I8x16ShuffleInstructionNode {
+    prefix: u8,
+    opcode: u32,
+    /// Indices into the 32 bytes of both operands, one per result lane.
+    pub lanes: [u8; 16],
 }
 
-impl Default for I64AddInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x7c }
+impl I8x16ShuffleInstructionNode {
+    pub fn new(lanes: [u8; 16]) -> Self {
+        Self {
+            prefix: 0xfd,
+            opcode: 0x0d,
+            lanes,
+        }
     }
 }
 
-impl Node for I64AddInstructionNode {
+impl Node for I8x16ShuffleInstructionNode {
     fn size(&self) -> u32 {
-        1
+        1 + encode_u32_to_leb128(self.opcode).len() as u32 + 16
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        let mut buffer = vec![self.prefix];
+        buffer.extend(encode_u32_to_leb128(self.opcode));
+        buffer.extend(self.lanes);
+        buffer
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct I64SubInstructionNode {
+pub struct EndInstructionNode {
     opcode: u8,
 }
 
-impl Default for I64SubInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x7d }
-    }
-}
-
-impl Node for I64SubInstructionNode {
+impl Node for EndInstructionNode {
     fn size(&self) -> u32 {
         1
     }
@@ -584,237 +2349,943 @@ impl Node for I64SubInstructionNode {
     }
 }
 
+impl Default for EndInstructionNode {
+    fn default() -> Self {
+        Self { opcode: 0x0b }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
-pub struct I32RemUInstructionNode {
+pub struct GetLocalInstructionNode {
     opcode: u8,
+    pub index: u32,
 }
 
-impl Default for I32RemUInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x70 }
+impl GetLocalInstructionNode {
+    pub fn new(index: u32) -> Self {
+        Self {
+            opcode: 0x20,
+            index,
+        }
     }
 }
 
-impl Node for I32RemUInstructionNode {
+impl Node for GetLocalInstructionNode {
     fn size(&self) -> u32 {
-        1
+        let mut size = 0;
+        size += 1; // opcode
+        size += encode_u32_to_leb128(self.index).len() as u32;
+        size
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(encode_u32_to_leb128(self.index));
+        buffer
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct I32RemSInstructionNode {
+pub struct SetLocalInstructionNode {
     opcode: u8,
+    pub index: u32,
 }
 
-impl Default for I32RemSInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x6f }
+impl SetLocalInstructionNode {
+    pub fn new(index: u32) -> Self {
+        Self {
+            opcode: 0x21,
+            index,
+        }
     }
 }
 
-impl Node for I32RemSInstructionNode {
+impl Node for SetLocalInstructionNode {
     fn size(&self) -> u32 {
-        1
+        let mut size = 0;
+        size += 1; // opcode
+        size += encode_u32_to_leb128(self.index).len() as u32;
+        size
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(encode_u32_to_leb128(self.index));
+        buffer
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct I32ShlInstructionNode {
+pub struct TeeLocalInstructionNode {
     opcode: u8,
+    pub index: u32,
 }
 
-impl Default for I32ShlInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x74 }
+impl TeeLocalInstructionNode {
+    pub fn new(index: u32) -> Self {
+        Self {
+            opcode: 0x22,
+            index,
+        }
     }
 }
 
-impl Node for I32ShlInstructionNode {
+impl Node for TeeLocalInstructionNode {
     fn size(&self) -> u32 {
-        1
-    }
+        let mut size = 0;
+        size += 1; // opcode
+        size += encode_u32_to_leb128(self.index).len() as u32;
+        size
+    }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(encode_u32_to_leb128(self.index));
+        buffer
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct I32EqzInstructionNode {
+/// `select` (0x1B), or typed `select t*` (0x1C) when `val_types` is present.
+#[derive(Debug, Clone)]
+pub struct SelectInstructionNode {
     opcode: u8,
+    pub val_types: Option<Vec<ValueType>>,
 }
 
-impl Default for I32EqzInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x45 }
+impl SelectInstructionNode {
+    pub fn new(val_types: Option<Vec<ValueType>>) -> Self {
+        let opcode = match val_types {
+            Some(_) => 0x1c,
+            None => 0x1b,
+        };
+        Self { opcode, val_types }
     }
 }
 
-impl Node for I32EqzInstructionNode {
+impl Node for SelectInstructionNode {
     fn size(&self) -> u32 {
-        1
+        let mut size = 0;
+        size += 1; // opcode
+        if let Some(val_types) = &self.val_types {
+            size += encode_u32_to_leb128(val_types.len() as u32).len() as u32;
+            for val_type in val_types.iter() {
+                size += val_type.size();
+            }
+        }
+        size
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        if let Some(val_types) = &self.val_types {
+            buffer.extend(encode_u32_to_leb128(val_types.len() as u32));
+            for val_type in val_types.iter() {
+                buffer.extend(val_type.encode());
+            }
+        }
+        buffer
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct I32EqInstructionNode {
+pub struct GetGlobalInstructionNode {
     opcode: u8,
+    pub index: u32,
 }
 
-impl Default for I32EqInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x46 }
+impl GetGlobalInstructionNode {
+    pub fn new(index: u32) -> Self {
+        Self {
+            opcode: 0x23,
+            index,
+        }
     }
 }
 
-impl Node for I32EqInstructionNode {
+impl Node for GetGlobalInstructionNode {
     fn size(&self) -> u32 {
-        1
+        let mut size = 0;
+        size += 1; // opcode
+        size += encode_u32_to_leb128(self.index).len() as u32;
+        size
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(encode_u32_to_leb128(self.index));
+        buffer
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct I32LtSInstructionNode {
+pub struct SetGlobalInstructionNode {
     opcode: u8,
+    pub index: u32,
 }
 
-impl Default for I32LtSInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x48 }
+impl SetGlobalInstructionNode {
+    pub fn new(index: u32) -> Self {
+        Self {
+            opcode: 0x24,
+            index,
+        }
     }
 }
 
-impl Node for I32LtSInstructionNode {
+impl Node for SetGlobalInstructionNode {
     fn size(&self) -> u32 {
-        1
+        let mut size = 0;
+        size += 1; // opcode
+        size += encode_u32_to_leb128(self.index).len() as u32;
+        size
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(encode_u32_to_leb128(self.index));
+        buffer
+    }
+}
+
+// https://webassembly.github.io/spec/core/binary/instructions.html#memory-instructions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemArgNode {
+    pub align: u32,
+    pub offset: u32,
+}
+
+impl Node for MemArgNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += encode_u32_to_leb128(self.align).len() as u32;
+        size += encode_u32_to_leb128(self.offset).len() as u32;
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        buffer.extend(encode_u32_to_leb128(self.align));
+        buffer.extend(encode_u32_to_leb128(self.offset));
+        buffer
     }
 }
 
+/// Shared by every load and store instruction, which only differ in opcode.
 #[derive(Debug, Clone, Copy)]
-pub struct I32LtUInstructionNode {
+pub struct MemoryAccessInstructionNode {
     opcode: u8,
+    pub memarg: MemArgNode,
 }
 
-impl Default for I32LtUInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x49 }
+impl MemoryAccessInstructionNode {
+    pub fn new(opcode: u8, memarg: MemArgNode) -> Self {
+        Self { opcode, memarg }
     }
 }
 
-impl Node for I32LtUInstructionNode {
+impl Node for MemoryAccessInstructionNode {
     fn size(&self) -> u32 {
-        1
+        let mut size = 0;
+        size += 1; // opcode
+        size += self.memarg.size();
+        size
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(self.memarg.encode());
+        buffer
     }
 }
 
+/// Shared by the vector loads and stores that take only a memarg.
 #[derive(Debug, Clone, Copy)]
-pub struct I32GeSInstructionNode {
-    opcode: u8,
+pub struct SimdMemoryAccessInstructionNode {
+    prefix: u8,
+    opcode: u32,
+    pub memarg: MemArgNode,
 }
 
-impl Default for I32GeSInstructionNode {
-    fn default() -> Self {
-        Self { opcode: 0x4e }
+impl SimdMemoryAccessInstructionNode {
+    pub fn new(opcode: u32, memarg: MemArgNode) -> Self {
+        Self {
+            prefix: 0xfd,
+            opcode,
+            memarg,
+        }
     }
 }
 
-impl Node for I32GeSInstructionNode {
+impl Node for SimdMemoryAccessInstructionNode {
     fn size(&self) -> u32 {
-        1
+        let mut size = 1 + encode_u32_to_leb128(self.opcode).len() as u32;
+        size += self.memarg.size();
+        size
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        let mut buffer = vec![self.prefix];
+        buffer.extend(encode_u32_to_leb128(self.opcode));
+        buffer.extend(self.memarg.encode());
+        buffer
     }
 }
 
+/// Shared by the vector loads and stores of a single lane.
 #[derive(Debug, Clone, Copy)]
-pub struct I32GeUInstructionNode {
-    opcode: u8,
+pub struct SimdMemoryLaneInstructionNode {
+    prefix: u8,
+    opcode: u32,
+    pub memarg: MemArgNode,
+    pub lane: u8,
+}
+
+impl SimdMemoryLaneInstructionNode {
+    pub fn new(opcode: u32, memarg: MemArgNode, lane: u8) -> Self {
+        Self {
+            prefix: 0xfd,
+            opcode,
+            memarg,
+            lane,
+        }
+    }
+}
+
+impl Node for SimdMemoryLaneInstructionNode {
+    fn size(&self) -> u32 {
+        let mut size = 1 + encode_u32_to_leb128(self.opcode).len() as u32;
+        size += self.memarg.size();
+        size += 1; // lane index
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![self.prefix];
+        buffer.extend(encode_u32_to_leb128(self.opcode));
+        buffer.extend(self.memarg.encode());
+        buffer.push(self.lane);
+        buffer
+    }
+}
+
+/// Shared by the `extract_lane` and `replace_lane` instructions.
+#[derive(Debug, Clone, Copy)]
+pub struct SimdLaneInstructionNode {
+    prefix: u8,
+    opcode: u32,
+    pub lane: u8,
+}
+
+impl SimdLaneInstructionNode {
+    pub fn new(opcode: u32, lane: u8) -> Self {
+        Self {
+            prefix: 0xfd,
+            opcode,
+            lane,
+        }
+    }
+}
+
+impl Node for SimdLaneInstructionNode {
+    fn size(&self) -> u32 {
+        1 + encode_u32_to_leb128(self.opcode).len() as u32 + 1
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![self.prefix];
+        buffer.extend(encode_u32_to_leb128(self.opcode));
+        buffer.push(self.lane);
+        buffer
+    }
+}
+
+/// Shared by the atomic loads, stores, read-modify-writes, waits and notify,
+/// which all take a memarg.
+#[derive(Debug, Clone, Copy)]
+pub struct AtomicMemoryAccessInstructionNode {
+    prefix: u8,
+    opcode: u32,
+    pub memarg: MemArgNode,
+}
+
+impl AtomicMemoryAccessInstructionNode {
+    pub fn new(opcode: u32, memarg: MemArgNode) -> Self {
+        Self {
+            prefix: 0xfe,
+            opcode,
+            memarg,
+        }
+    }
+}
+
+impl Node for AtomicMemoryAccessInstructionNode {
+    fn size(&self) -> u32 {
+        let mut size = 1 + encode_u32_to_leb128(self.opcode).len() as u32;
+        size += self.memarg.size();
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![self.prefix];
+        buffer.extend(encode_u32_to_leb128(self.opcode));
+        buffer.extend(self.memarg.encode());
+        buffer
+    }
 }
 
-impl Default for I32GeUInstructionNode {
+/// `atomic.fence`, followed by a reserved zero byte.
+#[derive(Debug, Clone, Copy)]
+pub struct AtomicFenceInstructionNode {
+    prefix: u8,
+    opcode: u32,
+    reserved: u8,
+}
+
+impl Default for AtomicFenceInstructionNode {
     fn default() -> Self {
-        Self { opcode: 0x4f }
+        Self {
+            prefix: 0xfe,
+            opcode: 0x03,
+            reserved: 0x00,
+        }
     }
 }
 
-impl Node for I32GeUInstructionNode {
+impl Node for AtomicFenceInstructionNode {
     fn size(&self) -> u32 {
-        1
+        1 + encode_u32_to_leb128(self.opcode).len() as u32 + 1
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        let mut buffer = vec![self.prefix];
+        buffer.extend(encode_u32_to_leb128(self.opcode));
+        buffer.push(self.reserved);
+        buffer
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct I32GtSInstructionNode {
+pub struct MemorySizeInstructionNode {
     opcode: u8,
+    pub memory_index: u8,
 }
 
-impl Default for I32GtSInstructionNode {
+impl Default for MemorySizeInstructionNode {
     fn default() -> Self {
-        Self { opcode: 0x55 }
+        Self {
+            opcode: 0x3f,
+            memory_index: 0x00,
+        }
     }
 }
 
-impl Node for I32GtSInstructionNode {
+impl Node for MemorySizeInstructionNode {
     fn size(&self) -> u32 {
-        1
+        2
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        vec![self.opcode, self.memory_index]
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct I32GtUInstructionNode {
+pub struct MemoryGrowInstructionNode {
     opcode: u8,
+    pub memory_index: u8,
 }
 
-impl Default for I32GtUInstructionNode {
+impl Default for MemoryGrowInstructionNode {
     fn default() -> Self {
-        Self { opcode: 0x56 }
+        Self {
+            opcode: 0x40,
+            memory_index: 0x00,
+        }
     }
 }
 
-impl Node for I32GtUInstructionNode {
+impl Node for MemoryGrowInstructionNode {
     fn size(&self) -> u32 {
-        1
+        2
     }
 
     fn encode(&self) -> Vec<u8> {
-        vec![self.opcode]
+        vec![self.opcode, self.memory_index]
     }
 }
 
+/// Declares instructions that consist of a bare opcode, such as the numeric
+/// operators, together with their `Default` and `Node` impls.
+macro_rules! opcode_instruction_nodes {
+    ($($name:ident => $opcode:expr,)*) => {
+        $(
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name {
+                opcode: u8,
+            }
+
+            impl Default for $name {
+                fn default() -> Self {
+                    Self { opcode: $opcode }
+                }
+            }
+
+            impl Node for $name {
+                fn size(&self) -> u32 {
+                    1
+                }
+
+                fn encode(&self) -> Vec<u8> {
+                    vec![self.opcode]
+                }
+            }
+        )*
+    };
+}
+
+opcode_instruction_nodes! {
+    UnreachableInstructionNode => 0x00,
+    NopInstructionNode => 0x01,
+    ReturnInstructionNode => 0x0f,
+    DropInstructionNode => 0x1a,
+    RefIsNullInstructionNode => 0xd1,
+    I32EqzInstructionNode => 0x45,
+    I32EqInstructionNode => 0x46,
+    I32NeInstructionNode => 0x47,
+    I32LtSInstructionNode => 0x48,
+    I32LtUInstructionNode => 0x49,
+    I32GtSInstructionNode => 0x4a,
+    I32GtUInstructionNode => 0x4b,
+    I32LeSInstructionNode => 0x4c,
+    I32LeUInstructionNode => 0x4d,
+    I32GeSInstructionNode => 0x4e,
+    I32GeUInstructionNode => 0x4f,
+    I32ClzInstructionNode => 0x67,
+    I32CtzInstructionNode => 0x68,
+    I32PopcntInstructionNode => 0x69,
+    I32AddInstructionNode => 0x6a,
+    I32SubInstructionNode => 0x6b,
+    I32MulInstructionNode => 0x6c,
+    I32DivSInstructionNode => 0x6d,
+    I32DivUInstructionNode => 0x6e,
+    I32RemSInstructionNode => 0x6f,
+    I32RemUInstructionNode => 0x70,
+    I32AndInstructionNode => 0x71,
+    I32OrInstructionNode => 0x72,
+    I32XorInstructionNode => 0x73,
+    I32ShlInstructionNode => 0x74,
+    I32ShrSInstructionNode => 0x75,
+    I32ShrUInstructionNode => 0x76,
+    I32RotlInstructionNode => 0x77,
+    I32RotrInstructionNode => 0x78,
+    I64EqzInstructionNode => 0x50,
+    I64EqInstructionNode => 0x51,
+    I64NeInstructionNode => 0x52,
+    I64LtSInstructionNode => 0x53,
+    I64LtUInstructionNode => 0x54,
+    I64GtSInstructionNode => 0x55,
+    I64GtUInstructionNode => 0x56,
+    I64LeSInstructionNode => 0x57,
+    I64LeUInstructionNode => 0x58,
+    I64GeSInstructionNode => 0x59,
+    I64GeUInstructionNode => 0x5a,
+    I64ClzInstructionNode => 0x79,
+    I64CtzInstructionNode => 0x7a,
+    I64PopcntInstructionNode => 0x7b,
+    I64AddInstructionNode => 0x7c,
+    I64SubInstructionNode => 0x7d,
+    I64MulInstructionNode => 0x7e,
+    I64DivSInstructionNode => 0x7f,
+    I64DivUInstructionNode => 0x80,
+    I64RemSInstructionNode => 0x81,
+    I64RemUInstructionNode => 0x82,
+    I64AndInstructionNode => 0x83,
+    I64OrInstructionNode => 0x84,
+    I64XorInstructionNode => 0x85,
+    I64ShlInstructionNode => 0x86,
+    I64ShrSInstructionNode => 0x87,
+    I64ShrUInstructionNode => 0x88,
+    I64RotlInstructionNode => 0x89,
+    I64RotrInstructionNode => 0x8a,
+    F32EqInstructionNode => 0x5b,
+    F32NeInstructionNode => 0x5c,
+    F32LtInstructionNode => 0x5d,
+    F32GtInstructionNode => 0x5e,
+    F32LeInstructionNode => 0x5f,
+    F32GeInstructionNode => 0x60,
+    F64EqInstructionNode => 0x61,
+    F64NeInstructionNode => 0x62,
+    F64LtInstructionNode => 0x63,
+    F64GtInstructionNode => 0x64,
+    F64LeInstructionNode => 0x65,
+    F64GeInstructionNode => 0x66,
+    F32AbsInstructionNode => 0x8b,
+    F32NegInstructionNode => 0x8c,
+    F32CeilInstructionNode => 0x8d,
+    F32FloorInstructionNode => 0x8e,
+    F32TruncInstructionNode => 0x8f,
+    F32NearestInstructionNode => 0x90,
+    F32SqrtInstructionNode => 0x91,
+    F32AddInstructionNode => 0x92,
+    F32SubInstructionNode => 0x93,
+    F32MulInstructionNode => 0x94,
+    F32DivInstructionNode => 0x95,
+    F32MinInstructionNode => 0x96,
+    F32MaxInstructionNode => 0x97,
+    F32CopysignInstructionNode => 0x98,
+    F64AbsInstructionNode => 0x99,
+    F64NegInstructionNode => 0x9a,
+    F64CeilInstructionNode => 0x9b,
+    F64FloorInstructionNode => 0x9c,
+    F64TruncInstructionNode => 0x9d,
+    F64NearestInstructionNode => 0x9e,
+    F64SqrtInstructionNode => 0x9f,
+    F64AddInstructionNode => 0xa0,
+    F64SubInstructionNode => 0xa1,
+    F64MulInstructionNode => 0xa2,
+    F64DivInstructionNode => 0xa3,
+    F64MinInstructionNode => 0xa4,
+    F64MaxInstructionNode => 0xa5,
+    F64CopysignInstructionNode => 0xa6,
+    I32WrapI64InstructionNode => 0xa7,
+    I32TruncF32SInstructionNode => 0xa8,
+    I32TruncF32UInstructionNode => 0xa9,
+    I32TruncF64SInstructionNode => 0xaa,
+    I32TruncF64UInstructionNode => 0xab,
+    I64ExtendI32SInstructionNode => 0xac,
+    I64ExtendI32UInstructionNode => 0xad,
+    I64TruncF32SInstructionNode => 0xae,
+    I64TruncF32UInstructionNode => 0xaf,
+    I64TruncF64SInstructionNode => 0xb0,
+    I64TruncF64UInstructionNode => 0xb1,
+    F32ConvertI32SInstructionNode => 0xb2,
+    F32ConvertI32UInstructionNode => 0xb3,
+    F32ConvertI64SInstructionNode => 0xb4,
+    F32ConvertI64UInstructionNode => 0xb5,
+    F32DemoteF64InstructionNode => 0xb6,
+    F64ConvertI32SInstructionNode => 0xb7,
+    F64ConvertI32UInstructionNode => 0xb8,
+    F64ConvertI64SInstructionNode => 0xb9,
+    F64ConvertI64UInstructionNode => 0xba,
+    F64PromoteF32InstructionNode => 0xbb,
+    I32ReinterpretF32InstructionNode => 0xbc,
+    I64ReinterpretF64InstructionNode => 0xbd,
+    F32ReinterpretI32InstructionNode => 0xbe,
+    F64ReinterpretI64InstructionNode => 0xbf,
+    I32Extend8SInstructionNode => 0xc0,
+    I32Extend16SInstructionNode => 0xc1,
+    I64Extend8SInstructionNode => 0xc2,
+    I64Extend16SInstructionNode => 0xc3,
+    I64Extend32SInstructionNode => 0xc4,
+}
+
+/// Declares instructions that consist of a prefix byte and a u32 sub-opcode,
+/// together with their `Default` and `Node` impls.
+macro_rules! prefixed_instruction_nodes {
+    ($($name:ident => ($prefix:expr, $opcode:expr),)*) => {
+        $(
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name {
+                prefix: u8,
+                opcode: u32,
+            }
+
+            impl Default for $name {
+                fn default() -> Self {
+                    Self {
+                        prefix: $prefix,
+                        opcode: $opcode,
+                    }
+                }
+            }
+
+            impl Node for $name {
+                fn size(&self) -> u32 {
+                    1 + encode_u32_to_leb128(self.opcode).len() as u32
+                }
+
+                fn encode(&self) -> Vec<u8> {
+                    let mut buffer = vec![self.prefix];
+                    buffer.extend(encode_u32_to_leb128(self.opcode));
+                    buffer
+                }
+            }
+        )*
+    };
+}
+
+prefixed_instruction_nodes! {
+    I32TruncSatF32SInstructionNode => (0xfc, 0),
+    I32TruncSatF32UInstructionNode => (0xfc, 1),
+    I32TruncSatF64SInstructionNode => (0xfc, 2),
+    I32TruncSatF64UInstructionNode => (0xfc, 3),
+    I64TruncSatF32SInstructionNode => (0xfc, 4),
+    I64TruncSatF32UInstructionNode => (0xfc, 5),
+    I64TruncSatF64SInstructionNode => (0xfc, 6),
+    I64TruncSatF64UInstructionNode => (0xfc, 7),
+}
+
+prefixed_instruction_nodes! {
+    I8x16SwizzleInstructionNode => (0xfd, 0x0e),
+    I8x16SplatInstructionNode => (0xfd, 0x0f),
+    I16x8SplatInstructionNode => (0xfd, 0x10),
+    I32x4SplatInstructionNode => (0xfd, 0x11),
+    I64x2SplatInstructionNode => (0xfd, 0x12),
+    F32x4SplatInstructionNode => (0xfd, 0x13),
+    F64x2SplatInstructionNode => (0xfd, 0x14),
+    I8x16EqInstructionNode => (0xfd, 0x23),
+    I8x16NeInstructionNode => (0xfd, 0x24),
+    I8x16LtSInstructionNode => (0xfd, 0x25),
+    I8x16LtUInstructionNode => (0xfd, 0x26),
+    I8x16GtSInstructionNode => (0xfd, 0x27),
+    I8x16GtUInstructionNode => (0xfd, 0x28),
+    I8x16LeSInstructionNode => (0xfd, 0x29),
+    I8x16LeUInstructionNode => (0xfd, 0x2a),
+    I8x16GeSInstructionNode => (0xfd, 0x2b),
+    I8x16GeUInstructionNode => (0xfd, 0x2c),
+    I16x8EqInstructionNode => (0xfd, 0x2d),
+    I16x8NeInstructionNode => (0xfd, 0x2e),
+    I16x8LtSInstructionNode => (0xfd, 0x2f),
+    I16x8LtUInstructionNode => (0xfd, 0x30),
+    I16x8GtSInstructionNode => (0xfd, 0x31),
+    I16x8GtUInstructionNode => (0xfd, 0x32),
+    I16x8LeSInstructionNode => (0xfd, 0x33),
+    I16x8LeUInstructionNode => (0xfd, 0x34),
+    I16x8GeSInstructionNode => (0xfd, 0x35),
+    I16x8GeUInstructionNode => (0xfd, 0x36),
+    I32x4EqInstructionNode => (0xfd, 0x37),
+    I32x4NeInstructionNode => (0xfd, 0x38),
+    I32x4LtSInstructionNode => (0xfd, 0x39),
+    I32x4LtUInstructionNode => (0xfd, 0x3a),
+    I32x4GtSInstructionNode => (0xfd, 0x3b),
+    I32x4GtUInstructionNode => (0xfd, 0x3c),
+    I32x4LeSInstructionNode => (0xfd, 0x3d),
+    I32x4LeUInstructionNode => (0xfd, 0x3e),
+    I32x4GeSInstructionNode => (0xfd, 0x3f),
+    I32x4GeUInstructionNode => (0xfd, 0x40),
+    F32x4EqInstructionNode => (0xfd, 0x41),
+    F32x4NeInstructionNode => (0xfd, 0x42),
+    F32x4LtInstructionNode => (0xfd, 0x43),
+    F32x4GtInstructionNode => (0xfd, 0x44),
+    F32x4LeInstructionNode => (0xfd, 0x45),
+    F32x4GeInstructionNode => (0xfd, 0x46),
+    F64x2EqInstructionNode => (0xfd, 0x47),
+    F64x2NeInstructionNode => (0xfd, 0x48),
+    F64x2LtInstructionNode => (0xfd, 0x49),
+    F64x2GtInstructionNode => (0xfd, 0x4a),
+    F64x2LeInstructionNode => (0xfd, 0x4b),
+    F64x2GeInstructionNode => (0xfd, 0x4c),
+    V128NotInstructionNode => (0xfd, 0x4d),
+    V128AndInstructionNode => (0xfd, 0x4e),
+    V128AndnotInstructionNode => (0xfd, 0x4f),
+    V128OrInstructionNode => (0xfd, 0x50),
+    V128XorInstructionNode => (0xfd, 0x51),
+    V128BitselectInstructionNode => (0xfd, 0x52),
+    V128AnyTrueInstructionNode => (0xfd, 0x53),
+    F32x4DemoteF64x2ZeroInstructionNode => (0xfd, 0x5e),
+    F64x2PromoteLowF32x4InstructionNode => (0xfd, 0x5f),
+    I8x16AbsInstructionNode => (0xfd, 0x60),
+    I8x16NegInstructionNode => (0xfd, 0x61),
+    I8x16PopcntInstructionNode => (0xfd, 0x62),
+    I8x16AllTrueInstructionNode => (0xfd, 0x63),
+    I8x16BitmaskInstructionNode => (0xfd, 0x64),
+    I8x16NarrowI16x8SInstructionNode => (0xfd, 0x65),
+    I8x16NarrowI16x8UInstructionNode => (0xfd, 0x66),
+    F32x4CeilInstructionNode => (0xfd, 0x67),
+    F32x4FloorInstructionNode => (0xfd, 0x68),
+    F32x4TruncInstructionNode => (0xfd, 0x69),
+    F32x4NearestInstructionNode => (0xfd, 0x6a),
+    I8x16ShlInstructionNode => (0xfd, 0x6b),
+    I8x16ShrSInstructionNode => (0xfd, 0x6c),
+    I8x16ShrUInstructionNode => (0xfd, 0x6d),
+    I8x16AddInstructionNode => (0xfd, 0x6e),
+    I8x16AddSatSInstructionNode => (0xfd, 0x6f),
+    I8x16AddSatUInstructionNode => (0xfd, 0x70),
+    I8x16SubInstructionNode => (0xfd, 0x71),
+    I8x16SubSatSInstructionNode => (0xfd, 0x72),
+    I8x16SubSatUInstructionNode => (0xfd, 0x73),
+    F64x2CeilInstructionNode => (0xfd, 0x74),
+    F64x2FloorInstructionNode => (0xfd, 0x75),
+    I8x16MinSInstructionNode => (0xfd, 0x76),
+    I8x16MinUInstructionNode => (0xfd, 0x77),
+    I8x16MaxSInstructionNode => (0xfd, 0x78),
+    I8x16MaxUInstructionNode => (0xfd, 0x79),
+    F64x2TruncInstructionNode => (0xfd, 0x7a),
+    I8x16AvgrUInstructionNode => (0xfd, 0x7b),
+    I16x8ExtaddPairwiseI8x16SInstructionNode => (0xfd, 0x7c),
+    I16x8ExtaddPairwiseI8x16UInstructionNode => (0xfd, 0x7d),
+    I32x4ExtaddPairwiseI16x8SInstructionNode => (0xfd, 0x7e),
+    I32x4ExtaddPairwiseI16x8UInstructionNode => (0xfd, 0x7f),
+    I16x8AbsInstructionNode => (0xfd, 0x80),
+    I16x8NegInstructionNode => (0xfd, 0x81),
+    I16x8Q15mulrSatSInstructionNode => (0xfd, 0x82),
+    I16x8AllTrueInstructionNode => (0xfd, 0x83),
+    I16x8BitmaskInstructionNode => (0xfd, 0x84),
+    I16x8NarrowI32x4SInstructionNode => (0xfd, 0x85),
+    I16x8NarrowI32x4UInstructionNode => (0xfd, 0x86),
+    I16x8ExtendLowI8x16SInstructionNode => (0xfd, 0x87),
+    I16x8ExtendHighI8x16SInstructionNode => (0xfd, 0x88),
+    I16x8ExtendLowI8x16UInstructionNode => (0xfd, 0x89),
+    I16x8ExtendHighI8x16UInstructionNode => (0xfd, 0x8a),
+    I16x8ShlInstructionNode => (0xfd, 0x8b),
+    I16x8ShrSInstructionNode => (0xfd, 0x8c),
+    I16x8ShrUInstructionNode => (0xfd, 0x8d),
+    I16x8AddInstructionNode => (0xfd, 0x8e),
+    I16x8AddSatSInstructionNode => (0xfd, 0x8f),
+    I16x8AddSatUInstructionNode => (0xfd, 0x90),
+    I16x8SubInstructionNode => (0xfd, 0x91),
+    I16x8SubSatSInstructionNode => (0xfd, 0x92),
+    I16x8SubSatUInstructionNode => (0xfd, 0x93),
+    F64x2NearestInstructionNode => (0xfd, 0x94),
+    I16x8MulInstructionNode => (0xfd, 0x95),
+    I16x8MinSInstructionNode => (0xfd, 0x96),
+    I16x8MinUInstructionNode => (0xfd, 0x97),
+    I16x8MaxSInstructionNode => (0xfd, 0x98),
+    I16x8MaxUInstructionNode => (0xfd, 0x99),
+    I16x8AvgrUInstructionNode => (0xfd, 0x9b),
+    I16x8ExtmulLowI8x16SInstructionNode => (0xfd, 0x9c),
+    I16x8ExtmulHighI8x16SInstructionNode => (0xfd, 0x9d),
+    I16x8ExtmulLowI8x16UInstructionNode => (0xfd, 0x9e),
+    I16x8ExtmulHighI8x16UInstructionNode => (0xfd, 0x9f),
+    I32x4AbsInstructionNode => (0xfd, 0xa0),
+    I32x4NegInstructionNode => (0xfd, 0xa1),
+    I32x4AllTrueInstructionNode => (0xfd, 0xa3),
+    I32x4BitmaskInstructionNode => (0xfd, 0xa4),
+    I32x4ExtendLowI16x8SInstructionNode => (0xfd, 0xa7),
+    I32x4ExtendHighI16x8SInstructionNode => (0xfd, 0xa8),
+    I32x4ExtendLowI16x8UInstructionNode => (0xfd, 0xa9),
+    I32x4ExtendHighI16x8UInstructionNode => (0xfd, 0xaa),
+    I32x4ShlInstructionNode => (0xfd, 0xab),
+    I32x4ShrSInstructionNode => (0xfd, 0xac),
+    I32x4ShrUInstructionNode => (0xfd, 0xad),
+    I32x4AddInstructionNode => (0xfd, 0xae),
+    I32x4SubInstructionNode => (0xfd, 0xb1),
+    I32x4MulInstructionNode => (0xfd, 0xb5),
+    I32x4MinSInstructionNode => (0xfd, 0xb6),
+    I32x4MinUInstructionNode => (0xfd, 0xb7),
+    I32x4MaxSInstructionNode => (0xfd, 0xb8),
+    I32x4MaxUInstructionNode => (0xfd, 0xb9),
+    I32x4DotI16x8SInstructionNode => (0xfd, 0xba),
+    I32x4ExtmulLowI16x8SInstructionNode => (0xfd, 0xbc),
+    I32x4ExtmulHighI16x8SInstructionNode => (0xfd, 0xbd),
+    I32x4ExtmulLowI16x8UInstructionNode => (0xfd, 0xbe),
+    I32x4ExtmulHighI16x8UInstructionNode => (0xfd, 0xbf),
+    I64x2AbsInstructionNode => (0xfd, 0xc0),
+    I64x2NegInstructionNode => (0xfd, 0xc1),
+    I64x2AllTrueInstructionNode => (0xfd, 0xc3),
+    I64x2BitmaskInstructionNode => (0xfd, 0xc4),
+    I64x2ExtendLowI32x4SInstructionNode => (0xfd, 0xc7),
+    I64x2ExtendHighI32x4SInstructionNode => (0xfd, 0xc8),
+    I64x2ExtendLowI32x4UInstructionNode => (0xfd, 0xc9),
+    I64x2ExtendHighI32x4UInstructionNode => (0xfd, 0xca),
+    I64x2ShlInstructionNode => (0xfd, 0xcb),
+    I64x2ShrSInstructionNode => (0xfd, 0xcc),
+    I64x2ShrUInstructionNode => (0xfd, 0xcd),
+    I64x2AddInstructionNode => (0xfd, 0xce),
+    I64x2SubInstructionNode => (0xfd, 0xd1),
+    I64x2MulInstructionNode => (0xfd, 0xd5),
+    I64x2EqInstructionNode => (0xfd, 0xd6),
+    I64x2NeInstructionNode => (0xfd, 0xd7),
+    I64x2LtSInstructionNode => (0xfd, 0xd8),
+    I64x2GtSInstructionNode => (0xfd, 0xd9),
+    I64x2LeSInstructionNode => (0xfd, 0xda),
+    I64x2GeSInstructionNode => (0xfd, 0xdb),
+    I64x2ExtmulLowI32x4SInstructionNode => (0xfd, 0xdc),
+    I64x2ExtmulHighI32x4SInstructionNode => (0xfd, 0xdd),
+    I64x2ExtmulLowI32x4UInstructionNode => (0xfd, 0xde),
+    I64x2ExtmulHighI32x4UInstructionNode => (0xfd, 0xdf),
+    F32x4AbsInstructionNode => (0xfd, 0xe0),
+    F32x4NegInstructionNode => (0xfd, 0xe1),
+    F32x4SqrtInstructionNode => (0xfd, 0xe3),
+    F32x4AddInstructionNode => (0xfd, 0xe4),
+    F32x4SubInstructionNode => (0xfd, 0xe5),
+    F32x4MulInstructionNode => (0xfd, 0xe6),
+    F32x4DivInstructionNode => (0xfd, 0xe7),
+    F32x4MinInstructionNode => (0xfd, 0xe8),
+    F32x4MaxInstructionNode => (0xfd, 0xe9),
+    F32x4PminInstructionNode => (0xfd, 0xea),
+    F32x4PmaxInstructionNode => (0xfd, 0xeb),
+    F64x2AbsInstructionNode => (0xfd, 0xec),
+    F64x2NegInstructionNode => (0xfd, 0xed),
+    F64x2SqrtInstructionNode => (0xfd, 0xef),
+    F64x2AddInstructionNode => (0xfd, 0xf0),
+    F64x2SubInstructionNode => (0xfd, 0xf1),
+    F64x2MulInstructionNode => (0xfd, 0xf2),
+    F64x2DivInstructionNode => (0xfd, 0xf3),
+    F64x2MinInstructionNode => (0xfd, 0xf4),
+    F64x2MaxInstructionNode => (0xfd, 0xf5),
+    F64x2PminInstructionNode => (0xfd, 0xf6),
+    F64x2PmaxInstructionNode => (0xfd, 0xf7),
+    I32x4TruncSatF32x4SInstructionNode => (0xfd, 0xf8),
+    I32x4TruncSatF32x4UInstructionNode => (0xfd, 0xf9),
+    F32x4ConvertI32x4SInstructionNode => (0xfd, 0xfa),
+    F32x4ConvertI32x4UInstructionNode => (0xfd, 0xfb),
+    I32x4TruncSatF64x2SZeroInstructionNode => (0xfd, 0xfc),
+    I32x4TruncSatF64x2UZeroInstructionNode => (0xfd, 0xfd),
+    F64x2ConvertLowI32x4SInstructionNode => (0xfd, 0xfe),
+    F64x2ConvertLowI32x4UInstructionNode => (0xfd, 0xff),
+}
+
+/// Like `prefixed_instruction_nodes!`, for instructions whose immediates are
+/// all u32 indices, encoded in the order they are listed.
+macro_rules! prefixed_index_instruction_nodes {
+    ($($name:ident => ($prefix:expr, $opcode:expr) { $($field:ident),* },)*) => {
+        $(
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name {
+                prefix: u8,
+                opcode: u32,
+                $(pub $field: u32,)*
+            }
+
+            impl $name {
+                pub fn new($($field: u32),*) -> Self {
+                    Self {
+                        prefix: $prefix,
+                        opcode: $opcode,
+                        $($field,)*
+                    }
+                }
+            }
+
+            impl Node for $name {
+                fn size(&self) -> u32 {
+                    let mut size = 1 + encode_u32_to_leb128(self.opcode).len() as u32;
+                    $(size += encode_u32_to_leb128(self.$field).len() as u32;)*
+                    size
+                }
+
+                fn encode(&self) -> Vec<u8> {
+                    let mut buffer = vec![self.prefix];
+                    buffer.extend(encode_u32_to_leb128(self.opcode));
+                    $(buffer.extend(encode_u32_to_leb128(self.$field));)*
+                    buffer
+                }
+            }
+        )*
+    };
+}
+
+prefixed_index_instruction_nodes! {
+    MemoryInitInstructionNode => (0xfc, 8) { data_index, memory_index },
+    DataDropInstructionNode => (0xfc, 9) { data_index },
+    MemoryCopyInstructionNode => (0xfc, 10) { dst_memory_index, src_memory_index },
+    MemoryFillInstructionNode => (0xfc, 11) { memory_index },
+    TableInitInstructionNode => (0xfc, 12) { elem_index, table_index },
+    ElemDropInstructionNode => (0xfc, 13) { elem_index },
+    TableCopyInstructionNode => (0xfc, 14) { dst_table_index, src_table_index },
+    TableGrowInstructionNode => (0xfc, 15) { table_index },
+    TableSizeInstructionNode => (0xfc, 16) { table_index },
+    TableFillInstructionNode => (0xfc, 17) { table_index },
+}
+
 #[derive(Debug, Clone)]
 pub struct IfInstructionNode {
     opcode: u8,
@@ -1022,6 +3493,48 @@ impl Node for BrIfInstructionNode {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct BrTableInstructionNode {
+    opcode: u8,
+    pub depths: Vec<usize>,
+    /// Taken when the operand is out of range of `depths`.
+    pub default_depth: usize,
+}
+
+impl BrTableInstructionNode {
+    pub fn new(depths: Vec<usize>, default_depth: usize) -> Self {
+        Self {
+            opcode: 0x0e,
+            depths,
+            default_depth,
+        }
+    }
+}
+
+impl Node for BrTableInstructionNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += 1; // opcode
+        size += encode_u32_to_leb128(self.depths.len() as u32).len() as u32;
+        for depth in self.depths.iter() {
+            size += encode_u32_to_leb128(*depth as u32).len() as u32;
+        }
+        size += encode_u32_to_leb128(self.default_depth as u32).len() as u32;
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(encode_u32_to_leb128(self.depths.len() as u32));
+        for depth in self.depths.iter() {
+            buffer.extend(encode_u32_to_leb128(*depth as u32));
+        }
+        buffer.extend(encode_u32_to_leb128(self.default_depth as u32));
+        buffer
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CallInstructionNode {
     opcode: u8,
@@ -1052,3 +3565,159 @@ impl Node for CallInstructionNode {
         buffer
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct CallIndirectInstructionNode {
+    opcode: u8,
+    pub type_index: u32,
+    pub table_index: u32,
+}
+
+impl CallIndirectInstructionNode {
+    pub fn new(type_index: u32, table_index: u32) -> Self {
+        Self {
+            opcode: 0x11,
+            type_index,
+            table_index,
+        }
+    }
+}
+
+impl Node for CallIndirectInstructionNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += 1; // opcode
+        size += encode_u32_to_leb128(self.type_index).len() as u32;
+        size += encode_u32_to_leb128(self.table_index).len() as u32;
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(encode_u32_to_leb128(self.type_index));
+        buffer.extend(encode_u32_to_leb128(self.table_index));
+        buffer
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TableGetInstructionNode {
+    opcode: u8,
+    pub table_index: u32,
+}
+
+impl TableGetInstructionNode {
+    pub fn new(table_index: u32) -> Self {
+        Self {
+            opcode: 0x25,
+            table_index,
+        }
+    }
+}
+
+impl Node for TableGetInstructionNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += 1; // opcode
+        size += encode_u32_to_leb128(self.table_index).len() as u32;
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(encode_u32_to_leb128(self.table_index));
+        buffer
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TableSetInstructionNode {
+    opcode: u8,
+    pub table_index: u32,
+}
+
+impl TableSetInstructionNode {
+    pub fn new(table_index: u32) -> Self {
+        Self {
+            opcode: 0x26,
+            table_index,
+        }
+    }
+}
+
+impl Node for TableSetInstructionNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += 1; // opcode
+        size += encode_u32_to_leb128(self.table_index).len() as u32;
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(encode_u32_to_leb128(self.table_index));
+        buffer
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RefNullInstructionNode {
+    opcode: u8,
+    pub ref_type: ReferenceTypeNode,
+}
+
+impl RefNullInstructionNode {
+    pub fn new(ref_type: ReferenceTypeNode) -> Self {
+        Self {
+            opcode: 0xd0,
+            ref_type,
+        }
+    }
+}
+
+impl Node for RefNullInstructionNode {
+    fn size(&self) -> u32 {
+        1 + self.ref_type.size()
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(self.ref_type.encode());
+        buffer
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RefFuncInstructionNode {
+    opcode: u8,
+    pub function_index: u32,
+}
+
+impl RefFuncInstructionNode {
+    pub fn new(function_index: u32) -> Self {
+        Self {
+            opcode: 0xd2,
+            function_index,
+        }
+    }
+}
+
+impl Node for RefFuncInstructionNode {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += 1; // opcode
+        size += encode_u32_to_leb128(self.function_index).len() as u32;
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        buffer.push(self.opcode);
+        buffer.extend(encode_u32_to_leb128(self.function_index));
+        buffer
+    }
+}
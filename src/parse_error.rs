@@ -0,0 +1,152 @@
+use std::fmt;
+
+use crate::{instance::MAX_TABLE_SIZE, module::section::SectionId};
+
+/// An error encountered while decoding a binary module, located by the
+/// absolute byte offset and, when known, the enclosing section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub offset: usize,
+    pub section: Option<SectionId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnexpectedEof,
+    BadMagic,
+    UnsupportedVersion,
+    UnknownSection(u8),
+    UnknownOpcode(u8),
+    /// An unknown sub-opcode after a prefix byte such as 0xFC.
+    UnknownPrefixedOpcode {
+        prefix: u8,
+        opcode: u32,
+    },
+    InvalidValueType(u8),
+    /// A byte that is out of range for the construct being decoded,
+    /// such as a limits flag or an import kind.
+    InvalidEncoding {
+        construct: &'static str,
+        value: u32,
+    },
+    Leb128Overflow,
+    SectionSizeMismatch {
+        expected: u32,
+        actual: u32,
+    },
+    MalformedUtf8,
+    /// A function declaring more locals than the parser accepts.
+    TooManyLocals {
+        limit: u32,
+    },
+    /// Blocks nested deeper than the parser accepts.
+    NestingTooDeep {
+        limit: u32,
+    },
+    /// Limits whose maximum is below their minimum.
+    LimitsMinExceedsMax {
+        min: u32,
+        max: u32,
+    },
+    /// A memory limit above the 65536 pages a 32-bit memory can address.
+    MemoryTooLarge {
+        pages: u32,
+    },
+    /// A table whose minimum size is above the implementation limit.
+    TableTooLarge {
+        size: u32,
+    },
+    /// The function section and the code section disagree on the number of
+    /// functions.
+    FunctionCodeMismatch {
+        functions: u32,
+        bodies: u32,
+    },
+    /// The data count section disagrees with the number of data segments.
+    DataCountMismatch {
+        declared: u32,
+        actual: u32,
+    },
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind, offset: usize, section: Option<SectionId>) -> Self {
+        Self {
+            kind,
+            offset,
+            section,
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnexpectedEof => write!(f, "unexpected end of input"),
+            ParseErrorKind::BadMagic => write!(f, "magic header not detected"),
+            ParseErrorKind::UnsupportedVersion => write!(f, "unsupported version"),
+            ParseErrorKind::UnknownSection(id) => write!(f, "unknown section id {}", id),
+            ParseErrorKind::UnknownOpcode(opcode) => write!(f, "unknown opcode {:#04x}", opcode),
+            ParseErrorKind::UnknownPrefixedOpcode { prefix, opcode } => {
+                write!(f, "unknown opcode {:#04x} {}", prefix, opcode)
+            }
+            ParseErrorKind::InvalidValueType(byte) => {
+                write!(f, "invalid value type {:#04x}", byte)
+            }
+            ParseErrorKind::InvalidEncoding { construct, value } => {
+                write!(f, "invalid {} {:#x}", construct, value)
+            }
+            ParseErrorKind::Leb128Overflow => write!(f, "integer representation too long"),
+            ParseErrorKind::SectionSizeMismatch { expected, actual } => write!(
+                f,
+                "section size mismatch: declared {} bytes, consumed {}",
+                expected, actual
+            ),
+            ParseErrorKind::MalformedUtf8 => write!(f, "malformed UTF-8 encoding"),
+            ParseErrorKind::TooManyLocals { limit } => {
+                write!(f, "too many locals: more than {}", limit)
+            }
+            ParseErrorKind::NestingTooDeep { limit } => {
+                write!(f, "blocks nested deeper than {} levels", limit)
+            }
+            ParseErrorKind::LimitsMinExceedsMax { min, max } => write!(
+                f,
+                "size minimum must not be greater than maximum: {} > {}",
+                min, max
+            ),
+            ParseErrorKind::MemoryTooLarge { pages } => write!(
+                f,
+                "memory size must be at most 65536 pages (4GiB), got {}",
+                pages
+            ),
+            ParseErrorKind::TableTooLarge { size } => write!(
+                f,
+                "table size must be at most {} elements, got {}",
+                MAX_TABLE_SIZE, size
+            ),
+            ParseErrorKind::FunctionCodeMismatch { functions, bodies } => write!(
+                f,
+                "function and code section have inconsistent lengths: {} and {}",
+                functions, bodies
+            ),
+            ParseErrorKind::DataCountMismatch { declared, actual } => write!(
+                f,
+                "data count and data section have inconsistent lengths: {} and {}",
+                declared, actual
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {:#x}", self.kind, self.offset)?;
+        if let Some(section) = self.section {
+            write!(f, " in {} section", section.name())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -1,385 +1,1153 @@
-use super::types::NumberType;
+use super::types::{GlobalType, Limits, MemoryType, ReferenceTypeNode, TableType};
+use crate::names::NameMap;
+use crate::simd::V128;
 use crate::{
-    instruction::Instruction,
-    leb128::{decode_signed_leb128, decode_unsigned_leb128},
+    instance::{MAX_PAGES, MAX_TABLE_SIZE},
+    instruction::{AtomicInstruction, Instruction, MiscInstruction, SimdInstruction},
     module::{
         section::{
-            CodeSectionNode, ExportSectionNode, FunctionSectionNode, SectionId, TypeSectionNode,
+            CodeSectionNode, CustomSectionNode, DataCountSectionNode, DataSectionNode,
+            ElementSectionNode, ExportSectionNode, FunctionSectionNode, GlobalSectionNode,
+            ImportSectionNode, MemorySectionNode, SectionId, StartSectionNode, TableSectionNode,
+            TypeSectionNode,
         },
         ModuleNode,
     },
     node::{
-        BlockInstructionNode, BrIfInstructionNode, BrInstructionNode, CallInstructionNode,
-        CodeNode, ElseInstructionNode, EndInstructionNode, ExportDescNode, ExportNode,
-        ExportTypeNode, ExpressionNode, FunctionTypeNode, GetLocalInstructionNode,
-        I32AddInstructionNode, I32ConstInstructionNode, I32EqzInstructionNode,
-        I32GeSInstructionNode, I32LtSInstructionNode, I32LtUInstructionNode,
-        I32RemSInstructionNode, I32RemUInstructionNode, I32SubInstructionNode, IfInstructionNode,
-        InstructionNode, LocalEntryNode, LoopInstructionNode, ResultTypeNode,
-        SetLocalInstructionNode,
+        AtomicFenceInstructionNode, AtomicMemoryAccessInstructionNode, BlockInstructionNode,
+        BrIfInstructionNode, BrInstructionNode, BrTableInstructionNode,
+        CallIndirectInstructionNode, CallInstructionNode, CodeNode, DataDropInstructionNode,
+        DataModeNode, DataNode, DropInstructionNode, ElemDropInstructionNode, ElementInitNode,
+        ElementModeNode, ElementNode, ElseInstructionNode, EndInstructionNode, ExportDescNode,
+        ExportNode, ExportTypeNode, ExpressionNode, F32AbsInstructionNode, F32AddInstructionNode,
+        F32CeilInstructionNode, F32ConstInstructionNode, F32ConvertI32SInstructionNode,
+        F32ConvertI32UInstructionNode, F32ConvertI64SInstructionNode,
+        F32ConvertI64UInstructionNode, F32CopysignInstructionNode, F32DemoteF64InstructionNode,
+        F32DivInstructionNode, F32EqInstructionNode, F32FloorInstructionNode, F32GeInstructionNode,
+        F32GtInstructionNode, F32LeInstructionNode, F32LtInstructionNode, F32MaxInstructionNode,
+        F32MinInstructionNode, F32MulInstructionNode, F32NeInstructionNode,
+        F32NearestInstructionNode, F32NegInstructionNode, F32ReinterpretI32InstructionNode,
+        F32SqrtInstructionNode, F32SubInstructionNode, F32TruncInstructionNode,
+        F32x4AbsInstructionNode, F32x4AddInstructionNode, F32x4CeilInstructionNode,
+        F32x4ConvertI32x4SInstructionNode, F32x4ConvertI32x4UInstructionNode,
+        F32x4DemoteF64x2ZeroInstructionNode, F32x4DivInstructionNode, F32x4EqInstructionNode,
+        F32x4FloorInstructionNode, F32x4GeInstructionNode, F32x4GtInstructionNode,
+        F32x4LeInstructionNode, F32x4LtInstructionNode, F32x4MaxInstructionNode,
+        F32x4MinInstructionNode, F32x4MulInstructionNode, F32x4NeInstructionNode,
+        F32x4NearestInstructionNode, F32x4NegInstructionNode, F32x4PmaxInstructionNode,
+        F32x4PminInstructionNode, F32x4SplatInstructionNode, F32x4SqrtInstructionNode,
+        F32x4SubInstructionNode, F32x4TruncInstructionNode, F64AbsInstructionNode,
+        F64AddInstructionNode, F64CeilInstructionNode, F64ConstInstructionNode,
+        F64ConvertI32SInstructionNode, F64ConvertI32UInstructionNode,
+        F64ConvertI64SInstructionNode, F64ConvertI64UInstructionNode, F64CopysignInstructionNode,
+        F64DivInstructionNode, F64EqInstructionNode, F64FloorInstructionNode, F64GeInstructionNode,
+        F64GtInstructionNode, F64LeInstructionNode, F64LtInstructionNode, F64MaxInstructionNode,
+        F64MinInstructionNode, F64MulInstructionNode, F64NeInstructionNode,
+        F64NearestInstructionNode, F64NegInstructionNode, F64PromoteF32InstructionNode,
+        F64ReinterpretI64InstructionNode, F64SqrtInstructionNode, F64SubInstructionNode,
+        F64TruncInstructionNode, F64x2AbsInstructionNode, F64x2AddInstructionNode,
+        F64x2CeilInstructionNode, F64x2ConvertLowI32x4SInstructionNode,
+        F64x2ConvertLowI32x4UInstructionNode, F64x2DivInstructionNode, F64x2EqInstructionNode,
+        F64x2FloorInstructionNode, F64x2GeInstructionNode, F64x2GtInstructionNode,
+        F64x2LeInstructionNode, F64x2LtInstructionNode, F64x2MaxInstructionNode,
+        F64x2MinInstructionNode, F64x2MulInstructionNode, F64x2NeInstructionNode,
+        F64x2NearestInstructionNode, F64x2NegInstructionNode, F64x2PmaxInstructionNode,
+        F64x2PminInstructionNode, F64x2PromoteLowF32x4InstructionNode, F64x2SplatInstructionNode,
+        F64x2SqrtInstructionNode, F64x2SubInstructionNode, F64x2TruncInstructionNode,
+        FunctionTypeNode, GetGlobalInstructionNode, GetLocalInstructionNode, GlobalNode,
+        I16x8AbsInstructionNode, I16x8AddInstructionNode, I16x8AddSatSInstructionNode,
+        I16x8AddSatUInstructionNode, I16x8AllTrueInstructionNode, I16x8AvgrUInstructionNode,
+        I16x8BitmaskInstructionNode, I16x8EqInstructionNode,
+        I16x8ExtaddPairwiseI8x16SInstructionNode, I16x8ExtaddPairwiseI8x16UInstructionNode,
+        I16x8ExtendHighI8x16SInstructionNode, I16x8ExtendHighI8x16UInstructionNode,
+        I16x8ExtendLowI8x16SInstructionNode, I16x8ExtendLowI8x16UInstructionNode,
+        I16x8ExtmulHighI8x16SInstructionNode, I16x8ExtmulHighI8x16UInstructionNode,
+        I16x8ExtmulLowI8x16SInstructionNode, I16x8ExtmulLowI8x16UInstructionNode,
+        I16x8GeSInstructionNode, I16x8GeUInstructionNode, I16x8GtSInstructionNode,
+        I16x8GtUInstructionNode, I16x8LeSInstructionNode, I16x8LeUInstructionNode,
+        I16x8LtSInstructionNode, I16x8LtUInstructionNode, I16x8MaxSInstructionNode,
+        I16x8MaxUInstructionNode, I16x8MinSInstructionNode, I16x8MinUInstructionNode,
+        I16x8MulInstructionNode, I16x8NarrowI32x4SInstructionNode,
+        I16x8NarrowI32x4UInstructionNode, I16x8NeInstructionNode, I16x8NegInstructionNode,
+        I16x8Q15mulrSatSInstructionNode, I16x8ShlInstructionNode, I16x8ShrSInstructionNode,
+        I16x8ShrUInstructionNode, I16x8SplatInstructionNode, I16x8SubInstructionNode,
+        I16x8SubSatSInstructionNode, I16x8SubSatUInstructionNode, I32AddInstructionNode,
+        I32AndInstructionNode, I32ClzInstructionNode, I32ConstInstructionNode,
+        I32CtzInstructionNode, I32DivSInstructionNode, I32DivUInstructionNode,
+        I32EqInstructionNode, I32EqzInstructionNode, I32Extend16SInstructionNode,
+        I32Extend8SInstructionNode, I32GeSInstructionNode, I32GeUInstructionNode,
+        I32GtSInstructionNode, I32GtUInstructionNode, I32LeSInstructionNode, I32LeUInstructionNode,
+        I32LtSInstructionNode, I32LtUInstructionNode, I32MulInstructionNode, I32NeInstructionNode,
+        I32OrInstructionNode, I32PopcntInstructionNode, I32ReinterpretF32InstructionNode,
+        I32RemSInstructionNode, I32RemUInstructionNode, I32RotlInstructionNode,
+        I32RotrInstructionNode, I32ShlInstructionNode, I32ShrSInstructionNode,
+        I32ShrUInstructionNode, I32SubInstructionNode, I32TruncF32SInstructionNode,
+        I32TruncF32UInstructionNode, I32TruncF64SInstructionNode, I32TruncF64UInstructionNode,
+        I32TruncSatF32SInstructionNode, I32TruncSatF32UInstructionNode,
+        I32TruncSatF64SInstructionNode, I32TruncSatF64UInstructionNode, I32WrapI64InstructionNode,
+        I32XorInstructionNode, I32x4AbsInstructionNode, I32x4AddInstructionNode,
+        I32x4AllTrueInstructionNode, I32x4BitmaskInstructionNode, I32x4DotI16x8SInstructionNode,
+        I32x4EqInstructionNode, I32x4ExtaddPairwiseI16x8SInstructionNode,
+        I32x4ExtaddPairwiseI16x8UInstructionNode, I32x4ExtendHighI16x8SInstructionNode,
+        I32x4ExtendHighI16x8UInstructionNode, I32x4ExtendLowI16x8SInstructionNode,
+        I32x4ExtendLowI16x8UInstructionNode, I32x4ExtmulHighI16x8SInstructionNode,
+        I32x4ExtmulHighI16x8UInstructionNode, I32x4ExtmulLowI16x8SInstructionNode,
+        I32x4ExtmulLowI16x8UInstructionNode, I32x4GeSInstructionNode, I32x4GeUInstructionNode,
+        I32x4GtSInstructionNode, I32x4GtUInstructionNode, I32x4LeSInstructionNode,
+        I32x4LeUInstructionNode, I32x4LtSInstructionNode, I32x4LtUInstructionNode,
+        I32x4MaxSInstructionNode, I32x4MaxUInstructionNode, I32x4MinSInstructionNode,
+        I32x4MinUInstructionNode, I32x4MulInstructionNode, I32x4NeInstructionNode,
+        I32x4NegInstructionNode, I32x4ShlInstructionNode, I32x4ShrSInstructionNode,
+        I32x4ShrUInstructionNode, I32x4SplatInstructionNode, I32x4SubInstructionNode,
+        I32x4TruncSatF32x4SInstructionNode, I32x4TruncSatF32x4UInstructionNode,
+        I32x4TruncSatF64x2SZeroInstructionNode, I32x4TruncSatF64x2UZeroInstructionNode,
+        I64AddInstructionNode, I64AndInstructionNode, I64ClzInstructionNode,
+        I64ConstInstructionNode, I64CtzInstructionNode, I64DivSInstructionNode,
+        I64DivUInstructionNode, I64EqInstructionNode, I64EqzInstructionNode,
+        I64Extend16SInstructionNode, I64Extend32SInstructionNode, I64Extend8SInstructionNode,
+        I64ExtendI32SInstructionNode, I64ExtendI32UInstructionNode, I64GeSInstructionNode,
+        I64GeUInstructionNode, I64GtSInstructionNode, I64GtUInstructionNode, I64LeSInstructionNode,
+        I64LeUInstructionNode, I64LtSInstructionNode, I64LtUInstructionNode, I64MulInstructionNode,
+        I64NeInstructionNode, I64OrInstructionNode, I64PopcntInstructionNode,
+        I64ReinterpretF64InstructionNode, I64RemSInstructionNode, I64RemUInstructionNode,
+        I64RotlInstructionNode, I64RotrInstructionNode, I64ShlInstructionNode,
+        I64ShrSInstructionNode, I64ShrUInstructionNode, I64SubInstructionNode,
+        I64TruncF32SInstructionNode, I64TruncF32UInstructionNode, I64TruncF64SInstructionNode,
+        I64TruncF64UInstructionNode, I64TruncSatF32SInstructionNode,
+        I64TruncSatF32UInstructionNode, I64TruncSatF64SInstructionNode,
+        I64TruncSatF64UInstructionNode, I64XorInstructionNode, I64x2AbsInstructionNode,
+        I64x2AddInstructionNode, I64x2AllTrueInstructionNode, I64x2BitmaskInstructionNode,
+        I64x2EqInstructionNode, I64x2ExtendHighI32x4SInstructionNode,
+        I64x2ExtendHighI32x4UInstructionNode, I64x2ExtendLowI32x4SInstructionNode,
+        I64x2ExtendLowI32x4UInstructionNode, I64x2ExtmulHighI32x4SInstructionNode,
+        I64x2ExtmulHighI32x4UInstructionNode, I64x2ExtmulLowI32x4SInstructionNode,
+        I64x2ExtmulLowI32x4UInstructionNode, I64x2GeSInstructionNode, I64x2GtSInstructionNode,
+        I64x2LeSInstructionNode, I64x2LtSInstructionNode, I64x2MulInstructionNode,
+        I64x2NeInstructionNode, I64x2NegInstructionNode, I64x2ShlInstructionNode,
+        I64x2ShrSInstructionNode, I64x2ShrUInstructionNode, I64x2SplatInstructionNode,
+        I64x2SubInstructionNode, I8x16AbsInstructionNode, I8x16AddInstructionNode,
+        I8x16AddSatSInstructionNode, I8x16AddSatUInstructionNode, I8x16AllTrueInstructionNode,
+        I8x16AvgrUInstructionNode, I8x16BitmaskInstructionNode, I8x16EqInstructionNode,
+        I8x16GeSInstructionNode, I8x16GeUInstructionNode, I8x16GtSInstructionNode,
+        I8x16GtUInstructionNode, I8x16LeSInstructionNode, I8x16LeUInstructionNode,
+        I8x16LtSInstructionNode, I8x16LtUInstructionNode, I8x16MaxSInstructionNode,
+        I8x16MaxUInstructionNode, I8x16MinSInstructionNode, I8x16MinUInstructionNode,
+        I8x16NarrowI16x8SInstructionNode, I8x16NarrowI16x8UInstructionNode, I8x16NeInstructionNode,
+        I8x16NegInstructionNode, I8x16PopcntInstructionNode, I8x16ShlInstructionNode,
+        I8x16ShrSInstructionNode, I8x16ShrUInstructionNode, I8x16ShuffleInstructionNode,
+        I8x16SplatInstructionNode, I8x16SubInstructionNode, I8x16SubSatSInstructionNode,
+        I8x16SubSatUInstructionNode, I8x16SwizzleInstructionNode, IfInstructionNode,
+        ImportDescNode, ImportNode, InstructionNode, LocalEntryNode, LoopInstructionNode,
+        MemArgNode, MemoryAccessInstructionNode, MemoryCopyInstructionNode,
+        MemoryFillInstructionNode, MemoryGrowInstructionNode, MemoryInitInstructionNode,
+        MemorySizeInstructionNode, NopInstructionNode, RefFuncInstructionNode,
+        RefIsNullInstructionNode, RefNullInstructionNode, ResultTypeNode, ReturnInstructionNode,
+        SelectInstructionNode, SetGlobalInstructionNode, SetLocalInstructionNode,
+        SimdLaneInstructionNode, SimdMemoryAccessInstructionNode, SimdMemoryLaneInstructionNode,
+        TableCopyInstructionNode, TableFillInstructionNode, TableGetInstructionNode,
+        TableGrowInstructionNode, TableInitInstructionNode, TableSetInstructionNode,
+        TableSizeInstructionNode, TeeLocalInstructionNode, UnreachableInstructionNode,
+        V128AndInstructionNode, V128AndnotInstructionNode, V128AnyTrueInstructionNode,
+        V128BitselectInstructionNode, V128ConstInstructionNode, V128NotInstructionNode,
+        V128OrInstructionNode, V128XorInstructionNode,
     },
+    parse_error::{ParseError, ParseErrorKind},
+    reader::Reader,
     types::{BlockType, ValueType},
 };
-use std::error::Error;
+use std::{collections::BTreeMap, error::Error};
+
+/// Deepest nesting of `block`, `loop` and `if` accepted in an expression.
+/// The decoded tree is cloned, encoded, validated and run by recursion, and
+/// cloning takes the most stack per level: this many levels fit in the 2 MiB
+/// stack of a spawned thread in a debug build.
+pub const MAX_NESTING_DEPTH: u32 = 200;
+/// Most locals a function may declare. Locals are expanded one value each
+/// when the function is instantiated, so a count of 2^32 - 1 would otherwise
+/// exhaust memory.
+pub const MAX_LOCALS: u32 = 50_000;
 
 pub struct Parser {}
 
+/// A `block`, `loop` or `if` whose body is still being decoded, along with
+/// what had been decoded of the enclosing body when it was entered.
+struct OpenBlock {
+    instruction: Instruction,
+    /// Where the block starts, counted like the offsets of its body.
+    offset: usize,
+    block_type: BlockType,
+    /// The then arm of an `if` once its `else` has been reached.
+    then_expr: Option<ExpressionNode>,
+    enclosing: Vec<InstructionNode>,
+    enclosing_offsets: Vec<usize>,
+    enclosing_size: u32,
+}
+
+impl OpenBlock {
+    /// Builds the node for the block once `expr` has reached its `end`.
+    fn into_node(self, expr: ExpressionNode, size: u32) -> InstructionNode {
+        match (self.instruction, self.then_expr) {
+            (Instruction::Block, _) => {
+                InstructionNode::Block(BlockInstructionNode::new(self.block_type, expr, size))
+            }
+            (Instruction::Loop, _) => {
+                InstructionNode::Loop(LoopInstructionNode::new(self.block_type, expr, size))
+            }
+            (_, Some(then_expr)) => InstructionNode::If(IfInstructionNode::new(
+                self.block_type,
+                then_expr,
+                Some(expr),
+                size,
+            )),
+            (_, None) => {
+                InstructionNode::If(IfInstructionNode::new(self.block_type, expr, None, size))
+            }
+        }
+    }
+}
+
 impl Parser {
     pub fn new() -> Result<Self, Box<dyn Error>> {
         Ok(Self {})
     }
 
-    pub fn parse(&self, bytes: &mut Vec<u8>) -> Result<ModuleNode, Box<dyn Error>> {
-        let (magic, version) = self.module_header(bytes).expect("Invalid header");
-        let mut module = ModuleNode::new(magic.try_into().unwrap(), version.try_into().unwrap())
-            .unwrap_or_else(|_| panic!("Invalid module"));
+    pub fn parse(&self, bytes: &[u8]) -> Result<ModuleNode, ParseError> {
+        let mut reader = Reader::new(bytes);
+        let (magic, version) = self.module_header(&mut reader)?;
+        let mut module =
+            ModuleNode::new(magic, version).unwrap_or_else(|_| panic!("Invalid module"));
 
-        if bytes.len() == 0 {
+        if reader.is_empty() {
             return Ok(module);
         }
 
-        while bytes.len() > 0 {
-            self.section(bytes, &mut module)
-                .expect("Failed to parse section");
+        // the last known section, used to place custom sections on emit
+        let mut last_id: Option<SectionId> = None;
+        while !reader.is_empty() {
+            let id = self.section(&mut reader, &mut module, last_id)?;
+            if id != SectionId::CustomSectionId {
+                last_id = Some(id);
+            }
+        }
+        // a function section needs a code section with a body for each function
+        if let (Some(section), None) = (module.function_section(), module.code_section()) {
+            if !section.type_indexes.is_empty() {
+                let kind = ParseErrorKind::FunctionCodeMismatch {
+                    functions: section.type_indexes.len() as u32,
+                    bodies: 0,
+                };
+                return Err(reader.error(kind));
+            }
+        }
+        // a data count of zero may stand in for an absent data section
+        if let (Some(section), None) = (module.data_count_section(), module.data_section()) {
+            if section.count != 0 {
+                let kind = ParseErrorKind::DataCountMismatch {
+                    declared: section.count,
+                    actual: 0,
+                };
+                return Err(reader.error(kind));
+            }
         }
 
         Ok(module)
     }
 
-    fn module_header(&self, bytes: &mut Vec<u8>) -> Result<(Vec<u8>, Vec<u8>), Box<dyn Error>> {
-        let magic_bytes = bytes[0..4].to_vec();
-        let version = bytes[4..8].to_vec();
-        *bytes = bytes[8..].to_vec();
+    /// magic = 0x00 0x61 0x73 0x6D, version = 0x01 0x00 0x00 0x00
+    fn module_header(&self, reader: &mut Reader) -> Result<([u8; 4], [u8; 4]), ParseError> {
+        let magic_bytes: [u8; 4] = reader
+            .read_bytes(4)
+            .map_err(|_| reader.error_at(ParseErrorKind::BadMagic, 0))?
+            .try_into()
+            .unwrap();
+        if magic_bytes != [0x00, 0x61, 0x73, 0x6D] {
+            return Err(reader.error_at(ParseErrorKind::BadMagic, 0));
+        }
+        let version: [u8; 4] = reader
+            .read_bytes(4)
+            .map_err(|_| reader.error_at(ParseErrorKind::UnsupportedVersion, 4))?
+            .try_into()
+            .unwrap();
+        if version != [0x01, 0x00, 0x00, 0x00] {
+            return Err(reader.error_at(ParseErrorKind::UnsupportedVersion, 4));
+        }
         Ok((magic_bytes, version))
     }
 
-    fn section(&self, bytes: &mut Vec<u8>, module: &mut ModuleNode) -> Result<(), Box<dyn Error>> {
-        let id = Parser::read_u8(bytes).expect("Failed to parse section id");
-        let (size, _) = Parser::read_u32(bytes).expect("Failed to parse section size");
-        let mut section_bytes = bytes[0..(size as usize)].to_vec();
-        (*bytes).drain(0..(size as usize));
-
-        dbg!("section id: {}, size: {}", id, size);
+    fn section(
+        &self,
+        reader: &mut Reader,
+        module: &mut ModuleNode,
+        last_id: Option<SectionId>,
+    ) -> Result<SectionId, ParseError> {
+        let id_offset = reader.offset();
+        let id = reader.read_u8()?;
+        let section_id = SectionId::try_from(id)
+            .map_err(|id| reader.error_at(ParseErrorKind::UnknownSection(id), id_offset))?;
+        let size = reader.read_u32()?;
+        let mut section_reader = reader.sub_reader(size as usize)?;
+        section_reader.set_section(section_id);
 
-        match SectionId::from(id) {
-            SectionId::CustomSectionId => todo!("Custom section"),
+        match section_id {
+            SectionId::CustomSectionId => {
+                let offset = section_reader.offset();
+                let name = self.name(&mut section_reader)?;
+                // a malformed name section only loses debug names, as the spec
+                // requires custom sections not to affect validity
+                if name == "name" {
+                    if let Ok(names) = self.name_section(&mut section_reader.clone()) {
+                        module.set_names(names);
+                    }
+                }
+                let payload = section_reader
+                    .read_bytes(section_reader.remaining())?
+                    .to_vec();
+                let section = CustomSectionNode {
+                    name,
+                    payload,
+                    offset,
+                };
+                module.add_custom_section(last_id, section);
+            }
             SectionId::TypeSectionId => {
-                let section = self
-                    .type_section(&mut section_bytes)
-                    .expect("Failed to parse type section");
+                let section = self.type_section(&mut section_reader)?;
                 module.set_type_section(section);
             }
-            SectionId::ImportSectionId => todo!("import section"),
+            SectionId::ImportSectionId => {
+                let section = self.import_section(&mut section_reader)?;
+                module.set_import_section(section);
+            }
             SectionId::FunctionSectionId => {
-                let section = self
-                    .function_section(&mut section_bytes)
-                    .expect("Failed to parse function section");
+                let section = self.function_section(&mut section_reader)?;
                 module.set_function_section(section);
             }
-            SectionId::GlobalSectionId => todo!("global section"),
+            SectionId::TableSectionId => {
+                let section = self.table_section(&mut section_reader)?;
+                module.set_table_section(section);
+            }
+            SectionId::MemorySectionId => {
+                let section = self.memory_section(&mut section_reader)?;
+                module.set_memory_section(section);
+            }
+            SectionId::GlobalSectionId => {
+                let section = self.global_section(&mut section_reader)?;
+                module.set_global_section(section);
+            }
             SectionId::ExportSectionId => {
-                let section = self
-                    .export_section(&mut section_bytes)
-                    .expect("Failed to parse export section");
+                let section = self.export_section(&mut section_reader)?;
                 module.set_export_section(section);
             }
-            SectionId::StartSectionId => todo!("start section"),
+            SectionId::StartSectionId => {
+                let section = self.start_section(&mut section_reader)?;
+                module.set_start_section(section);
+            }
             SectionId::CodeSectionId => {
-                let section = self
-                    .code_section(&mut section_bytes)
-                    .expect("Failed to parse code section");
+                let functions = module
+                    .function_section()
+                    .map_or(0, |section| section.type_indexes.len() as u32);
+                let section = self.code_section(&mut section_reader, functions)?;
                 module.set_code_section(section);
             }
-            SectionId::ElementSectionId => todo!("element section"),
-            SectionId::DataSectionId => todo!("data section"),
+            SectionId::ElementSectionId => {
+                let section = self.element_section(&mut section_reader)?;
+                module.set_element_section(section);
+            }
+            SectionId::DataCountSectionId => {
+                let offset = section_reader.offset();
+                let count = section_reader.read_u32()?;
+                module.set_data_count_section(DataCountSectionNode { count, offset });
+            }
+            SectionId::DataSectionId => {
+                let data_count = module.data_count_section().map(|section| section.count);
+                let section = self.data_section(&mut section_reader, data_count)?;
+                module.set_data_section(section);
+            }
         };
-        Ok(())
+        section_reader.finish()?;
+        Ok(section_id)
+    }
+
+    /// name section = modulenamesubsec? funcnamesubsec? localnamesubsec?
+    /// where each subsection = id:byte size:u32 content; unknown subsections are skipped.
+    fn name_section(&self, reader: &mut Reader) -> Result<NameMap, ParseError> {
+        let mut names = NameMap::default();
+
+        while !reader.is_empty() {
+            let id = reader.read_u8()?;
+            let size = reader.read_u32()?;
+            let mut subsection_reader = reader.sub_reader(size as usize)?;
+
+            match id {
+                0x00 => {
+                    names.module = Some(self.name(&mut subsection_reader)?);
+                }
+                0x01 => {
+                    names.functions = self.name_map(&mut subsection_reader)?;
+                }
+                0x02 => {
+                    let count = subsection_reader.read_u32()?;
+                    for _ in 0..count {
+                        let function_index = subsection_reader.read_u32()?;
+                        let locals = self.name_map(&mut subsection_reader)?;
+                        names.locals.insert(function_index, locals);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// namemap = vec(idx name)
+    fn name_map(&self, reader: &mut Reader) -> Result<BTreeMap<u32, String>, ParseError> {
+        let count = reader.read_u32()?;
+
+        let mut names: BTreeMap<u32, String> = BTreeMap::new();
+        for _ in 0..count {
+            let index = reader.read_u32()?;
+            let name = self.name(reader)?;
+            names.insert(index, name);
+        }
+        Ok(names)
     }
 
     /// type section = section1(vec((functype)*))
-    fn type_section(&self, bytes: &mut Vec<u8>) -> Result<TypeSectionNode, Box<dyn Error>> {
+    fn type_section(&self, reader: &mut Reader) -> Result<TypeSectionNode, ParseError> {
+        let offset = reader.offset();
         let mut function_types: Vec<FunctionTypeNode> = vec![];
-        let (count, _) = Parser::read_u32(bytes).expect("Failed to parse vector size");
+        let count = reader.read_u32()?;
 
         for _ in 0..count {
-            let function_type = self
-                .function_type(bytes)
-                .expect("Failed to parse function type");
+            let function_type = self.function_type(reader)?;
             function_types.push(function_type);
         }
 
-        Ok(TypeSectionNode { function_types })
+        Ok(TypeSectionNode {
+            function_types,
+            offset,
+        })
+    }
+
+    /// import section = section2(vec((import)*))
+    fn import_section(&self, reader: &mut Reader) -> Result<ImportSectionNode, ParseError> {
+        let offset = reader.offset();
+        let count = reader.read_u32()?;
+
+        let mut imports: Vec<ImportNode> = vec![];
+        for _ in 0..count {
+            let module = self.name(reader)?;
+            let name = self.name(reader)?;
+            let import_desc = self.import_desc(reader)?;
+
+            imports.push(ImportNode {
+                module,
+                name,
+                import_desc,
+            });
+        }
+
+        Ok(ImportSectionNode { imports, offset })
+    }
+
+    /// import desc = 0x00 typeidx | 0x01 tabletype | 0x02 memtype | 0x03 globaltype
+    fn import_desc(&self, reader: &mut Reader) -> Result<ImportDescNode, ParseError> {
+        let offset = reader.offset();
+        let id = reader.read_u8()?;
+
+        let import_desc = match id {
+            0x00 => {
+                let type_index = reader.read_u32()?;
+                ImportDescNode::Function(type_index)
+            }
+            0x01 => ImportDescNode::Table(self.table_type(reader)?),
+            0x02 => ImportDescNode::Memory(self.memory_type(reader)?),
+            0x03 => ImportDescNode::Global(self.global_type(reader)?),
+            _ => return Err(Parser::invalid(reader, "import kind", id as u32, offset)),
+        };
+        Ok(import_desc)
     }
 
     /// function section = section3(vec((typeidx)*))
-    fn function_section(&self, bytes: &mut Vec<u8>) -> Result<FunctionSectionNode, Box<dyn Error>> {
+    fn function_section(&self, reader: &mut Reader) -> Result<FunctionSectionNode, ParseError> {
+        let offset = reader.offset();
         let mut type_indexes: Vec<u32> = vec![];
-        let (count, _) = Parser::read_u32(bytes).expect("Failed to parse vector size");
+        let count = reader.read_u32()?;
 
         for _ in 0..count {
-            let (type_index, _) = Parser::read_u32(bytes).expect("Failed to parse type index");
+            let type_index = reader.read_u32()?;
             type_indexes.push(type_index);
         }
 
-        Ok(FunctionSectionNode { type_indexes })
+        Ok(FunctionSectionNode {
+            type_indexes,
+            offset,
+        })
+    }
+
+    /// table section = section4(vec((tabletype)*))
+    fn table_section(&self, reader: &mut Reader) -> Result<TableSectionNode, ParseError> {
+        let offset = reader.offset();
+        let count = reader.read_u32()?;
+
+        let mut tables: Vec<TableType> = vec![];
+        for _ in 0..count {
+            let table_type = self.table_type(reader)?;
+            tables.push(table_type);
+        }
+
+        Ok(TableSectionNode { tables, offset })
+    }
+
+    /// memory section = section5(vec((memtype)*))
+    fn memory_section(&self, reader: &mut Reader) -> Result<MemorySectionNode, ParseError> {
+        let offset = reader.offset();
+        let count = reader.read_u32()?;
+
+        let mut memories: Vec<MemoryType> = vec![];
+        for _ in 0..count {
+            let memory_type = self.memory_type(reader)?;
+            memories.push(memory_type);
+        }
+
+        Ok(MemorySectionNode { memories, offset })
+    }
+
+    /// global section = section6(vec((global)*))
+    fn global_section(&self, reader: &mut Reader) -> Result<GlobalSectionNode, ParseError> {
+        let offset = reader.offset();
+        let count = reader.read_u32()?;
+
+        let mut globals: Vec<GlobalNode> = vec![];
+        for _ in 0..count {
+            let global_type = self.global_type(reader)?;
+            let init = self.expression(reader)?;
+            globals.push(GlobalNode { global_type, init });
+        }
+
+        Ok(GlobalSectionNode { globals, offset })
     }
 
     /// export section = section7(vec((export)*))
-    fn export_section(&self, bytes: &mut Vec<u8>) -> Result<ExportSectionNode, Box<dyn Error>> {
-        let (count, _) = Parser::read_u32(bytes).expect("Failed to parse vector size");
+    fn export_section(&self, reader: &mut Reader) -> Result<ExportSectionNode, ParseError> {
+        let offset = reader.offset();
+        let count = reader.read_u32()?;
 
         let mut exports: Vec<ExportNode> = vec![];
         for _ in 0..count {
-            let (name_size, _) = Parser::read_u32(bytes).expect("Failed to parse name size");
-            let name_bytes =
-                Parser::read_bytes(bytes, name_size as usize).expect("Failed to parse name bytes");
-            let export_desc = self
-                .export_desc(bytes)
-                .expect("Failed to parse export desc");
-
-            exports.push(ExportNode {
-                name: String::from_utf8(name_bytes).unwrap(),
-                export_desc,
-            });
+            let name = self.name(reader)?;
+            let export_desc = self.export_desc(reader)?;
+
+            exports.push(ExportNode { name, export_desc });
         }
 
-        Ok(ExportSectionNode { exports })
+        Ok(ExportSectionNode { exports, offset })
     }
 
-    fn export_desc(&self, bytes: &mut Vec<u8>) -> Result<ExportDescNode, Box<dyn Error>> {
-        let id = Parser::read_u8(bytes).expect("Failed to parse export desc id");
-        let (index, _) = Parser::read_u32(bytes).expect("Failed to parse export desc index");
+    fn export_desc(&self, reader: &mut Reader) -> Result<ExportDescNode, ParseError> {
+        let offset = reader.offset();
+        let id = reader.read_u8()?;
+        let export_type = ExportTypeNode::try_from(id)
+            .map_err(|id| Parser::invalid(reader, "export kind", id as u32, offset))?;
+        let index = reader.read_u32()?;
 
         Ok(ExportDescNode {
-            export_type: ExportTypeNode::from(id),
+            export_type,
             index: index,
         })
     }
 
+    /// start section = section8(funcidx)
+    fn start_section(&self, reader: &mut Reader) -> Result<StartSectionNode, ParseError> {
+        let offset = reader.offset();
+        let function_index = reader.read_u32()?;
+        Ok(StartSectionNode {
+            function_index,
+            offset,
+        })
+    }
+
+    /// element section = section9(vec((elem)*))
+    fn element_section(&self, reader: &mut Reader) -> Result<ElementSectionNode, ParseError> {
+        let offset = reader.offset();
+        let count = reader.read_u32()?;
+        let mut elements: Vec<ElementNode> = vec![];
+
+        for _ in 0..count {
+            let element = self.element(reader)?;
+            elements.push(element);
+        }
+
+        Ok(ElementSectionNode { elements, offset })
+    }
+
+    /// elem = flag (0..=7), where bit 0 marks passive/declarative, bit 1 an explicit
+    /// table index (active) or declarative (otherwise), and bit 2 expression elements.
+    fn element(&self, reader: &mut Reader) -> Result<ElementNode, ParseError> {
+        let offset = reader.offset();
+        let flag = reader.read_u32()?;
+        if flag > 0x07 {
+            return Err(Parser::invalid(
+                reader,
+                "element segment flag",
+                flag,
+                offset,
+            ));
+        }
+
+        let mode = if flag & 0b001 == 0 {
+            let table_index = if flag & 0b010 != 0 {
+                reader.read_u32()?
+            } else {
+                0
+            };
+            let offset = self.expression(reader)?;
+            ElementModeNode::Active {
+                table_index,
+                offset,
+            }
+        } else if flag & 0b010 == 0 {
+            ElementModeNode::Passive
+        } else {
+            ElementModeNode::Declarative
+        };
+
+        let element_type = if flag & 0b011 == 0 {
+            ReferenceTypeNode::FunctionRef
+        } else if flag & 0b100 == 0 {
+            let offset = reader.offset();
+            let elemkind = reader.read_u8()?;
+            if elemkind != 0x00 {
+                return Err(Parser::invalid(
+                    reader,
+                    "element kind",
+                    elemkind as u32,
+                    offset,
+                ));
+            }
+            ReferenceTypeNode::FunctionRef
+        } else {
+            self.reference_type(reader)?
+        };
+
+        let count = reader.read_u32()?;
+        let init = if flag & 0b100 == 0 {
+            let mut indexes: Vec<u32> = vec![];
+            for _ in 0..count {
+                let index = reader.read_u32()?;
+                indexes.push(index);
+            }
+            ElementInitNode::Functions(indexes)
+        } else {
+            let mut exprs: Vec<ExpressionNode> = vec![];
+            for _ in 0..count {
+                let expr = self.expression(reader)?;
+                exprs.push(expr);
+            }
+            ElementInitNode::Expressions(exprs)
+        };
+
+        Ok(ElementNode {
+            mode,
+            element_type,
+            init,
+        })
+    }
+
     /// code section = section10(vec((code)*))
-    fn code_section(&self, bytes: &mut Vec<u8>) -> Result<CodeSectionNode, Box<dyn Error>> {
-        let (count, _) = Parser::read_u32(bytes).expect("Failed to parse vector size");
+    /// There must be a body for each of the `functions` in the function section.
+    fn code_section(
+        &self,
+        reader: &mut Reader,
+        functions: u32,
+    ) -> Result<CodeSectionNode, ParseError> {
+        let offset = reader.offset();
+        let count = reader.read_u32()?;
+        if count != functions {
+            let kind = ParseErrorKind::FunctionCodeMismatch {
+                functions,
+                bodies: count,
+            };
+            return Err(reader.error_at(kind, offset));
+        }
         let mut bodies: Vec<CodeNode> = vec![];
 
         for _ in 0..count {
-            let body = self.code(bytes).expect("Failed to parse function body");
+            let body = self.code(reader)?;
             bodies.push(body);
         }
 
-        Ok(CodeSectionNode { bodies })
+        Ok(CodeSectionNode { bodies, offset })
+    }
+
+    /// data section = section11(vec((data)*))
+    /// The segment count must match the data count section when there is one.
+    fn data_section(
+        &self,
+        reader: &mut Reader,
+        data_count: Option<u32>,
+    ) -> Result<DataSectionNode, ParseError> {
+        let offset = reader.offset();
+        let count = reader.read_u32()?;
+        if let Some(declared) = data_count {
+            if declared != count {
+                let kind = ParseErrorKind::DataCountMismatch {
+                    declared,
+                    actual: count,
+                };
+                return Err(reader.error_at(kind, offset));
+            }
+        }
+        let mut data: Vec<DataNode> = vec![];
+
+        for _ in 0..count {
+            let segment = self.data(reader)?;
+            data.push(segment);
+        }
+
+        Ok(DataSectionNode { data, offset })
+    }
+
+    /// data = 0 expr vec(byte) | 1 vec(byte) | 2 memidx expr vec(byte)
+    fn data(&self, reader: &mut Reader) -> Result<DataNode, ParseError> {
+        let offset = reader.offset();
+        let flag = reader.read_u32()?;
+
+        let mode = match flag {
+            0x00 => DataModeNode::Active {
+                memory_index: 0,
+                offset: self.expression(reader)?,
+            },
+            0x01 => DataModeNode::Passive,
+            0x02 => {
+                let memory_index = reader.read_u32()?;
+                DataModeNode::Active {
+                    memory_index,
+                    offset: self.expression(reader)?,
+                }
+            }
+            _ => return Err(Parser::invalid(reader, "data segment flag", flag, offset)),
+        };
+
+        let size = reader.read_u32()?;
+        let init = reader.read_bytes(size as usize)?.to_vec();
+
+        Ok(DataNode { mode, init })
     }
 
-    fn code(&self, bytes: &mut Vec<u8>) -> Result<CodeNode, Box<dyn Error>> {
-        let (function_body_size, _) =
-            Parser::read_u32(bytes).expect("Failed to parse function body size");
+    fn code(&self, reader: &mut Reader) -> Result<CodeNode, ParseError> {
+        let function_body_size = reader.read_u32()?;
+        let mut body_reader = reader.sub_reader(function_body_size as usize)?;
+        let offset = body_reader.offset();
 
-        let (local_count, _) =
-            Parser::read_u32(bytes).expect("Failed to parse function body local count");
+        let local_count = body_reader.read_u32()?;
         let mut local_entries: Vec<LocalEntryNode> = vec![];
+        let mut locals: u64 = 0;
 
         for _ in 0..local_count {
-            let local_entry = self
-                .local_entry(bytes)
-                .expect("Failed to parse local entry");
+            let offset = body_reader.offset();
+            let local_entry = self.local_entry(&mut body_reader)?;
+            locals += local_entry.count as u64;
+            if locals > MAX_LOCALS as u64 {
+                let kind = ParseErrorKind::TooManyLocals { limit: MAX_LOCALS };
+                return Err(body_reader.error_at(kind, offset));
+            }
             local_entries.push(local_entry);
         }
 
-        let expr = self
-            .expression(bytes, None, &mut 0)
-            .expect("Failed to parse expression");
+        let expr = self.expression_from(&mut body_reader, offset)?;
+        body_reader.finish()?;
 
         Ok(CodeNode {
             function_body_size,
             local_count,
             locals: local_entries,
             expr,
+            offset,
         })
     }
 
-    fn local_entry(&self, bytes: &mut Vec<u8>) -> Result<LocalEntryNode, Box<dyn Error>> {
-        let (count, _) = Parser::read_u32(bytes).expect("Failed to parse local entry count");
+    fn local_entry(&self, reader: &mut Reader) -> Result<LocalEntryNode, ParseError> {
+        let count = reader.read_u32()?;
 
-        let number_type = self
-            .number_type(bytes)
-            .expect("Failed to parse number type");
+        let val_type = self.value_type(reader)?;
 
-        Ok(LocalEntryNode {
-            count,
-            val_type: ValueType::Number(number_type),
-        })
+        Ok(LocalEntryNode { count, val_type })
+    }
+
+    /// expr = instr* 0x0B, where `block`, `loop` and `if` contain further
+    /// instructions up to their own 0x0B. Open blocks are kept on a stack
+    /// rather than decoded by recursion, so no nesting can overflow the stack.
+    fn expression(&self, reader: &mut Reader) -> Result<ExpressionNode, ParseError> {
+        let base = reader.offset();
+        self.expression_from(reader, base)
     }
 
-    fn expression(
+    /// Decodes an expression whose instruction offsets count from `base`.
+    fn expression_from(
         &self,
-        bytes: &mut Vec<u8>,
-        default_instructions: impl IntoIterator<Item = InstructionNode>,
-        size: &mut u32,
-    ) -> Result<ExpressionNode, Box<dyn Error>> {
+        reader: &mut Reader,
+        base: usize,
+    ) -> Result<ExpressionNode, ParseError> {
+        let mut blocks: Vec<OpenBlock> = vec![];
         let mut instructions: Vec<InstructionNode> = vec![];
-        default_instructions
-            .into_iter()
-            .for_each(|i| instructions.push(i));
+        let mut offsets: Vec<usize> = vec![];
+        // instructions decoded since the innermost block was entered
+        let mut size = 0;
 
         loop {
-            let instruction = self
-                .instruction(bytes)
-                .expect("Failed to parse instruction");
-            *size += 1;
+            let offset = reader.offset();
+            let opcode = reader.read_u8()?;
+            let instruction = Instruction::try_from(opcode)
+                .map_err(|opcode| reader.error_at(ParseErrorKind::UnknownOpcode(opcode), offset))?;
+
             match instruction {
-                InstructionNode::End(end_instr) => {
-                    instructions.push(InstructionNode::End(end_instr));
-                    break;
+                Instruction::Block | Instruction::Loop | Instruction::If => {
+                    if blocks.len() >= MAX_NESTING_DEPTH as usize {
+                        return Err(reader.error_at(
+                            ParseErrorKind::NestingTooDeep {
+                                limit: MAX_NESTING_DEPTH,
+                            },
+                            offset,
+                        ));
+                    }
+                    let block_type = self.block_type(reader)?;
+                    blocks.push(OpenBlock {
+                        instruction,
+                        offset: offset - base,
+                        block_type,
+                        then_expr: None,
+                        enclosing: std::mem::take(&mut instructions),
+                        enclosing_offsets: std::mem::take(&mut offsets),
+                        enclosing_size: std::mem::take(&mut size),
+                    });
                 }
-                InstructionNode::Else(else_instr) => {
-                    instructions.push(InstructionNode::Else(else_instr));
-                    break;
+                // the then arm ends before the else, which starts the else arm
+                Instruction::Else => match blocks.last_mut() {
+                    Some(block)
+                        if block.instruction == Instruction::If && block.then_expr.is_none() =>
+                    {
+                        size += 1;
+                        block.then_expr = Some(ExpressionNode {
+                            instructions: std::mem::take(&mut instructions),
+                            offsets: std::mem::take(&mut offsets),
+                        });
+                        offsets.push(offset - base);
+                        instructions.push(InstructionNode::Else(ElseInstructionNode::default()));
+                    }
+                    _ => {
+                        return Err(Parser::invalid(
+                            reader,
+                            "else outside of if",
+                            opcode as u32,
+                            offset,
+                        ))
+                    }
+                },
+                Instruction::End => {
+                    size += 1;
+                    instructions.push(InstructionNode::End(EndInstructionNode::default()));
+                    offsets.push(offset - base);
+                    let expr = ExpressionNode {
+                        instructions: std::mem::take(&mut instructions),
+                        offsets: std::mem::take(&mut offsets),
+                    };
+                    let Some(mut block) = blocks.pop() else {
+                        return Ok(expr);
+                    };
+                    let enclosing_size = block.enclosing_size;
+                    instructions = std::mem::take(&mut block.enclosing);
+                    offsets = std::mem::take(&mut block.enclosing_offsets);
+                    offsets.push(block.offset);
+                    instructions.push(block.into_node(expr, size));
+                    size = enclosing_size + 1;
                 }
                 _ => {
-                    instructions.push(instruction);
+                    instructions.push(self.instruction(reader, instruction, offset)?);
+                    offsets.push(offset - base);
+                    size += 1;
                 }
             }
         }
-
-        Ok(ExpressionNode { instructions })
     }
 
-    fn instruction(&self, bytes: &mut Vec<u8>) -> Result<InstructionNode, Box<dyn Error>> {
-        let opcode = Parser::read_u8(bytes).expect("Failed to parse opcode");
-        let instruction = Instruction::from(opcode);
-
+    /// Decodes an instruction other than the `block`, `loop`, `if`, `else`
+    /// and `end` that `expression` handles itself.
+    fn instruction(
+        &self,
+        reader: &mut Reader,
+        instruction: Instruction,
+        offset: usize,
+    ) -> Result<InstructionNode, ParseError> {
         match instruction {
-            Instruction::Unreachable => todo!(),
-            Instruction::Nop => todo!(),
-            Instruction::Block => {
-                let block_type = self.block_type(bytes).expect("Failed to parse block type");
-                let mut size = 0;
-                let expr = self
-                    .expression(bytes, None, &mut size)
-                    .expect("Failed to parse expression");
-                Ok(InstructionNode::Block(BlockInstructionNode::new(
-                    block_type, expr, size,
-                )))
-            }
-            Instruction::Loop => {
-                let block_type = self.block_type(bytes).expect("Failed to parse block type");
-                let mut size = 0;
-                let expr = self
-                    .expression(bytes, None, &mut size)
-                    .expect("Failed to parse expression");
-                Ok(InstructionNode::Loop(LoopInstructionNode::new(
-                    block_type, expr, size,
-                )))
-            }
-            Instruction::If => {
-                let block_type = self.block_type(bytes).expect("Failed to parse block type");
-                let mut size = 0;
-                let mut then_expr = self
-                    .expression(bytes, None, &mut size)
-                    .expect("Failed to parse if-then expression");
-                let last_instr = then_expr
-                    .instructions
-                    .pop()
-                    .unwrap_or_else(|| panic!("Failed to pop if-then instruction"));
-
-                match last_instr {
-                    InstructionNode::Else(_) => {
-                        let else_expr = self
-                            .expression(bytes, [last_instr.clone()], &mut size)
-                            .expect("Failed to parse if-else expression");
-                        Ok(InstructionNode::If(IfInstructionNode::new(
-                            block_type,
-                            then_expr,
-                            Some(else_expr),
-                            size,
-                        )))
-                    }
-                    InstructionNode::End(_) => {
-                        then_expr.instructions.push(last_instr);
-                        Ok(InstructionNode::If(IfInstructionNode::new(
-                            block_type, then_expr, None, size,
-                        )))
-                    }
-                    _ => panic!("Invalid if-then expression"),
-                }
-            }
-            Instruction::Else => {
-                let else_instr = ElseInstructionNode::default();
-                Ok(InstructionNode::Else(else_instr))
-            }
-            Instruction::End => Ok(InstructionNode::End(EndInstructionNode::default())),
+            Instruction::Block
+            | Instruction::Loop
+            | Instruction::If
+            | Instruction::Else
+            | Instruction::End => unreachable!("{:?} is decoded by expression", instruction),
             Instruction::Br => {
-                let (depth, _) = Parser::read_u32(bytes).expect("Failed to parse br depth");
+                let depth = reader.read_u32()?;
                 Ok(InstructionNode::Br(BrInstructionNode::new(depth as usize)))
             }
             Instruction::BrIf => {
-                let (depth, _) = Parser::read_u32(bytes).expect("Failed to parse br if depth");
+                let depth = reader.read_u32()?;
                 Ok(InstructionNode::BrIf(BrIfInstructionNode::new(
                     depth as usize,
                 )))
             }
-            Instruction::BrTable => todo!(),
-            Instruction::Return => todo!(),
+            Instruction::BrTable => {
+                let count = reader.read_u32()?;
+                let mut depths = vec![];
+                for _ in 0..count {
+                    depths.push(reader.read_u32()? as usize);
+                }
+                let default_depth = reader.read_u32()?;
+                Ok(InstructionNode::BrTable(BrTableInstructionNode::new(
+                    depths,
+                    default_depth as usize,
+                )))
+            }
+            Instruction::Unreachable => {
+                let node = InstructionNode::Unreachable(UnreachableInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::Nop => {
+                let node = InstructionNode::Nop(NopInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::Drop => {
+                let node = InstructionNode::Drop(DropInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::Select => {
+                let node = InstructionNode::Select(SelectInstructionNode::new(None));
+                Ok(node)
+            }
+            Instruction::SelectTyped => {
+                let val_types = self.result_types(reader)?.val_types;
+                let node = InstructionNode::Select(SelectInstructionNode::new(Some(val_types)));
+                Ok(node)
+            }
+            Instruction::Return => {
+                let node = InstructionNode::Return(ReturnInstructionNode::default());
+                Ok(node)
+            }
             Instruction::Call => {
-                let (index, _) = Parser::read_u32(bytes).expect("Failed to parse call index");
+                let index = reader.read_u32()?;
                 Ok(InstructionNode::Call(CallInstructionNode::new(index)))
             }
-            Instruction::CallIndirect => todo!(),
-            Instruction::Drop => todo!(),
-            Instruction::Select => todo!(),
+            Instruction::CallIndirect => {
+                let type_index = reader.read_u32()?;
+                let table_index = reader.read_u32()?;
+                Ok(InstructionNode::CallIndirect(
+                    CallIndirectInstructionNode::new(type_index, table_index),
+                ))
+            }
             Instruction::GetLocal => {
-                let (index, _) = Parser::read_u32(bytes).expect("Failed to parse get local index");
+                let index = reader.read_u32()?;
                 Ok(InstructionNode::GetLocal(GetLocalInstructionNode::new(
                     index,
                 )))
             }
             Instruction::SetLocal => {
-                let (index, _) = Parser::read_u32(bytes).expect("Failed to parse set local index");
+                let index = reader.read_u32()?;
                 Ok(InstructionNode::SetLocal(SetLocalInstructionNode::new(
                     index,
                 )))
             }
-            Instruction::TeeLocal => todo!(),
-            Instruction::GetGlobal => todo!(),
-            Instruction::SetGlobal => todo!(),
-            Instruction::I32Load => todo!(),
-            Instruction::I64Load => todo!(),
-            Instruction::F32Load => todo!(),
-            Instruction::F64Load => todo!(),
-            Instruction::I32Load8S => todo!(),
-            Instruction::I32Load8U => todo!(),
-            Instruction::I32Load16S => todo!(),
-            Instruction::I32Load16U => todo!(),
-            Instruction::I64Load8S => todo!(),
-            Instruction::I64Load8U => todo!(),
-            Instruction::I64Load16S => todo!(),
-            Instruction::I64Load16U => todo!(),
-            Instruction::I64Load32S => todo!(),
-            Instruction::I64Load32U => todo!(),
-            Instruction::I32Store => todo!(),
-            Instruction::I64Store => todo!(),
-            Instruction::F32Store => todo!(),
-            Instruction::F64Store => todo!(),
-            Instruction::I32Store8 => todo!(),
-            Instruction::I32Store16 => todo!(),
-            Instruction::I64Store8 => todo!(),
-            Instruction::I64Store16 => todo!(),
-            Instruction::I64Store32 => todo!(),
-            Instruction::CurrentMemory => todo!(),
-            Instruction::GrowMemory => todo!(),
+            Instruction::TeeLocal => {
+                let index = reader.read_u32()?;
+                Ok(InstructionNode::TeeLocal(TeeLocalInstructionNode::new(
+                    index,
+                )))
+            }
+            Instruction::GetGlobal => {
+                let index = reader.read_u32()?;
+                Ok(InstructionNode::GetGlobal(GetGlobalInstructionNode::new(
+                    index,
+                )))
+            }
+            Instruction::SetGlobal => {
+                let index = reader.read_u32()?;
+                Ok(InstructionNode::SetGlobal(SetGlobalInstructionNode::new(
+                    index,
+                )))
+            }
+            Instruction::I32Load => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32Load(MemoryAccessInstructionNode::new(
+                    Instruction::I32Load as u8,
+                    memarg,
+                )))
+            }
+            Instruction::I64Load => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64Load(MemoryAccessInstructionNode::new(
+                    Instruction::I64Load as u8,
+                    memarg,
+                )))
+            }
+            Instruction::F32Load => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::F32Load(MemoryAccessInstructionNode::new(
+                    Instruction::F32Load as u8,
+                    memarg,
+                )))
+            }
+            Instruction::F64Load => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::F64Load(MemoryAccessInstructionNode::new(
+                    Instruction::F64Load as u8,
+                    memarg,
+                )))
+            }
+            Instruction::I32Load8S => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32Load8S(
+                    MemoryAccessInstructionNode::new(Instruction::I32Load8S as u8, memarg),
+                ))
+            }
+            Instruction::I32Load8U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32Load8U(
+                    MemoryAccessInstructionNode::new(Instruction::I32Load8U as u8, memarg),
+                ))
+            }
+            Instruction::I32Load16S => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32Load16S(
+                    MemoryAccessInstructionNode::new(Instruction::I32Load16S as u8, memarg),
+                ))
+            }
+            Instruction::I32Load16U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32Load16U(
+                    MemoryAccessInstructionNode::new(Instruction::I32Load16U as u8, memarg),
+                ))
+            }
+            Instruction::I64Load8S => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64Load8S(
+                    MemoryAccessInstructionNode::new(Instruction::I64Load8S as u8, memarg),
+                ))
+            }
+            Instruction::I64Load8U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64Load8U(
+                    MemoryAccessInstructionNode::new(Instruction::I64Load8U as u8, memarg),
+                ))
+            }
+            Instruction::I64Load16S => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64Load16S(
+                    MemoryAccessInstructionNode::new(Instruction::I64Load16S as u8, memarg),
+                ))
+            }
+            Instruction::I64Load16U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64Load16U(
+                    MemoryAccessInstructionNode::new(Instruction::I64Load16U as u8, memarg),
+                ))
+            }
+            Instruction::I64Load32S => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64Load32S(
+                    MemoryAccessInstructionNode::new(Instruction::I64Load32S as u8, memarg),
+                ))
+            }
+            Instruction::I64Load32U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64Load32U(
+                    MemoryAccessInstructionNode::new(Instruction::I64Load32U as u8, memarg),
+                ))
+            }
+            Instruction::I32Store => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32Store(MemoryAccessInstructionNode::new(
+                    Instruction::I32Store as u8,
+                    memarg,
+                )))
+            }
+            Instruction::I64Store => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64Store(MemoryAccessInstructionNode::new(
+                    Instruction::I64Store as u8,
+                    memarg,
+                )))
+            }
+            Instruction::F32Store => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::F32Store(MemoryAccessInstructionNode::new(
+                    Instruction::F32Store as u8,
+                    memarg,
+                )))
+            }
+            Instruction::F64Store => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::F64Store(MemoryAccessInstructionNode::new(
+                    Instruction::F64Store as u8,
+                    memarg,
+                )))
+            }
+            Instruction::I32Store8 => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32Store8(
+                    MemoryAccessInstructionNode::new(Instruction::I32Store8 as u8, memarg),
+                ))
+            }
+            Instruction::I32Store16 => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32Store16(
+                    MemoryAccessInstructionNode::new(Instruction::I32Store16 as u8, memarg),
+                ))
+            }
+            Instruction::I64Store8 => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64Store8(
+                    MemoryAccessInstructionNode::new(Instruction::I64Store8 as u8, memarg),
+                ))
+            }
+            Instruction::I64Store16 => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64Store16(
+                    MemoryAccessInstructionNode::new(Instruction::I64Store16 as u8, memarg),
+                ))
+            }
+            Instruction::I64Store32 => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64Store32(
+                    MemoryAccessInstructionNode::new(Instruction::I64Store32 as u8, memarg),
+                ))
+            }
+            Instruction::CurrentMemory => {
+                reader.read_u8()?;
+                Ok(InstructionNode::MemorySize(
+                    MemorySizeInstructionNode::default(),
+                ))
+            }
+            Instruction::GrowMemory => {
+                reader.read_u8()?;
+                Ok(InstructionNode::MemoryGrow(
+                    MemoryGrowInstructionNode::default(),
+                ))
+            }
             Instruction::I32Const => {
-                let (value, _) = Parser::read_i32(bytes).expect("Failed to parse const i32");
+                let value = reader.read_i32()?;
                 let node = InstructionNode::I32Const(I32ConstInstructionNode::new(value));
                 Ok(node)
             }
-            Instruction::I64Const => todo!(),
-            Instruction::F32Const => todo!(),
-            Instruction::F64Const => todo!(),
             Instruction::I32Eqz => {
                 let node = InstructionNode::I32Eqz(I32EqzInstructionNode::default());
                 Ok(node)
             }
-            Instruction::I32Eq => todo!(),
-            Instruction::I32Ne => todo!(),
+            Instruction::I32Eq => {
+                let node = InstructionNode::I32Eq(I32EqInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32Ne => {
+                let node = InstructionNode::I32Ne(I32NeInstructionNode::default());
+                Ok(node)
+            }
             Instruction::I32LtS => {
                 let node = InstructionNode::I32LtS(I32LtSInstructionNode::default());
                 Ok(node)
@@ -388,41 +1156,42 @@ impl Parser {
                 let node = InstructionNode::I32LtU(I32LtUInstructionNode::default());
                 Ok(node)
             }
-            Instruction::I32GtS => todo!(),
-            Instruction::I32GtU => todo!(),
-            Instruction::I32LeS => todo!(),
-            Instruction::I32LeU => todo!(),
+            Instruction::I32GtS => {
+                let node = InstructionNode::I32GtS(I32GtSInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32GtU => {
+                let node = InstructionNode::I32GtU(I32GtUInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32LeS => {
+                let node = InstructionNode::I32LeS(I32LeSInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32LeU => {
+                let node = InstructionNode::I32LeU(I32LeUInstructionNode::default());
+                Ok(node)
+            }
             Instruction::I32GeS => {
                 let node = InstructionNode::I32GeS(I32GeSInstructionNode::default());
                 Ok(node)
             }
-            Instruction::I32GeU => todo!(),
-            Instruction::I64Eqz => todo!(),
-            Instruction::I64Eq => todo!(),
-            Instruction::I64Ne => todo!(),
-            Instruction::I64LtS => todo!(),
-            Instruction::I64LtU => todo!(),
-            Instruction::I64GtS => todo!(),
-            Instruction::I64GtU => todo!(),
-            Instruction::I64LeS => todo!(),
-            Instruction::I64LeU => todo!(),
-            Instruction::I64GeS => todo!(),
-            Instruction::I64GeU => todo!(),
-            Instruction::F32Eq => todo!(),
-            Instruction::F32Ne => todo!(),
-            Instruction::F32Lt => todo!(),
-            Instruction::F32Gt => todo!(),
-            Instruction::F32Le => todo!(),
-            Instruction::F32Ge => todo!(),
-            Instruction::F64Eq => todo!(),
-            Instruction::F64Ne => todo!(),
-            Instruction::F64Lt => todo!(),
-            Instruction::F64Gt => todo!(),
-            Instruction::F64Le => todo!(),
-            Instruction::F64Ge => todo!(),
-            Instruction::I32Clz => todo!(),
-            Instruction::I32Ctz => todo!(),
-            Instruction::I32Popcnt => todo!(),
+            Instruction::I32GeU => {
+                let node = InstructionNode::I32GeU(I32GeUInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32Clz => {
+                let node = InstructionNode::I32Clz(I32ClzInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32Ctz => {
+                let node = InstructionNode::I32Ctz(I32CtzInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32Popcnt => {
+                let node = InstructionNode::I32Popcnt(I32PopcntInstructionNode::default());
+                Ok(node)
+            }
             Instruction::I32Add => {
                 let node = InstructionNode::I32Add(I32AddInstructionNode::default());
                 Ok(node)
@@ -431,9 +1200,18 @@ impl Parser {
                 let node = InstructionNode::I32Sub(I32SubInstructionNode::default());
                 Ok(node)
             }
-            Instruction::I32Mul => todo!(),
-            Instruction::I32DivS => todo!(),
-            Instruction::I32DivU => todo!(),
+            Instruction::I32Mul => {
+                let node = InstructionNode::I32Mul(I32MulInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32DivS => {
+                let node = InstructionNode::I32DivS(I32DivSInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32DivU => {
+                let node = InstructionNode::I32DivU(I32DivUInstructionNode::default());
+                Ok(node)
+            }
             Instruction::I32RemS => {
                 let node = InstructionNode::I32RemS(I32RemSInstructionNode::default());
                 Ok(node)
@@ -442,152 +1220,2917 @@ impl Parser {
                 let node = InstructionNode::I32RemU(I32RemUInstructionNode::default());
                 Ok(node)
             }
-            Instruction::I32And => todo!(),
-            Instruction::I32Or => todo!(),
-            Instruction::I32Xor => todo!(),
-            Instruction::I32Shl => todo!(),
-            Instruction::I32ShrS => todo!(),
-            Instruction::I32ShrU => todo!(),
-            Instruction::I32Rotl => todo!(),
-            Instruction::I32Rotr => todo!(),
-            Instruction::I64Clz => todo!(),
-            Instruction::I64Ctz => todo!(),
-            Instruction::I64Popcnt => todo!(),
-            Instruction::I64Add => todo!(),
-            Instruction::I64Sub => todo!(),
-            Instruction::I64Mul => todo!(),
-            Instruction::I64DivS => todo!(),
-            Instruction::I64DivU => todo!(),
-            Instruction::I64RemS => todo!(),
-            Instruction::I64RemU => todo!(),
-            Instruction::I64And => todo!(),
-            Instruction::I64Or => todo!(),
-            Instruction::I64Xor => todo!(),
-            Instruction::I64Shl => todo!(),
-            Instruction::I64ShrS => todo!(),
-            Instruction::I64ShrU => todo!(),
-            Instruction::I64Rotl => todo!(),
-            Instruction::I64Rotr => todo!(),
-            Instruction::F32Abs => todo!(),
-            Instruction::F32Neg => todo!(),
-            Instruction::F32Ceil => todo!(),
-            Instruction::F32Floor => todo!(),
-            Instruction::F32Trunc => todo!(),
-            Instruction::F32Nearest => todo!(),
-            Instruction::F32Sqrt => todo!(),
-            Instruction::F32Add => todo!(),
-            Instruction::F32Sub => todo!(),
-            Instruction::F32Mul => todo!(),
-            Instruction::F32Div => todo!(),
-            Instruction::F32Min => todo!(),
-            Instruction::F32Max => todo!(),
-            Instruction::F32Copysign => todo!(),
-            Instruction::F64Abs => todo!(),
-            Instruction::F64Neg => todo!(),
-            Instruction::F64Ceil => todo!(),
-            Instruction::F64Floor => todo!(),
-            Instruction::F64Trunc => todo!(),
-            Instruction::F64Nearest => todo!(),
-            Instruction::F64Sqrt => todo!(),
-            Instruction::F64Add => todo!(),
-            Instruction::F64Sub => todo!(),
-            Instruction::F64Mul => todo!(),
-            Instruction::F64Div => todo!(),
-            Instruction::F64Min => todo!(),
-            Instruction::F64Max => todo!(),
-            Instruction::F64Copysign => todo!(),
-        }
-    }
-
-    /// functype = 0x60 (result type) (result type)
-    fn function_type(&self, bytes: &mut Vec<u8>) -> Result<FunctionTypeNode, Box<dyn Error>> {
-        let header = Parser::read_u8(bytes).expect("Failed to parse function type header");
-        let params = self
-            .result_types(bytes)
-            .expect("Failed to parse value type");
-        let returns = self
-            .result_types(bytes)
-            .expect("Failed to parse value type");
-
-        let function_type_node: FunctionTypeNode = FunctionTypeNode::new(params, returns);
-        function_type_node.validate_header(header);
-
-        Ok(function_type_node)
-    }
-
-    /// result type = vec((value type)*)
-    fn result_types(&self, bytes: &mut Vec<u8>) -> Result<ResultTypeNode, Box<dyn Error>> {
-        let (count, _) = Parser::read_u32(bytes).expect("Failed to read count");
-
-        let mut node = ResultTypeNode { val_types: vec![] };
-        for _ in 0..count {
-            let number_type = self
-                .number_type(bytes)
-                .expect("Failed to parse number type");
-            node.val_types.push(ValueType::Number(number_type));
-        }
-        Ok(node)
-    }
-
-    fn number_type(&self, bytes: &mut Vec<u8>) -> Result<NumberType, Box<dyn Error>> {
-        let byte = Parser::read_u8(bytes).expect("Failed to read number type id");
-        Ok(NumberType::from(byte))
-    }
-
-    fn block_type(&self, bytes: &mut Vec<u8>) -> Result<BlockType, Box<dyn Error>> {
-        let byte = Parser::read_u8(bytes).expect("Failed to read block type id");
-        let block_type = BlockType::from(byte);
-        Ok(block_type)
-    }
-
-    pub fn read_u8(bytes: &mut Vec<u8>) -> Result<u8, Box<dyn Error>> {
-        let byte = bytes[0];
-        (*bytes).drain(0..1);
-        Ok(byte)
-    }
-
-    pub fn read_u32(bytes: &mut Vec<u8>) -> Result<(u32, u32), Box<dyn Error>> {
-        decode_unsigned_leb128(bytes)
-    }
-
-    pub fn read_i32(bytes: &mut Vec<u8>) -> Result<(i32, u32), Box<dyn Error>> {
-        decode_signed_leb128(bytes)
-    }
+            Instruction::I32And => {
+                let node = InstructionNode::I32And(I32AndInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32Or => {
+                let node = InstructionNode::I32Or(I32OrInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32Xor => {
+                let node = InstructionNode::I32Xor(I32XorInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32Shl => {
+                let node = InstructionNode::I32Shl(I32ShlInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32ShrS => {
+                let node = InstructionNode::I32ShrS(I32ShrSInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32ShrU => {
+                let node = InstructionNode::I32ShrU(I32ShrUInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32Rotl => {
+                let node = InstructionNode::I32Rotl(I32RotlInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32Rotr => {
+                let node = InstructionNode::I32Rotr(I32RotrInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Const => {
+                let value = reader.read_i64()?;
+                let node = InstructionNode::I64Const(I64ConstInstructionNode::new(value));
+                Ok(node)
+            }
+            Instruction::I64Eqz => {
+                let node = InstructionNode::I64Eqz(I64EqzInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Eq => {
+                let node = InstructionNode::I64Eq(I64EqInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Ne => {
+                let node = InstructionNode::I64Ne(I64NeInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64LtS => {
+                let node = InstructionNode::I64LtS(I64LtSInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64LtU => {
+                let node = InstructionNode::I64LtU(I64LtUInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64GtS => {
+                let node = InstructionNode::I64GtS(I64GtSInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64GtU => {
+                let node = InstructionNode::I64GtU(I64GtUInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64LeS => {
+                let node = InstructionNode::I64LeS(I64LeSInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64LeU => {
+                let node = InstructionNode::I64LeU(I64LeUInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64GeS => {
+                let node = InstructionNode::I64GeS(I64GeSInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64GeU => {
+                let node = InstructionNode::I64GeU(I64GeUInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Clz => {
+                let node = InstructionNode::I64Clz(I64ClzInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Ctz => {
+                let node = InstructionNode::I64Ctz(I64CtzInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Popcnt => {
+                let node = InstructionNode::I64Popcnt(I64PopcntInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Add => {
+                let node = InstructionNode::I64Add(I64AddInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Sub => {
+                let node = InstructionNode::I64Sub(I64SubInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Mul => {
+                let node = InstructionNode::I64Mul(I64MulInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64DivS => {
+                let node = InstructionNode::I64DivS(I64DivSInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64DivU => {
+                let node = InstructionNode::I64DivU(I64DivUInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64RemS => {
+                let node = InstructionNode::I64RemS(I64RemSInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64RemU => {
+                let node = InstructionNode::I64RemU(I64RemUInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64And => {
+                let node = InstructionNode::I64And(I64AndInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Or => {
+                let node = InstructionNode::I64Or(I64OrInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Xor => {
+                let node = InstructionNode::I64Xor(I64XorInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Shl => {
+                let node = InstructionNode::I64Shl(I64ShlInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64ShrS => {
+                let node = InstructionNode::I64ShrS(I64ShrSInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64ShrU => {
+                let node = InstructionNode::I64ShrU(I64ShrUInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Rotl => {
+                let node = InstructionNode::I64Rotl(I64RotlInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Rotr => {
+                let node = InstructionNode::I64Rotr(I64RotrInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Const => {
+                let value = reader.read_f32()?;
+                let node = InstructionNode::F32Const(F32ConstInstructionNode::new(value));
+                Ok(node)
+            }
+            Instruction::F64Const => {
+                let value = reader.read_f64()?;
+                let node = InstructionNode::F64Const(F64ConstInstructionNode::new(value));
+                Ok(node)
+            }
+            Instruction::F32Eq => {
+                let node = InstructionNode::F32Eq(F32EqInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Ne => {
+                let node = InstructionNode::F32Ne(F32NeInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Lt => {
+                let node = InstructionNode::F32Lt(F32LtInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Gt => {
+                let node = InstructionNode::F32Gt(F32GtInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Le => {
+                let node = InstructionNode::F32Le(F32LeInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Ge => {
+                let node = InstructionNode::F32Ge(F32GeInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Eq => {
+                let node = InstructionNode::F64Eq(F64EqInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Ne => {
+                let node = InstructionNode::F64Ne(F64NeInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Lt => {
+                let node = InstructionNode::F64Lt(F64LtInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Gt => {
+                let node = InstructionNode::F64Gt(F64GtInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Le => {
+                let node = InstructionNode::F64Le(F64LeInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Ge => {
+                let node = InstructionNode::F64Ge(F64GeInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Abs => {
+                let node = InstructionNode::F32Abs(F32AbsInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Neg => {
+                let node = InstructionNode::F32Neg(F32NegInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Ceil => {
+                let node = InstructionNode::F32Ceil(F32CeilInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Floor => {
+                let node = InstructionNode::F32Floor(F32FloorInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Trunc => {
+                let node = InstructionNode::F32Trunc(F32TruncInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Nearest => {
+                let node = InstructionNode::F32Nearest(F32NearestInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Sqrt => {
+                let node = InstructionNode::F32Sqrt(F32SqrtInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Add => {
+                let node = InstructionNode::F32Add(F32AddInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Sub => {
+                let node = InstructionNode::F32Sub(F32SubInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Mul => {
+                let node = InstructionNode::F32Mul(F32MulInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Div => {
+                let node = InstructionNode::F32Div(F32DivInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Min => {
+                let node = InstructionNode::F32Min(F32MinInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Max => {
+                let node = InstructionNode::F32Max(F32MaxInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32Copysign => {
+                let node = InstructionNode::F32Copysign(F32CopysignInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Abs => {
+                let node = InstructionNode::F64Abs(F64AbsInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Neg => {
+                let node = InstructionNode::F64Neg(F64NegInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Ceil => {
+                let node = InstructionNode::F64Ceil(F64CeilInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Floor => {
+                let node = InstructionNode::F64Floor(F64FloorInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Trunc => {
+                let node = InstructionNode::F64Trunc(F64TruncInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Nearest => {
+                let node = InstructionNode::F64Nearest(F64NearestInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Sqrt => {
+                let node = InstructionNode::F64Sqrt(F64SqrtInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Add => {
+                let node = InstructionNode::F64Add(F64AddInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Sub => {
+                let node = InstructionNode::F64Sub(F64SubInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Mul => {
+                let node = InstructionNode::F64Mul(F64MulInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Div => {
+                let node = InstructionNode::F64Div(F64DivInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Min => {
+                let node = InstructionNode::F64Min(F64MinInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Max => {
+                let node = InstructionNode::F64Max(F64MaxInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64Copysign => {
+                let node = InstructionNode::F64Copysign(F64CopysignInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32WrapI64 => {
+                let node = InstructionNode::I32WrapI64(I32WrapI64InstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32TruncF32S => {
+                let node = InstructionNode::I32TruncF32S(I32TruncF32SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32TruncF32U => {
+                let node = InstructionNode::I32TruncF32U(I32TruncF32UInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32TruncF64S => {
+                let node = InstructionNode::I32TruncF64S(I32TruncF64SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32TruncF64U => {
+                let node = InstructionNode::I32TruncF64U(I32TruncF64UInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64ExtendI32S => {
+                let node = InstructionNode::I64ExtendI32S(I64ExtendI32SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64ExtendI32U => {
+                let node = InstructionNode::I64ExtendI32U(I64ExtendI32UInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64TruncF32S => {
+                let node = InstructionNode::I64TruncF32S(I64TruncF32SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64TruncF32U => {
+                let node = InstructionNode::I64TruncF32U(I64TruncF32UInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64TruncF64S => {
+                let node = InstructionNode::I64TruncF64S(I64TruncF64SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64TruncF64U => {
+                let node = InstructionNode::I64TruncF64U(I64TruncF64UInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32ConvertI32S => {
+                let node =
+                    InstructionNode::F32ConvertI32S(F32ConvertI32SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32ConvertI32U => {
+                let node =
+                    InstructionNode::F32ConvertI32U(F32ConvertI32UInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32ConvertI64S => {
+                let node =
+                    InstructionNode::F32ConvertI64S(F32ConvertI64SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32ConvertI64U => {
+                let node =
+                    InstructionNode::F32ConvertI64U(F32ConvertI64UInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32DemoteF64 => {
+                let node = InstructionNode::F32DemoteF64(F32DemoteF64InstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64ConvertI32S => {
+                let node =
+                    InstructionNode::F64ConvertI32S(F64ConvertI32SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64ConvertI32U => {
+                let node =
+                    InstructionNode::F64ConvertI32U(F64ConvertI32UInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64ConvertI64S => {
+                let node =
+                    InstructionNode::F64ConvertI64S(F64ConvertI64SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64ConvertI64U => {
+                let node =
+                    InstructionNode::F64ConvertI64U(F64ConvertI64UInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64PromoteF32 => {
+                let node = InstructionNode::F64PromoteF32(F64PromoteF32InstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32ReinterpretF32 => {
+                let node =
+                    InstructionNode::I32ReinterpretF32(I32ReinterpretF32InstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64ReinterpretF64 => {
+                let node =
+                    InstructionNode::I64ReinterpretF64(I64ReinterpretF64InstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F32ReinterpretI32 => {
+                let node =
+                    InstructionNode::F32ReinterpretI32(F32ReinterpretI32InstructionNode::default());
+                Ok(node)
+            }
+            Instruction::F64ReinterpretI64 => {
+                let node =
+                    InstructionNode::F64ReinterpretI64(F64ReinterpretI64InstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32Extend8S => {
+                let node = InstructionNode::I32Extend8S(I32Extend8SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I32Extend16S => {
+                let node = InstructionNode::I32Extend16S(I32Extend16SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Extend8S => {
+                let node = InstructionNode::I64Extend8S(I64Extend8SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Extend16S => {
+                let node = InstructionNode::I64Extend16S(I64Extend16SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::I64Extend32S => {
+                let node = InstructionNode::I64Extend32S(I64Extend32SInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::MiscPrefix => self.misc_instruction(reader, offset),
+            Instruction::SimdPrefix => self.simd_instruction(reader, offset),
+            Instruction::AtomicPrefix => self.atomic_instruction(reader, offset),
+            Instruction::RefNull => {
+                let ref_type = self.reference_type(reader)?;
+                Ok(InstructionNode::RefNull(RefNullInstructionNode::new(
+                    ref_type,
+                )))
+            }
+            Instruction::RefIsNull => {
+                let node = InstructionNode::RefIsNull(RefIsNullInstructionNode::default());
+                Ok(node)
+            }
+            Instruction::TableGet => {
+                let table_index = reader.read_u32()?;
+                Ok(InstructionNode::TableGet(TableGetInstructionNode::new(
+                    table_index,
+                )))
+            }
+            Instruction::TableSet => {
+                let table_index = reader.read_u32()?;
+                Ok(InstructionNode::TableSet(TableSetInstructionNode::new(
+                    table_index,
+                )))
+            }
+            Instruction::RefFunc => {
+                let index = reader.read_u32()?;
+                Ok(InstructionNode::RefFunc(RefFuncInstructionNode::new(index)))
+            }
+        }
+    }
+
+    /// Decodes an instruction under the 0xFC prefix, whose sub-opcode is a u32.
+    fn misc_instruction(
+        &self,
+        reader: &mut Reader,
+        offset: usize,
+    ) -> Result<InstructionNode, ParseError> {
+        let opcode = reader.read_u32()?;
+        let instruction = MiscInstruction::try_from(opcode).map_err(|opcode| {
+            reader.error_at(
+                ParseErrorKind::UnknownPrefixedOpcode {
+                    prefix: Instruction::MiscPrefix as u8,
+                    opcode,
+                },
+                offset,
+            )
+        })?;
+
+        match instruction {
+            MiscInstruction::I32TruncSatF32S => Ok(InstructionNode::I32TruncSatF32S(
+                I32TruncSatF32SInstructionNode::default(),
+            )),
+            MiscInstruction::I32TruncSatF32U => Ok(InstructionNode::I32TruncSatF32U(
+                I32TruncSatF32UInstructionNode::default(),
+            )),
+            MiscInstruction::I32TruncSatF64S => Ok(InstructionNode::I32TruncSatF64S(
+                I32TruncSatF64SInstructionNode::default(),
+            )),
+            MiscInstruction::I32TruncSatF64U => Ok(InstructionNode::I32TruncSatF64U(
+                I32TruncSatF64UInstructionNode::default(),
+            )),
+            MiscInstruction::I64TruncSatF32S => Ok(InstructionNode::I64TruncSatF32S(
+                I64TruncSatF32SInstructionNode::default(),
+            )),
+            MiscInstruction::I64TruncSatF32U => Ok(InstructionNode::I64TruncSatF32U(
+                I64TruncSatF32UInstructionNode::default(),
+            )),
+            MiscInstruction::I64TruncSatF64S => Ok(InstructionNode::I64TruncSatF64S(
+                I64TruncSatF64SInstructionNode::default(),
+            )),
+            MiscInstruction::I64TruncSatF64U => Ok(InstructionNode::I64TruncSatF64U(
+                I64TruncSatF64UInstructionNode::default(),
+            )),
+            MiscInstruction::MemoryInit => {
+                let data_index = reader.read_u32()?;
+                let memory_index = reader.read_u32()?;
+                Ok(InstructionNode::MemoryInit(MemoryInitInstructionNode::new(
+                    data_index,
+                    memory_index,
+                )))
+            }
+            MiscInstruction::DataDrop => {
+                let data_index = reader.read_u32()?;
+                Ok(InstructionNode::DataDrop(DataDropInstructionNode::new(
+                    data_index,
+                )))
+            }
+            MiscInstruction::MemoryCopy => {
+                let dst_memory_index = reader.read_u32()?;
+                let src_memory_index = reader.read_u32()?;
+                Ok(InstructionNode::MemoryCopy(MemoryCopyInstructionNode::new(
+                    dst_memory_index,
+                    src_memory_index,
+                )))
+            }
+            MiscInstruction::MemoryFill => {
+                let memory_index = reader.read_u32()?;
+                Ok(InstructionNode::MemoryFill(MemoryFillInstructionNode::new(
+                    memory_index,
+                )))
+            }
+            MiscInstruction::TableInit => {
+                let elem_index = reader.read_u32()?;
+                let table_index = reader.read_u32()?;
+                Ok(InstructionNode::TableInit(TableInitInstructionNode::new(
+                    elem_index,
+                    table_index,
+                )))
+            }
+            MiscInstruction::ElemDrop => {
+                let elem_index = reader.read_u32()?;
+                Ok(InstructionNode::ElemDrop(ElemDropInstructionNode::new(
+                    elem_index,
+                )))
+            }
+            MiscInstruction::TableGrow => {
+                let table_index = reader.read_u32()?;
+                Ok(InstructionNode::TableGrow(TableGrowInstructionNode::new(
+                    table_index,
+                )))
+            }
+            MiscInstruction::TableSize => {
+                let table_index = reader.read_u32()?;
+                Ok(InstructionNode::TableSize(TableSizeInstructionNode::new(
+                    table_index,
+                )))
+            }
+            MiscInstruction::TableFill => {
+                let table_index = reader.read_u32()?;
+                Ok(InstructionNode::TableFill(TableFillInstructionNode::new(
+                    table_index,
+                )))
+            }
+            MiscInstruction::TableCopy => {
+                let dst_table_index = reader.read_u32()?;
+                let src_table_index = reader.read_u32()?;
+                Ok(InstructionNode::TableCopy(TableCopyInstructionNode::new(
+                    dst_table_index,
+                    src_table_index,
+                )))
+            }
+        }
+    }
+
+    /// functype = 0x60 (result type) (result type)
+    fn function_type(&self, reader: &mut Reader) -> Result<FunctionTypeNode, ParseError> {
+        let offset = reader.offset();
+        let header = reader.read_u8()?;
+        if header != 0x60 {
+            return Err(Parser::invalid(
+                reader,
+                "function type header",
+                header as u32,
+                offset,
+            ));
+        }
+        let params = self.result_types(reader)?;
+        let returns = self.result_types(reader)?;
+
+        let function_type_node: FunctionTypeNode = FunctionTypeNode::new(params, returns);
+        function_type_node.validate_header(header);
+
+        Ok(function_type_node)
+    }
+
+    /// result type = vec((value type)*)
+    fn result_types(&self, reader: &mut Reader) -> Result<ResultTypeNode, ParseError> {
+        let count = reader.read_u32()?;
+
+        let mut node = ResultTypeNode { val_types: vec![] };
+        for _ in 0..count {
+            let val_type = self.value_type(reader)?;
+            node.val_types.push(val_type);
+        }
+        Ok(node)
+    }
+
+    /// valtype = numtype | reftype
+    fn value_type(&self, reader: &mut Reader) -> Result<ValueType, ParseError> {
+        let offset = reader.offset();
+        let byte = reader.read_u8()?;
+        ValueType::try_from(byte)
+            .map_err(|byte| reader.error_at(ParseErrorKind::InvalidValueType(byte), offset))
+    }
+
+    /// reftype = 0x70 funcref | 0x6F externref
+    fn reference_type(&self, reader: &mut Reader) -> Result<ReferenceTypeNode, ParseError> {
+        let offset = reader.offset();
+        let byte = reader.read_u8()?;
+        ReferenceTypeNode::try_from(byte)
+            .map_err(|byte| reader.error_at(ParseErrorKind::InvalidValueType(byte), offset))
+    }
+
+    /// Decodes an instruction under the 0xFD prefix, whose sub-opcode is a u32.
+    fn simd_instruction(
+        &self,
+        reader: &mut Reader,
+        offset: usize,
+    ) -> Result<InstructionNode, ParseError> {
+        let opcode = reader.read_u32()?;
+        let instruction = SimdInstruction::try_from(opcode).map_err(|opcode| {
+            reader.error_at(
+                ParseErrorKind::UnknownPrefixedOpcode {
+                    prefix: Instruction::SimdPrefix as u8,
+                    opcode,
+                },
+                offset,
+            )
+        })?;
+
+        match instruction {
+            SimdInstruction::V128Load => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load(
+                    SimdMemoryAccessInstructionNode::new(SimdInstruction::V128Load as u32, memarg),
+                ))
+            }
+            SimdInstruction::V128Load8x8S => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load8x8S(
+                    SimdMemoryAccessInstructionNode::new(
+                        SimdInstruction::V128Load8x8S as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load8x8U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load8x8U(
+                    SimdMemoryAccessInstructionNode::new(
+                        SimdInstruction::V128Load8x8U as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load16x4S => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load16x4S(
+                    SimdMemoryAccessInstructionNode::new(
+                        SimdInstruction::V128Load16x4S as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load16x4U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load16x4U(
+                    SimdMemoryAccessInstructionNode::new(
+                        SimdInstruction::V128Load16x4U as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load32x2S => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load32x2S(
+                    SimdMemoryAccessInstructionNode::new(
+                        SimdInstruction::V128Load32x2S as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load32x2U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load32x2U(
+                    SimdMemoryAccessInstructionNode::new(
+                        SimdInstruction::V128Load32x2U as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load8Splat => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load8Splat(
+                    SimdMemoryAccessInstructionNode::new(
+                        SimdInstruction::V128Load8Splat as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load16Splat => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load16Splat(
+                    SimdMemoryAccessInstructionNode::new(
+                        SimdInstruction::V128Load16Splat as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load32Splat => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load32Splat(
+                    SimdMemoryAccessInstructionNode::new(
+                        SimdInstruction::V128Load32Splat as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load64Splat => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load64Splat(
+                    SimdMemoryAccessInstructionNode::new(
+                        SimdInstruction::V128Load64Splat as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Store => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Store(
+                    SimdMemoryAccessInstructionNode::new(SimdInstruction::V128Store as u32, memarg),
+                ))
+            }
+            SimdInstruction::V128Const => {
+                let bytes = reader.read_bytes(16)?;
+                let value = V128::from_le_bytes(bytes.try_into().unwrap());
+                Ok(InstructionNode::V128Const(V128ConstInstructionNode::new(
+                    value,
+                )))
+            }
+            // each lane picks one of the 32 bytes of the two operands
+            SimdInstruction::I8x16Shuffle => {
+                let mut lanes = [0; 16];
+                for lane in lanes.iter_mut() {
+                    *lane = Parser::lane_index(reader, 32)?;
+                }
+                Ok(InstructionNode::I8x16Shuffle(
+                    I8x16ShuffleInstructionNode::new(lanes),
+                ))
+            }
+            SimdInstruction::I8x16Swizzle => Ok(InstructionNode::I8x16Swizzle(
+                I8x16SwizzleInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16Splat => Ok(InstructionNode::I8x16Splat(
+                I8x16SplatInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8Splat => Ok(InstructionNode::I16x8Splat(
+                I16x8SplatInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4Splat => Ok(InstructionNode::I32x4Splat(
+                I32x4SplatInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2Splat => Ok(InstructionNode::I64x2Splat(
+                I64x2SplatInstructionNode::default(),
+            )),
+            SimdInstruction::F32x4Splat => Ok(InstructionNode::F32x4Splat(
+                F32x4SplatInstructionNode::default(),
+            )),
+            SimdInstruction::F64x2Splat => Ok(InstructionNode::F64x2Splat(
+                F64x2SplatInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16ExtractLaneS => {
+                let lane = Parser::lane_index(reader, 16)?;
+                Ok(InstructionNode::I8x16ExtractLaneS(
+                    SimdLaneInstructionNode::new(SimdInstruction::I8x16ExtractLaneS as u32, lane),
+                ))
+            }
+            SimdInstruction::I8x16ExtractLaneU => {
+                let lane = Parser::lane_index(reader, 16)?;
+                Ok(InstructionNode::I8x16ExtractLaneU(
+                    SimdLaneInstructionNode::new(SimdInstruction::I8x16ExtractLaneU as u32, lane),
+                ))
+            }
+            SimdInstruction::I8x16ReplaceLane => {
+                let lane = Parser::lane_index(reader, 16)?;
+                Ok(InstructionNode::I8x16ReplaceLane(
+                    SimdLaneInstructionNode::new(SimdInstruction::I8x16ReplaceLane as u32, lane),
+                ))
+            }
+            SimdInstruction::I16x8ExtractLaneS => {
+                let lane = Parser::lane_index(reader, 8)?;
+                Ok(InstructionNode::I16x8ExtractLaneS(
+                    SimdLaneInstructionNode::new(SimdInstruction::I16x8ExtractLaneS as u32, lane),
+                ))
+            }
+            SimdInstruction::I16x8ExtractLaneU => {
+                let lane = Parser::lane_index(reader, 8)?;
+                Ok(InstructionNode::I16x8ExtractLaneU(
+                    SimdLaneInstructionNode::new(SimdInstruction::I16x8ExtractLaneU as u32, lane),
+                ))
+            }
+            SimdInstruction::I16x8ReplaceLane => {
+                let lane = Parser::lane_index(reader, 8)?;
+                Ok(InstructionNode::I16x8ReplaceLane(
+                    SimdLaneInstructionNode::new(SimdInstruction::I16x8ReplaceLane as u32, lane),
+                ))
+            }
+            SimdInstruction::I32x4ExtractLane => {
+                let lane = Parser::lane_index(reader, 4)?;
+                Ok(InstructionNode::I32x4ExtractLane(
+                    SimdLaneInstructionNode::new(SimdInstruction::I32x4ExtractLane as u32, lane),
+                ))
+            }
+            SimdInstruction::I32x4ReplaceLane => {
+                let lane = Parser::lane_index(reader, 4)?;
+                Ok(InstructionNode::I32x4ReplaceLane(
+                    SimdLaneInstructionNode::new(SimdInstruction::I32x4ReplaceLane as u32, lane),
+                ))
+            }
+            SimdInstruction::I64x2ExtractLane => {
+                let lane = Parser::lane_index(reader, 2)?;
+                Ok(InstructionNode::I64x2ExtractLane(
+                    SimdLaneInstructionNode::new(SimdInstruction::I64x2ExtractLane as u32, lane),
+                ))
+            }
+            SimdInstruction::I64x2ReplaceLane => {
+                let lane = Parser::lane_index(reader, 2)?;
+                Ok(InstructionNode::I64x2ReplaceLane(
+                    SimdLaneInstructionNode::new(SimdInstruction::I64x2ReplaceLane as u32, lane),
+                ))
+            }
+            SimdInstruction::F32x4ExtractLane => {
+                let lane = Parser::lane_index(reader, 4)?;
+                Ok(InstructionNode::F32x4ExtractLane(
+                    SimdLaneInstructionNode::new(SimdInstruction::F32x4ExtractLane as u32, lane),
+                ))
+            }
+            SimdInstruction::F32x4ReplaceLane => {
+                let lane = Parser::lane_index(reader, 4)?;
+                Ok(InstructionNode::F32x4ReplaceLane(
+                    SimdLaneInstructionNode::new(SimdInstruction::F32x4ReplaceLane as u32, lane),
+                ))
+            }
+            SimdInstruction::F64x2ExtractLane => {
+                let lane = Parser::lane_index(reader, 2)?;
+                Ok(InstructionNode::F64x2ExtractLane(
+                    SimdLaneInstructionNode::new(SimdInstruction::F64x2ExtractLane as u32, lane),
+                ))
+            }
+            SimdInstruction::F64x2ReplaceLane => {
+                let lane = Parser::lane_index(reader, 2)?;
+                Ok(InstructionNode::F64x2ReplaceLane(
+                    SimdLaneInstructionNode::new(SimdInstruction::F64x2ReplaceLane as u32, lane),
+                ))
+            }
+            SimdInstruction::I8x16Eq => {
+                Ok(InstructionNode::I8x16Eq(I8x16EqInstructionNode::default()))
+            }
+            SimdInstruction::I8x16Ne => {
+                Ok(InstructionNode::I8x16Ne(I8x16NeInstructionNode::default()))
+            }
+            SimdInstruction::I8x16LtS => {
+                Ok(InstructionNode::I8x16LtS(I8x16LtSInstructionNode::default()))
+            }
+            SimdInstruction::I8x16LtU => {
+                Ok(InstructionNode::I8x16LtU(I8x16LtUInstructionNode::default()))
+            }
+            SimdInstruction::I8x16GtS => {
+                Ok(InstructionNode::I8x16GtS(I8x16GtSInstructionNode::default()))
+            }
+            SimdInstruction::I8x16GtU => {
+                Ok(InstructionNode::I8x16GtU(I8x16GtUInstructionNode::default()))
+            }
+            SimdInstruction::I8x16LeS => {
+                Ok(InstructionNode::I8x16LeS(I8x16LeSInstructionNode::default()))
+            }
+            SimdInstruction::I8x16LeU => {
+                Ok(InstructionNode::I8x16LeU(I8x16LeUInstructionNode::default()))
+            }
+            SimdInstruction::I8x16GeS => {
+                Ok(InstructionNode::I8x16GeS(I8x16GeSInstructionNode::default()))
+            }
+            SimdInstruction::I8x16GeU => {
+                Ok(InstructionNode::I8x16GeU(I8x16GeUInstructionNode::default()))
+            }
+            SimdInstruction::I16x8Eq => {
+                Ok(InstructionNode::I16x8Eq(I16x8EqInstructionNode::default()))
+            }
+            SimdInstruction::I16x8Ne => {
+                Ok(InstructionNode::I16x8Ne(I16x8NeInstructionNode::default()))
+            }
+            SimdInstruction::I16x8LtS => {
+                Ok(InstructionNode::I16x8LtS(I16x8LtSInstructionNode::default()))
+            }
+            SimdInstruction::I16x8LtU => {
+                Ok(InstructionNode::I16x8LtU(I16x8LtUInstructionNode::default()))
+            }
+            SimdInstruction::I16x8GtS => {
+                Ok(InstructionNode::I16x8GtS(I16x8GtSInstructionNode::default()))
+            }
+            SimdInstruction::I16x8GtU => {
+                Ok(InstructionNode::I16x8GtU(I16x8GtUInstructionNode::default()))
+            }
+            SimdInstruction::I16x8LeS => {
+                Ok(InstructionNode::I16x8LeS(I16x8LeSInstructionNode::default()))
+            }
+            SimdInstruction::I16x8LeU => {
+                Ok(InstructionNode::I16x8LeU(I16x8LeUInstructionNode::default()))
+            }
+            SimdInstruction::I16x8GeS => {
+                Ok(InstructionNode::I16x8GeS(I16x8GeSInstructionNode::default()))
+            }
+            SimdInstruction::I16x8GeU => {
+                Ok(InstructionNode::I16x8GeU(I16x8GeUInstructionNode::default()))
+            }
+            SimdInstruction::I32x4Eq => {
+                Ok(InstructionNode::I32x4Eq(I32x4EqInstructionNode::default()))
+            }
+            SimdInstruction::I32x4Ne => {
+                Ok(InstructionNode::I32x4Ne(I32x4NeInstructionNode::default()))
+            }
+            SimdInstruction::I32x4LtS => {
+                Ok(InstructionNode::I32x4LtS(I32x4LtSInstructionNode::default()))
+            }
+            SimdInstruction::I32x4LtU => {
+                Ok(InstructionNode::I32x4LtU(I32x4LtUInstructionNode::default()))
+            }
+            SimdInstruction::I32x4GtS => {
+                Ok(InstructionNode::I32x4GtS(I32x4GtSInstructionNode::default()))
+            }
+            SimdInstruction::I32x4GtU => {
+                Ok(InstructionNode::I32x4GtU(I32x4GtUInstructionNode::default()))
+            }
+            SimdInstruction::I32x4LeS => {
+                Ok(InstructionNode::I32x4LeS(I32x4LeSInstructionNode::default()))
+            }
+            SimdInstruction::I32x4LeU => {
+                Ok(InstructionNode::I32x4LeU(I32x4LeUInstructionNode::default()))
+            }
+            SimdInstruction::I32x4GeS => {
+                Ok(InstructionNode::I32x4GeS(I32x4GeSInstructionNode::default()))
+            }
+            SimdInstruction::I32x4GeU => {
+                Ok(InstructionNode::I32x4GeU(I32x4GeUInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Eq => {
+                Ok(InstructionNode::F32x4Eq(F32x4EqInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Ne => {
+                Ok(InstructionNode::F32x4Ne(F32x4NeInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Lt => {
+                Ok(InstructionNode::F32x4Lt(F32x4LtInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Gt => {
+                Ok(InstructionNode::F32x4Gt(F32x4GtInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Le => {
+                Ok(InstructionNode::F32x4Le(F32x4LeInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Ge => {
+                Ok(InstructionNode::F32x4Ge(F32x4GeInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Eq => {
+                Ok(InstructionNode::F64x2Eq(F64x2EqInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Ne => {
+                Ok(InstructionNode::F64x2Ne(F64x2NeInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Lt => {
+                Ok(InstructionNode::F64x2Lt(F64x2LtInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Gt => {
+                Ok(InstructionNode::F64x2Gt(F64x2GtInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Le => {
+                Ok(InstructionNode::F64x2Le(F64x2LeInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Ge => {
+                Ok(InstructionNode::F64x2Ge(F64x2GeInstructionNode::default()))
+            }
+            SimdInstruction::V128Not => {
+                Ok(InstructionNode::V128Not(V128NotInstructionNode::default()))
+            }
+            SimdInstruction::V128And => {
+                Ok(InstructionNode::V128And(V128AndInstructionNode::default()))
+            }
+            SimdInstruction::V128Andnot => Ok(InstructionNode::V128Andnot(
+                V128AndnotInstructionNode::default(),
+            )),
+            SimdInstruction::V128Or => {
+                Ok(InstructionNode::V128Or(V128OrInstructionNode::default()))
+            }
+            SimdInstruction::V128Xor => {
+                Ok(InstructionNode::V128Xor(V128XorInstructionNode::default()))
+            }
+            SimdInstruction::V128Bitselect => Ok(InstructionNode::V128Bitselect(
+                V128BitselectInstructionNode::default(),
+            )),
+            SimdInstruction::V128AnyTrue => Ok(InstructionNode::V128AnyTrue(
+                V128AnyTrueInstructionNode::default(),
+            )),
+            SimdInstruction::V128Load8Lane => {
+                let memarg = self.memarg(reader)?;
+                let lane = Parser::lane_index(reader, 16)?;
+                Ok(InstructionNode::V128Load8Lane(
+                    SimdMemoryLaneInstructionNode::new(
+                        SimdInstruction::V128Load8Lane as u32,
+                        memarg,
+                        lane,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load16Lane => {
+                let memarg = self.memarg(reader)?;
+                let lane = Parser::lane_index(reader, 8)?;
+                Ok(InstructionNode::V128Load16Lane(
+                    SimdMemoryLaneInstructionNode::new(
+                        SimdInstruction::V128Load16Lane as u32,
+                        memarg,
+                        lane,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load32Lane => {
+                let memarg = self.memarg(reader)?;
+                let lane = Parser::lane_index(reader, 4)?;
+                Ok(InstructionNode::V128Load32Lane(
+                    SimdMemoryLaneInstructionNode::new(
+                        SimdInstruction::V128Load32Lane as u32,
+                        memarg,
+                        lane,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load64Lane => {
+                let memarg = self.memarg(reader)?;
+                let lane = Parser::lane_index(reader, 2)?;
+                Ok(InstructionNode::V128Load64Lane(
+                    SimdMemoryLaneInstructionNode::new(
+                        SimdInstruction::V128Load64Lane as u32,
+                        memarg,
+                        lane,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Store8Lane => {
+                let memarg = self.memarg(reader)?;
+                let lane = Parser::lane_index(reader, 16)?;
+                Ok(InstructionNode::V128Store8Lane(
+                    SimdMemoryLaneInstructionNode::new(
+                        SimdInstruction::V128Store8Lane as u32,
+                        memarg,
+                        lane,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Store16Lane => {
+                let memarg = self.memarg(reader)?;
+                let lane = Parser::lane_index(reader, 8)?;
+                Ok(InstructionNode::V128Store16Lane(
+                    SimdMemoryLaneInstructionNode::new(
+                        SimdInstruction::V128Store16Lane as u32,
+                        memarg,
+                        lane,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Store32Lane => {
+                let memarg = self.memarg(reader)?;
+                let lane = Parser::lane_index(reader, 4)?;
+                Ok(InstructionNode::V128Store32Lane(
+                    SimdMemoryLaneInstructionNode::new(
+                        SimdInstruction::V128Store32Lane as u32,
+                        memarg,
+                        lane,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Store64Lane => {
+                let memarg = self.memarg(reader)?;
+                let lane = Parser::lane_index(reader, 2)?;
+                Ok(InstructionNode::V128Store64Lane(
+                    SimdMemoryLaneInstructionNode::new(
+                        SimdInstruction::V128Store64Lane as u32,
+                        memarg,
+                        lane,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load32Zero => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load32Zero(
+                    SimdMemoryAccessInstructionNode::new(
+                        SimdInstruction::V128Load32Zero as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            SimdInstruction::V128Load64Zero => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::V128Load64Zero(
+                    SimdMemoryAccessInstructionNode::new(
+                        SimdInstruction::V128Load64Zero as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            SimdInstruction::F32x4DemoteF64x2Zero => Ok(InstructionNode::F32x4DemoteF64x2Zero(
+                F32x4DemoteF64x2ZeroInstructionNode::default(),
+            )),
+            SimdInstruction::F64x2PromoteLowF32x4 => Ok(InstructionNode::F64x2PromoteLowF32x4(
+                F64x2PromoteLowF32x4InstructionNode::default(),
+            )),
+            SimdInstruction::I8x16Abs => {
+                Ok(InstructionNode::I8x16Abs(I8x16AbsInstructionNode::default()))
+            }
+            SimdInstruction::I8x16Neg => {
+                Ok(InstructionNode::I8x16Neg(I8x16NegInstructionNode::default()))
+            }
+            SimdInstruction::I8x16Popcnt => Ok(InstructionNode::I8x16Popcnt(
+                I8x16PopcntInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16AllTrue => Ok(InstructionNode::I8x16AllTrue(
+                I8x16AllTrueInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16Bitmask => Ok(InstructionNode::I8x16Bitmask(
+                I8x16BitmaskInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16NarrowI16x8S => Ok(InstructionNode::I8x16NarrowI16x8S(
+                I8x16NarrowI16x8SInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16NarrowI16x8U => Ok(InstructionNode::I8x16NarrowI16x8U(
+                I8x16NarrowI16x8UInstructionNode::default(),
+            )),
+            SimdInstruction::F32x4Ceil => Ok(InstructionNode::F32x4Ceil(
+                F32x4CeilInstructionNode::default(),
+            )),
+            SimdInstruction::F32x4Floor => Ok(InstructionNode::F32x4Floor(
+                F32x4FloorInstructionNode::default(),
+            )),
+            SimdInstruction::F32x4Trunc => Ok(InstructionNode::F32x4Trunc(
+                F32x4TruncInstructionNode::default(),
+            )),
+            SimdInstruction::F32x4Nearest => Ok(InstructionNode::F32x4Nearest(
+                F32x4NearestInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16Shl => {
+                Ok(InstructionNode::I8x16Shl(I8x16ShlInstructionNode::default()))
+            }
+            SimdInstruction::I8x16ShrS => Ok(InstructionNode::I8x16ShrS(
+                I8x16ShrSInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16ShrU => Ok(InstructionNode::I8x16ShrU(
+                I8x16ShrUInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16Add => {
+                Ok(InstructionNode::I8x16Add(I8x16AddInstructionNode::default()))
+            }
+            SimdInstruction::I8x16AddSatS => Ok(InstructionNode::I8x16AddSatS(
+                I8x16AddSatSInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16AddSatU => Ok(InstructionNode::I8x16AddSatU(
+                I8x16AddSatUInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16Sub => {
+                Ok(InstructionNode::I8x16Sub(I8x16SubInstructionNode::default()))
+            }
+            SimdInstruction::I8x16SubSatS => Ok(InstructionNode::I8x16SubSatS(
+                I8x16SubSatSInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16SubSatU => Ok(InstructionNode::I8x16SubSatU(
+                I8x16SubSatUInstructionNode::default(),
+            )),
+            SimdInstruction::F64x2Ceil => Ok(InstructionNode::F64x2Ceil(
+                F64x2CeilInstructionNode::default(),
+            )),
+            SimdInstruction::F64x2Floor => Ok(InstructionNode::F64x2Floor(
+                F64x2FloorInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16MinS => Ok(InstructionNode::I8x16MinS(
+                I8x16MinSInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16MinU => Ok(InstructionNode::I8x16MinU(
+                I8x16MinUInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16MaxS => Ok(InstructionNode::I8x16MaxS(
+                I8x16MaxSInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16MaxU => Ok(InstructionNode::I8x16MaxU(
+                I8x16MaxUInstructionNode::default(),
+            )),
+            SimdInstruction::F64x2Trunc => Ok(InstructionNode::F64x2Trunc(
+                F64x2TruncInstructionNode::default(),
+            )),
+            SimdInstruction::I8x16AvgrU => Ok(InstructionNode::I8x16AvgrU(
+                I8x16AvgrUInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8ExtaddPairwiseI8x16S => {
+                Ok(InstructionNode::I16x8ExtaddPairwiseI8x16S(
+                    I16x8ExtaddPairwiseI8x16SInstructionNode::default(),
+                ))
+            }
+            SimdInstruction::I16x8ExtaddPairwiseI8x16U => {
+                Ok(InstructionNode::I16x8ExtaddPairwiseI8x16U(
+                    I16x8ExtaddPairwiseI8x16UInstructionNode::default(),
+                ))
+            }
+            SimdInstruction::I32x4ExtaddPairwiseI16x8S => {
+                Ok(InstructionNode::I32x4ExtaddPairwiseI16x8S(
+                    I32x4ExtaddPairwiseI16x8SInstructionNode::default(),
+                ))
+            }
+            SimdInstruction::I32x4ExtaddPairwiseI16x8U => {
+                Ok(InstructionNode::I32x4ExtaddPairwiseI16x8U(
+                    I32x4ExtaddPairwiseI16x8UInstructionNode::default(),
+                ))
+            }
+            SimdInstruction::I16x8Abs => {
+                Ok(InstructionNode::I16x8Abs(I16x8AbsInstructionNode::default()))
+            }
+            SimdInstruction::I16x8Neg => {
+                Ok(InstructionNode::I16x8Neg(I16x8NegInstructionNode::default()))
+            }
+            SimdInstruction::I16x8Q15mulrSatS => Ok(InstructionNode::I16x8Q15mulrSatS(
+                I16x8Q15mulrSatSInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8AllTrue => Ok(InstructionNode::I16x8AllTrue(
+                I16x8AllTrueInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8Bitmask => Ok(InstructionNode::I16x8Bitmask(
+                I16x8BitmaskInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8NarrowI32x4S => Ok(InstructionNode::I16x8NarrowI32x4S(
+                I16x8NarrowI32x4SInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8NarrowI32x4U => Ok(InstructionNode::I16x8NarrowI32x4U(
+                I16x8NarrowI32x4UInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8ExtendLowI8x16S => Ok(InstructionNode::I16x8ExtendLowI8x16S(
+                I16x8ExtendLowI8x16SInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8ExtendHighI8x16S => Ok(InstructionNode::I16x8ExtendHighI8x16S(
+                I16x8ExtendHighI8x16SInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8ExtendLowI8x16U => Ok(InstructionNode::I16x8ExtendLowI8x16U(
+                I16x8ExtendLowI8x16UInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8ExtendHighI8x16U => Ok(InstructionNode::I16x8ExtendHighI8x16U(
+                I16x8ExtendHighI8x16UInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8Shl => {
+                Ok(InstructionNode::I16x8Shl(I16x8ShlInstructionNode::default()))
+            }
+            SimdInstruction::I16x8ShrS => Ok(InstructionNode::I16x8ShrS(
+                I16x8ShrSInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8ShrU => Ok(InstructionNode::I16x8ShrU(
+                I16x8ShrUInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8Add => {
+                Ok(InstructionNode::I16x8Add(I16x8AddInstructionNode::default()))
+            }
+            SimdInstruction::I16x8AddSatS => Ok(InstructionNode::I16x8AddSatS(
+                I16x8AddSatSInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8AddSatU => Ok(InstructionNode::I16x8AddSatU(
+                I16x8AddSatUInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8Sub => {
+                Ok(InstructionNode::I16x8Sub(I16x8SubInstructionNode::default()))
+            }
+            SimdInstruction::I16x8SubSatS => Ok(InstructionNode::I16x8SubSatS(
+                I16x8SubSatSInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8SubSatU => Ok(InstructionNode::I16x8SubSatU(
+                I16x8SubSatUInstructionNode::default(),
+            )),
+            SimdInstruction::F64x2Nearest => Ok(InstructionNode::F64x2Nearest(
+                F64x2NearestInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8Mul => {
+                Ok(InstructionNode::I16x8Mul(I16x8MulInstructionNode::default()))
+            }
+            SimdInstruction::I16x8MinS => Ok(InstructionNode::I16x8MinS(
+                I16x8MinSInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8MinU => Ok(InstructionNode::I16x8MinU(
+                I16x8MinUInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8MaxS => Ok(InstructionNode::I16x8MaxS(
+                I16x8MaxSInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8MaxU => Ok(InstructionNode::I16x8MaxU(
+                I16x8MaxUInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8AvgrU => Ok(InstructionNode::I16x8AvgrU(
+                I16x8AvgrUInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8ExtmulLowI8x16S => Ok(InstructionNode::I16x8ExtmulLowI8x16S(
+                I16x8ExtmulLowI8x16SInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8ExtmulHighI8x16S => Ok(InstructionNode::I16x8ExtmulHighI8x16S(
+                I16x8ExtmulHighI8x16SInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8ExtmulLowI8x16U => Ok(InstructionNode::I16x8ExtmulLowI8x16U(
+                I16x8ExtmulLowI8x16UInstructionNode::default(),
+            )),
+            SimdInstruction::I16x8ExtmulHighI8x16U => Ok(InstructionNode::I16x8ExtmulHighI8x16U(
+                I16x8ExtmulHighI8x16UInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4Abs => {
+                Ok(InstructionNode::I32x4Abs(I32x4AbsInstructionNode::default()))
+            }
+            SimdInstruction::I32x4Neg => {
+                Ok(InstructionNode::I32x4Neg(I32x4NegInstructionNode::default()))
+            }
+            SimdInstruction::I32x4AllTrue => Ok(InstructionNode::I32x4AllTrue(
+                I32x4AllTrueInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4Bitmask => Ok(InstructionNode::I32x4Bitmask(
+                I32x4BitmaskInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4ExtendLowI16x8S => Ok(InstructionNode::I32x4ExtendLowI16x8S(
+                I32x4ExtendLowI16x8SInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4ExtendHighI16x8S => Ok(InstructionNode::I32x4ExtendHighI16x8S(
+                I32x4ExtendHighI16x8SInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4ExtendLowI16x8U => Ok(InstructionNode::I32x4ExtendLowI16x8U(
+                I32x4ExtendLowI16x8UInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4ExtendHighI16x8U => Ok(InstructionNode::I32x4ExtendHighI16x8U(
+                I32x4ExtendHighI16x8UInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4Shl => {
+                Ok(InstructionNode::I32x4Shl(I32x4ShlInstructionNode::default()))
+            }
+            SimdInstruction::I32x4ShrS => Ok(InstructionNode::I32x4ShrS(
+                I32x4ShrSInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4ShrU => Ok(InstructionNode::I32x4ShrU(
+                I32x4ShrUInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4Add => {
+                Ok(InstructionNode::I32x4Add(I32x4AddInstructionNode::default()))
+            }
+            SimdInstruction::I32x4Sub => {
+                Ok(InstructionNode::I32x4Sub(I32x4SubInstructionNode::default()))
+            }
+            SimdInstruction::I32x4Mul => {
+                Ok(InstructionNode::I32x4Mul(I32x4MulInstructionNode::default()))
+            }
+            SimdInstruction::I32x4MinS => Ok(InstructionNode::I32x4MinS(
+                I32x4MinSInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4MinU => Ok(InstructionNode::I32x4MinU(
+                I32x4MinUInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4MaxS => Ok(InstructionNode::I32x4MaxS(
+                I32x4MaxSInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4MaxU => Ok(InstructionNode::I32x4MaxU(
+                I32x4MaxUInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4DotI16x8S => Ok(InstructionNode::I32x4DotI16x8S(
+                I32x4DotI16x8SInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4ExtmulLowI16x8S => Ok(InstructionNode::I32x4ExtmulLowI16x8S(
+                I32x4ExtmulLowI16x8SInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4ExtmulHighI16x8S => Ok(InstructionNode::I32x4ExtmulHighI16x8S(
+                I32x4ExtmulHighI16x8SInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4ExtmulLowI16x8U => Ok(InstructionNode::I32x4ExtmulLowI16x8U(
+                I32x4ExtmulLowI16x8UInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4ExtmulHighI16x8U => Ok(InstructionNode::I32x4ExtmulHighI16x8U(
+                I32x4ExtmulHighI16x8UInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2Abs => {
+                Ok(InstructionNode::I64x2Abs(I64x2AbsInstructionNode::default()))
+            }
+            SimdInstruction::I64x2Neg => {
+                Ok(InstructionNode::I64x2Neg(I64x2NegInstructionNode::default()))
+            }
+            SimdInstruction::I64x2AllTrue => Ok(InstructionNode::I64x2AllTrue(
+                I64x2AllTrueInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2Bitmask => Ok(InstructionNode::I64x2Bitmask(
+                I64x2BitmaskInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2ExtendLowI32x4S => Ok(InstructionNode::I64x2ExtendLowI32x4S(
+                I64x2ExtendLowI32x4SInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2ExtendHighI32x4S => Ok(InstructionNode::I64x2ExtendHighI32x4S(
+                I64x2ExtendHighI32x4SInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2ExtendLowI32x4U => Ok(InstructionNode::I64x2ExtendLowI32x4U(
+                I64x2ExtendLowI32x4UInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2ExtendHighI32x4U => Ok(InstructionNode::I64x2ExtendHighI32x4U(
+                I64x2ExtendHighI32x4UInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2Shl => {
+                Ok(InstructionNode::I64x2Shl(I64x2ShlInstructionNode::default()))
+            }
+            SimdInstruction::I64x2ShrS => Ok(InstructionNode::I64x2ShrS(
+                I64x2ShrSInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2ShrU => Ok(InstructionNode::I64x2ShrU(
+                I64x2ShrUInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2Add => {
+                Ok(InstructionNode::I64x2Add(I64x2AddInstructionNode::default()))
+            }
+            SimdInstruction::I64x2Sub => {
+                Ok(InstructionNode::I64x2Sub(I64x2SubInstructionNode::default()))
+            }
+            SimdInstruction::I64x2Mul => {
+                Ok(InstructionNode::I64x2Mul(I64x2MulInstructionNode::default()))
+            }
+            SimdInstruction::I64x2Eq => {
+                Ok(InstructionNode::I64x2Eq(I64x2EqInstructionNode::default()))
+            }
+            SimdInstruction::I64x2Ne => {
+                Ok(InstructionNode::I64x2Ne(I64x2NeInstructionNode::default()))
+            }
+            SimdInstruction::I64x2LtS => {
+                Ok(InstructionNode::I64x2LtS(I64x2LtSInstructionNode::default()))
+            }
+            SimdInstruction::I64x2GtS => {
+                Ok(InstructionNode::I64x2GtS(I64x2GtSInstructionNode::default()))
+            }
+            SimdInstruction::I64x2LeS => {
+                Ok(InstructionNode::I64x2LeS(I64x2LeSInstructionNode::default()))
+            }
+            SimdInstruction::I64x2GeS => {
+                Ok(InstructionNode::I64x2GeS(I64x2GeSInstructionNode::default()))
+            }
+            SimdInstruction::I64x2ExtmulLowI32x4S => Ok(InstructionNode::I64x2ExtmulLowI32x4S(
+                I64x2ExtmulLowI32x4SInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2ExtmulHighI32x4S => Ok(InstructionNode::I64x2ExtmulHighI32x4S(
+                I64x2ExtmulHighI32x4SInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2ExtmulLowI32x4U => Ok(InstructionNode::I64x2ExtmulLowI32x4U(
+                I64x2ExtmulLowI32x4UInstructionNode::default(),
+            )),
+            SimdInstruction::I64x2ExtmulHighI32x4U => Ok(InstructionNode::I64x2ExtmulHighI32x4U(
+                I64x2ExtmulHighI32x4UInstructionNode::default(),
+            )),
+            SimdInstruction::F32x4Abs => {
+                Ok(InstructionNode::F32x4Abs(F32x4AbsInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Neg => {
+                Ok(InstructionNode::F32x4Neg(F32x4NegInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Sqrt => Ok(InstructionNode::F32x4Sqrt(
+                F32x4SqrtInstructionNode::default(),
+            )),
+            SimdInstruction::F32x4Add => {
+                Ok(InstructionNode::F32x4Add(F32x4AddInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Sub => {
+                Ok(InstructionNode::F32x4Sub(F32x4SubInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Mul => {
+                Ok(InstructionNode::F32x4Mul(F32x4MulInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Div => {
+                Ok(InstructionNode::F32x4Div(F32x4DivInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Min => {
+                Ok(InstructionNode::F32x4Min(F32x4MinInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Max => {
+                Ok(InstructionNode::F32x4Max(F32x4MaxInstructionNode::default()))
+            }
+            SimdInstruction::F32x4Pmin => Ok(InstructionNode::F32x4Pmin(
+                F32x4PminInstructionNode::default(),
+            )),
+            SimdInstruction::F32x4Pmax => Ok(InstructionNode::F32x4Pmax(
+                F32x4PmaxInstructionNode::default(),
+            )),
+            SimdInstruction::F64x2Abs => {
+                Ok(InstructionNode::F64x2Abs(F64x2AbsInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Neg => {
+                Ok(InstructionNode::F64x2Neg(F64x2NegInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Sqrt => Ok(InstructionNode::F64x2Sqrt(
+                F64x2SqrtInstructionNode::default(),
+            )),
+            SimdInstruction::F64x2Add => {
+                Ok(InstructionNode::F64x2Add(F64x2AddInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Sub => {
+                Ok(InstructionNode::F64x2Sub(F64x2SubInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Mul => {
+                Ok(InstructionNode::F64x2Mul(F64x2MulInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Div => {
+                Ok(InstructionNode::F64x2Div(F64x2DivInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Min => {
+                Ok(InstructionNode::F64x2Min(F64x2MinInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Max => {
+                Ok(InstructionNode::F64x2Max(F64x2MaxInstructionNode::default()))
+            }
+            SimdInstruction::F64x2Pmin => Ok(InstructionNode::F64x2Pmin(
+                F64x2PminInstructionNode::default(),
+            )),
+            SimdInstruction::F64x2Pmax => Ok(InstructionNode::F64x2Pmax(
+                F64x2PmaxInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4TruncSatF32x4S => Ok(InstructionNode::I32x4TruncSatF32x4S(
+                I32x4TruncSatF32x4SInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4TruncSatF32x4U => Ok(InstructionNode::I32x4TruncSatF32x4U(
+                I32x4TruncSatF32x4UInstructionNode::default(),
+            )),
+            SimdInstruction::F32x4ConvertI32x4S => Ok(InstructionNode::F32x4ConvertI32x4S(
+                F32x4ConvertI32x4SInstructionNode::default(),
+            )),
+            SimdInstruction::F32x4ConvertI32x4U => Ok(InstructionNode::F32x4ConvertI32x4U(
+                F32x4ConvertI32x4UInstructionNode::default(),
+            )),
+            SimdInstruction::I32x4TruncSatF64x2SZero => {
+                Ok(InstructionNode::I32x4TruncSatF64x2SZero(
+                    I32x4TruncSatF64x2SZeroInstructionNode::default(),
+                ))
+            }
+            SimdInstruction::I32x4TruncSatF64x2UZero => {
+                Ok(InstructionNode::I32x4TruncSatF64x2UZero(
+                    I32x4TruncSatF64x2UZeroInstructionNode::default(),
+                ))
+            }
+            SimdInstruction::F64x2ConvertLowI32x4S => Ok(InstructionNode::F64x2ConvertLowI32x4S(
+                F64x2ConvertLowI32x4SInstructionNode::default(),
+            )),
+            SimdInstruction::F64x2ConvertLowI32x4U => Ok(InstructionNode::F64x2ConvertLowI32x4U(
+                F64x2ConvertLowI32x4UInstructionNode::default(),
+            )),
+        }
+    }
+
+    /// Decodes an instruction under the 0xFE prefix, whose sub-opcode is a u32.
+    fn atomic_instruction(
+        &self,
+        reader: &mut Reader,
+        offset: usize,
+    ) -> Result<InstructionNode, ParseError> {
+        let opcode = reader.read_u32()?;
+        let instruction = AtomicInstruction::try_from(opcode).map_err(|opcode| {
+            reader.error_at(
+                ParseErrorKind::UnknownPrefixedOpcode {
+                    prefix: Instruction::AtomicPrefix as u8,
+                    opcode,
+                },
+                offset,
+            )
+        })?;
+
+        match instruction {
+            AtomicInstruction::MemoryAtomicNotify => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::MemoryAtomicNotify(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::MemoryAtomicNotify as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::MemoryAtomicWait32 => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::MemoryAtomicWait32(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::MemoryAtomicWait32 as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::MemoryAtomicWait64 => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::MemoryAtomicWait64(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::MemoryAtomicWait64 as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::AtomicFence => {
+                reader.read_u8()?;
+                Ok(InstructionNode::AtomicFence(
+                    AtomicFenceInstructionNode::default(),
+                ))
+            }
+            AtomicInstruction::I32AtomicLoad => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicLoad(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicLoad as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicLoad => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicLoad(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicLoad as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicLoad8U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicLoad8U(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicLoad8U as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicLoad16U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicLoad16U(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicLoad16U as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicLoad8U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicLoad8U(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicLoad8U as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicLoad16U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicLoad16U(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicLoad16U as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicLoad32U => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicLoad32U(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicLoad32U as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicStore => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicStore(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicStore as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicStore => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicStore(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicStore as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicStore8 => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicStore8(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicStore8 as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicStore16 => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicStore16(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicStore16 as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicStore8 => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicStore8(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicStore8 as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicStore16 => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicStore16(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicStore16 as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicStore32 => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicStore32(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicStore32 as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmwAdd => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmwAdd(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmwAdd as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmwAdd => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmwAdd(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmwAdd as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw8AddU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw8AddU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw8AddU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw16AddU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw16AddU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw16AddU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw8AddU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw8AddU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw8AddU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw16AddU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw16AddU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw16AddU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw32AddU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw32AddU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw32AddU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmwSub => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmwSub(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmwSub as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmwSub => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmwSub(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmwSub as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw8SubU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw8SubU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw8SubU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw16SubU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw16SubU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw16SubU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw8SubU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw8SubU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw8SubU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw16SubU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw16SubU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw16SubU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw32SubU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw32SubU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw32SubU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmwAnd => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmwAnd(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmwAnd as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmwAnd => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmwAnd(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmwAnd as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw8AndU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw8AndU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw8AndU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw16AndU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw16AndU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw16AndU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw8AndU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw8AndU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw8AndU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw16AndU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw16AndU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw16AndU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw32AndU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw32AndU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw32AndU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmwOr => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmwOr(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmwOr as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmwOr => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmwOr(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmwOr as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw8OrU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw8OrU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw8OrU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw16OrU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw16OrU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw16OrU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw8OrU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw8OrU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw8OrU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw16OrU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw16OrU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw16OrU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw32OrU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw32OrU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw32OrU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmwXor => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmwXor(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmwXor as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmwXor => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmwXor(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmwXor as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw8XorU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw8XorU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw8XorU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw16XorU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw16XorU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw16XorU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw8XorU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw8XorU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw8XorU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw16XorU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw16XorU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw16XorU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw32XorU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw32XorU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw32XorU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmwXchg => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmwXchg(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmwXchg as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmwXchg => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmwXchg(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmwXchg as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw8XchgU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw8XchgU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw8XchgU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw16XchgU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw16XchgU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw16XchgU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw8XchgU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw8XchgU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw8XchgU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw16XchgU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw16XchgU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw16XchgU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw32XchgU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw32XchgU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw32XchgU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmwCmpxchg => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmwCmpxchg(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmwCmpxchg as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmwCmpxchg => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmwCmpxchg(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmwCmpxchg as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw8CmpxchgU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw8CmpxchgU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw8CmpxchgU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I32AtomicRmw16CmpxchgU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I32AtomicRmw16CmpxchgU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I32AtomicRmw16CmpxchgU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw8CmpxchgU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw8CmpxchgU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw8CmpxchgU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw16CmpxchgU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw16CmpxchgU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw16CmpxchgU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+            AtomicInstruction::I64AtomicRmw32CmpxchgU => {
+                let memarg = self.memarg(reader)?;
+                Ok(InstructionNode::I64AtomicRmw32CmpxchgU(
+                    AtomicMemoryAccessInstructionNode::new(
+                        AtomicInstruction::I64AtomicRmw32CmpxchgU as u32,
+                        memarg,
+                    ),
+                ))
+            }
+        }
+    }
+
+    /// memarg = align offset
+    fn memarg(&self, reader: &mut Reader) -> Result<MemArgNode, ParseError> {
+        let align = reader.read_u32()?;
+        let offset = reader.read_u32()?;
+        Ok(MemArgNode { align, offset })
+    }
+
+    /// table type = reftype limits
+    fn table_type(&self, reader: &mut Reader) -> Result<TableType, ParseError> {
+        let element_type = self.reference_type(reader)?;
+        let offset = reader.offset();
+        let limits = self.limits(reader)?;
+        // as with memories, the minimum is allocated at instantiation
+        if limits.min > MAX_TABLE_SIZE {
+            return Err(reader.error_at(ParseErrorKind::TableTooLarge { size: limits.min }, offset));
+        }
+        Ok(TableType {
+            element_type,
+            limits,
+        })
+    }
+
+    /// memory type = limits, where the flag 0x03 marks a shared memory, which
+    /// must have a maximum
+    fn memory_type(&self, reader: &mut Reader) -> Result<MemoryType, ParseError> {
+        let offset = reader.offset();
+        let memory_type = if reader.peek_u8()? == 0x03 {
+            reader.read_u8()?;
+            let min = reader.read_u32()?;
+            let max = reader.read_u32()?;
+            MemoryType {
+                limits: Parser::ordered_limits(reader, min, Some(max), offset)?,
+                shared: true,
+            }
+        } else {
+            MemoryType {
+                limits: self.limits(reader)?,
+                shared: false,
+            }
+        };
+        // instantiation allocates the minimum at once, so an oversized memory
+        // has to be turned away before then
+        let Limits { min, max } = memory_type.limits;
+        let pages = max.unwrap_or(min);
+        if pages > MAX_PAGES {
+            return Err(reader.error_at(ParseErrorKind::MemoryTooLarge { pages }, offset));
+        }
+        Ok(memory_type)
+    }
+
+    /// global type = valtype mut
+    fn global_type(&self, reader: &mut Reader) -> Result<GlobalType, ParseError> {
+        let value_type = self.value_type(reader)?;
+        let offset = reader.offset();
+        let mutable = match reader.read_u8()? {
+            0x00 => false,
+            0x01 => true,
+            x => {
+                return Err(Parser::invalid(
+                    reader,
+                    "global mutability",
+                    x as u32,
+                    offset,
+                ))
+            }
+        };
+        Ok(GlobalType {
+            value_type,
+            mutable,
+        })
+    }
+
+    /// limits = 0x00 min | 0x01 min max
+    fn limits(&self, reader: &mut Reader) -> Result<Limits, ParseError> {
+        let offset = reader.offset();
+        let flag = reader.read_u8()?;
+        let min = reader.read_u32()?;
+        let max = match flag {
+            0x00 => None,
+            0x01 => {
+                let max = reader.read_u32()?;
+                Some(max)
+            }
+            _ => return Err(Parser::invalid(reader, "limits flag", flag as u32, offset)),
+        };
+        Parser::ordered_limits(reader, min, max, offset)
+    }
+
+    /// Rejects limits whose maximum is below their minimum.
+    fn ordered_limits(
+        reader: &Reader,
+        min: u32,
+        max: Option<u32>,
+        offset: usize,
+    ) -> Result<Limits, ParseError> {
+        match max {
+            Some(max) if max < min => {
+                Err(reader.error_at(ParseErrorKind::LimitsMinExceedsMax { min, max }, offset))
+            }
+            _ => Ok(Limits { min, max }),
+        }
+    }
+
+    /// name = vec(byte)
+    fn name(&self, reader: &mut Reader) -> Result<String, ParseError> {
+        let size = reader.read_u32()?;
+        let offset = reader.offset();
+        let name_bytes = reader.read_bytes(size as usize)?;
+        String::from_utf8(name_bytes.to_vec())
+            .map_err(|_| reader.error_at(ParseErrorKind::MalformedUtf8, offset))
+    }
+
+    /// blocktype = 0x40 | valtype | s33, where a non-negative s33 is a type index
+    fn block_type(&self, reader: &mut Reader) -> Result<BlockType, ParseError> {
+        let offset = reader.offset();
+        let byte = reader.peek_u8()?;
+        if let Ok(block_type) = BlockType::try_from(byte) {
+            reader.read_u8()?;
+            return Ok(block_type);
+        }
+        match reader.read_s33()? {
+            index if index >= 0 => Ok(BlockType::TypeIndex(index as u32)),
+            _ => Err(reader.error_at(ParseErrorKind::InvalidValueType(byte), offset)),
+        }
+    }
+
+    /// Reads a lane immediate, which must pick one of `lanes` lanes.
+    fn lane_index(reader: &mut Reader, lanes: u8) -> Result<u8, ParseError> {
+        let offset = reader.offset();
+        let lane = reader.read_u8()?;
+        if lane >= lanes {
+            return Err(Parser::invalid(reader, "lane index", lane as u32, offset));
+        }
+        Ok(lane)
+    }
 
-    pub fn read_bytes(bytes: &mut Vec<u8>, size: usize) -> Result<Vec<u8>, Box<dyn Error>> {
-        let b = bytes[0..size].to_vec();
-        (*bytes).drain(0..size);
-        Ok(b)
+    fn invalid(reader: &Reader, construct: &'static str, value: u32, offset: usize) -> ParseError {
+        reader.error_at(ParseErrorKind::InvalidEncoding { construct, value }, offset)
     }
 }
 
 #[cfg(test)]
 mod leb128_tests {
     use super::*;
+    use crate::leb128::encode_i64_to_leb128;
 
     #[test]
     fn read_u32_case1() {
-        let mut bytes = vec![229, 142, 38, 0, 0, 0, 0, 0];
-        let (value, size) = Parser::read_u32(&mut bytes).expect("Invalid u32");
+        let bytes = vec![229, 142, 38, 0, 0, 0, 0, 0];
+        let mut reader = Reader::new(&bytes);
+        let value = reader.read_u32().expect("Invalid u32");
         assert_eq!(value, 624485);
-        assert_eq!(size, 3);
+        assert_eq!(reader.offset(), 3);
     }
 
     #[test]
     fn read_u32_case2() {
-        let mut bytes = vec![0x80, 0x80, 0xC0, 0x00, 0x0B];
-        let (value, size) = Parser::read_u32(&mut bytes).expect("Invalid u32");
+        let bytes = vec![0x80, 0x80, 0xC0, 0x00, 0x0B];
+        let mut reader = Reader::new(&bytes);
+        let value = reader.read_u32().expect("Invalid u32");
 
         assert_eq!(value, 1048576);
-        assert_eq!(size, 4);
+        assert_eq!(reader.offset(), 4);
     }
 
     #[test]
     fn test_read_i32() {
-        let mut bytes = vec![127, 0, 0, 0, 0, 0, 0, 0];
-        let (value, size) = Parser::read_i32(&mut bytes).expect("Invalid i32");
+        let bytes = vec![127, 0, 0, 0, 0, 0, 0, 0];
+        let mut reader = Reader::new(&bytes);
+        let value = reader.read_i32().expect("Invalid i32");
 
         assert_eq!(value, -1);
-        assert_eq!(size, 1);
+        assert_eq!(reader.offset(), 1);
+    }
+
+    #[test]
+    fn test_read_i64() {
+        // i64::MIN takes all ten bytes
+        let bytes = vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f];
+        let mut reader = Reader::new(&bytes);
+        let value = reader.read_i64().expect("Invalid i64");
+
+        assert_eq!(value, i64::MIN);
+        assert_eq!(reader.offset(), 10);
+
+        for value in [0, -1, 63, -64, 64, i64::MAX, 0xcbf29ce484222325u64 as i64] {
+            let bytes = encode_i64_to_leb128(value);
+            let mut reader = Reader::new(&bytes);
+            assert_eq!(reader.read_i64().expect("Invalid i64"), value);
+            assert!(reader.is_empty());
+        }
+
+        // a tenth byte carrying more than the sign bit overflows
+        let bytes = vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01];
+        let mut reader = Reader::new(&bytes);
+        assert_eq!(
+            reader.read_i64().map_err(|e| e.kind),
+            Err(ParseErrorKind::Leb128Overflow)
+        );
+    }
+
+    #[test]
+    fn test_read_s33() {
+        for value in [0, 3, 64, u32::MAX as i64, -(1 << 32)] {
+            let bytes = encode_i64_to_leb128(value);
+            let mut reader = Reader::new(&bytes);
+            assert_eq!(reader.read_s33().expect("Invalid s33"), value);
+            assert!(reader.is_empty());
+        }
+
+        // 2^32 needs a 34th bit, as does any sixth byte
+        for bytes in [
+            vec![0x80, 0x80, 0x80, 0x80, 0x10],
+            vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x00],
+        ] {
+            let mut reader = Reader::new(&bytes);
+            assert_eq!(
+                reader.read_s33().map_err(|e| e.kind),
+                Err(ParseErrorKind::Leb128Overflow)
+            );
+        }
+    }
+
+    #[test]
+    fn sub_reader_keeps_absolute_offset() {
+        let bytes = vec![0x01, 0x02, 0x03, 0x04, 0x05];
+        let mut reader = Reader::new(&bytes);
+        reader.read_u8().expect("Invalid u8");
+        let mut sub_reader = reader.sub_reader(3).expect("Invalid size");
+        sub_reader.read_u8().expect("Invalid u8");
+
+        assert_eq!(sub_reader.offset(), 2);
+        assert_eq!(sub_reader.remaining(), 2);
+        assert_eq!(reader.offset(), 4);
+    }
+}
+
+#[cfg(test)]
+mod parse_error_tests {
+    use super::*;
+    use crate::leb128::encode_u32_to_leb128;
+
+    const HEADER: [u8; 8] = [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+
+    fn parse(sections: &[u8]) -> Result<ModuleNode, ParseError> {
+        let mut bytes = HEADER.to_vec();
+        bytes.extend(sections);
+        Parser::new().unwrap().parse(&bytes)
+    }
+
+    fn parse_err(bytes: &[u8]) -> ParseError {
+        Parser::new()
+            .unwrap()
+            .parse(bytes)
+            .expect_err("Expected a parse error")
+    }
+
+    #[test]
+    fn bad_magic() {
+        let error = parse_err(&[0x00, 0x61, 0x73, 0x6E, 0x01, 0x00, 0x00, 0x00]);
+        assert_eq!(error, ParseError::new(ParseErrorKind::BadMagic, 0, None));
+
+        let error = parse_err(&[]);
+        assert_eq!(error.kind, ParseErrorKind::BadMagic);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let error = parse_err(&[0x00, 0x61, 0x73, 0x6D, 0x02, 0x00, 0x00, 0x00]);
+        assert_eq!(
+            error,
+            ParseError::new(ParseErrorKind::UnsupportedVersion, 4, None)
+        );
+    }
+
+    #[test]
+    fn unknown_section() {
+        let error = parse(&[0x20, 0x00]).expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(ParseErrorKind::UnknownSection(0x20), 8, None)
+        );
+    }
+
+    #[test]
+    fn unexpected_eof() {
+        // type section declaring 5 bytes but holding only 1
+        let error = parse(&[0x01, 0x05, 0x01]).expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(ParseErrorKind::UnexpectedEof, 10, None)
+        );
+    }
+
+    #[test]
+    fn invalid_value_type() {
+        let error =
+            parse(&[0x01, 0x05, 0x01, 0x60, 0x01, 0x55, 0x00]).expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::InvalidValueType(0x55),
+                13,
+                Some(SectionId::TypeSectionId)
+            )
+        );
+    }
+
+    #[test]
+    fn leb128_overflow() {
+        let error =
+            parse(&[0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F]).expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(ParseErrorKind::Leb128Overflow, 9, None)
+        );
+    }
+
+    #[test]
+    fn section_size_mismatch() {
+        // type section declaring one trailing byte more than its contents
+        let error =
+            parse(&[0x01, 0x05, 0x01, 0x60, 0x00, 0x00, 0x00]).expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::SectionSizeMismatch {
+                    expected: 5,
+                    actual: 4
+                },
+                14,
+                Some(SectionId::TypeSectionId)
+            )
+        );
+    }
+
+    #[test]
+    fn data_count_mismatch() {
+        // data count section declaring 2 segments ahead of a data section with 1
+        let error = parse(&[
+            0x0C, 0x01, 0x02, // data count section
+            0x0B, 0x03, 0x01, 0x01, 0x00, // data section
+        ])
+        .expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::DataCountMismatch {
+                    declared: 2,
+                    actual: 1
+                },
+                13,
+                Some(SectionId::DataSectionId)
+            )
+        );
+
+        // or with no data section at all
+        let error = parse(&[0x0C, 0x01, 0x01]).expect_err("Expected a parse error");
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::DataCountMismatch {
+                declared: 1,
+                actual: 0
+            }
+        );
+        assert!(parse(&[0x0C, 0x01, 0x00]).is_ok());
+    }
+
+    #[test]
+    fn function_code_mismatch() {
+        // a function section declaring 2 functions ahead of a code section with 1
+        let error = parse(&[
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+            0x03, 0x03, 0x02, 0x00, 0x00, // function section
+            0x0A, 0x04, 0x01, 0x02, 0x00, 0x0B, // code section
+        ])
+        .expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::FunctionCodeMismatch {
+                    functions: 2,
+                    bodies: 1
+                },
+                21,
+                Some(SectionId::CodeSectionId)
+            )
+        );
+
+        // or with no code section at all
+        let error = parse(&[
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+            0x03, 0x02, 0x01, 0x00, // function section
+        ])
+        .expect_err("Expected a parse error");
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::FunctionCodeMismatch {
+                functions: 1,
+                bodies: 0
+            }
+        );
+
+        // and a body without a function
+        let error =
+            parse(&[0x0A, 0x04, 0x01, 0x02, 0x00, 0x0B]).expect_err("Expected a parse error");
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::FunctionCodeMismatch {
+                functions: 0,
+                bodies: 1
+            }
+        );
+    }
+
+    #[test]
+    fn too_many_locals() {
+        // 0xFFFFFFFF i32 locals, which would otherwise be expanded at instantiation
+        let error = parse(&function_locals(&[
+            0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F, 0x7F,
+        ]))
+        .expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::TooManyLocals { limit: MAX_LOCALS },
+                23,
+                Some(SectionId::CodeSectionId)
+            )
+        );
+
+        // the limit applies to the sum of the entries, each within it
+        let mut entries = vec![0x01];
+        entries.extend(encode_u32_to_leb128(MAX_LOCALS));
+        entries.push(0x7F);
+        assert!(parse(&function_locals(&entries)).is_ok());
+        entries[0] = 0x02;
+        entries.extend([0x01, 0x7E]);
+        let error = parse(&function_locals(&entries)).expect_err("Expected a parse error");
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::TooManyLocals { limit: MAX_LOCALS }
+        );
+        assert_eq!(error.offset, 27);
+    }
+
+    /// A module whose one function declares the local entries `locals`,
+    /// given with their count, and has an empty body.
+    fn function_locals(locals: &[u8]) -> Vec<u8> {
+        let mut sections = vec![
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+            0x03, 0x02, 0x01, 0x00, // function section
+            0x0A, // code section
+        ];
+        sections.extend([locals.len() as u8 + 3, 0x01, locals.len() as u8 + 1]);
+        sections.extend(locals);
+        sections.push(0x0B);
+        sections
+    }
+
+    #[test]
+    fn memory_too_large() {
+        // (memory 0xFFFFFFFF), which would otherwise be allocated at instantiation
+        let error = parse(&[0x05, 0x07, 0x01, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F])
+            .expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::MemoryTooLarge { pages: u32::MAX },
+                11,
+                Some(SectionId::MemorySectionId)
+            )
+        );
+
+        // a maximum one page past the limit
+        let error = parse(&[0x05, 0x06, 0x01, 0x01, 0x00, 0x81, 0x80, 0x04])
+            .expect_err("Expected a parse error");
+        assert_eq!(error.kind, ParseErrorKind::MemoryTooLarge { pages: 65537 });
+        assert!(parse(&[0x05, 0x06, 0x01, 0x01, 0x00, 0x80, 0x80, 0x04]).is_ok());
+    }
+
+    #[test]
+    fn table_too_large() {
+        let error = parse(&[0x04, 0x08, 0x01, 0x70, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F])
+            .expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::TableTooLarge { size: u32::MAX },
+                12,
+                Some(SectionId::TableSectionId)
+            )
+        );
+
+        // a maximum past the limit only bounds table.grow
+        assert!(parse(&[0x04, 0x09, 0x01, 0x70, 0x01, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F]).is_ok());
+    }
+
+    #[test]
+    fn limits_min_exceeds_max() {
+        let error =
+            parse(&[0x05, 0x04, 0x01, 0x01, 0x02, 0x01]).expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::LimitsMinExceedsMax { min: 2, max: 1 },
+                11,
+                Some(SectionId::MemorySectionId)
+            )
+        );
+
+        // as for a shared memory
+        let error =
+            parse(&[0x05, 0x04, 0x01, 0x03, 0x02, 0x01]).expect_err("Expected a parse error");
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::LimitsMinExceedsMax { min: 2, max: 1 }
+        );
+    }
+
+    /// A module whose one function nests `depth` empty blocks.
+    fn nested_blocks(depth: usize) -> Vec<u8> {
+        let mut body = vec![0x00];
+        body.extend([0x02, 0x40].repeat(depth));
+        body.extend(vec![0x0B; depth + 1]);
+        let mut code = vec![0x01];
+        code.extend(encode_u32_to_leb128(body.len() as u32));
+        code.extend(body);
+        let mut sections = vec![
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+            0x03, 0x02, 0x01, 0x00, // function section
+            0x0A, // code section
+        ];
+        sections.extend(encode_u32_to_leb128(code.len() as u32));
+        sections.extend(code);
+        sections
+    }
+
+    #[test]
+    fn nesting_too_deep() {
+        assert!(parse(&nested_blocks(MAX_NESTING_DEPTH as usize)).is_ok());
+
+        // the block one past the limit starts 2 bytes after the one before it
+        let error = parse(&nested_blocks(MAX_NESTING_DEPTH as usize + 1))
+            .expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::NestingTooDeep {
+                    limit: MAX_NESTING_DEPTH
+                },
+                25 + 2 * MAX_NESTING_DEPTH as usize,
+                Some(SectionId::CodeSectionId)
+            )
+        );
+
+        // far deeper nesting is turned away just the same
+        let error = parse(&nested_blocks(200_000)).expect_err("Expected a parse error");
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::NestingTooDeep {
+                limit: MAX_NESTING_DEPTH
+            }
+        );
+    }
+
+    #[test]
+    fn else_outside_of_if() {
+        let error = parse(&[
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+            0x03, 0x02, 0x01, 0x00, // function section
+            0x0A, 0x05, 0x01, 0x03, 0x00, 0x05, 0x0B, // code section
+        ])
+        .expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::InvalidEncoding {
+                    construct: "else outside of if",
+                    value: 0x05
+                },
+                23,
+                Some(SectionId::CodeSectionId)
+            )
+        );
+    }
+
+    /// A module whose one function is `body` followed by `end`.
+    fn function_body(body: &[u8]) -> Vec<u8> {
+        let mut sections = vec![
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+            0x03, 0x02, 0x01, 0x00, // function section
+            0x0A, // code section
+        ];
+        sections.extend([body.len() as u8 + 4, 0x01, body.len() as u8 + 2, 0x00]);
+        sections.extend(body);
+        sections.push(0x0B);
+        sections
+    }
+
+    #[test]
+    fn invalid_lane_index() {
+        // i8x16.extract_lane_s has 16 lanes, so 15 is the last
+        assert!(parse(&function_body(&[0xFD, 0x15, 0x0F])).is_ok());
+        let error = parse(&function_body(&[0xFD, 0x15, 0x10])).expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::InvalidEncoding {
+                    construct: "lane index",
+                    value: 16
+                },
+                25,
+                Some(SectionId::CodeSectionId)
+            )
+        );
+
+        // i64x2.extract_lane
+        let error = parse(&function_body(&[0xFD, 0x1D, 0x02])).expect_err("Expected a parse error");
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::InvalidEncoding {
+                construct: "lane index",
+                value: 2
+            }
+        );
+
+        // v128.load64_lane, whose lane follows the memarg
+        let error = parse(&function_body(&[0xFD, 0x57, 0x03, 0x00, 0x02]))
+            .expect_err("Expected a parse error");
+        assert_eq!(error.offset, 27);
+
+        // i8x16.shuffle picks from the 32 bytes of both operands
+        let mut shuffle = vec![0xFD, 0x0D];
+        shuffle.extend(16..32);
+        assert!(parse(&function_body(&shuffle)).is_ok());
+        shuffle[6] = 32;
+        let error = parse(&function_body(&shuffle)).expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::InvalidEncoding {
+                    construct: "lane index",
+                    value: 32
+                },
+                29,
+                Some(SectionId::CodeSectionId)
+            )
+        );
+    }
+
+    #[test]
+    fn unknown_opcode() {
+        let error = parse(&[
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+            0x03, 0x02, 0x01, 0x00, // function section
+            0x0A, 0x05, 0x01, 0x03, 0x00, 0x06, 0x0B, // code section
+        ])
+        .expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::UnknownOpcode(0x06),
+                23,
+                Some(SectionId::CodeSectionId)
+            )
+        );
+    }
+
+    #[test]
+    fn unknown_prefixed_opcode() {
+        let error = parse(&[
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+            0x03, 0x02, 0x01, 0x00, // function section
+            0x0A, 0x07, 0x01, 0x05, 0x00, 0xFC, 0xFF, 0x01, 0x0B, // code section
+        ])
+        .expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::UnknownPrefixedOpcode {
+                    prefix: 0xFC,
+                    opcode: 255
+                },
+                23,
+                Some(SectionId::CodeSectionId)
+            )
+        );
+    }
+
+    #[test]
+    fn malformed_utf8() {
+        let error =
+            parse(&[0x07, 0x05, 0x01, 0x01, 0xFF, 0x00, 0x00]).expect_err("Expected a parse error");
+        assert_eq!(
+            error,
+            ParseError::new(
+                ParseErrorKind::MalformedUtf8,
+                12,
+                Some(SectionId::ExportSectionId)
+            )
+        );
+        assert_eq!(
+            error.to_string(),
+            "malformed UTF-8 encoding at offset 0xc in export section"
+        );
     }
 }
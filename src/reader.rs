@@ -0,0 +1,160 @@
+use crate::{
+    float::{F32, F64},
+    leb128::{decode_signed_leb128, decode_signed_leb128_64, decode_unsigned_leb128, Leb128Error},
+    module::section::SectionId,
+    parse_error::{ParseError, ParseErrorKind},
+};
+
+/// A cursor over a borrowed byte slice that tracks its absolute offset
+/// in the module, so that parsing never copies or shifts the input.
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    /// Absolute offset of `bytes[0]` in the module.
+    base: usize,
+    /// The section being read, reported in errors.
+    section: Option<SectionId>,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self::with_offset(bytes, 0)
+    }
+
+    pub fn with_offset(bytes: &'a [u8], base: usize) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            base,
+            section: None,
+        }
+    }
+
+    /// Absolute offset of the next byte to be read.
+    pub fn offset(&self) -> usize {
+        self.base + self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    pub fn set_section(&mut self, section: SectionId) {
+        self.section = Some(section);
+    }
+
+    /// Builds an error located at the next byte to be read.
+    pub fn error(&self, kind: ParseErrorKind) -> ParseError {
+        self.error_at(kind, self.offset())
+    }
+
+    pub fn error_at(&self, kind: ParseErrorKind, offset: usize) -> ParseError {
+        ParseError::new(kind, offset, self.section)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, ParseError> {
+        let byte = *self
+            .bytes
+            .get(self.position)
+            .ok_or_else(|| self.error(ParseErrorKind::UnexpectedEof))?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    /// Returns the next byte without consuming it.
+    pub fn peek_u8(&self) -> Result<u8, ParseError> {
+        self.bytes
+            .get(self.position)
+            .copied()
+            .ok_or_else(|| self.error(ParseErrorKind::UnexpectedEof))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, ParseError> {
+        let (value, size) = decode_unsigned_leb128(&self.bytes[self.position..])
+            .map_err(|e| self.leb128_error(e))?;
+        self.position += size as usize;
+        Ok(value)
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, ParseError> {
+        let (value, size) =
+            decode_signed_leb128(&self.bytes[self.position..]).map_err(|e| self.leb128_error(e))?;
+        self.position += size as usize;
+        Ok(value)
+    }
+
+    pub fn read_i64(&mut self) -> Result<i64, ParseError> {
+        let (value, size) = decode_signed_leb128_64(&self.bytes[self.position..])
+            .map_err(|e| self.leb128_error(e))?;
+        self.position += size as usize;
+        Ok(value)
+    }
+
+    /// Reads a signed 33-bit LEB128 value, as used for block type indexes.
+    pub fn read_s33(&mut self) -> Result<i64, ParseError> {
+        let (value, size) = decode_signed_leb128_64(&self.bytes[self.position..])
+            .map_err(|e| self.leb128_error(e))?;
+        // at most 5 bytes, whose unused high bits extend the sign of bit 32
+        if size > 5 || !(-(1 << 32)..(1 << 32)).contains(&value) {
+            return Err(self.leb128_error(Leb128Error::Overflow));
+        }
+        self.position += size as usize;
+        Ok(value)
+    }
+
+    pub fn read_f32(&mut self) -> Result<F32, ParseError> {
+        let bytes = self.read_bytes(4)?;
+        Ok(F32::from_bits(u32::from_le_bytes(
+            bytes.try_into().unwrap(),
+        )))
+    }
+
+    pub fn read_f64(&mut self) -> Result<F64, ParseError> {
+        let bytes = self.read_bytes(8)?;
+        Ok(F64::from_bits(u64::from_le_bytes(
+            bytes.try_into().unwrap(),
+        )))
+    }
+
+    pub fn read_bytes(&mut self, size: usize) -> Result<&'a [u8], ParseError> {
+        if size > self.remaining() {
+            return Err(self.error(ParseErrorKind::UnexpectedEof));
+        }
+        let bytes = &self.bytes[self.position..self.position + size];
+        self.position += size;
+        Ok(bytes)
+    }
+
+    /// Splits off the next `size` bytes as a reader of their own, keeping absolute offsets.
+    pub fn sub_reader(&mut self, size: usize) -> Result<Reader<'a>, ParseError> {
+        let base = self.offset();
+        let bytes = self.read_bytes(size)?;
+        Ok(Reader {
+            section: self.section,
+            ..Reader::with_offset(bytes, base)
+        })
+    }
+
+    /// Fails with `SectionSizeMismatch` unless every byte of a sized reader was consumed.
+    pub fn finish(&self) -> Result<(), ParseError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        Err(self.error(ParseErrorKind::SectionSizeMismatch {
+            expected: self.bytes.len() as u32,
+            actual: self.position as u32,
+        }))
+    }
+
+    fn leb128_error(&self, error: Leb128Error) -> ParseError {
+        match error {
+            Leb128Error::UnexpectedEof => self.error(ParseErrorKind::UnexpectedEof),
+            Leb128Error::Overflow => self.error(ParseErrorKind::Leb128Overflow),
+        }
+    }
+}
@@ -1,52 +1,105 @@
+use std::{fmt, sync::Arc, time::Duration};
+
 use crate::{
-    instance::{Export, FunctionInstance, Instance},
-    node::{BrInstructionNode, ExpressionNode, InstructionNode},
-    stack::{Label, LabelType, Number, StackEntry, Value},
-    types::{BlockType, NumberType, ValueType},
+    float::{checked_trunc, F32, F64},
+    instance::{Export, FunctionInstance, HostFunction, Instance, MemoryInstance, ModuleFunction},
+    node::{
+        BlockInstructionNode, BrInstructionNode, CallIndirectInstructionNode, ExpressionNode,
+        IfInstructionNode, InstructionNode, LoopInstructionNode, MemArgNode,
+    },
+    simd::V128,
+    stack::{FuncRef, Label, LabelType, Number, Reference, StackEntry, Value},
+    trap::Trap,
+    types::BlockType,
 };
 
-#[derive(Debug, Clone)]
+/// Most calls and block bodies that may be entered at once before execution
+/// traps with `CallStackExhausted`. Each one recurses through a few native
+/// stack frames, and this many fit in the 2 MiB stack of a spawned thread in
+/// a debug build.
+pub const MAX_DEPTH: usize = 512;
+
+// exclusive bounds of the values that `trunc` can convert to each integer type
+const I32_MIN_EXCLUSIVE: f64 = -2147483649.0;
+const I32_MAX_EXCLUSIVE: f64 = 2147483648.0;
+const U32_MAX_EXCLUSIVE: f64 = 4294967296.0;
+// the f64 just below -2^63
+const I64_MIN_EXCLUSIVE: f64 = -9223372036854777856.0;
+const I64_MAX_EXCLUSIVE: f64 = 9223372036854775808.0;
+const U64_MAX_EXCLUSIVE: f64 = 18446744073709551616.0;
+
+#[derive(Clone)]
 pub struct Frame {
-    function: FunctionInstance,
-    locals: Vec<Option<Value>>,
+    function: ModuleFunction,
+    locals: Vec<Value>,
     ip: usize,
+    /// Operand stack height and label count when the frame was entered, which
+    /// a branch to the function body unwinds to.
+    stack_height: usize,
+    label_height: usize,
 }
 
 impl Frame {
-    fn new(function: FunctionInstance, args: Option<Vec<Value>>) -> Self {
-        let local_count =
-            function.code.locals.len() + function.function_type.params.val_types.len();
-
-        if let Some(args) = args {
-            let mut locals = vec![None; local_count];
-            for (i, arg) in args.into_iter().enumerate() {
-                locals[i] = Some(arg);
-            }
-            Frame {
-                function,
-                locals,
-                ip: 0,
-            }
-        } else {
-            Frame {
-                function,
-                locals: vec![None; local_count],
-                ip: 0,
-            }
+    fn new(function: ModuleFunction, args: Option<Vec<Value>>) -> Self {
+        // declared locals start out as the zero value of their type
+        let mut locals = args.unwrap_or_default();
+        for val_type in function.code.locals.iter() {
+            locals.push(Value::zero(*val_type));
+        }
+        Frame {
+            function,
+            locals,
+            ip: 0,
+            stack_height: 0,
+            label_height: 0,
         }
     }
 
-    fn next_instruction(&mut self) -> InstructionNode {
-        self.ip += 1;
-        self.function.code.body[self.ip - 1].clone()
-    }
-
-    fn get_local(&self, index: usize) -> &Option<Value> {
+    fn get_local(&self, index: usize) -> &Value {
         &self.locals[index]
     }
 
     fn set_local(&mut self, index: usize, value: Value) {
-        self.locals[index] = Some(value);
+        self.locals[index] = value;
+    }
+
+    /// Describes a local as `$name in $function` for diagnostics.
+    fn describe_local(&self, index: usize) -> String {
+        let local = self
+            .function
+            .local_names
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| index.to_string());
+        format!("{} in {}", local, self.function.name)
+    }
+}
+
+impl fmt::Debug for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // print names such as `$gcd` and `$rem` as-is, without quotes
+        struct Name<'a>(&'a str);
+        impl fmt::Debug for Name<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.0)
+            }
+        }
+
+        struct Locals<'a>(&'a Frame);
+        impl fmt::Debug for Locals<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let names = self.0.function.local_names.iter();
+                f.debug_map()
+                    .entries(names.map(|name| Name(name)).zip(self.0.locals.iter()))
+                    .finish()
+            }
+        }
+
+        f.debug_struct("Frame")
+            .field("function", &Name(&self.function.name))
+            .field("locals", &Locals(self))
+            .field("ip", &self.ip)
+            .finish()
     }
 }
 
@@ -57,9 +110,13 @@ pub struct Runtime {
     frame_index: usize,
     stack: Vec<StackEntry>,
     sp: usize,
+    /// Calls and block bodies entered and not yet left, up to `MAX_DEPTH`.
     depth: usize,
     label_positions: Vec<usize>,
     control_instructions: Vec<InstructionNode>,
+    /// Instances waiting on a call into a function another instance defined,
+    /// innermost last.
+    callers: Vec<Instance>,
 }
 
 impl Runtime {
@@ -73,11 +130,19 @@ impl Runtime {
             depth: 0,
             label_positions: vec![],
             control_instructions: vec![],
+            callers: vec![],
         }
     }
 
-    fn push_frame(&mut self, function: FunctionInstance, args: Option<Vec<Value>>) {
-        self.frames.push(Frame::new(function, args));
+    pub fn into_instance(self) -> Instance {
+        self.instance
+    }
+
+    fn push_frame(&mut self, function: ModuleFunction, args: Option<Vec<Value>>) {
+        let mut frame = Frame::new(function, args);
+        frame.stack_height = self.sp;
+        frame.label_height = self.label_positions.len();
+        self.frames.push(frame);
         self.frame_index += 1;
     }
 
@@ -108,21 +173,26 @@ impl Runtime {
             .unwrap_or_else(|| panic!("No stack entry to pop"))
     }
 
-    fn pop_result(&mut self) -> Option<StackEntry> {
-        if self.sp > 0 {
-            Some(self.pop_stack())
-        } else {
-            None
-        }
-    }
-
-    fn push_label(&mut self, label_type: LabelType, arity: BlockType, size: u32) {
-        self.push_stack(StackEntry::label(Label {
-            label_type,
-            arity,
-            size: size as usize,
-        }));
-        self.label_positions.push(self.sp - 1);
+    /// Pushes the label of a block beneath the parameters it takes, and
+    /// returns the number of results it leaves on the stack.
+    fn push_label(&mut self, label_type: LabelType, block_type: BlockType, size: u32) -> usize {
+        let (params, results) = block_type.arity(&self.instance.types);
+        let arity = match label_type {
+            LabelType::Loop => params,
+            LabelType::Block | LabelType::If => results,
+        };
+        let label_idx = self.sp - params;
+        self.stack.insert(
+            label_idx,
+            StackEntry::label(Label {
+                label_type,
+                arity,
+                size: size as usize,
+            }),
+        );
+        self.sp += 1;
+        self.label_positions.push(label_idx);
+        results
     }
 
     fn pop_label(&mut self) {
@@ -134,139 +204,193 @@ impl Runtime {
         self.sp = label_idx;
     }
 
-    fn get_label(&mut self, depth: usize) -> Label {
-        self.label_positions.reverse();
-        let label_idx = self.label_positions[depth];
-        self.label_positions.reverse();
-
-        match &self.stack[label_idx] {
-            StackEntry::label(label) => label.clone(),
-            _ => panic!("Stack entry is not a label"),
-        }
-    }
-
-    pub fn execute(&mut self, name: &String, args: Option<Vec<Value>>) -> Option<Number> {
+    pub fn execute(&mut self, name: &String, args: Option<Vec<Value>>) -> Result<Vec<Value>, Trap> {
         let export = &self.instance.export_map.get(name).unwrap();
-        match export {
-            Export::Function { index, name: _ } => {
-                let function = self.instance.functions[*index].clone();
-                self.call(function.clone(), args);
-            }
+        let function = match export {
+            Export::Function { index, name: _ } => self.instance.functions[*index].clone(),
+            _ => panic!("Export {} is not a function", name),
         };
+        let arity = function.function_type().returns.val_types.len();
+        if let Err(trap) = self.call(function, args) {
+            self.reset();
+            return Err(trap);
+        }
 
-        let entry = self.pop_result();
-        match entry {
-            Some(entry) => match entry {
-                StackEntry::value(value) => match value {
-                    Value::num(number) => Some(number),
-                },
+        let results = self.stack.split_off(self.sp - arity);
+        self.sp -= arity;
+        Ok(results
+            .into_iter()
+            .map(|entry| match entry {
+                StackEntry::value(value) => value,
                 _ => panic!("result must be value"),
-            },
-            None => None,
-        }
+            })
+            .collect())
     }
 
-    pub fn call(&mut self, function: FunctionInstance, args: Option<Vec<Value>>) {
+    /// Discards the state left behind by a trapped execution.
+    fn reset(&mut self) {
+        self.frames.clear();
+        self.frame_index = 0;
+        self.stack.clear();
+        self.sp = 0;
+        self.depth = 0;
+        self.label_positions.clear();
+        self.control_instructions.clear();
+        self.callers.clear();
+    }
+
+    /// Calls a function of the instance. Only functions that instantiation
+    /// has validated against the instance can be called, so this is not
+    /// open to the host, which goes through `execute`.
+    pub(crate) fn call(
+        &mut self,
+        function: FunctionInstance,
+        args: Option<Vec<Value>>,
+    ) -> Result<(), Trap> {
+        let function = match function {
+            FunctionInstance::Module(function) => function,
+            FunctionInstance::Host(function) => {
+                self.call_host(&function, args);
+                return Ok(());
+            }
+        };
+
+        self.enter()?;
         self.push_frame(function, args);
         let mut frame = self.current_frame();
-        while frame.ip < frame.function.code.body.len() {
-            let instruction = frame.next_instruction();
-            self.invoke(&mut frame, &instruction);
+        let code = Arc::clone(&frame.function.code);
+        while frame.ip < code.body.len() {
+            let instruction = &code.body[frame.ip];
+            frame.ip += 1;
+            self.invoke(&mut frame, instruction)?;
+            // a branch out of every label targets the function body itself
+            if self.control_instructions.pop().is_some() {
+                break;
+            }
         }
         self.pop_frame();
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn call_host(&mut self, function: &HostFunction, args: Option<Vec<Value>>) {
+        for result in function.call(&args.unwrap_or_default()) {
+            self.push_stack(StackEntry::value(result));
+        }
+    }
+
+    /// Looks up the function behind `func_ref` in the instance that defined
+    /// it and, once `check` accepts it, pops its arguments. When another
+    /// instance defined it, that instance stands in for this runtime's own,
+    /// which waits in `callers` until `leave_ref`.
+    fn enter_ref(
+        &mut self,
+        func_ref: &FuncRef,
+        check: impl FnOnce(&FunctionInstance) -> Result<(), Trap>,
+    ) -> Result<(bool, FunctionInstance, Vec<Value>), Trap> {
+        let instance = if func_ref.belongs_to(&self.instance) {
+            None
+        } else {
+            // a function whose instance is gone can no longer be called
+            Some(func_ref.instance().ok_or(Trap::UninitializedElement)?)
+        };
+        let functions = &instance.as_ref().unwrap_or(&self.instance).functions;
+        let function = functions[func_ref.index() as usize].clone();
+        check(&function)?;
+        let args = self.pop_args(&function);
+        let foreign = instance.is_some();
+        if let Some(instance) = instance {
+            let caller = std::mem::replace(&mut self.instance, instance);
+            self.callers.push(caller);
+        }
+        Ok((foreign, function, args))
+    }
+
+    fn leave_ref(&mut self) {
+        self.instance = self
+            .callers
+            .pop()
+            .expect("a foreign call returns to the instance that made it");
+    }
+
+    /// Runs an instruction. Blocks and calls recurse back into here, so they
+    /// are kept apart from the other instructions, whose match takes far more
+    /// stack than a level of nesting should.
+    fn invoke(&mut self, frame: &mut Frame, instruction: &InstructionNode) -> Result<(), Trap> {
+        match instruction {
+            InstructionNode::Block(node) => self.block(frame, node),
+            InstructionNode::Loop(node) => self.loop_block(frame, node),
+            InstructionNode::If(node) => self.if_block(frame, node),
+            InstructionNode::Call(node) => {
+                let function = self.instance.functions[node.function_index as usize].clone();
+                let args = self.pop_args(&function);
+                self.call(function, Some(args))
+            }
+            InstructionNode::CallIndirect(node) => self.call_indirect(node),
+            instruction => self.execute_instruction(frame, instruction),
+        }
     }
 
-    pub fn invoke(&mut self, frame: &mut Frame, instruction: &InstructionNode) {
+    #[inline(never)]
+    fn execute_instruction(
+        &mut self,
+        frame: &mut Frame,
+        instruction: &InstructionNode,
+    ) -> Result<(), Trap> {
         match instruction {
             InstructionNode::I32Const(node) => {
                 self.push_stack(StackEntry::value(Value::num(Number::i32(node.value))));
             }
-            InstructionNode::Block(node) => {
-                self.push_label(LabelType::Block, node.block_type, node.size);
-                self.expression(frame, &node.expr);
-                if self.control_instructions.len() > 0 {
-                    let control_instruction = self.control_instructions.pop().unwrap();
-                    match control_instruction {
-                        InstructionNode::Br(br_node) => {
-                            self.jump_to_label(br_node.depth);
-                        }
-                        _ => todo!("unimplemented control instruction"),
-                    }
-                } else {
-                    self.cleanup_block_label(node.block_type);
-                }
-            }
-            InstructionNode::Loop(node) => {
-                self.push_label(LabelType::Loop, node.block_type, node.size);
-                loop {
-                    self.expression(frame, &node.expr);
-                    if self.control_instructions.len() > 0 {
-                        let control_instruction = self.control_instructions.pop().unwrap();
-                        match control_instruction {
-                            InstructionNode::Br(br_node) => {
-                                if br_node.depth > 0 {
-                                    self.jump_to_label(br_node.depth);
-                                    break;
-                                }
-                            }
-                            _ => todo!("unimplemented control instruction"),
-                        }
-                    }
-                }
-            }
-            InstructionNode::If(node) => {
-                let condition = self.pop_stack();
-                if let StackEntry::value(Value::num(Number::i32(value))) = condition {
-                    if value != 0 {
-                        self.push_label(LabelType::If, node.block_type, node.size);
-                        self.expression(frame, &node.then_expr);
-                        self.cleanup_block_label(node.block_type);
-                    } else if let Some(else_) = node.else_expr.clone() {
-                        self.push_label(LabelType::If, node.block_type, node.size);
-                        self.expression(frame, &else_);
-                        self.cleanup_block_label(node.block_type);
-                    }
-                } else {
-                    panic!("if condition must be i32");
-                }
+            InstructionNode::I64Const(node) => {
+                self.push_stack(StackEntry::value(Value::num(Number::i64(node.value))));
             }
+            InstructionNode::F32Const(node) => self.push_f32(node.value),
+            InstructionNode::F64Const(node) => self.push_f64(node.value),
             InstructionNode::Else(_) => {}
-            InstructionNode::Br(node) => {
-                self.control_instructions
-                    .push(InstructionNode::Br(BrInstructionNode::new(node.depth)));
-            }
+            InstructionNode::Br(node) => self.branch(frame, node.depth),
             InstructionNode::BrIf(node) => {
                 let condition = self.pop_stack();
                 if let StackEntry::value(Value::num(Number::i32(value))) = condition {
                     if value != 0 {
-                        self.control_instructions
-                            .push(InstructionNode::Br(BrInstructionNode::new(node.depth)));
+                        self.branch(frame, node.depth);
                     }
                 } else {
                     panic!("br_if condition must be i32");
                 }
             }
-            InstructionNode::Call(node) => {
-                let function = self.instance.functions[node.function_index as usize].clone();
-                let mut args: Vec<Value> = vec![];
-                function
-                    .function_type
-                    .params
-                    .val_types
-                    .iter()
-                    .for_each(|_| {
-                        let entry = self.pop_stack();
-                        if let StackEntry::value(v) = entry {
-                            args.push(v);
-                        }
-                    });
-
-                self.call(function, Some(args));
+            InstructionNode::BrTable(node) => {
+                let index = self.pop_i32() as u32 as usize;
+                let depth = node.depths.get(index).unwrap_or(&node.default_depth);
+                self.branch(frame, *depth);
+            }
+            InstructionNode::Return(_) => {
+                // one past the innermost label of the frame is its body
+                let depth = self.label_positions.len() - frame.label_height;
+                self.branch(frame, depth);
+            }
+            InstructionNode::Block(_)
+            | InstructionNode::Loop(_)
+            | InstructionNode::If(_)
+            | InstructionNode::Call(_)
+            | InstructionNode::CallIndirect(_) => {
+                unreachable!("{:?} is run by invoke", instruction)
+            }
+            InstructionNode::Unreachable(_) => return Err(Trap::Unreachable),
+            InstructionNode::Nop(_) => {}
+            InstructionNode::Drop(_) => {
+                self.pop_stack();
+            }
+            // typed select only differs for validation, so both run the same way
+            InstructionNode::Select(_) => {
+                let condition = self.pop_i32();
+                let rhs = self.pop_stack();
+                let lhs = self.pop_stack();
+                self.push_stack(if condition != 0 { lhs } else { rhs });
             }
             InstructionNode::End(_) => {}
             InstructionNode::GetLocal(node) => {
-                let value = frame.get_local(node.index as usize);
-                self.push_stack(StackEntry::value(value.clone().unwrap()));
+                let value = frame.get_local(node.index as usize).clone();
+                self.push_stack(StackEntry::value(value));
             }
             InstructionNode::SetLocal(node) => {
                 let entry = self.pop_stack();
@@ -274,211 +398,2011 @@ impl Runtime {
                     StackEntry::value(v) => {
                         frame.set_local(node.index as usize, v);
                     }
-                    _ => panic!("set_local must be value"),
+                    _ => panic!(
+                        "local.set of {} must be value",
+                        frame.describe_local(node.index as usize)
+                    ),
                 }
             }
-            InstructionNode::I32Add(_) => {
-                let rhs = self.pop_stack();
-                let lhs = self.pop_stack();
-                match (rhs, lhs) {
-                    (StackEntry::value(Value::num(a)), StackEntry::value(Value::num(b))) => {
-                        self.push_stack(StackEntry::value(Value::num(a + b)));
+            InstructionNode::TeeLocal(node) => {
+                let entry = self.pop_stack();
+                match entry {
+                    StackEntry::value(v) => {
+                        frame.set_local(node.index as usize, v.clone());
+                        self.push_stack(StackEntry::value(v));
                     }
-                    _ => panic!("i32.add must have two i32 values on the stack"),
+                    _ => panic!(
+                        "local.tee of {} must be value",
+                        frame.describe_local(node.index as usize)
+                    ),
                 }
             }
-            InstructionNode::I32Sub(_) => {
-                let rhs = self.pop_stack();
-                let lhs = self.pop_stack();
-                match (rhs, lhs) {
-                    (StackEntry::value(Value::num(rhs)), StackEntry::value(Value::num(lhs))) => {
-                        self.push_stack(StackEntry::value(Value::num(lhs - rhs)));
-                    }
-                    _ => panic!("i32.sub must have two i32 values on the stack"),
-                }
+            InstructionNode::GetGlobal(node) => {
+                let value = self.instance.globals[node.index as usize].get();
+                self.push_stack(StackEntry::value(value));
             }
-            InstructionNode::I32GeS(_) => {
-                let rhs = self.pop_stack();
-                let lhs = self.pop_stack();
-                match (rhs, lhs) {
-                    (StackEntry::value(Value::num(rhs)), StackEntry::value(Value::num(lhs))) => {
-                        self.push_stack(StackEntry::value(Value::num(if lhs >= rhs {
-                            Number::i32(1)
-                        } else {
-                            Number::i32(0)
-                        })));
-                    }
-                    _ => panic!("i32.ge_s must have two i32 values on the stack"),
-                }
-            } // InstructionNode::I32Mul => {
-            //     let rhs = frame.pop_u32();
-            //     let lhs = frame.pop_u32();
-            //     frame.push_u32(a * b);
-            // }
-            // InstructionNode::I32DivS => {
-            //     let rhs = frame.pop_u32();
-            //     let lhs = frame.pop_u32();
-            //     frame.push_u32(a / b);
-            // }
-            // InstructionNode::I32DivU => {
-            //     let rhs = frame.pop_u32();
-            //     let lhs = frame.pop_u32();
-            //     frame.push_u32(a / b);
-            // }
-            InstructionNode::I32RemS(_) => {
-                let rhs = self.pop_stack();
-                let lhs = self.pop_stack();
-                match (rhs, lhs) {
-                    (StackEntry::value(Value::num(rhs)), StackEntry::value(Value::num(lhs))) => {
-                        self.push_stack(StackEntry::value(Value::num(lhs % rhs)));
-                    }
-                    _ => panic!("i32.rem_s must have two i32 values on the stack"),
-                }
-            } // InstructionNode::I32RemU => {
-            //     let rhs = frame.pop_u32();
-            //     let lhs = frame.pop_u32();
-            //     frame.push_u32(a % b);
-            // }
-            // InstructionNode::I32And => {
-            //     let rhs = frame.pop_u32();
-            //     let lhs = frame.pop_u32();
-            //     frame.push_u32(a & b);
-            // }
-            // InstructionNode::I32Or => {
-            //     let rhs = frame.pop_u32();
-            //     let lhs = frame.pop_u32();
-            //     frame.push_u32(a | b);
-            // }
-            // InstructionNode::I32Xor => {
-            //     let rhs = frame.pop_u32();
-            //     let lhs = frame.pop_u32();
-            //     frame.push_u32(a ^ b);
-            // }
-            // InstructionNode::I32Shl => {
-            //     let rhs = frame.pop_u32();
-            //     let lhs = frame.pop_u32();
-            //     frame.push_u32(a << b);
-            // }
-            // InstructionNode::I32ShrS => {
-            //     let rhs = frame.pop_u32();
-            //     let lhs = frame.pop_u32();
-            //     frame.push_u32(a >> b);
-            // }
-            InstructionNode::I32Eqz(_) => {
-                let v = self.pop_stack();
-                match v {
-                    StackEntry::value(Value::num(Number::i32(v))) => {
-                        self.push_stack(StackEntry::value(Value::num(Number::i32(if v == 0 {
-                            1
-                        } else {
-                            0
-                        }))));
-                    }
-                    _ => panic!("i32.eqz must have one i32 value on the stack"),
+            InstructionNode::SetGlobal(node) => {
+                let entry = self.pop_stack();
+                match entry {
+                    // instantiation rejects a global.set of an immutable global
+                    StackEntry::value(v) => self.instance.globals[node.index as usize].set(v),
+                    _ => panic!("set_global must be value"),
                 }
             }
-            InstructionNode::I32Eq(_) => {
-                let rhs = self.pop_stack();
-                let lhs = self.pop_stack();
-                match (rhs, lhs) {
-                    (StackEntry::value(Value::num(rhs)), StackEntry::value(Value::num(lhs))) => {
-                        self.push_stack(StackEntry::value(Value::num(Number::i32(
-                            if lhs == rhs { 1 } else { 0 },
-                        ))));
-                    }
-                    _ => panic!("i32.eq must have two i32 values on the stack"),
-                }
+            InstructionNode::I32Eqz(_) => self.i32_unary(|v| (v == 0) as i32),
+            InstructionNode::I32Eq(_) => self.i32_compare(|lhs, rhs| lhs == rhs),
+            InstructionNode::I32Ne(_) => self.i32_compare(|lhs, rhs| lhs != rhs),
+            InstructionNode::I32LtS(_) => self.i32_compare(|lhs, rhs| lhs < rhs),
+            InstructionNode::I32LtU(_) => self.i32_compare(|lhs, rhs| (lhs as u32) < (rhs as u32)),
+            InstructionNode::I32GtS(_) => self.i32_compare(|lhs, rhs| lhs > rhs),
+            InstructionNode::I32GtU(_) => self.i32_compare(|lhs, rhs| (lhs as u32) > (rhs as u32)),
+            InstructionNode::I32LeS(_) => self.i32_compare(|lhs, rhs| lhs <= rhs),
+            InstructionNode::I32LeU(_) => self.i32_compare(|lhs, rhs| (lhs as u32) <= (rhs as u32)),
+            InstructionNode::I32GeS(_) => self.i32_compare(|lhs, rhs| lhs >= rhs),
+            InstructionNode::I32GeU(_) => self.i32_compare(|lhs, rhs| (lhs as u32) >= (rhs as u32)),
+            InstructionNode::I32Clz(_) => self.i32_unary(|v| v.leading_zeros() as i32),
+            InstructionNode::I32Ctz(_) => self.i32_unary(|v| v.trailing_zeros() as i32),
+            InstructionNode::I32Popcnt(_) => self.i32_unary(|v| v.count_ones() as i32),
+            InstructionNode::I32Add(_) => self.i32_binary(|lhs, rhs| lhs.wrapping_add(rhs)),
+            InstructionNode::I32Sub(_) => self.i32_binary(|lhs, rhs| lhs.wrapping_sub(rhs)),
+            InstructionNode::I32Mul(_) => self.i32_binary(|lhs, rhs| lhs.wrapping_mul(rhs)),
+            InstructionNode::I32DivS(_) => self.i32_binary_checked(|lhs, rhs| match rhs {
+                0 => Err(Trap::IntegerDivideByZero),
+                -1 if lhs == i32::MIN => Err(Trap::IntegerOverflow),
+                _ => Ok(lhs / rhs),
+            })?,
+            InstructionNode::I32DivU(_) => self.i32_binary_checked(|lhs, rhs| match rhs {
+                0 => Err(Trap::IntegerDivideByZero),
+                _ => Ok(((lhs as u32) / (rhs as u32)) as i32),
+            })?,
+            InstructionNode::I32RemS(_) => self.i32_binary_checked(|lhs, rhs| match rhs {
+                0 => Err(Trap::IntegerDivideByZero),
+                // i32::MIN % -1 overflows in Rust, but is defined as 0
+                _ => Ok(lhs.wrapping_rem(rhs)),
+            })?,
+            InstructionNode::I32RemU(_) => self.i32_binary_checked(|lhs, rhs| match rhs {
+                0 => Err(Trap::IntegerDivideByZero),
+                _ => Ok(((lhs as u32) % (rhs as u32)) as i32),
+            })?,
+            InstructionNode::I32And(_) => self.i32_binary(|lhs, rhs| lhs & rhs),
+            InstructionNode::I32Or(_) => self.i32_binary(|lhs, rhs| lhs | rhs),
+            InstructionNode::I32Xor(_) => self.i32_binary(|lhs, rhs| lhs ^ rhs),
+            // shift and rotate counts are taken modulo 32
+            InstructionNode::I32Shl(_) => self.i32_binary(|lhs, rhs| lhs.wrapping_shl(rhs as u32)),
+            InstructionNode::I32ShrS(_) => self.i32_binary(|lhs, rhs| lhs.wrapping_shr(rhs as u32)),
+            InstructionNode::I32ShrU(_) => {
+                self.i32_binary(|lhs, rhs| (lhs as u32).wrapping_shr(rhs as u32) as i32)
             }
-            InstructionNode::I32LtS(_) => {
-                let rhs = self.pop_stack();
-                let lhs = self.pop_stack();
-                match (rhs, lhs) {
-                    (StackEntry::value(Value::num(rhs)), StackEntry::value(Value::num(lhs))) => {
-                        self.push_stack(StackEntry::value(Value::num(Number::i32(if lhs < rhs {
-                            1
-                        } else {
-                            0
-                        }))));
-                    }
-                    _ => panic!("i32.lt_s must have two i32 values on the stack"),
-                }
+            InstructionNode::I32Rotl(_) => self.i32_binary(|lhs, rhs| lhs.rotate_left(rhs as u32)),
+            InstructionNode::I32Rotr(_) => self.i32_binary(|lhs, rhs| lhs.rotate_right(rhs as u32)),
+            InstructionNode::I64Eqz(_) => {
+                let value = self.pop_i64();
+                self.push_i32((value == 0) as i32);
             }
-            InstructionNode::I32LtU(_) => {
-                let rhs = self.pop_stack();
-                let lhs = self.pop_stack();
-                match (rhs, lhs) {
-                    (StackEntry::value(Value::num(rhs)), StackEntry::value(Value::num(lhs))) => {
-                        self.push_stack(StackEntry::value(Value::num(Number::i32(if lhs < rhs {
-                            1
-                        } else {
-                            0
-                        }))));
-                    }
-                    _ => panic!("i32.lt_u must have two i32 values on the stack"),
-                }
+            InstructionNode::I64Eq(_) => self.i64_compare(|lhs, rhs| lhs == rhs),
+            InstructionNode::I64Ne(_) => self.i64_compare(|lhs, rhs| lhs != rhs),
+            InstructionNode::I64LtS(_) => self.i64_compare(|lhs, rhs| lhs < rhs),
+            InstructionNode::I64LtU(_) => self.i64_compare(|lhs, rhs| (lhs as u64) < (rhs as u64)),
+            InstructionNode::I64GtS(_) => self.i64_compare(|lhs, rhs| lhs > rhs),
+            InstructionNode::I64GtU(_) => self.i64_compare(|lhs, rhs| (lhs as u64) > (rhs as u64)),
+            InstructionNode::I64LeS(_) => self.i64_compare(|lhs, rhs| lhs <= rhs),
+            InstructionNode::I64LeU(_) => self.i64_compare(|lhs, rhs| (lhs as u64) <= (rhs as u64)),
+            InstructionNode::I64GeS(_) => self.i64_compare(|lhs, rhs| lhs >= rhs),
+            InstructionNode::I64GeU(_) => self.i64_compare(|lhs, rhs| (lhs as u64) >= (rhs as u64)),
+            InstructionNode::I64Clz(_) => self.i64_unary(|v| v.leading_zeros() as i64),
+            InstructionNode::I64Ctz(_) => self.i64_unary(|v| v.trailing_zeros() as i64),
+            InstructionNode::I64Popcnt(_) => self.i64_unary(|v| v.count_ones() as i64),
+            InstructionNode::I64Add(_) => self.i64_binary(|lhs, rhs| lhs.wrapping_add(rhs)),
+            InstructionNode::I64Sub(_) => self.i64_binary(|lhs, rhs| lhs.wrapping_sub(rhs)),
+            InstructionNode::I64Mul(_) => self.i64_binary(|lhs, rhs| lhs.wrapping_mul(rhs)),
+            InstructionNode::I64DivS(_) => self.i64_binary_checked(|lhs, rhs| match rhs {
+                0 => Err(Trap::IntegerDivideByZero),
+                -1 if lhs == i64::MIN => Err(Trap::IntegerOverflow),
+                _ => Ok(lhs / rhs),
+            })?,
+            InstructionNode::I64DivU(_) => self.i64_binary_checked(|lhs, rhs| match rhs {
+                0 => Err(Trap::IntegerDivideByZero),
+                _ => Ok(((lhs as u64) / (rhs as u64)) as i64),
+            })?,
+            InstructionNode::I64RemS(_) => self.i64_binary_checked(|lhs, rhs| match rhs {
+                0 => Err(Trap::IntegerDivideByZero),
+                _ => Ok(lhs.wrapping_rem(rhs)),
+            })?,
+            InstructionNode::I64RemU(_) => self.i64_binary_checked(|lhs, rhs| match rhs {
+                0 => Err(Trap::IntegerDivideByZero),
+                _ => Ok(((lhs as u64) % (rhs as u64)) as i64),
+            })?,
+            InstructionNode::I64And(_) => self.i64_binary(|lhs, rhs| lhs & rhs),
+            InstructionNode::I64Or(_) => self.i64_binary(|lhs, rhs| lhs | rhs),
+            InstructionNode::I64Xor(_) => self.i64_binary(|lhs, rhs| lhs ^ rhs),
+            // shift and rotate counts are taken modulo 64
+            InstructionNode::I64Shl(_) => self.i64_binary(|lhs, rhs| lhs.wrapping_shl(rhs as u32)),
+            InstructionNode::I64ShrS(_) => self.i64_binary(|lhs, rhs| lhs.wrapping_shr(rhs as u32)),
+            InstructionNode::I64ShrU(_) => {
+                self.i64_binary(|lhs, rhs| (lhs as u64).wrapping_shr(rhs as u32) as i64)
             }
-            InstructionNode::I32RemU(_) => todo!(),
-        };
-    }
-
-    pub fn expression(&mut self, frame: &mut Frame, expr: &ExpressionNode) {
-        for instruction in expr.instructions.iter() {
-            self.invoke(frame, instruction);
-            if self.control_instructions.len() > 0 {
-                break;
+            InstructionNode::I64Rotl(_) => self.i64_binary(|lhs, rhs| lhs.rotate_left(rhs as u32)),
+            InstructionNode::I64Rotr(_) => self.i64_binary(|lhs, rhs| lhs.rotate_right(rhs as u32)),
+            // comparisons involving a NaN are false, except ne
+            InstructionNode::F32Eq(_) => self.f32_compare(|lhs, rhs| lhs == rhs),
+            InstructionNode::F32Ne(_) => self.f32_compare(|lhs, rhs| lhs != rhs),
+            InstructionNode::F32Lt(_) => self.f32_compare(|lhs, rhs| lhs < rhs),
+            InstructionNode::F32Gt(_) => self.f32_compare(|lhs, rhs| lhs > rhs),
+            InstructionNode::F32Le(_) => self.f32_compare(|lhs, rhs| lhs <= rhs),
+            InstructionNode::F32Ge(_) => self.f32_compare(|lhs, rhs| lhs >= rhs),
+            InstructionNode::F32Abs(_) => self.f32_unary(F32::abs),
+            InstructionNode::F32Neg(_) => self.f32_unary(|v| -v),
+            InstructionNode::F32Ceil(_) => self.f32_unary(F32::ceil),
+            InstructionNode::F32Floor(_) => self.f32_unary(F32::floor),
+            InstructionNode::F32Trunc(_) => self.f32_unary(F32::trunc),
+            InstructionNode::F32Nearest(_) => self.f32_unary(F32::nearest),
+            InstructionNode::F32Sqrt(_) => self.f32_unary(F32::sqrt),
+            InstructionNode::F32Add(_) => self.f32_binary(|lhs, rhs| lhs + rhs),
+            InstructionNode::F32Sub(_) => self.f32_binary(|lhs, rhs| lhs - rhs),
+            InstructionNode::F32Mul(_) => self.f32_binary(|lhs, rhs| lhs * rhs),
+            InstructionNode::F32Div(_) => self.f32_binary(|lhs, rhs| lhs / rhs),
+            InstructionNode::F32Min(_) => self.f32_binary(F32::min),
+            InstructionNode::F32Max(_) => self.f32_binary(F32::max),
+            InstructionNode::F32Copysign(_) => self.f32_binary(F32::copysign),
+            // comparisons involving a NaN are false, except ne
+            InstructionNode::F64Eq(_) => self.f64_compare(|lhs, rhs| lhs == rhs),
+            InstructionNode::F64Ne(_) => self.f64_compare(|lhs, rhs| lhs != rhs),
+            InstructionNode::F64Lt(_) => self.f64_compare(|lhs, rhs| lhs < rhs),
+            InstructionNode::F64Gt(_) => self.f64_compare(|lhs, rhs| lhs > rhs),
+            InstructionNode::F64Le(_) => self.f64_compare(|lhs, rhs| lhs <= rhs),
+            InstructionNode::F64Ge(_) => self.f64_compare(|lhs, rhs| lhs >= rhs),
+            InstructionNode::F64Abs(_) => self.f64_unary(F64::abs),
+            InstructionNode::F64Neg(_) => self.f64_unary(|v| -v),
+            InstructionNode::F64Ceil(_) => self.f64_unary(F64::ceil),
+            InstructionNode::F64Floor(_) => self.f64_unary(F64::floor),
+            InstructionNode::F64Trunc(_) => self.f64_unary(F64::trunc),
+            InstructionNode::F64Nearest(_) => self.f64_unary(F64::nearest),
+            InstructionNode::F64Sqrt(_) => self.f64_unary(F64::sqrt),
+            InstructionNode::F64Add(_) => self.f64_binary(|lhs, rhs| lhs + rhs),
+            InstructionNode::F64Sub(_) => self.f64_binary(|lhs, rhs| lhs - rhs),
+            InstructionNode::F64Mul(_) => self.f64_binary(|lhs, rhs| lhs * rhs),
+            InstructionNode::F64Div(_) => self.f64_binary(|lhs, rhs| lhs / rhs),
+            InstructionNode::F64Min(_) => self.f64_binary(F64::min),
+            InstructionNode::F64Max(_) => self.f64_binary(F64::max),
+            InstructionNode::F64Copysign(_) => self.f64_binary(F64::copysign),
+            InstructionNode::I32WrapI64(_) => {
+                let value = self.pop_i64();
+                self.push_i32(value as i32);
             }
-        }
-    }
-
-    fn jump_to_label(&mut self, depth: usize) {
-        // TODO: br でも stack に戻り値を push する必要があるか不明のため、必要であれば直す
-        self.cleanup_block_label(BlockType::Empty);
-        if depth > 0 {
-            self.control_instructions
-                .push(InstructionNode::Br(BrInstructionNode::new(depth - 1)));
-        }
-    }
-
-    fn cleanup_block_label(&mut self, block_type: BlockType) {
-        match block_type {
-            BlockType::Empty => {
-                self.pop_label();
-            }
-            BlockType::ValType(ValueType::Number(v)) => {
-                let result = self.pop_stack();
-                match v {
-                    NumberType::I32 => match result.clone() {
-                        StackEntry::value(Value::num(n)) => match n {
-                            Number::i32(_) => {}
-                            _ => unreachable!(),
-                        },
-                        StackEntry::label(_) => unreachable!(),
-                    },
-                    NumberType::I64 => match result.clone() {
-                        StackEntry::value(Value::num(n)) => match n {
-                            Number::i64(_) => {}
-                            _ => unreachable!(),
-                        },
-                        StackEntry::label(_) => unreachable!(),
-                    },
-                    NumberType::F32 => match result.clone() {
-                        StackEntry::value(Value::num(n)) => match n {
-                            Number::f32(_) => {}
-                            _ => unreachable!(),
-                        },
-                        StackEntry::label(_) => unreachable!(),
-                    },
-                    NumberType::F64 => match result.clone() {
-                        StackEntry::value(Value::num(n)) => match n {
-                            Number::f64(_) => {}
-                            _ => unreachable!(),
-                        },
-                        StackEntry::label(_) => unreachable!(),
-                    },
-                }
-                self.pop_label();
-                self.push_stack(result);
+            InstructionNode::I32TruncF32S(_) => {
+                let value = self.pop_f32().to_float() as f64;
+                self.push_i32(checked_trunc(value, I32_MIN_EXCLUSIVE, I32_MAX_EXCLUSIVE)? as i32);
             }
-        }
-    }
+            InstructionNode::I32TruncF32U(_) => {
+                let value = self.pop_f32().to_float() as f64;
+                self.push_i32(checked_trunc(value, -1.0, U32_MAX_EXCLUSIVE)? as u32 as i32);
+            }
+            InstructionNode::I32TruncF64S(_) => {
+                let value = self.pop_f64().to_float();
+                self.push_i32(checked_trunc(value, I32_MIN_EXCLUSIVE, I32_MAX_EXCLUSIVE)? as i32);
+            }
+            InstructionNode::I32TruncF64U(_) => {
+                let value = self.pop_f64().to_float();
+                self.push_i32(checked_trunc(value, -1.0, U32_MAX_EXCLUSIVE)? as u32 as i32);
+            }
+            InstructionNode::I64ExtendI32S(_) => {
+                let value = self.pop_i32();
+                self.push_i64(value as i64);
+            }
+            InstructionNode::I64ExtendI32U(_) => {
+                let value = self.pop_i32();
+                self.push_i64(value as u32 as i64);
+            }
+            InstructionNode::I64TruncF32S(_) => {
+                let value = self.pop_f32().to_float() as f64;
+                self.push_i64(checked_trunc(value, I64_MIN_EXCLUSIVE, I64_MAX_EXCLUSIVE)? as i64);
+            }
+            InstructionNode::I64TruncF32U(_) => {
+                let value = self.pop_f32().to_float() as f64;
+                self.push_i64(checked_trunc(value, -1.0, U64_MAX_EXCLUSIVE)? as u64 as i64);
+            }
+            InstructionNode::I64TruncF64S(_) => {
+                let value = self.pop_f64().to_float();
+                self.push_i64(checked_trunc(value, I64_MIN_EXCLUSIVE, I64_MAX_EXCLUSIVE)? as i64);
+            }
+            InstructionNode::I64TruncF64U(_) => {
+                let value = self.pop_f64().to_float();
+                self.push_i64(checked_trunc(value, -1.0, U64_MAX_EXCLUSIVE)? as u64 as i64);
+            }
+            // `as` from an integer to a float rounds to nearest, ties to even
+            InstructionNode::F32ConvertI32S(_) => {
+                let value = self.pop_i32();
+                self.push_f32(F32::from(value as f32));
+            }
+            InstructionNode::F32ConvertI32U(_) => {
+                let value = self.pop_i32();
+                self.push_f32(F32::from(value as u32 as f32));
+            }
+            InstructionNode::F32ConvertI64S(_) => {
+                let value = self.pop_i64();
+                self.push_f32(F32::from(value as f32));
+            }
+            InstructionNode::F32ConvertI64U(_) => {
+                let value = self.pop_i64();
+                self.push_f32(F32::from(value as u64 as f32));
+            }
+            InstructionNode::F32DemoteF64(_) => {
+                let value = self.pop_f64();
+                self.push_f32(F32::from(value.to_float() as f32));
+            }
+            InstructionNode::F64ConvertI32S(_) => {
+                let value = self.pop_i32();
+                self.push_f64(F64::from(value as f64));
+            }
+            InstructionNode::F64ConvertI32U(_) => {
+                let value = self.pop_i32();
+                self.push_f64(F64::from(value as u32 as f64));
+            }
+            InstructionNode::F64ConvertI64S(_) => {
+                let value = self.pop_i64();
+                self.push_f64(F64::from(value as f64));
+            }
+            InstructionNode::F64ConvertI64U(_) => {
+                let value = self.pop_i64();
+                self.push_f64(F64::from(value as u64 as f64));
+            }
+            InstructionNode::F64PromoteF32(_) => {
+                let value = self.pop_f32();
+                self.push_f64(F64::from(value.to_float() as f64));
+            }
+            InstructionNode::I32ReinterpretF32(_) => {
+                let value = self.pop_f32();
+                self.push_i32(value.to_bits() as i32);
+            }
+            InstructionNode::I64ReinterpretF64(_) => {
+                let value = self.pop_f64();
+                self.push_i64(value.to_bits() as i64);
+            }
+            InstructionNode::F32ReinterpretI32(_) => {
+                let value = self.pop_i32();
+                self.push_f32(F32::from_bits(value as u32));
+            }
+            InstructionNode::F64ReinterpretI64(_) => {
+                let value = self.pop_i64();
+                self.push_f64(F64::from_bits(value as u64));
+            }
+            InstructionNode::I32Extend8S(_) => self.i32_unary(|v| v as i8 as i32),
+            InstructionNode::I32Extend16S(_) => self.i32_unary(|v| v as i16 as i32),
+            InstructionNode::I64Extend8S(_) => self.i64_unary(|v| v as i8 as i64),
+            InstructionNode::I64Extend16S(_) => self.i64_unary(|v| v as i16 as i64),
+            InstructionNode::I64Extend32S(_) => self.i64_unary(|v| v as i32 as i64),
+            // saturating truncation clamps to the target range and maps NaN to 0,
+            // which is exactly what `as` does from a float to an integer
+            InstructionNode::I32TruncSatF32S(_) => {
+                let value = self.pop_f32().to_float();
+                self.push_i32(value as i32);
+            }
+            InstructionNode::I32TruncSatF32U(_) => {
+                let value = self.pop_f32().to_float();
+                self.push_i32(value as u32 as i32);
+            }
+            InstructionNode::I32TruncSatF64S(_) => {
+                let value = self.pop_f64().to_float();
+                self.push_i32(value as i32);
+            }
+            InstructionNode::I32TruncSatF64U(_) => {
+                let value = self.pop_f64().to_float();
+                self.push_i32(value as u32 as i32);
+            }
+            InstructionNode::I64TruncSatF32S(_) => {
+                let value = self.pop_f32().to_float();
+                self.push_i64(value as i64);
+            }
+            InstructionNode::I64TruncSatF32U(_) => {
+                let value = self.pop_f32().to_float();
+                self.push_i64(value as u64 as i64);
+            }
+            InstructionNode::I64TruncSatF64S(_) => {
+                let value = self.pop_f64().to_float();
+                self.push_i64(value as i64);
+            }
+            InstructionNode::I64TruncSatF64U(_) => {
+                let value = self.pop_f64().to_float();
+                self.push_i64(value as u64 as i64);
+            }
+            // lanes are numbered from the lowest byte, as vectors are stored little-endian
+            InstructionNode::V128Load(node) => {
+                let bytes = self.load::<16>(node.memarg)?;
+                self.push_v128(V128::from_le_bytes(bytes));
+            }
+            InstructionNode::V128Load8x8S(node) => {
+                let value = self.load_half(node.memarg)?;
+                self.push_v128(value.extend_low::<i8, i16>());
+            }
+            InstructionNode::V128Load8x8U(node) => {
+                let value = self.load_half(node.memarg)?;
+                self.push_v128(value.extend_low::<u8, u16>());
+            }
+            InstructionNode::V128Load16x4S(node) => {
+                let value = self.load_half(node.memarg)?;
+                self.push_v128(value.extend_low::<i16, i32>());
+            }
+            InstructionNode::V128Load16x4U(node) => {
+                let value = self.load_half(node.memarg)?;
+                self.push_v128(value.extend_low::<u16, u32>());
+            }
+            InstructionNode::V128Load32x2S(node) => {
+                let value = self.load_half(node.memarg)?;
+                self.push_v128(value.extend_low::<i32, i64>());
+            }
+            InstructionNode::V128Load32x2U(node) => {
+                let value = self.load_half(node.memarg)?;
+                self.push_v128(value.extend_low::<u32, u64>());
+            }
+            InstructionNode::V128Load8Splat(node) => {
+                let bytes = self.load::<1>(node.memarg)?;
+                self.push_v128(V128::splat(u8::from_le_bytes(bytes)));
+            }
+            InstructionNode::V128Load16Splat(node) => {
+                let bytes = self.load::<2>(node.memarg)?;
+                self.push_v128(V128::splat(u16::from_le_bytes(bytes)));
+            }
+            InstructionNode::V128Load32Splat(node) => {
+                let bytes = self.load::<4>(node.memarg)?;
+                self.push_v128(V128::splat(u32::from_le_bytes(bytes)));
+            }
+            InstructionNode::V128Load64Splat(node) => {
+                let bytes = self.load::<8>(node.memarg)?;
+                self.push_v128(V128::splat(u64::from_le_bytes(bytes)));
+            }
+            InstructionNode::V128Store(node) => {
+                let value = self.pop_v128();
+                self.store(node.memarg, &value.to_le_bytes())?;
+            }
+            InstructionNode::V128Const(node) => self.push_v128(node.value),
+            InstructionNode::I8x16Shuffle(node) => {
+                self.v128_binary(|lhs, rhs| lhs.shuffle(rhs, &node.lanes))
+            }
+            InstructionNode::I8x16Swizzle(_) => self.v128_binary(|lhs, rhs| lhs.swizzle(rhs)),
+            InstructionNode::I8x16Splat(_) => {
+                let value = self.pop_i32();
+                self.push_v128(V128::splat(value as i8));
+            }
+            InstructionNode::I16x8Splat(_) => {
+                let value = self.pop_i32();
+                self.push_v128(V128::splat(value as i16));
+            }
+            InstructionNode::I32x4Splat(_) => {
+                let value = self.pop_i32();
+                self.push_v128(V128::splat(value));
+            }
+            InstructionNode::I64x2Splat(_) => {
+                let value = self.pop_i64();
+                self.push_v128(V128::splat(value));
+            }
+            InstructionNode::F32x4Splat(_) => {
+                let value = self.pop_f32();
+                self.push_v128(V128::splat(value));
+            }
+            InstructionNode::F64x2Splat(_) => {
+                let value = self.pop_f64();
+                self.push_v128(V128::splat(value));
+            }
+            InstructionNode::I8x16ExtractLaneS(node) => {
+                let value = self.pop_v128();
+                self.push_i32(value.lane::<i8>(node.lane) as i32);
+            }
+            InstructionNode::I8x16ExtractLaneU(node) => {
+                let value = self.pop_v128();
+                self.push_i32(value.lane::<u8>(node.lane) as i32);
+            }
+            InstructionNode::I8x16ReplaceLane(node) => {
+                let lane = self.pop_i32();
+                let value = self.pop_v128();
+                self.push_v128(value.replace_lane(node.lane, lane as i8));
+            }
+            InstructionNode::I16x8ExtractLaneS(node) => {
+                let value = self.pop_v128();
+                self.push_i32(value.lane::<i16>(node.lane) as i32);
+            }
+            InstructionNode::I16x8ExtractLaneU(node) => {
+                let value = self.pop_v128();
+                self.push_i32(value.lane::<u16>(node.lane) as i32);
+            }
+            InstructionNode::I16x8ReplaceLane(node) => {
+                let lane = self.pop_i32();
+                let value = self.pop_v128();
+                self.push_v128(value.replace_lane(node.lane, lane as i16));
+            }
+            InstructionNode::I32x4ExtractLane(node) => {
+                let value = self.pop_v128();
+                self.push_i32(value.lane::<i32>(node.lane));
+            }
+            InstructionNode::I32x4ReplaceLane(node) => {
+                let lane = self.pop_i32();
+                let value = self.pop_v128();
+                self.push_v128(value.replace_lane(node.lane, lane));
+            }
+            InstructionNode::I64x2ExtractLane(node) => {
+                let value = self.pop_v128();
+                self.push_i64(value.lane::<i64>(node.lane));
+            }
+            InstructionNode::I64x2ReplaceLane(node) => {
+                let lane = self.pop_i64();
+                let value = self.pop_v128();
+                self.push_v128(value.replace_lane(node.lane, lane));
+            }
+            InstructionNode::F32x4ExtractLane(node) => {
+                let value = self.pop_v128();
+                self.push_f32(value.lane::<F32>(node.lane));
+            }
+            InstructionNode::F32x4ReplaceLane(node) => {
+                let lane = self.pop_f32();
+                let value = self.pop_v128();
+                self.push_v128(value.replace_lane(node.lane, lane));
+            }
+            InstructionNode::F64x2ExtractLane(node) => {
+                let value = self.pop_v128();
+                self.push_f64(value.lane::<F64>(node.lane));
+            }
+            InstructionNode::F64x2ReplaceLane(node) => {
+                let lane = self.pop_f64();
+                let value = self.pop_v128();
+                self.push_v128(value.replace_lane(node.lane, lane));
+            }
+            InstructionNode::I8x16Eq(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i8, rhs| lhs == rhs))
+            }
+            InstructionNode::I8x16Ne(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i8, rhs| lhs != rhs))
+            }
+            InstructionNode::I8x16LtS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i8, rhs| lhs < rhs))
+            }
+            InstructionNode::I8x16LtU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: u8, rhs| lhs < rhs))
+            }
+            InstructionNode::I8x16GtS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i8, rhs| lhs > rhs))
+            }
+            InstructionNode::I8x16GtU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: u8, rhs| lhs > rhs))
+            }
+            InstructionNode::I8x16LeS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i8, rhs| lhs <= rhs))
+            }
+            InstructionNode::I8x16LeU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: u8, rhs| lhs <= rhs))
+            }
+            InstructionNode::I8x16GeS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i8, rhs| lhs >= rhs))
+            }
+            InstructionNode::I8x16GeU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: u8, rhs| lhs >= rhs))
+            }
+            InstructionNode::I16x8Eq(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i16, rhs| lhs == rhs))
+            }
+            InstructionNode::I16x8Ne(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i16, rhs| lhs != rhs))
+            }
+            InstructionNode::I16x8LtS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i16, rhs| lhs < rhs))
+            }
+            InstructionNode::I16x8LtU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: u16, rhs| lhs < rhs))
+            }
+            InstructionNode::I16x8GtS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i16, rhs| lhs > rhs))
+            }
+            InstructionNode::I16x8GtU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: u16, rhs| lhs > rhs))
+            }
+            InstructionNode::I16x8LeS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i16, rhs| lhs <= rhs))
+            }
+            InstructionNode::I16x8LeU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: u16, rhs| lhs <= rhs))
+            }
+            InstructionNode::I16x8GeS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i16, rhs| lhs >= rhs))
+            }
+            InstructionNode::I16x8GeU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: u16, rhs| lhs >= rhs))
+            }
+            InstructionNode::I32x4Eq(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i32, rhs| lhs == rhs))
+            }
+            InstructionNode::I32x4Ne(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i32, rhs| lhs != rhs))
+            }
+            InstructionNode::I32x4LtS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i32, rhs| lhs < rhs))
+            }
+            InstructionNode::I32x4LtU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: u32, rhs| lhs < rhs))
+            }
+            InstructionNode::I32x4GtS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i32, rhs| lhs > rhs))
+            }
+            InstructionNode::I32x4GtU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: u32, rhs| lhs > rhs))
+            }
+            InstructionNode::I32x4LeS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i32, rhs| lhs <= rhs))
+            }
+            InstructionNode::I32x4LeU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: u32, rhs| lhs <= rhs))
+            }
+            InstructionNode::I32x4GeS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i32, rhs| lhs >= rhs))
+            }
+            InstructionNode::I32x4GeU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: u32, rhs| lhs >= rhs))
+            }
+            InstructionNode::F32x4Eq(_) => self.v128_binary(|lhs, rhs| {
+                lhs.compare(rhs, |lhs: F32, rhs: F32| lhs.to_float() == rhs.to_float())
+            }),
+            InstructionNode::F32x4Ne(_) => self.v128_binary(|lhs, rhs| {
+                lhs.compare(rhs, |lhs: F32, rhs: F32| lhs.to_float() != rhs.to_float())
+            }),
+            InstructionNode::F32x4Lt(_) => self.v128_binary(|lhs, rhs| {
+                lhs.compare(rhs, |lhs: F32, rhs: F32| lhs.to_float() < rhs.to_float())
+            }),
+            InstructionNode::F32x4Gt(_) => self.v128_binary(|lhs, rhs| {
+                lhs.compare(rhs, |lhs: F32, rhs: F32| lhs.to_float() > rhs.to_float())
+            }),
+            InstructionNode::F32x4Le(_) => self.v128_binary(|lhs, rhs| {
+                lhs.compare(rhs, |lhs: F32, rhs: F32| lhs.to_float() <= rhs.to_float())
+            }),
+            InstructionNode::F32x4Ge(_) => self.v128_binary(|lhs, rhs| {
+                lhs.compare(rhs, |lhs: F32, rhs: F32| lhs.to_float() >= rhs.to_float())
+            }),
+            InstructionNode::F64x2Eq(_) => self.v128_binary(|lhs, rhs| {
+                lhs.compare(rhs, |lhs: F64, rhs: F64| lhs.to_float() == rhs.to_float())
+            }),
+            InstructionNode::F64x2Ne(_) => self.v128_binary(|lhs, rhs| {
+                lhs.compare(rhs, |lhs: F64, rhs: F64| lhs.to_float() != rhs.to_float())
+            }),
+            InstructionNode::F64x2Lt(_) => self.v128_binary(|lhs, rhs| {
+                lhs.compare(rhs, |lhs: F64, rhs: F64| lhs.to_float() < rhs.to_float())
+            }),
+            InstructionNode::F64x2Gt(_) => self.v128_binary(|lhs, rhs| {
+                lhs.compare(rhs, |lhs: F64, rhs: F64| lhs.to_float() > rhs.to_float())
+            }),
+            InstructionNode::F64x2Le(_) => self.v128_binary(|lhs, rhs| {
+                lhs.compare(rhs, |lhs: F64, rhs: F64| lhs.to_float() <= rhs.to_float())
+            }),
+            InstructionNode::F64x2Ge(_) => self.v128_binary(|lhs, rhs| {
+                lhs.compare(rhs, |lhs: F64, rhs: F64| lhs.to_float() >= rhs.to_float())
+            }),
+            InstructionNode::V128Not(_) => {
+                self.v128_unary(|value| V128::from_bits(!value.to_bits()))
+            }
+            InstructionNode::V128And(_) => {
+                self.v128_binary(|lhs, rhs| V128::from_bits(lhs.to_bits() & rhs.to_bits()))
+            }
+            InstructionNode::V128Andnot(_) => {
+                self.v128_binary(|lhs, rhs| V128::from_bits(lhs.to_bits() & !rhs.to_bits()))
+            }
+            InstructionNode::V128Or(_) => {
+                self.v128_binary(|lhs, rhs| V128::from_bits(lhs.to_bits() | rhs.to_bits()))
+            }
+            InstructionNode::V128Xor(_) => {
+                self.v128_binary(|lhs, rhs| V128::from_bits(lhs.to_bits() ^ rhs.to_bits()))
+            }
+            InstructionNode::V128Bitselect(_) => {
+                let mask = self.pop_v128().to_bits();
+                self.v128_binary(|lhs, rhs| {
+                    V128::from_bits((lhs.to_bits() & mask) | (rhs.to_bits() & !mask))
+                });
+            }
+            InstructionNode::V128AnyTrue(_) => {
+                self.v128_reduce(|value| (value.to_bits() != 0) as i32)
+            }
+            InstructionNode::V128Load8Lane(node) => {
+                let value = self.pop_v128();
+                let bytes = self.load::<1>(node.memarg)?;
+                self.push_v128(value.replace_lane(node.lane, u8::from_le_bytes(bytes)));
+            }
+            InstructionNode::V128Load16Lane(node) => {
+                let value = self.pop_v128();
+                let bytes = self.load::<2>(node.memarg)?;
+                self.push_v128(value.replace_lane(node.lane, u16::from_le_bytes(bytes)));
+            }
+            InstructionNode::V128Load32Lane(node) => {
+                let value = self.pop_v128();
+                let bytes = self.load::<4>(node.memarg)?;
+                self.push_v128(value.replace_lane(node.lane, u32::from_le_bytes(bytes)));
+            }
+            InstructionNode::V128Load64Lane(node) => {
+                let value = self.pop_v128();
+                let bytes = self.load::<8>(node.memarg)?;
+                self.push_v128(value.replace_lane(node.lane, u64::from_le_bytes(bytes)));
+            }
+            InstructionNode::V128Store8Lane(node) => {
+                let value = self.pop_v128();
+                let lane: u8 = value.lane(node.lane);
+                self.store(node.memarg, &lane.to_le_bytes())?;
+            }
+            InstructionNode::V128Store16Lane(node) => {
+                let value = self.pop_v128();
+                let lane: u16 = value.lane(node.lane);
+                self.store(node.memarg, &lane.to_le_bytes())?;
+            }
+            InstructionNode::V128Store32Lane(node) => {
+                let value = self.pop_v128();
+                let lane: u32 = value.lane(node.lane);
+                self.store(node.memarg, &lane.to_le_bytes())?;
+            }
+            InstructionNode::V128Store64Lane(node) => {
+                let value = self.pop_v128();
+                let lane: u64 = value.lane(node.lane);
+                self.store(node.memarg, &lane.to_le_bytes())?;
+            }
+            InstructionNode::V128Load32Zero(node) => {
+                let bytes = self.load::<4>(node.memarg)?;
+                self.push_v128(V128::from_bits(u32::from_le_bytes(bytes) as u128));
+            }
+            InstructionNode::V128Load64Zero(node) => {
+                let bytes = self.load::<8>(node.memarg)?;
+                self.push_v128(V128::from_bits(u64::from_le_bytes(bytes) as u128));
+            }
+            InstructionNode::F32x4DemoteF64x2Zero(_) => self.v128_unary(|value| {
+                let lanes = value.lanes::<F64>().into_iter();
+                let demoted = lanes.map(|lane| F32::from(lane.to_float() as f32));
+                V128::from_lanes(demoted.chain([F32::default(); 2]))
+            }),
+            InstructionNode::F64x2PromoteLowF32x4(_) => self.v128_unary(|value| {
+                V128::from_lanes(
+                    value
+                        .low_half::<F32>()
+                        .into_iter()
+                        .map(|lane| F64::from(lane.to_float() as f64)),
+                )
+            }),
+            InstructionNode::I8x16Abs(_) => self.v128_unary(|value| value.map(i8::wrapping_abs)),
+            InstructionNode::I8x16Neg(_) => self.v128_unary(|value| value.map(i8::wrapping_neg)),
+            InstructionNode::I8x16Popcnt(_) => {
+                self.v128_unary(|value| value.map(|lane: u8| lane.count_ones() as u8))
+            }
+            InstructionNode::I8x16AllTrue(_) => {
+                self.v128_reduce(|value| value.all_true::<i8>() as i32)
+            }
+            InstructionNode::I8x16Bitmask(_) => self.v128_reduce(|value| value.bitmask::<i8>()),
+            InstructionNode::I8x16NarrowI16x8S(_) => self.v128_binary(|lhs, rhs| {
+                lhs.narrow(rhs, |lane: i16| {
+                    lane.clamp(i8::MIN as i16, i8::MAX as i16) as i8
+                })
+            }),
+            InstructionNode::I8x16NarrowI16x8U(_) => self.v128_binary(|lhs, rhs| {
+                lhs.narrow(rhs, |lane: i16| lane.clamp(0, u8::MAX as i16) as u8)
+            }),
+            InstructionNode::F32x4Ceil(_) => self.v128_unary(|value| value.map(F32::ceil)),
+            InstructionNode::F32x4Floor(_) => self.v128_unary(|value| value.map(F32::floor)),
+            InstructionNode::F32x4Trunc(_) => self.v128_unary(|value| value.map(F32::trunc)),
+            InstructionNode::F32x4Nearest(_) => self.v128_unary(|value| value.map(F32::nearest)),
+            InstructionNode::I8x16Shl(_) => {
+                self.v128_shift(|value, shift| value.map(|lane: i8| lane.wrapping_shl(shift)))
+            }
+            InstructionNode::I8x16ShrS(_) => {
+                self.v128_shift(|value, shift| value.map(|lane: i8| lane.wrapping_shr(shift)))
+            }
+            InstructionNode::I8x16ShrU(_) => {
+                self.v128_shift(|value, shift| value.map(|lane: u8| lane.wrapping_shr(shift)))
+            }
+            InstructionNode::I8x16Add(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i8::wrapping_add))
+            }
+            InstructionNode::I8x16AddSatS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i8::saturating_add))
+            }
+            InstructionNode::I8x16AddSatU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, u8::saturating_add))
+            }
+            InstructionNode::I8x16Sub(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i8::wrapping_sub))
+            }
+            InstructionNode::I8x16SubSatS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i8::saturating_sub))
+            }
+            InstructionNode::I8x16SubSatU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, u8::saturating_sub))
+            }
+            InstructionNode::F64x2Ceil(_) => self.v128_unary(|value| value.map(F64::ceil)),
+            InstructionNode::F64x2Floor(_) => self.v128_unary(|value| value.map(F64::floor)),
+            InstructionNode::I8x16MinS(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, i8::min)),
+            InstructionNode::I8x16MinU(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, u8::min)),
+            InstructionNode::I8x16MaxS(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, i8::max)),
+            InstructionNode::I8x16MaxU(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, u8::max)),
+            InstructionNode::F64x2Trunc(_) => self.v128_unary(|value| value.map(F64::trunc)),
+            InstructionNode::I8x16AvgrU(_) => self.v128_binary(|lhs, rhs| {
+                lhs.zip(rhs, |lhs: u8, rhs| {
+                    (lhs as u16 + rhs as u16).div_ceil(2) as u8
+                })
+            }),
+            InstructionNode::I16x8ExtaddPairwiseI8x16S(_) => {
+                self.v128_unary(|value| value.extadd_pairwise::<i8, i16>())
+            }
+            InstructionNode::I16x8ExtaddPairwiseI8x16U(_) => {
+                self.v128_unary(|value| value.extadd_pairwise::<u8, u16>())
+            }
+            InstructionNode::I32x4ExtaddPairwiseI16x8S(_) => {
+                self.v128_unary(|value| value.extadd_pairwise::<i16, i32>())
+            }
+            InstructionNode::I32x4ExtaddPairwiseI16x8U(_) => {
+                self.v128_unary(|value| value.extadd_pairwise::<u16, u32>())
+            }
+            InstructionNode::I16x8Abs(_) => self.v128_unary(|value| value.map(i16::wrapping_abs)),
+            InstructionNode::I16x8Neg(_) => self.v128_unary(|value| value.map(i16::wrapping_neg)),
+            InstructionNode::I16x8Q15mulrSatS(_) => self.v128_binary(|lhs, rhs| {
+                lhs.zip(rhs, |lhs: i16, rhs| {
+                    let product = (lhs as i32 * rhs as i32 + 0x4000) >> 15;
+                    product.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+                })
+            }),
+            InstructionNode::I16x8AllTrue(_) => {
+                self.v128_reduce(|value| value.all_true::<i16>() as i32)
+            }
+            InstructionNode::I16x8Bitmask(_) => self.v128_reduce(|value| value.bitmask::<i16>()),
+            InstructionNode::I16x8NarrowI32x4S(_) => self.v128_binary(|lhs, rhs| {
+                lhs.narrow(rhs, |lane: i32| {
+                    lane.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+                })
+            }),
+            InstructionNode::I16x8NarrowI32x4U(_) => self.v128_binary(|lhs, rhs| {
+                lhs.narrow(rhs, |lane: i32| lane.clamp(0, u16::MAX as i32) as u16)
+            }),
+            InstructionNode::I16x8ExtendLowI8x16S(_) => {
+                self.v128_unary(|value| value.extend_low::<i8, i16>())
+            }
+            InstructionNode::I16x8ExtendHighI8x16S(_) => {
+                self.v128_unary(|value| value.extend_high::<i8, i16>())
+            }
+            InstructionNode::I16x8ExtendLowI8x16U(_) => {
+                self.v128_unary(|value| value.extend_low::<u8, u16>())
+            }
+            InstructionNode::I16x8ExtendHighI8x16U(_) => {
+                self.v128_unary(|value| value.extend_high::<u8, u16>())
+            }
+            InstructionNode::I16x8Shl(_) => {
+                self.v128_shift(|value, shift| value.map(|lane: i16| lane.wrapping_shl(shift)))
+            }
+            InstructionNode::I16x8ShrS(_) => {
+                self.v128_shift(|value, shift| value.map(|lane: i16| lane.wrapping_shr(shift)))
+            }
+            InstructionNode::I16x8ShrU(_) => {
+                self.v128_shift(|value, shift| value.map(|lane: u16| lane.wrapping_shr(shift)))
+            }
+            InstructionNode::I16x8Add(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i16::wrapping_add))
+            }
+            InstructionNode::I16x8AddSatS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i16::saturating_add))
+            }
+            InstructionNode::I16x8AddSatU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, u16::saturating_add))
+            }
+            InstructionNode::I16x8Sub(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i16::wrapping_sub))
+            }
+            InstructionNode::I16x8SubSatS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i16::saturating_sub))
+            }
+            InstructionNode::I16x8SubSatU(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, u16::saturating_sub))
+            }
+            InstructionNode::F64x2Nearest(_) => self.v128_unary(|value| value.map(F64::nearest)),
+            InstructionNode::I16x8Mul(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i16::wrapping_mul))
+            }
+            InstructionNode::I16x8MinS(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, i16::min)),
+            InstructionNode::I16x8MinU(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, u16::min)),
+            InstructionNode::I16x8MaxS(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, i16::max)),
+            InstructionNode::I16x8MaxU(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, u16::max)),
+            InstructionNode::I16x8AvgrU(_) => self.v128_binary(|lhs, rhs| {
+                lhs.zip(rhs, |lhs: u16, rhs| {
+                    (lhs as u32 + rhs as u32).div_ceil(2) as u16
+                })
+            }),
+            InstructionNode::I16x8ExtmulLowI8x16S(_) => {
+                self.v128_binary(|lhs, rhs| lhs.extmul_low::<i8, i16>(rhs))
+            }
+            InstructionNode::I16x8ExtmulHighI8x16S(_) => {
+                self.v128_binary(|lhs, rhs| lhs.extmul_high::<i8, i16>(rhs))
+            }
+            InstructionNode::I16x8ExtmulLowI8x16U(_) => {
+                self.v128_binary(|lhs, rhs| lhs.extmul_low::<u8, u16>(rhs))
+            }
+            InstructionNode::I16x8ExtmulHighI8x16U(_) => {
+                self.v128_binary(|lhs, rhs| lhs.extmul_high::<u8, u16>(rhs))
+            }
+            InstructionNode::I32x4Abs(_) => self.v128_unary(|value| value.map(i32::wrapping_abs)),
+            InstructionNode::I32x4Neg(_) => self.v128_unary(|value| value.map(i32::wrapping_neg)),
+            InstructionNode::I32x4AllTrue(_) => {
+                self.v128_reduce(|value| value.all_true::<i32>() as i32)
+            }
+            InstructionNode::I32x4Bitmask(_) => self.v128_reduce(|value| value.bitmask::<i32>()),
+            InstructionNode::I32x4ExtendLowI16x8S(_) => {
+                self.v128_unary(|value| value.extend_low::<i16, i32>())
+            }
+            InstructionNode::I32x4ExtendHighI16x8S(_) => {
+                self.v128_unary(|value| value.extend_high::<i16, i32>())
+            }
+            InstructionNode::I32x4ExtendLowI16x8U(_) => {
+                self.v128_unary(|value| value.extend_low::<u16, u32>())
+            }
+            InstructionNode::I32x4ExtendHighI16x8U(_) => {
+                self.v128_unary(|value| value.extend_high::<u16, u32>())
+            }
+            InstructionNode::I32x4Shl(_) => {
+                self.v128_shift(|value, shift| value.map(|lane: i32| lane.wrapping_shl(shift)))
+            }
+            InstructionNode::I32x4ShrS(_) => {
+                self.v128_shift(|value, shift| value.map(|lane: i32| lane.wrapping_shr(shift)))
+            }
+            InstructionNode::I32x4ShrU(_) => {
+                self.v128_shift(|value, shift| value.map(|lane: u32| lane.wrapping_shr(shift)))
+            }
+            InstructionNode::I32x4Add(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i32::wrapping_add))
+            }
+            InstructionNode::I32x4Sub(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i32::wrapping_sub))
+            }
+            InstructionNode::I32x4Mul(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i32::wrapping_mul))
+            }
+            InstructionNode::I32x4MinS(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, i32::min)),
+            InstructionNode::I32x4MinU(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, u32::min)),
+            InstructionNode::I32x4MaxS(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, i32::max)),
+            InstructionNode::I32x4MaxU(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, u32::max)),
+            InstructionNode::I32x4DotI16x8S(_) => self.v128_binary(|lhs, rhs| lhs.dot(rhs)),
+            InstructionNode::I32x4ExtmulLowI16x8S(_) => {
+                self.v128_binary(|lhs, rhs| lhs.extmul_low::<i16, i32>(rhs))
+            }
+            InstructionNode::I32x4ExtmulHighI16x8S(_) => {
+                self.v128_binary(|lhs, rhs| lhs.extmul_high::<i16, i32>(rhs))
+            }
+            InstructionNode::I32x4ExtmulLowI16x8U(_) => {
+                self.v128_binary(|lhs, rhs| lhs.extmul_low::<u16, u32>(rhs))
+            }
+            InstructionNode::I32x4ExtmulHighI16x8U(_) => {
+                self.v128_binary(|lhs, rhs| lhs.extmul_high::<u16, u32>(rhs))
+            }
+            InstructionNode::I64x2Abs(_) => self.v128_unary(|value| value.map(i64::wrapping_abs)),
+            InstructionNode::I64x2Neg(_) => self.v128_unary(|value| value.map(i64::wrapping_neg)),
+            InstructionNode::I64x2AllTrue(_) => {
+                self.v128_reduce(|value| value.all_true::<i64>() as i32)
+            }
+            InstructionNode::I64x2Bitmask(_) => self.v128_reduce(|value| value.bitmask::<i64>()),
+            InstructionNode::I64x2ExtendLowI32x4S(_) => {
+                self.v128_unary(|value| value.extend_low::<i32, i64>())
+            }
+            InstructionNode::I64x2ExtendHighI32x4S(_) => {
+                self.v128_unary(|value| value.extend_high::<i32, i64>())
+            }
+            InstructionNode::I64x2ExtendLowI32x4U(_) => {
+                self.v128_unary(|value| value.extend_low::<u32, u64>())
+            }
+            InstructionNode::I64x2ExtendHighI32x4U(_) => {
+                self.v128_unary(|value| value.extend_high::<u32, u64>())
+            }
+            InstructionNode::I64x2Shl(_) => {
+                self.v128_shift(|value, shift| value.map(|lane: i64| lane.wrapping_shl(shift)))
+            }
+            InstructionNode::I64x2ShrS(_) => {
+                self.v128_shift(|value, shift| value.map(|lane: i64| lane.wrapping_shr(shift)))
+            }
+            InstructionNode::I64x2ShrU(_) => {
+                self.v128_shift(|value, shift| value.map(|lane: u64| lane.wrapping_shr(shift)))
+            }
+            InstructionNode::I64x2Add(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i64::wrapping_add))
+            }
+            InstructionNode::I64x2Sub(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i64::wrapping_sub))
+            }
+            InstructionNode::I64x2Mul(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, i64::wrapping_mul))
+            }
+            InstructionNode::I64x2Eq(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i64, rhs| lhs == rhs))
+            }
+            InstructionNode::I64x2Ne(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i64, rhs| lhs != rhs))
+            }
+            InstructionNode::I64x2LtS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i64, rhs| lhs < rhs))
+            }
+            InstructionNode::I64x2GtS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i64, rhs| lhs > rhs))
+            }
+            InstructionNode::I64x2LeS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i64, rhs| lhs <= rhs))
+            }
+            InstructionNode::I64x2GeS(_) => {
+                self.v128_binary(|lhs, rhs| lhs.compare(rhs, |lhs: i64, rhs| lhs >= rhs))
+            }
+            InstructionNode::I64x2ExtmulLowI32x4S(_) => {
+                self.v128_binary(|lhs, rhs| lhs.extmul_low::<i32, i64>(rhs))
+            }
+            InstructionNode::I64x2ExtmulHighI32x4S(_) => {
+                self.v128_binary(|lhs, rhs| lhs.extmul_high::<i32, i64>(rhs))
+            }
+            InstructionNode::I64x2ExtmulLowI32x4U(_) => {
+                self.v128_binary(|lhs, rhs| lhs.extmul_low::<u32, u64>(rhs))
+            }
+            InstructionNode::I64x2ExtmulHighI32x4U(_) => {
+                self.v128_binary(|lhs, rhs| lhs.extmul_high::<u32, u64>(rhs))
+            }
+            InstructionNode::F32x4Abs(_) => self.v128_unary(|value| value.map(F32::abs)),
+            InstructionNode::F32x4Neg(_) => self.v128_unary(|value| value.map(|lane: F32| -lane)),
+            InstructionNode::F32x4Sqrt(_) => self.v128_unary(|value| value.map(F32::sqrt)),
+            InstructionNode::F32x4Add(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, |lhs: F32, rhs| lhs + rhs))
+            }
+            InstructionNode::F32x4Sub(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, |lhs: F32, rhs| lhs - rhs))
+            }
+            InstructionNode::F32x4Mul(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, |lhs: F32, rhs| lhs * rhs))
+            }
+            InstructionNode::F32x4Div(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, |lhs: F32, rhs| lhs / rhs))
+            }
+            InstructionNode::F32x4Min(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, F32::min)),
+            InstructionNode::F32x4Max(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, F32::max)),
+            // pseudo-min and -max are a plain `<`, which keeps lhs when either is NaN
+            InstructionNode::F32x4Pmin(_) => self.v128_binary(|lhs, rhs| {
+                lhs.zip(rhs, |lhs: F32, rhs: F32| {
+                    if rhs.to_float() < lhs.to_float() {
+                        rhs
+                    } else {
+                        lhs
+                    }
+                })
+            }),
+            InstructionNode::F32x4Pmax(_) => self.v128_binary(|lhs, rhs| {
+                lhs.zip(rhs, |lhs: F32, rhs: F32| {
+                    if lhs.to_float() < rhs.to_float() {
+                        rhs
+                    } else {
+                        lhs
+                    }
+                })
+            }),
+            InstructionNode::F64x2Abs(_) => self.v128_unary(|value| value.map(F64::abs)),
+            InstructionNode::F64x2Neg(_) => self.v128_unary(|value| value.map(|lane: F64| -lane)),
+            InstructionNode::F64x2Sqrt(_) => self.v128_unary(|value| value.map(F64::sqrt)),
+            InstructionNode::F64x2Add(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, |lhs: F64, rhs| lhs + rhs))
+            }
+            InstructionNode::F64x2Sub(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, |lhs: F64, rhs| lhs - rhs))
+            }
+            InstructionNode::F64x2Mul(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, |lhs: F64, rhs| lhs * rhs))
+            }
+            InstructionNode::F64x2Div(_) => {
+                self.v128_binary(|lhs, rhs| lhs.zip(rhs, |lhs: F64, rhs| lhs / rhs))
+            }
+            InstructionNode::F64x2Min(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, F64::min)),
+            InstructionNode::F64x2Max(_) => self.v128_binary(|lhs, rhs| lhs.zip(rhs, F64::max)),
+            InstructionNode::F64x2Pmin(_) => self.v128_binary(|lhs, rhs| {
+                lhs.zip(rhs, |lhs: F64, rhs: F64| {
+                    if rhs.to_float() < lhs.to_float() {
+                        rhs
+                    } else {
+                        lhs
+                    }
+                })
+            }),
+            InstructionNode::F64x2Pmax(_) => self.v128_binary(|lhs, rhs| {
+                lhs.zip(rhs, |lhs: F64, rhs: F64| {
+                    if lhs.to_float() < rhs.to_float() {
+                        rhs
+                    } else {
+                        lhs
+                    }
+                })
+            }),
+            // like the scalar trunc_sat, `as` saturates and maps NaN to 0
+            InstructionNode::I32x4TruncSatF32x4S(_) => {
+                self.v128_unary(|value| value.convert(|lane: F32| lane.to_float() as i32))
+            }
+            InstructionNode::I32x4TruncSatF32x4U(_) => {
+                self.v128_unary(|value| value.convert(|lane: F32| lane.to_float() as u32))
+            }
+            InstructionNode::F32x4ConvertI32x4S(_) => {
+                self.v128_unary(|value| value.convert(|lane: i32| F32::from(lane as f32)))
+            }
+            InstructionNode::F32x4ConvertI32x4U(_) => {
+                self.v128_unary(|value| value.convert(|lane: u32| F32::from(lane as f32)))
+            }
+            InstructionNode::I32x4TruncSatF64x2SZero(_) => self.v128_unary(|value| {
+                let lanes = value.lanes::<F64>().into_iter();
+                V128::from_lanes(lanes.map(|lane| lane.to_float() as i32).chain([0, 0]))
+            }),
+            InstructionNode::I32x4TruncSatF64x2UZero(_) => self.v128_unary(|value| {
+                let lanes = value.lanes::<F64>().into_iter();
+                V128::from_lanes(lanes.map(|lane| lane.to_float() as u32).chain([0, 0]))
+            }),
+            InstructionNode::F64x2ConvertLowI32x4S(_) => self.v128_unary(|value| {
+                V128::from_lanes(
+                    value
+                        .low_half::<i32>()
+                        .into_iter()
+                        .map(|lane| F64::from(lane as f64)),
+                )
+            }),
+            InstructionNode::F64x2ConvertLowI32x4U(_) => self.v128_unary(|value| {
+                V128::from_lanes(
+                    value
+                        .low_half::<u32>()
+                        .into_iter()
+                        .map(|lane| F64::from(lane as f64)),
+                )
+            }),
+            InstructionNode::I32Load(node) => {
+                let bytes = self.load::<4>(node.memarg)?;
+                self.push_stack(StackEntry::value(Value::num(Number::i32(
+                    i32::from_le_bytes(bytes),
+                ))));
+            }
+            InstructionNode::I64Load(node) => {
+                let bytes = self.load::<8>(node.memarg)?;
+                self.push_stack(StackEntry::value(Value::num(Number::i64(
+                    i64::from_le_bytes(bytes),
+                ))));
+            }
+            InstructionNode::F32Load(node) => {
+                let bytes = self.load::<4>(node.memarg)?;
+                self.push_f32(F32::from_bits(u32::from_le_bytes(bytes)));
+            }
+            InstructionNode::F64Load(node) => {
+                let bytes = self.load::<8>(node.memarg)?;
+                self.push_f64(F64::from_bits(u64::from_le_bytes(bytes)));
+            }
+            InstructionNode::I32Load8S(node) => {
+                let bytes = self.load::<1>(node.memarg)?;
+                self.push_stack(StackEntry::value(Value::num(Number::i32(
+                    i8::from_le_bytes(bytes) as i32,
+                ))));
+            }
+            InstructionNode::I32Load8U(node) => {
+                let bytes = self.load::<1>(node.memarg)?;
+                self.push_stack(StackEntry::value(Value::num(Number::i32(
+                    u8::from_le_bytes(bytes) as i32,
+                ))));
+            }
+            InstructionNode::I32Load16S(node) => {
+                let bytes = self.load::<2>(node.memarg)?;
+                self.push_stack(StackEntry::value(Value::num(Number::i32(
+                    i16::from_le_bytes(bytes) as i32,
+                ))));
+            }
+            InstructionNode::I32Load16U(node) => {
+                let bytes = self.load::<2>(node.memarg)?;
+                self.push_stack(StackEntry::value(Value::num(Number::i32(
+                    u16::from_le_bytes(bytes) as i32,
+                ))));
+            }
+            InstructionNode::I64Load8S(node) => {
+                let bytes = self.load::<1>(node.memarg)?;
+                self.push_stack(StackEntry::value(Value::num(Number::i64(
+                    i8::from_le_bytes(bytes) as i64,
+                ))));
+            }
+            InstructionNode::I64Load8U(node) => {
+                let bytes = self.load::<1>(node.memarg)?;
+                self.push_stack(StackEntry::value(Value::num(Number::i64(
+                    u8::from_le_bytes(bytes) as i64,
+                ))));
+            }
+            InstructionNode::I64Load16S(node) => {
+                let bytes = self.load::<2>(node.memarg)?;
+                self.push_stack(StackEntry::value(Value::num(Number::i64(
+                    i16::from_le_bytes(bytes) as i64,
+                ))));
+            }
+            InstructionNode::I64Load16U(node) => {
+                let bytes = self.load::<2>(node.memarg)?;
+                self.push_stack(StackEntry::value(Value::num(Number::i64(
+                    u16::from_le_bytes(bytes) as i64,
+                ))));
+            }
+            InstructionNode::I64Load32S(node) => {
+                let bytes = self.load::<4>(node.memarg)?;
+                self.push_stack(StackEntry::value(Value::num(Number::i64(
+                    i32::from_le_bytes(bytes) as i64,
+                ))));
+            }
+            InstructionNode::I64Load32U(node) => {
+                let bytes = self.load::<4>(node.memarg)?;
+                self.push_stack(StackEntry::value(Value::num(Number::i64(
+                    u32::from_le_bytes(bytes) as i64,
+                ))));
+            }
+            InstructionNode::I32Store(node) => match self.pop_stack() {
+                StackEntry::value(Value::num(Number::i32(v))) => {
+                    self.store(node.memarg, &v.to_le_bytes())?;
+                }
+                _ => panic!("i32.store must have an i32 value on the stack"),
+            },
+            InstructionNode::I64Store(node) => match self.pop_stack() {
+                StackEntry::value(Value::num(Number::i64(v))) => {
+                    self.store(node.memarg, &v.to_le_bytes())?;
+                }
+                _ => panic!("i64.store must have an i64 value on the stack"),
+            },
+            InstructionNode::F32Store(node) => match self.pop_stack() {
+                StackEntry::value(Value::num(Number::f32(v))) => {
+                    self.store(node.memarg, &v.to_bits().to_le_bytes())?;
+                }
+                _ => panic!("f32.store must have an f32 value on the stack"),
+            },
+            InstructionNode::F64Store(node) => match self.pop_stack() {
+                StackEntry::value(Value::num(Number::f64(v))) => {
+                    self.store(node.memarg, &v.to_bits().to_le_bytes())?;
+                }
+                _ => panic!("f64.store must have an f64 value on the stack"),
+            },
+            InstructionNode::I32Store8(node) => match self.pop_stack() {
+                StackEntry::value(Value::num(Number::i32(v))) => {
+                    self.store(node.memarg, &(v as u8).to_le_bytes())?;
+                }
+                _ => panic!("i32.store8 must have an i32 value on the stack"),
+            },
+            InstructionNode::I32Store16(node) => match self.pop_stack() {
+                StackEntry::value(Value::num(Number::i32(v))) => {
+                    self.store(node.memarg, &(v as u16).to_le_bytes())?;
+                }
+                _ => panic!("i32.store16 must have an i32 value on the stack"),
+            },
+            InstructionNode::I64Store8(node) => match self.pop_stack() {
+                StackEntry::value(Value::num(Number::i64(v))) => {
+                    self.store(node.memarg, &(v as u8).to_le_bytes())?;
+                }
+                _ => panic!("i64.store8 must have an i64 value on the stack"),
+            },
+            InstructionNode::I64Store16(node) => match self.pop_stack() {
+                StackEntry::value(Value::num(Number::i64(v))) => {
+                    self.store(node.memarg, &(v as u16).to_le_bytes())?;
+                }
+                _ => panic!("i64.store16 must have an i64 value on the stack"),
+            },
+            InstructionNode::I64Store32(node) => match self.pop_stack() {
+                StackEntry::value(Value::num(Number::i64(v))) => {
+                    self.store(node.memarg, &(v as u32).to_le_bytes())?;
+                }
+                _ => panic!("i64.store32 must have an i64 value on the stack"),
+            },
+            InstructionNode::MemoryAtomicNotify(node) => {
+                let count = self.pop_i32() as u32;
+                let address = self.pop_address();
+                let woken = self.memory().notify(address, node.memarg.offset, count)?;
+                self.push_i32(woken as i32);
+            }
+            InstructionNode::MemoryAtomicWait32(node) => {
+                let timeout = self.pop_i64();
+                let expected = self.pop_i32();
+                let address = self.pop_address();
+                // a negative timeout waits forever
+                let timeout = u64::try_from(timeout).ok().map(Duration::from_nanos);
+                let outcome = self.memory().wait(
+                    address,
+                    node.memarg.offset,
+                    expected.to_le_bytes(),
+                    timeout,
+                )?;
+                self.push_i32(outcome as i32);
+            }
+            InstructionNode::MemoryAtomicWait64(node) => {
+                let timeout = self.pop_i64();
+                let expected = self.pop_i64();
+                let address = self.pop_address();
+                let timeout = u64::try_from(timeout).ok().map(Duration::from_nanos);
+                let outcome = self.memory().wait(
+                    address,
+                    node.memarg.offset,
+                    expected.to_le_bytes(),
+                    timeout,
+                )?;
+                self.push_i32(outcome as i32);
+            }
+            // every access to a shared memory already takes its lock, which
+            // orders it with the accesses of other threads
+            InstructionNode::AtomicFence(_) => {}
+            InstructionNode::I32AtomicLoad(node) => {
+                let value = self.atomic_load::<4>(node.memarg)?;
+                self.push_i32(value as i32);
+            }
+            InstructionNode::I64AtomicLoad(node) => {
+                let value = self.atomic_load::<8>(node.memarg)?;
+                self.push_i64(value as i64);
+            }
+            InstructionNode::I32AtomicLoad8U(node) => {
+                let value = self.atomic_load::<1>(node.memarg)?;
+                self.push_i32(value as i32);
+            }
+            InstructionNode::I32AtomicLoad16U(node) => {
+                let value = self.atomic_load::<2>(node.memarg)?;
+                self.push_i32(value as i32);
+            }
+            InstructionNode::I64AtomicLoad8U(node) => {
+                let value = self.atomic_load::<1>(node.memarg)?;
+                self.push_i64(value as i64);
+            }
+            InstructionNode::I64AtomicLoad16U(node) => {
+                let value = self.atomic_load::<2>(node.memarg)?;
+                self.push_i64(value as i64);
+            }
+            InstructionNode::I64AtomicLoad32U(node) => {
+                let value = self.atomic_load::<4>(node.memarg)?;
+                self.push_i64(value as i64);
+            }
+            InstructionNode::I32AtomicStore(node) => {
+                let value = self.pop_i32();
+                self.atomic_store(node.memarg, &(value as u32).to_le_bytes())?;
+            }
+            InstructionNode::I64AtomicStore(node) => {
+                let value = self.pop_i64();
+                self.atomic_store(node.memarg, &(value as u64).to_le_bytes())?;
+            }
+            InstructionNode::I32AtomicStore8(node) => {
+                let value = self.pop_i32();
+                self.atomic_store(node.memarg, &(value as u8).to_le_bytes())?;
+            }
+            InstructionNode::I32AtomicStore16(node) => {
+                let value = self.pop_i32();
+                self.atomic_store(node.memarg, &(value as u16).to_le_bytes())?;
+            }
+            InstructionNode::I64AtomicStore8(node) => {
+                let value = self.pop_i64();
+                self.atomic_store(node.memarg, &(value as u8).to_le_bytes())?;
+            }
+            InstructionNode::I64AtomicStore16(node) => {
+                let value = self.pop_i64();
+                self.atomic_store(node.memarg, &(value as u16).to_le_bytes())?;
+            }
+            InstructionNode::I64AtomicStore32(node) => {
+                let value = self.pop_i64();
+                self.atomic_store(node.memarg, &(value as u32).to_le_bytes())?;
+            }
+            InstructionNode::I32AtomicRmwAdd(node) => {
+                self.atomic_rmw::<4>(node.memarg, u64::wrapping_add)?;
+            }
+            InstructionNode::I64AtomicRmwAdd(node) => {
+                self.atomic_rmw::<8>(node.memarg, u64::wrapping_add)?;
+            }
+            InstructionNode::I32AtomicRmw8AddU(node) => {
+                self.atomic_rmw::<1>(node.memarg, u64::wrapping_add)?;
+            }
+            InstructionNode::I32AtomicRmw16AddU(node) => {
+                self.atomic_rmw::<2>(node.memarg, u64::wrapping_add)?;
+            }
+            InstructionNode::I64AtomicRmw8AddU(node) => {
+                self.atomic_rmw::<1>(node.memarg, u64::wrapping_add)?;
+            }
+            InstructionNode::I64AtomicRmw16AddU(node) => {
+                self.atomic_rmw::<2>(node.memarg, u64::wrapping_add)?;
+            }
+            InstructionNode::I64AtomicRmw32AddU(node) => {
+                self.atomic_rmw::<4>(node.memarg, u64::wrapping_add)?;
+            }
+            InstructionNode::I32AtomicRmwSub(node) => {
+                self.atomic_rmw::<4>(node.memarg, u64::wrapping_sub)?;
+            }
+            InstructionNode::I64AtomicRmwSub(node) => {
+                self.atomic_rmw::<8>(node.memarg, u64::wrapping_sub)?;
+            }
+            InstructionNode::I32AtomicRmw8SubU(node) => {
+                self.atomic_rmw::<1>(node.memarg, u64::wrapping_sub)?;
+            }
+            InstructionNode::I32AtomicRmw16SubU(node) => {
+                self.atomic_rmw::<2>(node.memarg, u64::wrapping_sub)?;
+            }
+            InstructionNode::I64AtomicRmw8SubU(node) => {
+                self.atomic_rmw::<1>(node.memarg, u64::wrapping_sub)?;
+            }
+            InstructionNode::I64AtomicRmw16SubU(node) => {
+                self.atomic_rmw::<2>(node.memarg, u64::wrapping_sub)?;
+            }
+            InstructionNode::I64AtomicRmw32SubU(node) => {
+                self.atomic_rmw::<4>(node.memarg, u64::wrapping_sub)?;
+            }
+            InstructionNode::I32AtomicRmwAnd(node) => {
+                self.atomic_rmw::<4>(node.memarg, |old, value| old & value)?;
+            }
+            InstructionNode::I64AtomicRmwAnd(node) => {
+                self.atomic_rmw::<8>(node.memarg, |old, value| old & value)?;
+            }
+            InstructionNode::I32AtomicRmw8AndU(node) => {
+                self.atomic_rmw::<1>(node.memarg, |old, value| old & value)?;
+            }
+            InstructionNode::I32AtomicRmw16AndU(node) => {
+                self.atomic_rmw::<2>(node.memarg, |old, value| old & value)?;
+            }
+            InstructionNode::I64AtomicRmw8AndU(node) => {
+                self.atomic_rmw::<1>(node.memarg, |old, value| old & value)?;
+            }
+            InstructionNode::I64AtomicRmw16AndU(node) => {
+                self.atomic_rmw::<2>(node.memarg, |old, value| old & value)?;
+            }
+            InstructionNode::I64AtomicRmw32AndU(node) => {
+                self.atomic_rmw::<4>(node.memarg, |old, value| old & value)?;
+            }
+            InstructionNode::I32AtomicRmwOr(node) => {
+                self.atomic_rmw::<4>(node.memarg, |old, value| old | value)?;
+            }
+            InstructionNode::I64AtomicRmwOr(node) => {
+                self.atomic_rmw::<8>(node.memarg, |old, value| old | value)?;
+            }
+            InstructionNode::I32AtomicRmw8OrU(node) => {
+                self.atomic_rmw::<1>(node.memarg, |old, value| old | value)?;
+            }
+            InstructionNode::I32AtomicRmw16OrU(node) => {
+                self.atomic_rmw::<2>(node.memarg, |old, value| old | value)?;
+            }
+            InstructionNode::I64AtomicRmw8OrU(node) => {
+                self.atomic_rmw::<1>(node.memarg, |old, value| old | value)?;
+            }
+            InstructionNode::I64AtomicRmw16OrU(node) => {
+                self.atomic_rmw::<2>(node.memarg, |old, value| old | value)?;
+            }
+            InstructionNode::I64AtomicRmw32OrU(node) => {
+                self.atomic_rmw::<4>(node.memarg, |old, value| old | value)?;
+            }
+            InstructionNode::I32AtomicRmwXor(node) => {
+                self.atomic_rmw::<4>(node.memarg, |old, value| old ^ value)?;
+            }
+            InstructionNode::I64AtomicRmwXor(node) => {
+                self.atomic_rmw::<8>(node.memarg, |old, value| old ^ value)?;
+            }
+            InstructionNode::I32AtomicRmw8XorU(node) => {
+                self.atomic_rmw::<1>(node.memarg, |old, value| old ^ value)?;
+            }
+            InstructionNode::I32AtomicRmw16XorU(node) => {
+                self.atomic_rmw::<2>(node.memarg, |old, value| old ^ value)?;
+            }
+            InstructionNode::I64AtomicRmw8XorU(node) => {
+                self.atomic_rmw::<1>(node.memarg, |old, value| old ^ value)?;
+            }
+            InstructionNode::I64AtomicRmw16XorU(node) => {
+                self.atomic_rmw::<2>(node.memarg, |old, value| old ^ value)?;
+            }
+            InstructionNode::I64AtomicRmw32XorU(node) => {
+                self.atomic_rmw::<4>(node.memarg, |old, value| old ^ value)?;
+            }
+            InstructionNode::I32AtomicRmwXchg(node) => {
+                self.atomic_rmw::<4>(node.memarg, |_, value| value)?;
+            }
+            InstructionNode::I64AtomicRmwXchg(node) => {
+                self.atomic_rmw::<8>(node.memarg, |_, value| value)?;
+            }
+            InstructionNode::I32AtomicRmw8XchgU(node) => {
+                self.atomic_rmw::<1>(node.memarg, |_, value| value)?;
+            }
+            InstructionNode::I32AtomicRmw16XchgU(node) => {
+                self.atomic_rmw::<2>(node.memarg, |_, value| value)?;
+            }
+            InstructionNode::I64AtomicRmw8XchgU(node) => {
+                self.atomic_rmw::<1>(node.memarg, |_, value| value)?;
+            }
+            InstructionNode::I64AtomicRmw16XchgU(node) => {
+                self.atomic_rmw::<2>(node.memarg, |_, value| value)?;
+            }
+            InstructionNode::I64AtomicRmw32XchgU(node) => {
+                self.atomic_rmw::<4>(node.memarg, |_, value| value)?;
+            }
+            InstructionNode::I32AtomicRmwCmpxchg(node) => self.atomic_cmpxchg::<4>(node.memarg)?,
+            InstructionNode::I64AtomicRmwCmpxchg(node) => self.atomic_cmpxchg::<8>(node.memarg)?,
+            InstructionNode::I32AtomicRmw8CmpxchgU(node) => {
+                self.atomic_cmpxchg::<1>(node.memarg)?
+            }
+            InstructionNode::I32AtomicRmw16CmpxchgU(node) => {
+                self.atomic_cmpxchg::<2>(node.memarg)?
+            }
+            InstructionNode::I64AtomicRmw8CmpxchgU(node) => {
+                self.atomic_cmpxchg::<1>(node.memarg)?
+            }
+            InstructionNode::I64AtomicRmw16CmpxchgU(node) => {
+                self.atomic_cmpxchg::<2>(node.memarg)?
+            }
+            InstructionNode::I64AtomicRmw32CmpxchgU(node) => {
+                self.atomic_cmpxchg::<4>(node.memarg)?
+            }
+            InstructionNode::MemorySize(_) => {
+                let size = self.memory().size();
+                self.push_stack(StackEntry::value(Value::num(Number::i32(size as i32))));
+            }
+            InstructionNode::MemoryGrow(_) => match self.pop_stack() {
+                StackEntry::value(Value::num(Number::i32(delta))) => {
+                    let result = match self.memory_mut().grow(delta as u32) {
+                        Some(previous) => previous as i32,
+                        None => -1,
+                    };
+                    self.push_stack(StackEntry::value(Value::num(Number::i32(result))));
+                }
+                _ => panic!("memory.grow must have an i32 value on the stack"),
+            },
+            // bounds are checked before anything is written, even when the
+            // length is zero, and a dropped segment behaves as an empty one
+            InstructionNode::MemoryInit(node) => {
+                let len = self.pop_i32() as u32;
+                let src = self.pop_i32() as u32 as usize;
+                let dst = self.pop_i32() as u32;
+                let data = self.instance.datas[node.data_index as usize].data();
+                let bytes = data
+                    .get(src..src + len as usize)
+                    .ok_or(Trap::MemoryOutOfBounds)?;
+                self.instance.memories[node.memory_index as usize].store(dst, 0, bytes)?;
+            }
+            InstructionNode::DataDrop(node) => {
+                self.instance.datas[node.data_index as usize].drop_data();
+            }
+            InstructionNode::MemoryCopy(node) => {
+                let len = self.pop_i32() as u32;
+                let src = self.pop_i32() as u32;
+                let dst = self.pop_i32() as u32;
+                if node.dst_memory_index == node.src_memory_index {
+                    self.instance.memories[node.dst_memory_index as usize].copy(dst, src, len)?;
+                } else {
+                    let bytes =
+                        self.instance.memories[node.src_memory_index as usize].read(src, len)?;
+                    self.instance.memories[node.dst_memory_index as usize].store(dst, 0, &bytes)?;
+                }
+            }
+            InstructionNode::MemoryFill(node) => {
+                let len = self.pop_i32() as u32;
+                let value = self.pop_i32() as u8;
+                let dst = self.pop_i32() as u32;
+                self.instance.memories[node.memory_index as usize].fill(dst, value, len)?;
+            }
+            InstructionNode::TableInit(node) => {
+                let len = self.pop_i32() as u32;
+                let src = self.pop_i32() as u32 as usize;
+                let dst = self.pop_i32() as u32;
+                let elements = self.instance.elems[node.elem_index as usize].elements();
+                let elements = elements
+                    .get(src..src + len as usize)
+                    .ok_or(Trap::TableOutOfBounds)?;
+                self.instance.tables[node.table_index as usize].init(dst, elements)?;
+            }
+            InstructionNode::ElemDrop(node) => {
+                self.instance.elems[node.elem_index as usize].drop_elements();
+            }
+            InstructionNode::TableCopy(node) => {
+                let len = self.pop_i32() as u32;
+                let src = self.pop_i32() as u32;
+                let dst = self.pop_i32() as u32;
+                // copied out first, which also covers overlapping ranges
+                let elements =
+                    self.instance.tables[node.src_table_index as usize].slice(src, len)?;
+                self.instance.tables[node.dst_table_index as usize].init(dst, &elements)?;
+            }
+            InstructionNode::RefNull(node) => self.push_ref(Reference::Null(node.ref_type)),
+            InstructionNode::RefIsNull(_) => {
+                let reference = self.pop_ref();
+                self.push_i32(reference.is_null() as i32);
+            }
+            InstructionNode::RefFunc(node) => {
+                let address = self.instance.address().clone();
+                self.push_ref(Reference::Func(FuncRef::new(node.function_index, address)));
+            }
+            InstructionNode::TableGet(node) => {
+                let index = self.pop_i32() as u32;
+                let table = &self.instance.tables[node.table_index as usize];
+                let reference = table.slice(index, 1)?[0].clone();
+                self.push_ref(reference);
+            }
+            InstructionNode::TableSet(node) => {
+                let reference = self.pop_ref();
+                let index = self.pop_i32() as u32;
+                self.instance.tables[node.table_index as usize].set(index, reference)?;
+            }
+            InstructionNode::TableSize(node) => {
+                let size = self.instance.tables[node.table_index as usize].size();
+                self.push_i32(size as i32);
+            }
+            InstructionNode::TableGrow(node) => {
+                let delta = self.pop_i32() as u32;
+                let reference = self.pop_ref();
+                let result =
+                    match self.instance.tables[node.table_index as usize].grow(delta, reference) {
+                        Some(previous) => previous as i32,
+                        None => -1,
+                    };
+                self.push_i32(result);
+            }
+            InstructionNode::TableFill(node) => {
+                let len = self.pop_i32() as u32;
+                let reference = self.pop_ref();
+                let index = self.pop_i32() as u32;
+                self.instance.tables[node.table_index as usize].fill(index, reference, len)?;
+            }
+        };
+        Ok(())
+    }
+
+    /// Pops the arguments of `function` off the stack, in parameter order.
+    fn pop_args(&mut self, function: &FunctionInstance) -> Vec<Value> {
+        let mut args: Vec<Value> = vec![];
+        function
+            .function_type()
+            .params
+            .val_types
+            .iter()
+            .for_each(|_| {
+                let entry = self.pop_stack();
+                if let StackEntry::value(v) = entry {
+                    args.push(v);
+                }
+            });
+        args.reverse();
+        args
+    }
+
+    /// Runs the body of a block, loop or if one level deeper.
+    fn expression(&mut self, frame: &mut Frame, expr: &ExpressionNode) -> Result<(), Trap> {
+        self.enter()?;
+        for instruction in expr.instructions.iter() {
+            self.invoke(frame, instruction)?;
+            if self.control_instructions.len() > 0 {
+                break;
+            }
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    /// Takes one of the `MAX_DEPTH` levels of blocks and calls. The level is
+    /// given back by the caller once it returns; a trap leaves it taken, as
+    /// the runtime is reset before it runs again.
+    fn enter(&mut self) -> Result<(), Trap> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Trap::CallStackExhausted);
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn block(&mut self, frame: &mut Frame, node: &BlockInstructionNode) -> Result<(), Trap> {
+        let results = self.push_label(LabelType::Block, node.block_type, node.size);
+        self.expression(frame, &node.expr)?;
+        match self.control_instructions.pop() {
+            Some(InstructionNode::Br(br_node)) => self.propagate_branch(br_node.depth),
+            Some(other) => unreachable!("only branches are pending, found {:?}", other),
+            None => self.exit_label(results),
+        }
+        Ok(())
+    }
+
+    fn loop_block(&mut self, frame: &mut Frame, node: &LoopInstructionNode) -> Result<(), Trap> {
+        let results = self.push_label(LabelType::Loop, node.block_type, node.size);
+        loop {
+            self.expression(frame, &node.expr)?;
+            match self.control_instructions.pop() {
+                // the loop label is still in place, so run the body again
+                Some(InstructionNode::Br(br_node)) if br_node.depth == 0 => {}
+                Some(InstructionNode::Br(br_node)) => {
+                    self.propagate_branch(br_node.depth);
+                    break;
+                }
+                Some(other) => unreachable!("only branches are pending, found {:?}", other),
+                None => {
+                    self.exit_label(results);
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn if_block(&mut self, frame: &mut Frame, node: &IfInstructionNode) -> Result<(), Trap> {
+        let condition = self.pop_stack();
+        let StackEntry::value(Value::num(Number::i32(value))) = condition else {
+            panic!("if condition must be i32");
+        };
+        let expr = if value != 0 {
+            Some(&node.then_expr)
+        } else {
+            node.else_expr.as_ref()
+        };
+        if let Some(expr) = expr {
+            let results = self.push_label(LabelType::If, node.block_type, node.size);
+            self.expression(frame, expr)?;
+            match self.control_instructions.pop() {
+                Some(InstructionNode::Br(br_node)) => self.propagate_branch(br_node.depth),
+                Some(other) => unreachable!("only branches are pending, found {:?}", other),
+                None => self.exit_label(results),
+            }
+        }
+        Ok(())
+    }
+
+    fn call_indirect(&mut self, node: &CallIndirectInstructionNode) -> Result<(), Trap> {
+        let (foreign, function, args) = self.enter_indirect(node)?;
+        let result = self.call(function, Some(args));
+        if foreign {
+            self.leave_ref();
+        }
+        result
+    }
+
+    /// Resolves the callee of `call_indirect` through `enter_ref`. Kept apart
+    /// from `call_indirect` so that the recursion through it stays small.
+    fn enter_indirect(
+        &mut self,
+        node: &CallIndirectInstructionNode,
+    ) -> Result<(bool, FunctionInstance, Vec<Value>), Trap> {
+        let index = match self.pop_stack() {
+            StackEntry::value(Value::num(Number::i32(index))) => index as u32,
+            _ => panic!("call_indirect index must be i32"),
+        };
+        let table = &self.instance.tables[node.table_index as usize];
+        let func_ref = match table.get(index)? {
+            Reference::Func(func_ref) => func_ref,
+            _ => return Err(Trap::UninitializedElement),
+        };
+        let expected = self.instance.types[node.type_index as usize].clone();
+        self.enter_ref(&func_ref, |function| {
+            if *function.function_type() != expected {
+                return Err(Trap::IndirectCallTypeMismatch);
+            }
+            Ok(())
+        })
+    }
+
+    fn pop_i32(&mut self) -> i32 {
+        match self.pop_stack() {
+            StackEntry::value(Value::num(Number::i32(value))) => value,
+            entry => panic!("expected an i32 value on the stack, found {:?}", entry),
+        }
+    }
+
+    fn push_i32(&mut self, value: i32) {
+        self.push_stack(StackEntry::value(Value::num(Number::i32(value))));
+    }
+
+    fn i32_unary(&mut self, op: impl FnOnce(i32) -> i32) {
+        let value = self.pop_i32();
+        self.push_i32(op(value));
+    }
+
+    fn i32_binary(&mut self, op: impl FnOnce(i32, i32) -> i32) {
+        let rhs = self.pop_i32();
+        let lhs = self.pop_i32();
+        self.push_i32(op(lhs, rhs));
+    }
+
+    fn i32_binary_checked(
+        &mut self,
+        op: impl FnOnce(i32, i32) -> Result<i32, Trap>,
+    ) -> Result<(), Trap> {
+        let rhs = self.pop_i32();
+        let lhs = self.pop_i32();
+        self.push_i32(op(lhs, rhs)?);
+        Ok(())
+    }
+
+    fn i32_compare(&mut self, op: impl FnOnce(i32, i32) -> bool) {
+        let rhs = self.pop_i32();
+        let lhs = self.pop_i32();
+        self.push_i32(op(lhs, rhs) as i32);
+    }
+
+    fn pop_v128(&mut self) -> V128 {
+        match self.pop_stack() {
+            StackEntry::value(Value::V128(value)) => value,
+            entry => panic!("expected a v128 value on the stack, found {:?}", entry),
+        }
+    }
+
+    fn push_v128(&mut self, value: V128) {
+        self.push_stack(StackEntry::value(Value::V128(value)));
+    }
+
+    fn v128_unary(&mut self, f: impl Fn(V128) -> V128) {
+        let value = self.pop_v128();
+        self.push_v128(f(value));
+    }
+
+    fn v128_binary(&mut self, f: impl Fn(V128, V128) -> V128) {
+        let rhs = self.pop_v128();
+        let lhs = self.pop_v128();
+        self.push_v128(f(lhs, rhs));
+    }
+
+    /// Shifts take the shift count as an i32, which each lane reduces modulo
+    /// its width.
+    fn v128_shift(&mut self, f: impl Fn(V128, u32) -> V128) {
+        let shift = self.pop_i32() as u32;
+        let value = self.pop_v128();
+        self.push_v128(f(value, shift));
+    }
+
+    fn v128_reduce(&mut self, f: impl Fn(V128) -> i32) {
+        let value = self.pop_v128();
+        self.push_i32(f(value));
+    }
+
+    fn pop_ref(&mut self) -> Reference {
+        match self.pop_stack() {
+            StackEntry::value(Value::Ref(reference)) => reference,
+            _ => panic!("Expected a reference on the stack"),
+        }
+    }
+
+    fn push_ref(&mut self, reference: Reference) {
+        self.push_stack(StackEntry::value(Value::Ref(reference)));
+    }
+
+    fn pop_i64(&mut self) -> i64 {
+        match self.pop_stack() {
+            StackEntry::value(Value::num(Number::i64(value))) => value,
+            entry => panic!("expected an i64 value on the stack, found {:?}", entry),
+        }
+    }
+
+    fn push_i64(&mut self, value: i64) {
+        self.push_stack(StackEntry::value(Value::num(Number::i64(value))));
+    }
+
+    fn i64_unary(&mut self, op: impl FnOnce(i64) -> i64) {
+        let value = self.pop_i64();
+        self.push_i64(op(value));
+    }
+
+    fn i64_binary(&mut self, op: impl FnOnce(i64, i64) -> i64) {
+        let rhs = self.pop_i64();
+        let lhs = self.pop_i64();
+        self.push_i64(op(lhs, rhs));
+    }
+
+    fn i64_binary_checked(
+        &mut self,
+        op: impl FnOnce(i64, i64) -> Result<i64, Trap>,
+    ) -> Result<(), Trap> {
+        let rhs = self.pop_i64();
+        let lhs = self.pop_i64();
+        self.push_i64(op(lhs, rhs)?);
+        Ok(())
+    }
+
+    fn i64_compare(&mut self, op: impl FnOnce(i64, i64) -> bool) {
+        let rhs = self.pop_i64();
+        let lhs = self.pop_i64();
+        self.push_i32(op(lhs, rhs) as i32);
+    }
+
+    fn pop_f32(&mut self) -> F32 {
+        match self.pop_stack() {
+            StackEntry::value(Value::num(Number::f32(value))) => value,
+            entry => panic!("expected an f32 value on the stack, found {:?}", entry),
+        }
+    }
+
+    fn push_f32(&mut self, value: F32) {
+        self.push_stack(StackEntry::value(Value::num(Number::f32(value))));
+    }
+
+    fn f32_unary(&mut self, op: impl FnOnce(F32) -> F32) {
+        let value = self.pop_f32();
+        self.push_f32(op(value));
+    }
+
+    fn f32_binary(&mut self, op: impl FnOnce(F32, F32) -> F32) {
+        let rhs = self.pop_f32();
+        let lhs = self.pop_f32();
+        self.push_f32(op(lhs, rhs));
+    }
+
+    fn f32_compare(&mut self, op: impl FnOnce(f32, f32) -> bool) {
+        let rhs = self.pop_f32();
+        let lhs = self.pop_f32();
+        self.push_i32(op(lhs.to_float(), rhs.to_float()) as i32);
+    }
+
+    fn pop_f64(&mut self) -> F64 {
+        match self.pop_stack() {
+            StackEntry::value(Value::num(Number::f64(value))) => value,
+            entry => panic!("expected an f64 value on the stack, found {:?}", entry),
+        }
+    }
+
+    fn push_f64(&mut self, value: F64) {
+        self.push_stack(StackEntry::value(Value::num(Number::f64(value))));
+    }
+
+    fn f64_unary(&mut self, op: impl FnOnce(F64) -> F64) {
+        let value = self.pop_f64();
+        self.push_f64(op(value));
+    }
+
+    fn f64_binary(&mut self, op: impl FnOnce(F64, F64) -> F64) {
+        let rhs = self.pop_f64();
+        let lhs = self.pop_f64();
+        self.push_f64(op(lhs, rhs));
+    }
+
+    fn f64_compare(&mut self, op: impl FnOnce(f64, f64) -> bool) {
+        let rhs = self.pop_f64();
+        let lhs = self.pop_f64();
+        self.push_i32(op(lhs.to_float(), rhs.to_float()) as i32);
+    }
+
+    fn memory(&self) -> &MemoryInstance {
+        self.instance
+            .memories
+            .first()
+            .expect("instantiation rejects memory instructions without a memory")
+    }
+
+    fn memory_mut(&mut self) -> &mut MemoryInstance {
+        self.instance
+            .memories
+            .first_mut()
+            .expect("instantiation rejects memory instructions without a memory")
+    }
+
+    fn pop_address(&mut self) -> u32 {
+        match self.pop_stack() {
+            StackEntry::value(Value::num(Number::i32(address))) => address as u32,
+            _ => panic!("memory address must be i32"),
+        }
+    }
+
+    fn load<const N: usize>(&mut self, memarg: MemArgNode) -> Result<[u8; N], Trap> {
+        let address = self.pop_address();
+        self.memory().load::<N>(address, memarg.offset)
+    }
+
+    /// Loads 8 bytes into the low half of a vector, for the extending loads.
+    fn load_half(&mut self, memarg: MemArgNode) -> Result<V128, Trap> {
+        let bytes = self.load::<8>(memarg)?;
+        Ok(V128::from_bits(u64::from_le_bytes(bytes) as u128))
+    }
+
+    fn store(&mut self, memarg: MemArgNode, bytes: &[u8]) -> Result<(), Trap> {
+        let address = self.pop_address();
+        self.memory_mut().store(address, memarg.offset, bytes)
+    }
+
+    /// Pops the i32 or i64 operand of an atomic instruction as unsigned bits,
+    /// along with whether it is an i64.
+    fn pop_atomic_operand(&mut self) -> (u64, bool) {
+        match self.pop_stack() {
+            StackEntry::value(Value::num(Number::i32(value))) => (value as u32 as u64, false),
+            StackEntry::value(Value::num(Number::i64(value))) => (value as u64, true),
+            _ => panic!("atomic operand must be an integer"),
+        }
+    }
+
+    fn push_atomic_result(&mut self, value: u64, wide: bool) {
+        if wide {
+            self.push_i64(value as i64);
+        } else {
+            self.push_i32(value as i32);
+        }
+    }
+
+    /// Loads `N` bytes atomically, zero-extended.
+    fn atomic_load<const N: usize>(&mut self, memarg: MemArgNode) -> Result<u64, Trap> {
+        let address = self.pop_address();
+        let bytes = self.memory().atomic_load::<N>(address, memarg.offset)?;
+        Ok(zero_extend(bytes))
+    }
+
+    fn atomic_store(&mut self, memarg: MemArgNode, bytes: &[u8]) -> Result<(), Trap> {
+        let address = self.pop_address();
+        self.memory_mut()
+            .atomic_store(address, memarg.offset, bytes)
+    }
+
+    /// Replaces the `N` bytes at the address with `op` of them and the
+    /// operand, and pushes the replaced value as the operand's type.
+    fn atomic_rmw<const N: usize>(
+        &mut self,
+        memarg: MemArgNode,
+        op: impl FnOnce(u64, u64) -> u64,
+    ) -> Result<(), Trap> {
+        let (operand, wide) = self.pop_atomic_operand();
+        let address = self.pop_address();
+        let old = self
+            .memory_mut()
+            .atomic_rmw::<N>(address, memarg.offset, |bytes| {
+                wrap(op(zero_extend(bytes), operand))
+            })?;
+        self.push_atomic_result(zero_extend(old), wide);
+        Ok(())
+    }
+
+    /// Stores the replacement if the `N` bytes at the address hold the
+    /// expected value, wrapped to `N` bytes, and pushes the value found.
+    fn atomic_cmpxchg<const N: usize>(&mut self, memarg: MemArgNode) -> Result<(), Trap> {
+        let (replacement, wide) = self.pop_atomic_operand();
+        let (expected, _) = self.pop_atomic_operand();
+        let address = self.pop_address();
+        let expected = wrap::<N>(expected);
+        let old = self
+            .memory_mut()
+            .atomic_rmw::<N>(address, memarg.offset, |bytes| {
+                if bytes == expected {
+                    wrap(replacement)
+                } else {
+                    bytes
+                }
+            })?;
+        self.push_atomic_result(zero_extend(old), wide);
+        Ok(())
+    }
+
+    /// Unwinds the stack for a branch to the label `depth` levels out, keeping
+    /// only the values the label takes, and leaves the branch pending so that
+    /// the enclosing blocks stop executing.
+    // https://webassembly.github.io/spec/core/exec/instructions.html#exec-br
+    fn branch(&mut self, frame: &Frame, depth: usize) {
+        let labels = self.label_positions.len() - frame.label_height;
+        let (arity, height) = if depth < labels {
+            let remaining = self.label_positions.len() - depth;
+            let label_idx = self.label_positions[remaining - 1];
+            let label = match &self.stack[label_idx] {
+                StackEntry::label(label) => label.clone(),
+                _ => panic!("Stack entry is not a label"),
+            };
+            match label.label_type {
+                // a loop is re-entered with its parameters, so its label stays
+                LabelType::Loop => {
+                    self.label_positions.truncate(remaining);
+                    (label.arity, label_idx + 1)
+                }
+                LabelType::Block | LabelType::If => {
+                    self.label_positions.truncate(remaining - 1);
+                    (label.arity, label_idx)
+                }
+            }
+        } else {
+            // the outermost label is the function body
+            self.label_positions.truncate(frame.label_height);
+            let arity = frame.function.function_type.returns.val_types.len();
+            (arity, frame.stack_height)
+        };
+
+        let values = self.stack.split_off(self.sp - arity);
+        self.stack.truncate(height);
+        self.stack.extend(values);
+        self.sp = self.stack.len();
+        self.control_instructions
+            .push(InstructionNode::Br(BrInstructionNode::new(depth)));
+    }
+
+    /// Passes a pending branch that did not target the current label on to the
+    /// enclosing one; `branch` has already unwound the stack.
+    fn propagate_branch(&mut self, depth: usize) {
+        if depth > 0 {
+            self.control_instructions
+                .push(InstructionNode::Br(BrInstructionNode::new(depth - 1)));
+        }
+    }
+
+    /// Pops the innermost label when execution reaches the end of its block,
+    /// keeping the `arity` result values on top of the stack.
+    fn exit_label(&mut self, arity: usize) {
+        let values = self.stack.split_off(self.sp - arity);
+        self.sp -= arity;
+        self.pop_label();
+        self.stack.extend(values);
+        self.sp += arity;
+    }
+}
+
+/// Reads `N` little-endian bytes as an unsigned integer.
+fn zero_extend<const N: usize>(bytes: [u8; N]) -> u64 {
+    let mut wide = [0; 8];
+    wide[..N].copy_from_slice(&bytes);
+    u64::from_le_bytes(wide)
+}
+
+/// Keeps the low `N` bytes of `value`, little-endian.
+fn wrap<const N: usize>(value: u64) -> [u8; N] {
+    let mut bytes = [0; N];
+    bytes.copy_from_slice(&value.to_le_bytes()[..N]);
+    bytes
 }
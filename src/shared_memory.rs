@@ -0,0 +1,133 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        Arc, Condvar, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    },
+    time::{Duration, Instant},
+};
+
+/// A handle to the bytes of a shared linear memory and the threads waiting on
+/// them. Clones refer to the same memory, which is how instances running on
+/// different threads communicate.
+// https://github.com/WebAssembly/threads/blob/main/proposals/threads/Overview.md
+#[derive(Clone)]
+pub struct SharedMemory(Arc<SharedState>);
+
+struct SharedState {
+    data: RwLock<Vec<u8>>,
+    waiters: Mutex<WaitQueue>,
+    /// Signalled whenever `notify` takes waiters off the queue.
+    notified: Condvar,
+}
+
+#[derive(Default)]
+struct WaitQueue {
+    next_ticket: u64,
+    /// Waiting threads in arrival order, as the address each waits on and a
+    /// ticket identifying the wait.
+    waiting: VecDeque<(usize, u64)>,
+}
+
+/// The result of `memory.atomic.wait32` and `wait64`, as the i32 they return.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitOutcome {
+    Woken = 0,
+    NotEqual = 1,
+    TimedOut = 2,
+}
+
+impl SharedMemory {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self(Arc::new(SharedState {
+            data: RwLock::new(data),
+            waiters: Mutex::new(WaitQueue::default()),
+            notified: Condvar::new(),
+        }))
+    }
+
+    // a panic while a lock was held leaves the bytes no less valid, so a
+    // poisoned lock is used as is
+    pub fn read(&self) -> RwLockReadGuard<'_, Vec<u8>> {
+        self.0.data.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, Vec<u8>> {
+        self.0.data.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn waiters(&self) -> MutexGuard<'_, WaitQueue> {
+        self.0
+            .waiters
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Parks the thread until a `notify` on `address` wakes it, unless the
+    /// bytes there differ from `expected` or the `timeout` passes first.
+    /// Without a timeout the thread waits for as long as it takes.
+    pub fn wait(&self, address: usize, expected: &[u8], timeout: Option<Duration>) -> WaitOutcome {
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        // the queue stays locked from the comparison until the thread is
+        // parked, so a store followed by a notify cannot slip in between
+        let mut queue = self.waiters();
+        if self.read()[address..address + expected.len()] != *expected {
+            return WaitOutcome::NotEqual;
+        }
+        let ticket = queue.next_ticket;
+        queue.next_ticket += 1;
+        queue.waiting.push_back((address, ticket));
+
+        loop {
+            if !queue.waiting.iter().any(|&(_, waiting)| waiting == ticket) {
+                return WaitOutcome::Woken;
+            }
+            queue = match deadline {
+                None => self
+                    .0
+                    .notified
+                    .wait(queue)
+                    .unwrap_or_else(PoisonError::into_inner),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        queue.waiting.retain(|&(_, waiting)| waiting != ticket);
+                        return WaitOutcome::TimedOut;
+                    }
+                    self.0
+                        .notified
+                        .wait_timeout(queue, deadline - now)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+            };
+        }
+    }
+
+    /// Wakes up to `count` of the threads waiting on `address`, longest
+    /// waiting first, and returns how many were woken.
+    pub fn notify(&self, address: usize, count: u32) -> u32 {
+        let mut queue = self.waiters();
+        let mut woken = 0;
+        queue.waiting.retain(|&(waiting, _)| {
+            if waiting == address && woken < count {
+                woken += 1;
+                false
+            } else {
+                true
+            }
+        });
+        if woken > 0 {
+            self.0.notified.notify_all();
+        }
+        woken
+    }
+}
+
+impl fmt::Debug for SharedMemory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedMemory")
+            .field("len", &self.read().len())
+            .finish_non_exhaustive()
+    }
+}
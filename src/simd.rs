@@ -0,0 +1,257 @@
+use std::{
+    fmt,
+    ops::{Add, Mul},
+};
+
+use crate::float::{F32, F64};
+
+/// A 128-bit vector, stored as its bit pattern and split into lanes of
+/// whichever shape an instruction works on. Lane 0 holds the lowest bytes,
+/// matching the little-endian layout of the vector in memory.
+///
+/// Every lane operation is computed one lane at a time on plain integers and
+/// floats, so no SIMD support is needed from the host.
+// https://webassembly.github.io/spec/core/exec/numerics.html#vector-operations
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct V128(u128);
+
+/// A scalar type that a `V128` can be split into.
+pub trait Lane: Copy {
+    const SIZE: usize;
+
+    fn read(bytes: &[u8]) -> Self;
+
+    fn write(self, bytes: &mut [u8]);
+}
+
+macro_rules! integer_lanes {
+    ($($int:ident),*) => {
+        $(
+            impl Lane for $int {
+                const SIZE: usize = std::mem::size_of::<$int>();
+
+                fn read(bytes: &[u8]) -> Self {
+                    $int::from_le_bytes(bytes.try_into().unwrap())
+                }
+
+                fn write(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+integer_lanes!(i8, u8, i16, u16, i32, u32, i64, u64);
+
+impl Lane for F32 {
+    const SIZE: usize = 4;
+
+    fn read(bytes: &[u8]) -> Self {
+        F32::from_bits(u32::read(bytes))
+    }
+
+    fn write(self, bytes: &mut [u8]) {
+        self.to_bits().write(bytes);
+    }
+}
+
+impl Lane for F64 {
+    const SIZE: usize = 8;
+
+    fn read(bytes: &[u8]) -> Self {
+        F64::from_bits(u64::read(bytes))
+    }
+
+    fn write(self, bytes: &mut [u8]) {
+        self.to_bits().write(bytes);
+    }
+}
+
+impl V128 {
+    pub fn from_bits(bits: u128) -> Self {
+        Self(bits)
+    }
+
+    pub fn to_bits(self) -> u128 {
+        self.0
+    }
+
+    pub fn from_le_bytes(bytes: [u8; 16]) -> Self {
+        Self(u128::from_le_bytes(bytes))
+    }
+
+    pub fn to_le_bytes(self) -> [u8; 16] {
+        self.0.to_le_bytes()
+    }
+
+    pub fn lanes<T: Lane>(self) -> Vec<T> {
+        self.to_le_bytes()
+            .chunks_exact(T::SIZE)
+            .map(T::read)
+            .collect()
+    }
+
+    /// Builds a vector from exactly `16 / T::SIZE` lanes.
+    pub fn from_lanes<T: Lane>(lanes: impl IntoIterator<Item = T>) -> Self {
+        let mut bytes = [0; 16];
+        let mut count = 0;
+        for lane in lanes {
+            lane.write(&mut bytes[count * T::SIZE..][..T::SIZE]);
+            count += 1;
+        }
+        assert_eq!(count * T::SIZE, 16, "a v128 needs {} lanes", 16 / T::SIZE);
+        Self::from_le_bytes(bytes)
+    }
+
+    pub fn low_half<T: Lane>(self) -> Vec<T> {
+        let mut lanes = self.lanes();
+        lanes.truncate(lanes.len() / 2);
+        lanes
+    }
+
+    pub fn high_half<T: Lane>(self) -> Vec<T> {
+        let mut lanes = self.lanes();
+        lanes.drain(..lanes.len() / 2);
+        lanes
+    }
+
+    pub fn splat<T: Lane>(value: T) -> Self {
+        Self::from_lanes(std::iter::repeat_n(value, 16 / T::SIZE))
+    }
+
+    pub fn lane<T: Lane>(self, index: u8) -> T {
+        T::read(&self.to_le_bytes()[index as usize * T::SIZE..][..T::SIZE])
+    }
+
+    pub fn replace_lane<T: Lane>(self, index: u8, value: T) -> Self {
+        let mut bytes = self.to_le_bytes();
+        value.write(&mut bytes[index as usize * T::SIZE..][..T::SIZE]);
+        Self::from_le_bytes(bytes)
+    }
+
+    pub fn map<T: Lane>(self, f: impl Fn(T) -> T) -> Self {
+        Self::from_lanes(self.lanes().into_iter().map(f))
+    }
+
+    pub fn zip<T: Lane>(self, other: Self, f: impl Fn(T, T) -> T) -> Self {
+        let lanes = self.lanes().into_iter().zip(other.lanes());
+        Self::from_lanes(lanes.map(|(lhs, rhs)| f(lhs, rhs)))
+    }
+
+    /// Sets every bit of the lanes where `f` holds, and clears the others.
+    pub fn compare<T: Lane>(self, other: Self, f: impl Fn(T, T) -> bool) -> Self {
+        let mut bytes = [0; 16];
+        let lanes = self.lanes().into_iter().zip(other.lanes());
+        for (chunk, (lhs, rhs)) in bytes.chunks_exact_mut(T::SIZE).zip(lanes) {
+            if f(lhs, rhs) {
+                chunk.fill(0xff);
+            }
+        }
+        Self::from_le_bytes(bytes)
+    }
+
+    /// Converts each lane to a lane of another type of the same width.
+    pub fn convert<T: Lane, U: Lane>(self, f: impl Fn(T) -> U) -> Self {
+        Self::from_lanes(self.lanes().into_iter().map(f))
+    }
+
+    /// Narrows the lanes of `self` followed by those of `other` into lanes of
+    /// half the width.
+    pub fn narrow<T: Lane, U: Lane>(self, other: Self, f: impl Fn(T) -> U) -> Self {
+        Self::from_lanes(self.lanes().into_iter().chain(other.lanes()).map(f))
+    }
+
+    pub fn extend_low<T: Lane, U: Lane + From<T>>(self) -> Self {
+        Self::from_lanes(self.low_half::<T>().into_iter().map(U::from))
+    }
+
+    pub fn extend_high<T: Lane, U: Lane + From<T>>(self) -> Self {
+        Self::from_lanes(self.high_half::<T>().into_iter().map(U::from))
+    }
+
+    /// Multiplies the lower halves of the lanes, widened so that no product
+    /// overflows.
+    pub fn extmul_low<T, U>(self, other: Self) -> Self
+    where
+        T: Lane,
+        U: Lane + From<T> + Mul<Output = U>,
+    {
+        let lanes = self.low_half::<T>().into_iter().zip(other.low_half());
+        Self::from_lanes(lanes.map(|(lhs, rhs)| U::from(lhs) * U::from(rhs)))
+    }
+
+    pub fn extmul_high<T, U>(self, other: Self) -> Self
+    where
+        T: Lane,
+        U: Lane + From<T> + Mul<Output = U>,
+    {
+        let lanes = self.high_half::<T>().into_iter().zip(other.high_half());
+        Self::from_lanes(lanes.map(|(lhs, rhs)| U::from(lhs) * U::from(rhs)))
+    }
+
+    /// Adds adjacent pairs of lanes into a lane of twice the width.
+    pub fn extadd_pairwise<T, U>(self) -> Self
+    where
+        T: Lane,
+        U: Lane + From<T> + Add<Output = U>,
+    {
+        let lanes = self.lanes::<T>();
+        Self::from_lanes(
+            lanes
+                .chunks_exact(2)
+                .map(|pair| U::from(pair[0]) + U::from(pair[1])),
+        )
+    }
+
+    /// `i32x4.dot_i16x8_s`: sums the products of adjacent pairs of i16 lanes.
+    pub fn dot(self, other: Self) -> Self {
+        let products: Vec<i32> = self
+            .lanes::<i16>()
+            .into_iter()
+            .zip(other.lanes::<i16>())
+            .map(|(lhs, rhs)| lhs as i32 * rhs as i32)
+            .collect();
+        // only -32768 * -32768 twice overflows, wrapping to i32::MIN
+        Self::from_lanes(
+            products
+                .chunks_exact(2)
+                .map(|pair| pair[0].wrapping_add(pair[1])),
+        )
+    }
+
+    /// Collects the sign bit of every lane, lane 0 in the lowest bit.
+    pub fn bitmask<T: Lane + PartialOrd + Default>(self) -> i32 {
+        self.lanes::<T>()
+            .into_iter()
+            .enumerate()
+            .fold(0, |mask, (index, lane)| {
+                mask | ((lane < T::default()) as i32) << index
+            })
+    }
+
+    pub fn all_true<T: Lane + PartialEq + Default>(self) -> bool {
+        self.lanes::<T>()
+            .into_iter()
+            .all(|lane| lane != T::default())
+    }
+
+    /// `i8x16.shuffle`: picks each byte from the 32 bytes of `self` and `other`.
+    pub fn shuffle(self, other: Self, indices: &[u8; 16]) -> Self {
+        let bytes = [self.to_le_bytes(), other.to_le_bytes()].concat();
+        Self::from_lanes(indices.iter().map(|&index| bytes[index as usize]))
+    }
+
+    /// `i8x16.swizzle`: picks each byte from `self`, or 0 for an index past the end.
+    pub fn swizzle(self, indices: Self) -> Self {
+        let bytes = self.to_le_bytes();
+        let lanes = indices.lanes::<u8>().into_iter();
+        Self::from_lanes(lanes.map(|index| bytes.get(index as usize).copied().unwrap_or(0)))
+    }
+}
+
+impl fmt::Debug for V128 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v128:{:#034x}", self.0)
+    }
+}
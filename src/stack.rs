@@ -1,6 +1,17 @@
-use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::{
+    any::Any,
+    fmt,
+    ops::{Add, Div, Mul, Rem, Sub},
+    sync::{Arc, OnceLock, Weak},
+};
 
-use crate::types::BlockType;
+use crate::{
+    float::{F32, F64},
+    instance::Instance,
+    simd::V128,
+    trap::Trap,
+    types::{NumberType, ReferenceTypeNode, ValueType},
+};
 
 #[derive(Debug, Clone)]
 pub enum StackEntry {
@@ -11,7 +22,9 @@ pub enum StackEntry {
 #[derive(Debug, Clone)]
 pub struct Label {
     pub label_type: LabelType,
-    pub arity: BlockType,
+    /// The number of values a branch to the label carries: the results of a
+    /// block, or the parameters of a loop.
+    pub arity: usize,
     pub size: usize,
 }
 
@@ -22,89 +35,216 @@ pub enum LabelType {
     If,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     num(Number),
+    V128(V128),
+    Ref(Reference),
+}
+
+impl Value {
+    /// The default value of a local of `value_type`.
+    pub fn zero(value_type: ValueType) -> Self {
+        match value_type {
+            ValueType::Number(NumberType::I32) => Value::num(Number::i32(0)),
+            ValueType::Number(NumberType::I64) => Value::num(Number::i64(0)),
+            ValueType::Number(NumberType::F32) => Value::num(Number::f32(F32::default())),
+            ValueType::Number(NumberType::F64) => Value::num(Number::f64(F64::default())),
+            ValueType::Vector(_) => Value::V128(V128::default()),
+            ValueType::Reference(ref_type) => Value::Ref(Reference::Null(ref_type)),
+        }
+    }
+}
+
+// https://webassembly.github.io/spec/core/exec/runtime.html#values
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reference {
+    Null(ReferenceTypeNode),
+    Func(FuncRef),
+    Extern(ExternRef),
+}
+
+impl Reference {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Reference::Null(_))
+    }
+}
+
+/// A function as wasm holds it in a `funcref`: its index in the instance that
+/// defined it, along with that instance. A table shared between instances
+/// thus calls each of its functions in the instance it came from.
+///
+/// The instance is held weakly, as a table of the instance holding its own
+/// functions would otherwise keep it alive forever. Two handles are equal
+/// when they refer to the same function of the same instance.
+#[derive(Clone)]
+pub struct FuncRef {
+    index: u32,
+    instance: Weak<OnceLock<Instance>>,
+}
+
+impl FuncRef {
+    pub(crate) fn new(index: u32, instance: Weak<OnceLock<Instance>>) -> Self {
+        Self { index, instance }
+    }
+
+    /// The index of the function in its instance.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Whether the function was defined by `instance`.
+    pub fn belongs_to(&self, instance: &Instance) -> bool {
+        self.instance.ptr_eq(instance.address())
+    }
+
+    /// The instance that defined the function, unless it has been dropped.
+    pub fn instance(&self) -> Option<Instance> {
+        self.instance.upgrade()?.get().cloned()
+    }
+}
+
+impl PartialEq for FuncRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.instance.ptr_eq(&other.instance)
+    }
+}
+
+impl fmt::Debug for FuncRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FuncRef({})", self.index)
+    }
+}
+
+/// An opaque host object that wasm can hold as an `externref` but never look
+/// into. Two handles are equal when they refer to the same object.
+#[derive(Clone)]
+pub struct ExternRef(Arc<dyn Any + Send + Sync>);
+
+impl ExternRef {
+    pub fn new(value: impl Any + Send + Sync) -> Self {
+        Self(Arc::new(value))
+    }
+
+    /// Returns the host object if it is a `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+}
+
+impl PartialEq for ExternRef {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for ExternRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ExternRef({:p})", Arc::as_ptr(&self.0))
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum Number {
     i32(i32),
     i64(i64),
-    f32(f32),
-    f64(f64),
+    /// Floats are kept as bit patterns, see [`F32`].
+    f32(F32),
+    f64(F64),
 }
 
+// Number operators follow the wasm semantics of the matching instruction:
+// integers wrap modulo 2^N, `/` and `%` are the signed `div_s` and `rem_s`,
+// and operands of different types are rejected with `Trap::TypeMismatch`.
 impl Add for Number {
-    type Output = Number;
+    type Output = Result<Number, Trap>;
 
-    fn add(self, rhs: Number) -> Number {
+    fn add(self, rhs: Number) -> Self::Output {
         match (self, rhs) {
-            (Number::i32(a), Number::i32(b)) => Number::i32(a + b),
-            (Number::i64(a), Number::i64(b)) => Number::i64(a + b),
-            (Number::f32(a), Number::f32(b)) => Number::f32(a + b),
-            (Number::f64(a), Number::f64(b)) => Number::f64(a + b),
-            _ => panic!("Cannot add numbers of different types"),
+            (Number::i32(a), Number::i32(b)) => Ok(Number::i32(a.wrapping_add(b))),
+            (Number::i64(a), Number::i64(b)) => Ok(Number::i64(a.wrapping_add(b))),
+            (Number::f32(a), Number::f32(b)) => Ok(Number::f32(a + b)),
+            (Number::f64(a), Number::f64(b)) => Ok(Number::f64(a + b)),
+            _ => Err(Trap::TypeMismatch),
         }
     }
 }
 
 impl Sub for Number {
-    type Output = Number;
+    type Output = Result<Number, Trap>;
 
-    fn sub(self, rhs: Number) -> Number {
+    fn sub(self, rhs: Number) -> Self::Output {
         match (self, rhs) {
-            (Number::i32(a), Number::i32(b)) => Number::i32(a - b),
-            (Number::i64(a), Number::i64(b)) => Number::i64(a - b),
-            (Number::f32(a), Number::f32(b)) => Number::f32(a - b),
-            (Number::f64(a), Number::f64(b)) => Number::f64(a - b),
-            _ => panic!("Cannot subtract numbers of different types"),
+            (Number::i32(a), Number::i32(b)) => Ok(Number::i32(a.wrapping_sub(b))),
+            (Number::i64(a), Number::i64(b)) => Ok(Number::i64(a.wrapping_sub(b))),
+            (Number::f32(a), Number::f32(b)) => Ok(Number::f32(a - b)),
+            (Number::f64(a), Number::f64(b)) => Ok(Number::f64(a - b)),
+            _ => Err(Trap::TypeMismatch),
         }
     }
 }
 
 impl Mul for Number {
-    type Output = Number;
+    type Output = Result<Number, Trap>;
 
     fn mul(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Number::i32(a), Number::i32(b)) => Number::i32(a * b),
-            (Number::i64(a), Number::i64(b)) => Number::i64(a * b),
-            (Number::f32(a), Number::f32(b)) => Number::f32(a * b),
-            (Number::f64(a), Number::f64(b)) => Number::f64(a * b),
-            _ => panic!("Cannot multiply numbers of different types"),
+            (Number::i32(a), Number::i32(b)) => Ok(Number::i32(a.wrapping_mul(b))),
+            (Number::i64(a), Number::i64(b)) => Ok(Number::i64(a.wrapping_mul(b))),
+            (Number::f32(a), Number::f32(b)) => Ok(Number::f32(a * b)),
+            (Number::f64(a), Number::f64(b)) => Ok(Number::f64(a * b)),
+            _ => Err(Trap::TypeMismatch),
         }
     }
 }
 
 impl Div for Number {
-    type Output = Number;
+    type Output = Result<Number, Trap>;
 
     fn div(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Number::i32(a), Number::i32(b)) => Number::i32(a / b),
-            (Number::i64(a), Number::i64(b)) => Number::i64(a / b),
-            (Number::f32(a), Number::f32(b)) => Number::f32(a / b),
-            (Number::f64(a), Number::f64(b)) => Number::f64(a / b),
-            _ => panic!("Cannot divide numbers of different types"),
+            (Number::i32(_), Number::i32(0)) | (Number::i64(_), Number::i64(0)) => {
+                Err(Trap::IntegerDivideByZero)
+            }
+            (Number::i32(a), Number::i32(b)) => a
+                .checked_div(b)
+                .map(Number::i32)
+                .ok_or(Trap::IntegerOverflow),
+            (Number::i64(a), Number::i64(b)) => a
+                .checked_div(b)
+                .map(Number::i64)
+                .ok_or(Trap::IntegerOverflow),
+            (Number::f32(a), Number::f32(b)) => Ok(Number::f32(a / b)),
+            (Number::f64(a), Number::f64(b)) => Ok(Number::f64(a / b)),
+            _ => Err(Trap::TypeMismatch),
         }
     }
 }
 
 impl Rem for Number {
-    type Output = Number;
+    type Output = Result<Number, Trap>;
 
     fn rem(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Number::i32(a), Number::i32(b)) => Number::i32(a % b),
-            (Number::i64(a), Number::i64(b)) => Number::i64(a % b),
-            (Number::f32(a), Number::f32(b)) => Number::f32(a % b),
-            (Number::f64(a), Number::f64(b)) => Number::f64(a % b),
-            _ => panic!("Cannot divide numbers of different types"),
+            (Number::i32(_), Number::i32(0)) | (Number::i64(_), Number::i64(0)) => {
+                Err(Trap::IntegerDivideByZero)
+            }
+            // MIN % -1 is 0 rather than an overflow
+            (Number::i32(a), Number::i32(b)) => Ok(Number::i32(a.wrapping_rem(b))),
+            (Number::i64(a), Number::i64(b)) => Ok(Number::i64(a.wrapping_rem(b))),
+            (Number::f32(a), Number::f32(b)) => {
+                Ok(Number::f32((a.to_float() % b.to_float()).into()))
+            }
+            (Number::f64(a), Number::f64(b)) => {
+                Ok(Number::f64((a.to_float() % b.to_float()).into()))
+            }
+            _ => Err(Trap::TypeMismatch),
         }
     }
 }
 
+// floats compare bit for bit, so that a NaN result can be asserted exactly;
+// numbers of different types are never equal
 impl PartialEq for Number {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -112,19 +252,80 @@ impl PartialEq for Number {
             (Number::i64(a), Number::i64(b)) => a == b,
             (Number::f32(a), Number::f32(b)) => a == b,
             (Number::f64(a), Number::f64(b)) => a == b,
-            _ => panic!("Cannot compare numbers of different types"),
+            _ => false,
         }
     }
 }
 
+// numbers of different types are unordered
 impl PartialOrd for Number {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         match (self, other) {
             (Number::i32(a), Number::i32(b)) => a.partial_cmp(b),
             (Number::i64(a), Number::i64(b)) => a.partial_cmp(b),
-            (Number::f32(a), Number::f32(b)) => a.partial_cmp(b),
-            (Number::f64(a), Number::f64(b)) => a.partial_cmp(b),
-            _ => panic!("Cannot compare numbers of different types"),
+            (Number::f32(a), Number::f32(b)) => a.to_float().partial_cmp(&b.to_float()),
+            (Number::f64(a), Number::f64(b)) => a.to_float().partial_cmp(&b.to_float()),
+            _ => None,
         }
     }
 }
+
+#[cfg(test)]
+mod number_tests {
+    use super::*;
+
+    #[test]
+    fn integer_arithmetic_wraps() {
+        assert_eq!(
+            Number::i32(i32::MAX) + Number::i32(1),
+            Ok(Number::i32(i32::MIN))
+        );
+        assert_eq!(
+            Number::i32(i32::MIN) - Number::i32(1),
+            Ok(Number::i32(i32::MAX))
+        );
+        assert_eq!(Number::i64(i64::MAX) * Number::i64(2), Ok(Number::i64(-2)));
+    }
+
+    #[test]
+    fn integer_division_traps() {
+        assert_eq!(
+            Number::i32(1) / Number::i32(0),
+            Err(Trap::IntegerDivideByZero)
+        );
+        assert_eq!(
+            Number::i64(1) % Number::i64(0),
+            Err(Trap::IntegerDivideByZero)
+        );
+        assert_eq!(
+            Number::i32(i32::MIN) / Number::i32(-1),
+            Err(Trap::IntegerOverflow)
+        );
+        assert_eq!(
+            Number::i64(i64::MIN) / Number::i64(-1),
+            Err(Trap::IntegerOverflow)
+        );
+        assert_eq!(Number::i32(i32::MIN) % Number::i32(-1), Ok(Number::i32(0)));
+        assert_eq!(Number::i32(-7) / Number::i32(2), Ok(Number::i32(-3)));
+        assert_eq!(Number::i32(-7) % Number::i32(2), Ok(Number::i32(-1)));
+    }
+
+    #[test]
+    fn float_division_by_zero_does_not_trap() {
+        assert_eq!(
+            Number::f64(F64::from(1.0)) / Number::f64(F64::from(0.0)),
+            Ok(Number::f64(F64::from(f64::INFINITY)))
+        );
+    }
+
+    #[test]
+    fn mixed_types_are_rejected() {
+        assert_eq!(Number::i32(1) + Number::i64(1), Err(Trap::TypeMismatch));
+        assert_eq!(
+            Number::i32(1) / Number::f32(F32::from(1.0)),
+            Err(Trap::TypeMismatch)
+        );
+        assert_ne!(Number::i32(1), Number::i64(1));
+        assert_eq!(Number::i32(1).partial_cmp(&Number::i64(1)), None);
+    }
+}
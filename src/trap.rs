@@ -0,0 +1,43 @@
+use std::fmt;
+
+// https://webassembly.github.io/spec/core/intro/overview.html#trap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trap {
+    Unreachable,
+    MemoryOutOfBounds,
+    TableOutOfBounds,
+    UndefinedElement,
+    UninitializedElement,
+    IndirectCallTypeMismatch,
+    IntegerDivideByZero,
+    IntegerOverflow,
+    InvalidConversionToInteger,
+    UnalignedAtomic,
+    ExpectedSharedMemory,
+    /// Calls and blocks nested deeper than the runtime's `MAX_DEPTH`.
+    CallStackExhausted,
+    /// Operands of different types, which a validated module never produces.
+    TypeMismatch,
+}
+
+impl fmt::Display for Trap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trap::Unreachable => write!(f, "unreachable"),
+            Trap::MemoryOutOfBounds => write!(f, "out of bounds memory access"),
+            Trap::TableOutOfBounds => write!(f, "out of bounds table access"),
+            Trap::UndefinedElement => write!(f, "undefined element"),
+            Trap::UninitializedElement => write!(f, "uninitialized element"),
+            Trap::IndirectCallTypeMismatch => write!(f, "indirect call type mismatch"),
+            Trap::IntegerDivideByZero => write!(f, "integer divide by zero"),
+            Trap::IntegerOverflow => write!(f, "integer overflow"),
+            Trap::InvalidConversionToInteger => write!(f, "invalid conversion to integer"),
+            Trap::UnalignedAtomic => write!(f, "unaligned atomic"),
+            Trap::ExpectedSharedMemory => write!(f, "expected shared memory"),
+            Trap::CallStackExhausted => write!(f, "call stack exhausted"),
+            Trap::TypeMismatch => write!(f, "type mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for Trap {}
@@ -1,4 +1,7 @@
-use crate::node::Node;
+use crate::{
+    leb128::{encode_i64_to_leb128, encode_u32_to_leb128},
+    node::{FunctionTypeNode, Node},
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum NumberType {
@@ -8,17 +11,19 @@ pub enum NumberType {
     F64,
 }
 
-// https://doc.rust-lang.org/std/convert/trait.From.html
-impl From<u8> for NumberType {
-    fn from(byte: u8) -> Self {
+// https://doc.rust-lang.org/std/convert/trait.TryFrom.html
+impl TryFrom<u8> for NumberType {
+    type Error = u8;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
         use NumberType::*;
 
         match byte {
-            0x7F => I32,
-            0x7E => I64,
-            0x7D => F32,
-            0x7C => F64,
-            _ => unreachable!("Invalid ValueType {:x}", byte),
+            0x7F => Ok(I32),
+            0x7E => Ok(I64),
+            0x7D => Ok(F32),
+            0x7C => Ok(F64),
+            _ => Err(byte),
         }
     }
 }
@@ -52,35 +57,67 @@ impl Node for NumberType {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum VectorTypeNode {
     V128,
 }
 
-impl From<u8> for VectorTypeNode {
-    fn from(byte: u8) -> Self {
+impl TryFrom<u8> for VectorTypeNode {
+    type Error = u8;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
         use VectorTypeNode::*;
 
         match byte {
-            0x7F => V128,
-            _ => unreachable!("Invalid ValueType {:x}", byte),
+            0x7B => Ok(V128),
+            _ => Err(byte),
+        }
+    }
+}
+
+impl Node for VectorTypeNode {
+    fn size(&self) -> u32 {
+        1
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            VectorTypeNode::V128 => vec![0x7B],
         }
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ReferenceTypeNode {
     FunctionRef,
     ExternRef,
 }
 
-impl From<u8> for ReferenceTypeNode {
-    fn from(byte: u8) -> Self {
+impl TryFrom<u8> for ReferenceTypeNode {
+    type Error = u8;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
         use ReferenceTypeNode::*;
 
         match byte {
-            0x70 => FunctionRef,
-            0x6F => ExternRef,
-            _ => unreachable!("Invalid ReferenceType {:x}", byte),
+            0x70 => Ok(FunctionRef),
+            0x6F => Ok(ExternRef),
+            _ => Err(byte),
+        }
+    }
+}
+
+impl Node for ReferenceTypeNode {
+    fn size(&self) -> u32 {
+        1
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        use ReferenceTypeNode::*;
+
+        match self {
+            FunctionRef => vec![0x70],
+            ExternRef => vec![0x6F],
         }
     }
 }
@@ -88,19 +125,21 @@ impl From<u8> for ReferenceTypeNode {
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ValueType {
     Number(NumberType),
-    // Vector(VectorType),
-    // Reference(ReferenceType),
+    Vector(VectorTypeNode),
+    Reference(ReferenceTypeNode),
 }
 
-impl From<u8> for ValueType {
-    fn from(byte: u8) -> Self {
+impl TryFrom<u8> for ValueType {
+    type Error = u8;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
         use ValueType::*;
 
         match byte {
-            0x7F | 0x7E | 0x7D | 0x7C => Number(NumberType::from(byte)),
-            // 0x => Vector(VectorType::from(byte)),
-            // 0x | 0x6F => Reference(ReferenceType::from(byte)),
-            _ => unreachable!("Invalid ValueType {:x}", byte),
+            0x7C..=0x7F => Ok(Number(NumberType::try_from(byte)?)),
+            0x7B => Ok(Vector(VectorTypeNode::try_from(byte)?)),
+            0x70 | 0x6F => Ok(Reference(ReferenceTypeNode::try_from(byte)?)),
+            _ => Err(byte),
         }
     }
 }
@@ -111,8 +150,9 @@ impl Into<u8> for ValueType {
 
         match self {
             Number(number_type) => number_type.into(),
-            // Vector(vector_type) => vector_type.into(),
-            // Reference(reference_type) => reference_type.into(),
+            Vector(VectorTypeNode::V128) => 0x7B,
+            Reference(ReferenceTypeNode::FunctionRef) => 0x70,
+            Reference(ReferenceTypeNode::ExternRef) => 0x6F,
         }
     }
 }
@@ -127,36 +167,65 @@ impl Node for ValueType {
 
         match self {
             Number(number_type) => number_type.encode(),
-            // Vector(vector_type) => vector_type.encode(),
-            // Reference(reference_type) => reference_type.encode(),
+            Vector(vector_type) => vector_type.encode(),
+            Reference(reference_type) => reference_type.encode(),
         }
     }
 }
 
+// https://webassembly.github.io/spec/core/binary/instructions.html#control-instructions
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum BlockType {
     Empty,
     ValType(ValueType),
-    // S33,
+    /// A function type, encoded as a non-negative s33, giving the block its
+    /// parameters and results.
+    TypeIndex(u32),
+}
+
+impl BlockType {
+    /// The number of values the block takes from and leaves on the stack;
+    /// type indexes are looked up in the module's `types`.
+    pub fn arity(&self, types: &[FunctionTypeNode]) -> (usize, usize) {
+        match self {
+            BlockType::Empty => (0, 0),
+            BlockType::ValType(_) => (0, 1),
+            BlockType::TypeIndex(index) => {
+                let function_type = &types[*index as usize];
+                (
+                    function_type.params.val_types.len(),
+                    function_type.returns.val_types.len(),
+                )
+            }
+        }
+    }
 }
 
-impl From<u8> for BlockType {
-    fn from(x: u8) -> BlockType {
+impl TryFrom<u8> for BlockType {
+    type Error = u8;
+
+    fn try_from(x: u8) -> Result<BlockType, Self::Error> {
         match x {
-            0x40 => BlockType::Empty,
-            0x7F => BlockType::ValType(ValueType::Number(NumberType::I32)),
-            // 0x7E => BlockType::ValType(ValueType::Number(NumberType::I64)),
-            // 0x7D => BlockType::ValType(ValueType::Number(NumberType::F32)),
-            // 0x7C => BlockType::ValType(ValueType::Number(NumberType::F64)),
-            // 0x70 => BlockType::S33,
-            _ => unreachable!("{} is an invalid value in BlockType", x),
+            0x40 => Ok(BlockType::Empty),
+            0x7F => Ok(BlockType::ValType(ValueType::Number(NumberType::I32))),
+            0x7E => Ok(BlockType::ValType(ValueType::Number(NumberType::I64))),
+            0x7D => Ok(BlockType::ValType(ValueType::Number(NumberType::F32))),
+            0x7C => Ok(BlockType::ValType(ValueType::Number(NumberType::F64))),
+            0x7B => Ok(BlockType::ValType(ValueType::Vector(VectorTypeNode::V128))),
+            0x70 | 0x6F => Ok(BlockType::ValType(ValueType::Reference(
+                ReferenceTypeNode::try_from(x)?,
+            ))),
+            _ => Err(x),
         }
     }
 }
 
 impl Node for BlockType {
     fn size(&self) -> u32 {
-        1
+        match self {
+            BlockType::TypeIndex(index) => encode_i64_to_leb128(*index as i64).len() as u32,
+            _ => 1,
+        }
     }
 
     fn encode(&self) -> Vec<u8> {
@@ -165,7 +234,120 @@ impl Node for BlockType {
         match self {
             Empty => vec![0x40],
             ValType(val_type) => val_type.encode(),
-            // S33 => vec![0x70],
+            TypeIndex(index) => encode_i64_to_leb128(*index as i64),
         }
     }
 }
+
+// https://webassembly.github.io/spec/core/binary/types.html#limits
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Limits {
+    pub min: u32,
+    pub max: Option<u32>,
+}
+
+impl Node for Limits {
+    fn size(&self) -> u32 {
+        let mut size = 0;
+        size += 1; // flag
+        size += encode_u32_to_leb128(self.min).len() as u32;
+        if let Some(max) = self.max {
+            size += encode_u32_to_leb128(max).len() as u32;
+        }
+        size
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        match self.max {
+            Some(max) => {
+                buffer.push(0x01);
+                buffer.extend(encode_u32_to_leb128(self.min));
+                buffer.extend(encode_u32_to_leb128(max));
+            }
+            None => {
+                buffer.push(0x00);
+                buffer.extend(encode_u32_to_leb128(self.min));
+            }
+        }
+        buffer
+    }
+}
+
+impl Limits {
+    /// Import matching: https://webassembly.github.io/spec/core/valid/types.html#limits
+    pub fn matches(&self, import: &Limits) -> bool {
+        if self.min < import.min {
+            return false;
+        }
+        match (self.max, import.max) {
+            (_, None) => true,
+            (Some(max), Some(import_max)) => max <= import_max,
+            (None, Some(_)) => false,
+        }
+    }
+}
+
+// https://webassembly.github.io/spec/core/binary/types.html#table-types
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TableType {
+    pub element_type: ReferenceTypeNode,
+    pub limits: Limits,
+}
+
+impl Node for TableType {
+    fn size(&self) -> u32 {
+        self.element_type.size() + self.limits.size()
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        buffer.extend(self.element_type.encode());
+        buffer.extend(self.limits.encode());
+        buffer
+    }
+}
+
+// https://webassembly.github.io/spec/core/binary/types.html#memory-types
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MemoryType {
+    pub limits: Limits,
+    /// Whether the memory can be accessed by several threads at once.
+    // https://github.com/WebAssembly/threads/blob/main/proposals/threads/Overview.md
+    pub shared: bool,
+}
+
+impl Node for MemoryType {
+    fn size(&self) -> u32 {
+        self.limits.size()
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = self.limits.encode();
+        if self.shared {
+            // a shared memory has a maximum, so this turns flag 0x01 into 0x03
+            buffer[0] |= 0x02;
+        }
+        buffer
+    }
+}
+
+// https://webassembly.github.io/spec/core/binary/types.html#global-types
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct GlobalType {
+    pub value_type: ValueType,
+    pub mutable: bool,
+}
+
+impl Node for GlobalType {
+    fn size(&self) -> u32 {
+        self.value_type.size() + 1
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        buffer.extend(self.value_type.encode());
+        buffer.push(if self.mutable { 0x01 } else { 0x00 });
+        buffer
+    }
+}
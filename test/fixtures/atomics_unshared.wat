@@ -0,0 +1,13 @@
+(module
+  (memory 1)
+
+  ;; each instruction under test is exported under its own name
+  (func (export "memory.atomic.notify") (param i32 i32) (result i32)
+    (memory.atomic.notify (local.get 0) (local.get 1)))
+  (func (export "memory.atomic.wait32") (param i32 i32 i64) (result i32)
+    (memory.atomic.wait32 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "i32.atomic.load") (param i32) (result i32)
+    (i32.atomic.load (local.get 0)))
+  (func (export "i32.atomic.rmw.add") (param i32 i32) (result i32)
+    (i32.atomic.rmw.add (local.get 0) (local.get 1)))
+)
@@ -0,0 +1,51 @@
+(module
+  ;; 100, 101 and 102 for cases 0 to 2, and 99 for anything else
+  (func (export "switch") (param $case i32) (result i32)
+    (block $default
+      (block $two
+        (block $one
+          (block $zero
+            (br_table $zero $one $two $default (local.get $case)))
+          (return (i32.const 100)))
+        (return (i32.const 101)))
+      (return (i32.const 102)))
+    (i32.const 99))
+
+  ;; br_table carrying a value, with the default as the only target
+  (func (export "br_table_value") (param i32) (result i32)
+    (block (result i32)
+      (i32.const 1)
+      (i32.const 7)
+      (br_table 0 (local.get 0))))
+
+  ;; the index of the first multiple of 7 at or above $from, returned from
+  ;; inside two loops
+  (func $next_multiple (export "next_multiple") (param $from i32) (result i32)
+    (local $i i32)
+    (local.set $i (local.get $from))
+    (loop $outer
+      (block $skip
+        (loop $inner
+          (br_if $skip (i32.rem_u (local.get $i) (i32.const 7)))
+          (return (local.get $i))))
+      (local.set $i (i32.add (local.get $i) (i32.const 1)))
+      (br $outer))
+    (i32.const -1))
+
+  ;; return leaves the stack of the caller's block intact
+  (func (export "call_returning") (param i32) (result i32)
+    (block (result i32)
+      (i32.const 1000)
+      (call $next_multiple (local.get 0))
+      (i32.add)))
+
+  (func (export "early_return") (param i32) (result i32)
+    (if (local.get 0)
+      (then (return (i32.const 1))))
+    (i32.const 2))
+
+  ;; return takes no values here, so the 5 is discarded
+  (func (export "return_void")
+    (i32.const 5)
+    (return))
+)
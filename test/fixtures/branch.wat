@@ -0,0 +1,101 @@
+(module
+  ;; number of steps for $n to reach 1
+  (func (export "collatz") (param $n i32) (result i32)
+    (local $steps i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.eq (local.get $n) (i32.const 1)))
+        (if (i32.and (local.get $n) (i32.const 1))
+          (then (local.set $n (i32.add (i32.mul (local.get $n) (i32.const 3)) (i32.const 1))))
+          (else (local.set $n (i32.shr_u (local.get $n) (i32.const 1)))))
+        (local.set $steps (i32.add (local.get $steps) (i32.const 1)))
+        (br $next)))
+    (local.get $steps))
+
+  ;; number of pairs j < i < $n
+  (func (export "count_pairs") (param $n i32) (result i32)
+    (local $i i32)
+    (local $j i32)
+    (local $count i32)
+    (block $outer_done
+      (loop $outer
+        (br_if $outer_done (i32.ge_s (local.get $i) (local.get $n)))
+        (local.set $j (i32.const 0))
+        (block $inner_done
+          (loop $inner
+            (br_if $inner_done (i32.ge_s (local.get $j) (local.get $i)))
+            (local.set $count (i32.add (local.get $count) (i32.const 1)))
+            (local.set $j (i32.add (local.get $j) (i32.const 1)))
+            (br $inner)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $outer)))
+    (local.get $count))
+
+  ;; the first factorisation $i * $j of $target as $i * 100 + $j, or -1,
+  ;; carried out of both loops by a single br
+  (func (export "find_factor") (param $target i32) (result i32)
+    (local $i i32)
+    (local $j i32)
+    (block $found (result i32)
+      (local.set $i (i32.const 2))
+      (loop $outer
+        (local.set $j (i32.const 2))
+        (loop $inner
+          (if (i32.eq (i32.mul (local.get $i) (local.get $j)) (local.get $target))
+            (then (br $found (i32.add (i32.mul (local.get $i) (i32.const 100)) (local.get $j)))))
+          (local.set $j (i32.add (local.get $j) (i32.const 1)))
+          (br_if $inner (i32.lt_s (local.get $j) (local.get $target))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br_if $outer (i32.lt_s (local.get $i) (local.get $target))))
+      (i32.const -1)))
+
+  ;; br keeps only the label's result and discards the 7 below it
+  (func (export "br_value") (result i32)
+    (block (result i32)
+      (i32.const 7)
+      (i32.const 42)
+      (br 0)))
+
+  (func (export "br_nested_value") (result i32)
+    (block (result i32)
+      (block
+        (block
+          (i32.const 9)
+          (br 2)))
+      (i32.const 0)))
+
+  ;; 10 when taken, 11 when not
+  (func (export "br_if_value") (param i32) (result i32)
+    (block (result i32)
+      (i32.const 10)
+      (br_if 0 (local.get 0))
+      (i32.const 1)
+      (i32.add)))
+
+  (func (export "br_from_if") (param i32) (result i32)
+    (block (result i32)
+      (if (local.get 0)
+        (then
+          (i32.const 5)
+          (br 1)))
+      (i32.const 6)))
+
+  ;; depth 2 targets the function body, which returns the argument
+  (func $br_function (export "br_function") (param i32) (result i32)
+    (block
+      (block
+        (local.get 0)
+        (br 2)))
+    (i32.const 0))
+
+  ;; the callee's branch must leave the caller's labels alone
+  (func (export "call_in_block") (param i32) (result i32)
+    (block (result i32)
+      (call $br_function (local.get 0)))
+    (i32.const 1)
+    (i32.add))
+
+  (func (export "loop_result") (result i32)
+    (loop (result i32)
+      (i32.const 4)))
+)
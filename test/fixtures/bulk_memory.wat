@@ -0,0 +1,44 @@
+(module
+  (type $i32_to_i32 (func (param i32) (result i32)))
+  (type $range (func (param i32 i32 i32)))
+  (type $void (func))
+  (type $returns_i32 (func (result i32)))
+
+  (table 4 funcref)
+  (memory 1)
+
+  (func $f0 (type $returns_i32) (i32.const 0))
+  (func $f1 (type $returns_i32) (i32.const 1))
+  (func $f2 (type $returns_i32) (i32.const 2))
+
+  (func (export "load8_u") (type $i32_to_i32)
+    (i32.load8_u (local.get 0)))
+
+  ;; each of these takes (dst, src or value, len)
+  (func (export "fill") (type $range)
+    (memory.fill (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "copy") (type $range)
+    (memory.copy (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init") (type $range)
+    (memory.init $hello (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init_active") (type $range)
+    (memory.init $bytes (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "drop_data") (type $void)
+    (data.drop $hello))
+
+  (func (export "table_init") (type $range)
+    (table.init $funcs (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "table_copy") (type $range)
+    (table.copy (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "elem_drop") (type $void)
+    (elem.drop $funcs))
+
+  (func (export "call") (type $i32_to_i32)
+    (call_indirect (type $returns_i32) (local.get 0)))
+
+  (elem $funcs func $f0 $f1 $f2)
+
+  ;; a data count section (id 12) precedes the code section
+  (data $hello "hello")
+  (data $bytes (i32.const 0) "\01\02\03\04\05")
+)
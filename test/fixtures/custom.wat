@@ -0,0 +1,8 @@
+;; custom sections cannot be written in text format; custom.wasm places
+;; "first" before the type section, "between" after the function section
+;; and "last" after the code section.
+(module
+  (func (export "answer") (result i32)
+    (i32.const 42)
+  )
+)
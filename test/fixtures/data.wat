@@ -0,0 +1,9 @@
+(module
+  (memory 1)
+  (data (i32.const 16) "hello")
+  (data $passive "world")
+
+  (func (export "load_byte") (param $addr i32) (result i32)
+    (i32.load8_u (local.get $addr))
+  )
+)
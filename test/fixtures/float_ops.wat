@@ -0,0 +1,174 @@
+(module
+  (func (export "f32.eq") (param f32 f32) (result i32)
+    (f32.eq (local.get 0) (local.get 1))
+  )
+
+  (func (export "f32.ne") (param f32 f32) (result i32)
+    (f32.ne (local.get 0) (local.get 1))
+  )
+
+  (func (export "f32.lt") (param f32 f32) (result i32)
+    (f32.lt (local.get 0) (local.get 1))
+  )
+
+  (func (export "f32.gt") (param f32 f32) (result i32)
+    (f32.gt (local.get 0) (local.get 1))
+  )
+
+  (func (export "f32.le") (param f32 f32) (result i32)
+    (f32.le (local.get 0) (local.get 1))
+  )
+
+  (func (export "f32.ge") (param f32 f32) (result i32)
+    (f32.ge (local.get 0) (local.get 1))
+  )
+
+  (func (export "f32.abs") (param f32) (result f32)
+    (f32.abs (local.get 0))
+  )
+
+  (func (export "f32.neg") (param f32) (result f32)
+    (f32.neg (local.get 0))
+  )
+
+  (func (export "f32.ceil") (param f32) (result f32)
+    (f32.ceil (local.get 0))
+  )
+
+  (func (export "f32.floor") (param f32) (result f32)
+    (f32.floor (local.get 0))
+  )
+
+  (func (export "f32.trunc") (param f32) (result f32)
+    (f32.trunc (local.get 0))
+  )
+
+  (func (export "f32.nearest") (param f32) (result f32)
+    (f32.nearest (local.get 0))
+  )
+
+  (func (export "f32.sqrt") (param f32) (result f32)
+    (f32.sqrt (local.get 0))
+  )
+
+  (func (export "f32.add") (param f32 f32) (result f32)
+    (f32.add (local.get 0) (local.get 1))
+  )
+
+  (func (export "f32.sub") (param f32 f32) (result f32)
+    (f32.sub (local.get 0) (local.get 1))
+  )
+
+  (func (export "f32.mul") (param f32 f32) (result f32)
+    (f32.mul (local.get 0) (local.get 1))
+  )
+
+  (func (export "f32.div") (param f32 f32) (result f32)
+    (f32.div (local.get 0) (local.get 1))
+  )
+
+  (func (export "f32.min") (param f32 f32) (result f32)
+    (f32.min (local.get 0) (local.get 1))
+  )
+
+  (func (export "f32.max") (param f32 f32) (result f32)
+    (f32.max (local.get 0) (local.get 1))
+  )
+
+  (func (export "f32.copysign") (param f32 f32) (result f32)
+    (f32.copysign (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.eq") (param f64 f64) (result i32)
+    (f64.eq (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.ne") (param f64 f64) (result i32)
+    (f64.ne (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.lt") (param f64 f64) (result i32)
+    (f64.lt (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.gt") (param f64 f64) (result i32)
+    (f64.gt (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.le") (param f64 f64) (result i32)
+    (f64.le (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.ge") (param f64 f64) (result i32)
+    (f64.ge (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.abs") (param f64) (result f64)
+    (f64.abs (local.get 0))
+  )
+
+  (func (export "f64.neg") (param f64) (result f64)
+    (f64.neg (local.get 0))
+  )
+
+  (func (export "f64.ceil") (param f64) (result f64)
+    (f64.ceil (local.get 0))
+  )
+
+  (func (export "f64.floor") (param f64) (result f64)
+    (f64.floor (local.get 0))
+  )
+
+  (func (export "f64.trunc") (param f64) (result f64)
+    (f64.trunc (local.get 0))
+  )
+
+  (func (export "f64.nearest") (param f64) (result f64)
+    (f64.nearest (local.get 0))
+  )
+
+  (func (export "f64.sqrt") (param f64) (result f64)
+    (f64.sqrt (local.get 0))
+  )
+
+  (func (export "f64.add") (param f64 f64) (result f64)
+    (f64.add (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.sub") (param f64 f64) (result f64)
+    (f64.sub (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.mul") (param f64 f64) (result f64)
+    (f64.mul (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.div") (param f64 f64) (result f64)
+    (f64.div (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.min") (param f64 f64) (result f64)
+    (f64.min (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.max") (param f64 f64) (result f64)
+    (f64.max (local.get 0) (local.get 1))
+  )
+
+  (func (export "f64.copysign") (param f64 f64) (result f64)
+    (f64.copysign (local.get 0) (local.get 1))
+  )
+
+  ;; NaN payloads survive locals and blocks bit for bit
+  (func (export "f32.snan") (result f32)
+    (local f32)
+    (local.set 0 (f32.const nan:0x200001))
+    (block (result f32) (local.get 0))
+  )
+
+  (func (export "f64.snan") (result f64)
+    (local f64)
+    (local.set 0 (f64.const -nan:0x4000000000001))
+    (block (result f64) (local.get 0))
+  )
+)
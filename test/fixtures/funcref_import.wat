@@ -0,0 +1,13 @@
+(module
+  (type $get (func (result i32)))
+  (import "env" "table" (table 2 funcref))
+
+  (elem declare func $own)
+
+  ;; function 0 here, as $first is function 0 of the exporting module
+  (func $own (result i32) (i32.const -1))
+  (func (export "call") (param i32) (result i32)
+    (call_indirect (type $get) (local.get 0)))
+  (func (export "install") (param i32)
+    (table.set 0 (local.get 0) (ref.func $own)))
+)
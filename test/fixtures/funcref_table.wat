@@ -0,0 +1,12 @@
+(module
+  (type $get (func (result i32)))
+  (global $base (mut i32) (i32.const 100))
+  (table (export "table") 2 funcref)
+  (elem (i32.const 0) $first $second)
+
+  ;; reads a global of this instance, which the caller does not have
+  (func $first (result i32) (global.get $base))
+  (func $second (result i32) (i32.const 2))
+  (func (export "call") (param i32) (result i32)
+    (call_indirect (type $get) (local.get 0)))
+)
@@ -0,0 +1,10 @@
+(module
+  (import "env" "step" (global $step i32))
+  (global $counter (mut i32) (i32.const 0))
+  (global $base i32 (global.get $step))
+
+  (func (export "count") (result i32)
+    (global.set $counter (i32.add (global.get $counter) (global.get $base)))
+    (global.get $counter)
+  )
+)
@@ -0,0 +1,8 @@
+;; invalid module: global.set targets an immutable global
+(module
+  (global $constant i32 (i32.const 42))
+
+  (func (export "set_constant")
+    (global.set $constant (i32.const 0))
+  )
+)
@@ -0,0 +1,117 @@
+(module
+  (func (export "eqz") (param i32) (result i32)
+    (i32.eqz (local.get 0))
+  )
+
+  (func (export "clz") (param i32) (result i32)
+    (i32.clz (local.get 0))
+  )
+
+  (func (export "ctz") (param i32) (result i32)
+    (i32.ctz (local.get 0))
+  )
+
+  (func (export "popcnt") (param i32) (result i32)
+    (i32.popcnt (local.get 0))
+  )
+
+  (func (export "eq") (param i32 i32) (result i32)
+    (i32.eq (local.get 0) (local.get 1))
+  )
+
+  (func (export "ne") (param i32 i32) (result i32)
+    (i32.ne (local.get 0) (local.get 1))
+  )
+
+  (func (export "lt_s") (param i32 i32) (result i32)
+    (i32.lt_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "lt_u") (param i32 i32) (result i32)
+    (i32.lt_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "gt_s") (param i32 i32) (result i32)
+    (i32.gt_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "gt_u") (param i32 i32) (result i32)
+    (i32.gt_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "le_s") (param i32 i32) (result i32)
+    (i32.le_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "le_u") (param i32 i32) (result i32)
+    (i32.le_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "ge_s") (param i32 i32) (result i32)
+    (i32.ge_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "ge_u") (param i32 i32) (result i32)
+    (i32.ge_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "add") (param i32 i32) (result i32)
+    (i32.add (local.get 0) (local.get 1))
+  )
+
+  (func (export "sub") (param i32 i32) (result i32)
+    (i32.sub (local.get 0) (local.get 1))
+  )
+
+  (func (export "mul") (param i32 i32) (result i32)
+    (i32.mul (local.get 0) (local.get 1))
+  )
+
+  (func (export "div_s") (param i32 i32) (result i32)
+    (i32.div_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "div_u") (param i32 i32) (result i32)
+    (i32.div_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "rem_s") (param i32 i32) (result i32)
+    (i32.rem_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "rem_u") (param i32 i32) (result i32)
+    (i32.rem_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "and") (param i32 i32) (result i32)
+    (i32.and (local.get 0) (local.get 1))
+  )
+
+  (func (export "or") (param i32 i32) (result i32)
+    (i32.or (local.get 0) (local.get 1))
+  )
+
+  (func (export "xor") (param i32 i32) (result i32)
+    (i32.xor (local.get 0) (local.get 1))
+  )
+
+  (func (export "shl") (param i32 i32) (result i32)
+    (i32.shl (local.get 0) (local.get 1))
+  )
+
+  (func (export "shr_s") (param i32 i32) (result i32)
+    (i32.shr_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "shr_u") (param i32 i32) (result i32)
+    (i32.shr_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "rotl") (param i32 i32) (result i32)
+    (i32.rotl (local.get 0) (local.get 1))
+  )
+
+  (func (export "rotr") (param i32 i32) (result i32)
+    (i32.rotr (local.get 0) (local.get 1))
+  )
+)
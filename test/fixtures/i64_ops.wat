@@ -0,0 +1,130 @@
+(module
+  (func (export "eqz") (param i64) (result i32)
+    (i64.eqz (local.get 0))
+  )
+
+  (func (export "clz") (param i64) (result i64)
+    (i64.clz (local.get 0))
+  )
+
+  (func (export "ctz") (param i64) (result i64)
+    (i64.ctz (local.get 0))
+  )
+
+  (func (export "popcnt") (param i64) (result i64)
+    (i64.popcnt (local.get 0))
+  )
+
+  (func (export "eq") (param i64 i64) (result i32)
+    (i64.eq (local.get 0) (local.get 1))
+  )
+
+  (func (export "ne") (param i64 i64) (result i32)
+    (i64.ne (local.get 0) (local.get 1))
+  )
+
+  (func (export "lt_s") (param i64 i64) (result i32)
+    (i64.lt_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "lt_u") (param i64 i64) (result i32)
+    (i64.lt_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "gt_s") (param i64 i64) (result i32)
+    (i64.gt_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "gt_u") (param i64 i64) (result i32)
+    (i64.gt_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "le_s") (param i64 i64) (result i32)
+    (i64.le_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "le_u") (param i64 i64) (result i32)
+    (i64.le_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "ge_s") (param i64 i64) (result i32)
+    (i64.ge_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "ge_u") (param i64 i64) (result i32)
+    (i64.ge_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "add") (param i64 i64) (result i64)
+    (i64.add (local.get 0) (local.get 1))
+  )
+
+  (func (export "sub") (param i64 i64) (result i64)
+    (i64.sub (local.get 0) (local.get 1))
+  )
+
+  (func (export "mul") (param i64 i64) (result i64)
+    (i64.mul (local.get 0) (local.get 1))
+  )
+
+  (func (export "div_s") (param i64 i64) (result i64)
+    (i64.div_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "div_u") (param i64 i64) (result i64)
+    (i64.div_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "rem_s") (param i64 i64) (result i64)
+    (i64.rem_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "rem_u") (param i64 i64) (result i64)
+    (i64.rem_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "and") (param i64 i64) (result i64)
+    (i64.and (local.get 0) (local.get 1))
+  )
+
+  (func (export "or") (param i64 i64) (result i64)
+    (i64.or (local.get 0) (local.get 1))
+  )
+
+  (func (export "xor") (param i64 i64) (result i64)
+    (i64.xor (local.get 0) (local.get 1))
+  )
+
+  (func (export "shl") (param i64 i64) (result i64)
+    (i64.shl (local.get 0) (local.get 1))
+  )
+
+  (func (export "shr_s") (param i64 i64) (result i64)
+    (i64.shr_s (local.get 0) (local.get 1))
+  )
+
+  (func (export "shr_u") (param i64 i64) (result i64)
+    (i64.shr_u (local.get 0) (local.get 1))
+  )
+
+  (func (export "rotl") (param i64 i64) (result i64)
+    (i64.rotl (local.get 0) (local.get 1))
+  )
+
+  (func (export "rotr") (param i64 i64) (result i64)
+    (i64.rotr (local.get 0) (local.get 1))
+  )
+
+  ;; one round of FNV-1a: (hash ^ byte) * prime
+  (func (export "fnv1a_step") (param $hash i64) (param $byte i64) (result i64)
+    (local $prime i64)
+    (local.set $prime (i64.const 0x100000001b3))
+    (block (result i64)
+      (i64.mul (i64.xor (local.get $hash) (local.get $byte)) (local.get $prime))
+    )
+  )
+
+  (func (export "fnv1a_offset") (result i64)
+    (i64.const 0xcbf29ce484222325)
+  )
+)
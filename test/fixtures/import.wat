@@ -0,0 +1,7 @@
+(module
+  (import "env" "print" (func $print (param i32)))
+  (import "env" "table" (table 1 funcref))
+  (import "env" "memory" (memory 1 2))
+  (import "env" "counter" (global $counter (mut i32)))
+  (func (export "noop"))
+)
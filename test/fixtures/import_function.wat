@@ -0,0 +1,7 @@
+(module
+  (import "env" "sub" (func $sub (param i32 i32) (result i32)))
+
+  (func (export "decrement") (param $p i32) (result i32)
+    (call $sub (local.get $p) (i32.const 1))
+  )
+)
@@ -0,0 +1,22 @@
+(module
+  (import "env" "table" (table 2 funcref))
+  (import "env" "memory" (memory 1))
+  (import "env" "counter" (global $counter (mut i32)))
+
+  (elem declare func $answer)
+
+  (func $answer (result i32) (i32.const 42))
+  (func (export "load") (param i32) (result i32)
+    (i32.load (local.get 0)))
+  (func (export "store") (param i32 i32)
+    (i32.store (local.get 0) (local.get 1)))
+  (func (export "bump")
+    (global.set $counter (i32.add (global.get $counter) (i32.const 1))))
+  (func (export "install") (param i32)
+    (table.set 0 (local.get 0) (ref.func $answer)))
+
+  ;; the imports are exported back to the host as they are
+  (export "table" (table 0))
+  (export "memory" (memory 0))
+  (export "counter" (global $counter))
+)
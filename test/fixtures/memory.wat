@@ -0,0 +1,31 @@
+(module
+  (memory 1 2)
+
+  (func (export "store_load") (param $addr i32) (param $value i32) (result i32)
+    (i32.store offset=4 (local.get $addr) (local.get $value))
+    (i32.load offset=4 (local.get $addr))
+  )
+
+  (func (export "load8_s") (param $addr i32) (result i32)
+    (i32.store8 (local.get $addr) (i32.const 255))
+    (i32.load8_s (local.get $addr))
+  )
+
+  (func (export "load16_u") (param $addr i32) (result i32)
+    (i32.store16 (local.get $addr) (i32.const -1))
+    (i32.load16_u (local.get $addr))
+  )
+
+  (func (export "load32_u") (param $addr i32) (result i64)
+    (i32.store (local.get $addr) (i32.const -1))
+    (i64.load32_u (local.get $addr))
+  )
+
+  (func (export "grow") (param $delta i32) (result i32)
+    (memory.grow (local.get $delta))
+  )
+
+  (func (export "size") (result i32)
+    (memory.size)
+  )
+)
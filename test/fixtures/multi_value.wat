@@ -0,0 +1,69 @@
+(module
+  (type $swap_type (func (param i32 i32) (result i32 i32)))
+  (type $pair (func (result i32 i32)))
+  (type $i32_to_pair (func (param i32) (result i32 i32)))
+  (type $binop (func (param i32 i32) (result i32)))
+  (type $i32_to_i32 (func (param i32) (result i32)))
+  (type $select_op (func (param i32 i32 i32) (result i32)))
+  (type $mixed (func (param i32) (result i32 i64)))
+
+  (func $swap (export "swap") (type $swap_type)
+    (local.get 1)
+    (local.get 0))
+  (func (export "swap_twice") (type $swap_type)
+    (call $swap (call $swap (local.get 0) (local.get 1))))
+  ;; unsigned quotient and remainder
+  (func (export "divmod") (type $swap_type)
+    (i32.div_u (local.get 0) (local.get 1))
+    (i32.rem_u (local.get 0) (local.get 1)))
+  (func (export "swap_sub") (type $binop)
+    (i32.sub (call $swap (local.get 0) (local.get 1))))
+
+  (func (export "block_params") (type $binop)
+    (local.get 0)
+    (local.get 1)
+    (block (type $binop) (param i32 i32) (result i32)
+      (i32.sub)))
+  (func (export "block_results") (type $pair)
+    (block (type $pair) (result i32 i32)
+      (i32.const 1)
+      (i32.const 2)))
+  ;; a branch carries both results out of the block
+  (func (export "br_values") (type $i32_to_pair)
+    (block (type $pair) (result i32 i32)
+      (i32.const 7)
+      (i32.const 8)
+      (br_if 0 (local.get 0))
+      (drop)
+      (drop)
+      (i32.const 9)
+      (i32.const 10)))
+
+  ;; sums n down to 1, looping with (acc, n) as the loop parameters
+  (func (export "loop_sum") (type $i32_to_i32) (local $n i32)
+    (i32.const 0)
+    (local.get 0)
+    (loop (type $binop) (param i32 i32) (result i32)
+      (local.set $n)
+      (i32.add (local.get $n))
+      (local.tee $n (i32.sub (local.get $n) (i32.const 1)))
+      (br_if 0 (local.get $n))
+      (drop)))
+
+  (func (export "if_params") (type $select_op)
+    (local.get 1)
+    (local.get 2)
+    (if (type $binop) (param i32 i32) (result i32) (local.get 0)
+      (then (i32.add))
+      (else (i32.sub))))
+  (func (export "if_results") (type $i32_to_pair)
+    (if (type $pair) (result i32 i32) (local.get 0)
+      (then (i32.const 1) (i32.const 2))
+      (else (i32.const 3) (i32.const 4))))
+
+  (func (export "return_values") (type $mixed)
+    (if (local.get 0)
+      (then (return (i32.const 1) (i64.const 2))))
+    (i32.const 3)
+    (i64.const 4))
+)
@@ -0,0 +1,37 @@
+(module
+  (func (export "select_i32") (param i32) (result i32)
+    (select (i32.const 10) (i32.const 20) (local.get 0)))
+
+  (func (export "select_i64") (param i32) (result i64)
+    (select (i64.const -1) (i64.const 0x1_0000_0000) (local.get 0)))
+
+  ;; typed select, encoded as 0x1C
+  (func (export "select_typed_f64") (param i32) (result f64)
+    (select (result f64) (f64.const 1.5) (f64.const -2.5) (local.get 0)))
+
+  (func (export "drop") (result i32)
+    (i32.const 1)
+    (i32.const 2)
+    (drop))
+
+  ;; 2x stored by local.tee and also kept on the stack, so 4x
+  (func (export "tee") (param i32) (result i32)
+    (local i32)
+    (i32.add
+      (local.tee 1 (i32.mul (local.get 0) (i32.const 2)))
+      (local.get 1)))
+
+  (func (export "nop") (result i32)
+    (nop)
+    (i32.const 3)
+    (nop))
+
+  (func (export "unreachable") (result i32)
+    (unreachable))
+
+  (func (export "unreachable_in_loop") (param i32) (result i32)
+    (loop
+      (br_if 0 (local.tee 0 (i32.sub (local.get 0) (i32.const 1))))
+      (unreachable))
+    (i32.const 0))
+)
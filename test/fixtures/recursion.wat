@@ -0,0 +1,16 @@
+(module
+  (type $forever (func (result i32)))
+  (table 1 funcref)
+  (elem (i32.const 0) $forever_indirect)
+
+  (func $countdown (export "countdown") (param i32) (result i32)
+    (if (result i32) (local.get 0)
+      (then (call $countdown (i32.sub (local.get 0) (i32.const 1))))
+      (else (i32.const 0))))
+
+  (func $forever (export "forever") (type $forever)
+    (call $forever))
+
+  (func $forever_indirect (export "forever_indirect") (type $forever)
+    (call_indirect (type $forever) (i32.const 0)))
+)
@@ -0,0 +1,56 @@
+(module
+  (type $describe_type (func (param externref) (result i32)))
+  (type $make_type (func (param i32) (result externref)))
+  (type $returns_i32 (func (result i32)))
+  (type $index (func (param i32)))
+  (type $i32_to_i32 (func (param i32) (result i32)))
+  (type $set_type (func (param i32 externref)))
+  (type $grow_type (func (param externref i32) (result i32)))
+  (type $fill_type (func (param i32 externref i32)))
+  (type $swap_type (func (param externref) (result externref)))
+
+  (import "env" "describe" (func $describe (type $describe_type)))
+  (import "env" "make" (func $make (type $make_type)))
+
+  (table $funcs 3 funcref)
+  (table $objects 2 10 externref)
+
+  (global $last (mut externref) (ref.null extern))
+
+  (elem declare func $answer)
+
+  (func $answer (type $returns_i32) (i32.const 42))
+
+  (func (export "is_null_func") (type $returns_i32)
+    (ref.is_null (ref.null func)))
+  (func (export "is_null_answer") (type $returns_i32)
+    (ref.is_null (ref.func $answer)))
+
+  (func (export "store_answer") (type $index)
+    (table.set $funcs (local.get 0) (ref.func $answer)))
+  (func (export "call_slot") (type $i32_to_i32)
+    (call_indirect $funcs (type $returns_i32) (local.get 0)))
+
+  (func (export "set_object") (type $set_type)
+    (table.set $objects (local.get 0) (local.get 1)))
+  (func (export "get_object") (type $make_type)
+    (table.get $objects (local.get 0)))
+  (func (export "objects_size") (type $returns_i32)
+    (table.size $objects))
+  (func (export "grow_objects") (type $grow_type)
+    (table.grow $objects (local.get 0) (local.get 1)))
+  (func (export "fill_objects") (type $fill_type)
+    (table.fill $objects (local.get 0) (local.get 1) (local.get 2)))
+
+  ;; externrefs pass through wasm to and from the host untouched
+  (func (export "describe") (type $describe_type)
+    (call $describe (local.get 0)))
+  (func (export "make") (type $make_type)
+    (call $make (local.get 0)))
+
+  ;; stores its argument and returns the previously stored one
+  (func (export "remember") (type $swap_type) (local $previous externref)
+    (local.set $previous (global.get $last))
+    (global.set $last (local.get 0))
+    (local.get $previous))
+)
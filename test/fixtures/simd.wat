@@ -0,0 +1,226 @@
+(module
+  (type $binary (func (param v128 v128) (result v128)))
+  (type $unary (func (param v128) (result v128)))
+  (type $shift (func (param v128 i32) (result v128)))
+  (type $test (func (param v128) (result i32)))
+  (type $extract_i64 (func (param v128) (result i64)))
+  (type $extract_f32 (func (param v128) (result f32)))
+  (type $extract_f64 (func (param v128) (result f64)))
+  (type $replace_i64 (func (param v128 i64) (result v128)))
+  (type $replace_f32 (func (param v128 f32) (result v128)))
+  (type $splat_i32 (func (param i32) (result v128)))
+  (type $splat_i64 (func (param i64) (result v128)))
+  (type $splat_f32 (func (param f32) (result v128)))
+  (type $splat_f64 (func (param f64) (result v128)))
+  (type $ternary (func (param v128 v128 v128) (result v128)))
+  (type $returns_v128 (func (result v128)))
+  (type $load_lane (func (param i32 v128) (result v128)))
+  (type $store (func (param i32 v128)))
+  (type $brighten (func (param i32 i32)))
+
+  (memory 1)
+  (global $ones v128 (v128.const i8x16 -1 -1 -1 -1 -1 -1 -1 -1 -1 -1 -1 -1 -1 -1 -1 -1))
+
+  ;; each instruction under test is exported under its own name
+  (func (export "i8x16.add_sat_s") (type $binary)
+    (i8x16.add_sat_s (local.get 0) (local.get 1)))
+  (func (export "i8x16.add_sat_u") (type $binary)
+    (i8x16.add_sat_u (local.get 0) (local.get 1)))
+  (func (export "i8x16.sub_sat_u") (type $binary)
+    (i8x16.sub_sat_u (local.get 0) (local.get 1)))
+  (func (export "i8x16.avgr_u") (type $binary)
+    (i8x16.avgr_u (local.get 0) (local.get 1)))
+  (func (export "i8x16.min_u") (type $binary)
+    (i8x16.min_u (local.get 0) (local.get 1)))
+  (func (export "i8x16.narrow_i16x8_s") (type $binary)
+    (i8x16.narrow_i16x8_s (local.get 0) (local.get 1)))
+  (func (export "i8x16.narrow_i16x8_u") (type $binary)
+    (i8x16.narrow_i16x8_u (local.get 0) (local.get 1)))
+  (func (export "i8x16.swizzle") (type $binary)
+    (i8x16.swizzle (local.get 0) (local.get 1)))
+  (func (export "i16x8.q15mulr_sat_s") (type $binary)
+    (i16x8.q15mulr_sat_s (local.get 0) (local.get 1)))
+  (func (export "i16x8.mul") (type $binary)
+    (i16x8.mul (local.get 0) (local.get 1)))
+  (func (export "i16x8.extmul_low_i8x16_s") (type $binary)
+    (i16x8.extmul_low_i8x16_s (local.get 0) (local.get 1)))
+  (func (export "i16x8.extmul_high_i8x16_u") (type $binary)
+    (i16x8.extmul_high_i8x16_u (local.get 0) (local.get 1)))
+  (func (export "i16x8.lt_s") (type $binary)
+    (i16x8.lt_s (local.get 0) (local.get 1)))
+  (func (export "i16x8.lt_u") (type $binary)
+    (i16x8.lt_u (local.get 0) (local.get 1)))
+  (func (export "i32x4.dot_i16x8_s") (type $binary)
+    (i32x4.dot_i16x8_s (local.get 0) (local.get 1)))
+  (func (export "i32x4.min_s") (type $binary)
+    (i32x4.min_s (local.get 0) (local.get 1)))
+  (func (export "i32x4.max_u") (type $binary)
+    (i32x4.max_u (local.get 0) (local.get 1)))
+  (func (export "i32x4.gt_u") (type $binary)
+    (i32x4.gt_u (local.get 0) (local.get 1)))
+  (func (export "i64x2.mul") (type $binary)
+    (i64x2.mul (local.get 0) (local.get 1)))
+  (func (export "i64x2.lt_s") (type $binary)
+    (i64x2.lt_s (local.get 0) (local.get 1)))
+  (func (export "i64x2.extmul_high_i32x4_u") (type $binary)
+    (i64x2.extmul_high_i32x4_u (local.get 0) (local.get 1)))
+  (func (export "f32x4.add") (type $binary)
+    (f32x4.add (local.get 0) (local.get 1)))
+  (func (export "f32x4.div") (type $binary)
+    (f32x4.div (local.get 0) (local.get 1)))
+  (func (export "f32x4.min") (type $binary)
+    (f32x4.min (local.get 0) (local.get 1)))
+  (func (export "f32x4.max") (type $binary)
+    (f32x4.max (local.get 0) (local.get 1)))
+  (func (export "f32x4.pmin") (type $binary)
+    (f32x4.pmin (local.get 0) (local.get 1)))
+  (func (export "f32x4.pmax") (type $binary)
+    (f32x4.pmax (local.get 0) (local.get 1)))
+  (func (export "f32x4.eq") (type $binary)
+    (f32x4.eq (local.get 0) (local.get 1)))
+  (func (export "f32x4.lt") (type $binary)
+    (f32x4.lt (local.get 0) (local.get 1)))
+  (func (export "f64x2.sub") (type $binary)
+    (f64x2.sub (local.get 0) (local.get 1)))
+  (func (export "f64x2.max") (type $binary)
+    (f64x2.max (local.get 0) (local.get 1)))
+  (func (export "f64x2.ne") (type $binary)
+    (f64x2.ne (local.get 0) (local.get 1)))
+  (func (export "v128.and") (type $binary)
+    (v128.and (local.get 0) (local.get 1)))
+  (func (export "v128.andnot") (type $binary)
+    (v128.andnot (local.get 0) (local.get 1)))
+  (func (export "i8x16.shuffle") (type $binary)
+    (i8x16.shuffle 0 16 1 17 2 18 3 19 4 20 5 21 6 22 7 23 (local.get 0) (local.get 1)))
+  (func (export "i8x16.abs") (type $unary)
+    (i8x16.abs (local.get 0)))
+  (func (export "i8x16.neg") (type $unary)
+    (i8x16.neg (local.get 0)))
+  (func (export "i8x16.popcnt") (type $unary)
+    (i8x16.popcnt (local.get 0)))
+  (func (export "i16x8.extadd_pairwise_i8x16_u") (type $unary)
+    (i16x8.extadd_pairwise_i8x16_u (local.get 0)))
+  (func (export "i32x4.extadd_pairwise_i16x8_s") (type $unary)
+    (i32x4.extadd_pairwise_i16x8_s (local.get 0)))
+  (func (export "i16x8.extend_high_i8x16_s") (type $unary)
+    (i16x8.extend_high_i8x16_s (local.get 0)))
+  (func (export "i32x4.extend_low_i16x8_u") (type $unary)
+    (i32x4.extend_low_i16x8_u (local.get 0)))
+  (func (export "i64x2.extend_low_i32x4_s") (type $unary)
+    (i64x2.extend_low_i32x4_s (local.get 0)))
+  (func (export "i64x2.abs") (type $unary)
+    (i64x2.abs (local.get 0)))
+  (func (export "f32x4.nearest") (type $unary)
+    (f32x4.nearest (local.get 0)))
+  (func (export "f32x4.sqrt") (type $unary)
+    (f32x4.sqrt (local.get 0)))
+  (func (export "f32x4.neg") (type $unary)
+    (f32x4.neg (local.get 0)))
+  (func (export "f64x2.floor") (type $unary)
+    (f64x2.floor (local.get 0)))
+  (func (export "i32x4.trunc_sat_f32x4_s") (type $unary)
+    (i32x4.trunc_sat_f32x4_s (local.get 0)))
+  (func (export "i32x4.trunc_sat_f32x4_u") (type $unary)
+    (i32x4.trunc_sat_f32x4_u (local.get 0)))
+  (func (export "f32x4.convert_i32x4_u") (type $unary)
+    (f32x4.convert_i32x4_u (local.get 0)))
+  (func (export "i32x4.trunc_sat_f64x2_s_zero") (type $unary)
+    (i32x4.trunc_sat_f64x2_s_zero (local.get 0)))
+  (func (export "f64x2.convert_low_i32x4_s") (type $unary)
+    (f64x2.convert_low_i32x4_s (local.get 0)))
+  (func (export "f32x4.demote_f64x2_zero") (type $unary)
+    (f32x4.demote_f64x2_zero (local.get 0)))
+  (func (export "f64x2.promote_low_f32x4") (type $unary)
+    (f64x2.promote_low_f32x4 (local.get 0)))
+  (func (export "v128.not") (type $unary)
+    (v128.not (local.get 0)))
+  (func (export "i8x16.shl") (type $shift)
+    (i8x16.shl (local.get 0) (local.get 1)))
+  (func (export "i8x16.shr_u") (type $shift)
+    (i8x16.shr_u (local.get 0) (local.get 1)))
+  (func (export "i16x8.shr_s") (type $shift)
+    (i16x8.shr_s (local.get 0) (local.get 1)))
+  (func (export "i64x2.shr_s") (type $shift)
+    (i64x2.shr_s (local.get 0) (local.get 1)))
+  (func (export "v128.any_true") (type $test)
+    (v128.any_true (local.get 0)))
+  (func (export "i8x16.all_true") (type $test)
+    (i8x16.all_true (local.get 0)))
+  (func (export "i32x4.all_true") (type $test)
+    (i32x4.all_true (local.get 0)))
+  (func (export "i8x16.bitmask") (type $test)
+    (i8x16.bitmask (local.get 0)))
+  (func (export "i16x8.bitmask") (type $test)
+    (i16x8.bitmask (local.get 0)))
+  (func (export "i64x2.bitmask") (type $test)
+    (i64x2.bitmask (local.get 0)))
+  (func (export "i8x16.extract_lane_s") (type $test)
+    (i8x16.extract_lane_s 15 (local.get 0)))
+  (func (export "i8x16.extract_lane_u") (type $test)
+    (i8x16.extract_lane_u 15 (local.get 0)))
+  (func (export "i16x8.extract_lane_s") (type $test)
+    (i16x8.extract_lane_s 7 (local.get 0)))
+  (func (export "i32x4.extract_lane") (type $test)
+    (i32x4.extract_lane 3 (local.get 0)))
+  (func (export "i64x2.extract_lane") (type $extract_i64)
+    (i64x2.extract_lane 1 (local.get 0)))
+  (func (export "f32x4.extract_lane") (type $extract_f32)
+    (f32x4.extract_lane 2 (local.get 0)))
+  (func (export "f64x2.extract_lane") (type $extract_f64)
+    (f64x2.extract_lane 1 (local.get 0)))
+  (func (export "i8x16.replace_lane") (type $shift)
+    (i8x16.replace_lane 0 (local.get 0) (local.get 1)))
+  (func (export "i32x4.replace_lane") (type $shift)
+    (i32x4.replace_lane 3 (local.get 0) (local.get 1)))
+  (func (export "i64x2.replace_lane") (type $replace_i64)
+    (i64x2.replace_lane 0 (local.get 0) (local.get 1)))
+  (func (export "f32x4.replace_lane") (type $replace_f32)
+    (f32x4.replace_lane 1 (local.get 0) (local.get 1)))
+  (func (export "i8x16.splat") (type $splat_i32)
+    (i8x16.splat (local.get 0)))
+  (func (export "i16x8.splat") (type $splat_i32)
+    (i16x8.splat (local.get 0)))
+  (func (export "i64x2.splat") (type $splat_i64)
+    (i64x2.splat (local.get 0)))
+  (func (export "f32x4.splat") (type $splat_f32)
+    (f32x4.splat (local.get 0)))
+  (func (export "f64x2.splat") (type $splat_f64)
+    (f64x2.splat (local.get 0)))
+  (func (export "v128.bitselect") (type $ternary)
+    (v128.bitselect (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "v128.const") (type $returns_v128)
+    (v128.const i32x4 1 2 3 4))
+  (func (export "v128.load") (type $splat_i32)
+    (v128.load (local.get 0)))
+  (func (export "v128.load8x8_s") (type $splat_i32)
+    (v128.load8x8_s (local.get 0)))
+  (func (export "v128.load16x4_u") (type $splat_i32)
+    (v128.load16x4_u (local.get 0)))
+  (func (export "v128.load32x2_s") (type $splat_i32)
+    (v128.load32x2_s (local.get 0)))
+  (func (export "v128.load8_splat") (type $splat_i32)
+    (v128.load8_splat (local.get 0)))
+  (func (export "v128.load64_splat") (type $splat_i32)
+    (v128.load64_splat (local.get 0)))
+  (func (export "v128.load32_zero") (type $splat_i32)
+    (v128.load32_zero (local.get 0)))
+  (func (export "v128.load8_lane") (type $load_lane)
+    (v128.load8_lane 15 (local.get 0) (local.get 1)))
+  (func (export "v128.load32_lane") (type $load_lane)
+    (v128.load32_lane 1 (local.get 0) (local.get 1)))
+  (func (export "v128.store") (type $store)
+    (v128.store (local.get 0) (local.get 1)))
+  (func (export "v128.store16_lane") (type $store)
+    (v128.store16_lane 1 (local.get 0) (local.get 1)))
+  (func (export "ones") (type $returns_v128)
+    (global.get $ones))
+
+  ;; brightens the 16 pixels at the address, saturating at 255
+  (func (export "brighten") (type $brighten)
+    (v128.store (local.get 0)
+      (i8x16.add_sat_u (v128.load (local.get 0)) (i8x16.splat (local.get 1)))))
+
+  (data (i32.const 0)
+    "\00\01\02\03\04\05\06\07\08\09\0a\0b\0c\0d\0e\0f"
+    "\80\81\82\83\84\85\86\87\88\89\8a\8b\8c\8d\8e\8f")
+)
@@ -0,0 +1,4 @@
+(module
+  (func $f (param i32))
+  (start $f)
+)
@@ -0,0 +1,4 @@
+(module
+  (func $start (call $start))
+  (start $start)
+)
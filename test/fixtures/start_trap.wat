@@ -0,0 +1,8 @@
+(module
+  (memory 1)
+
+  (func $boom
+    (i32.store (i32.const 65536) (i32.const 1))
+  )
+  (start $boom)
+)
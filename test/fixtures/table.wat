@@ -0,0 +1,23 @@
+(module
+  (type $binary (func (param i32 i32) (result i32)))
+  (type $unary (func (param i32) (result i32)))
+  (type $ternary (func (param i32 i32 i32) (result i32)))
+  (table 4 funcref)
+  (elem (i32.const 0) $add $sub $negate)
+  (elem func $add)
+  (elem declare func $sub)
+  (elem funcref (ref.null func) (ref.func $negate))
+
+  (func $add (type $binary)
+    (i32.add (local.get 0) (local.get 1))
+  )
+  (func $sub (type $binary)
+    (i32.sub (local.get 0) (local.get 1))
+  )
+  (func $negate (type $unary)
+    (i32.sub (i32.const 0) (local.get 0))
+  )
+  (func (export "call_binary") (type $ternary)
+    (call_indirect (type $binary) (local.get 1) (local.get 2) (local.get 0))
+  )
+)
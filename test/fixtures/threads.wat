@@ -0,0 +1,148 @@
+(module
+  (import "env" "memory" (memory 1 1 shared))
+
+  ;; each instruction under test is exported under its own name
+  (func (export "memory.atomic.notify") (param i32 i32) (result i32)
+    (memory.atomic.notify (local.get 0) (local.get 1)))
+  (func (export "memory.atomic.wait32") (param i32 i32 i64) (result i32)
+    (memory.atomic.wait32 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "memory.atomic.wait64") (param i32 i64 i64) (result i32)
+    (memory.atomic.wait64 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "atomic.fence")
+    (atomic.fence))
+  (func (export "i32.atomic.load") (param i32) (result i32)
+    (i32.atomic.load (local.get 0)))
+  (func (export "i64.atomic.load") (param i32) (result i64)
+    (i64.atomic.load (local.get 0)))
+  (func (export "i32.atomic.load8_u") (param i32) (result i32)
+    (i32.atomic.load8_u (local.get 0)))
+  (func (export "i32.atomic.load16_u") (param i32) (result i32)
+    (i32.atomic.load16_u (local.get 0)))
+  (func (export "i64.atomic.load8_u") (param i32) (result i64)
+    (i64.atomic.load8_u (local.get 0)))
+  (func (export "i64.atomic.load16_u") (param i32) (result i64)
+    (i64.atomic.load16_u (local.get 0)))
+  (func (export "i64.atomic.load32_u") (param i32) (result i64)
+    (i64.atomic.load32_u (local.get 0)))
+  (func (export "i32.atomic.store") (param i32 i32)
+    (i32.atomic.store (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.store") (param i32 i64)
+    (i64.atomic.store (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.store8") (param i32 i32)
+    (i32.atomic.store8 (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.store16") (param i32 i32)
+    (i32.atomic.store16 (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.store8") (param i32 i64)
+    (i64.atomic.store8 (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.store16") (param i32 i64)
+    (i64.atomic.store16 (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.store32") (param i32 i64)
+    (i64.atomic.store32 (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw.add") (param i32 i32) (result i32)
+    (i32.atomic.rmw.add (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw.add") (param i32 i64) (result i64)
+    (i64.atomic.rmw.add (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw8.add_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw8.add_u (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw16.add_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw16.add_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw8.add_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw8.add_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw16.add_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw16.add_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw32.add_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw32.add_u (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw.sub") (param i32 i32) (result i32)
+    (i32.atomic.rmw.sub (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw.sub") (param i32 i64) (result i64)
+    (i64.atomic.rmw.sub (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw8.sub_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw8.sub_u (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw16.sub_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw16.sub_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw8.sub_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw8.sub_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw16.sub_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw16.sub_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw32.sub_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw32.sub_u (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw.and") (param i32 i32) (result i32)
+    (i32.atomic.rmw.and (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw.and") (param i32 i64) (result i64)
+    (i64.atomic.rmw.and (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw8.and_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw8.and_u (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw16.and_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw16.and_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw8.and_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw8.and_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw16.and_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw16.and_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw32.and_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw32.and_u (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw.or") (param i32 i32) (result i32)
+    (i32.atomic.rmw.or (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw.or") (param i32 i64) (result i64)
+    (i64.atomic.rmw.or (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw8.or_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw8.or_u (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw16.or_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw16.or_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw8.or_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw8.or_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw16.or_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw16.or_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw32.or_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw32.or_u (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw.xor") (param i32 i32) (result i32)
+    (i32.atomic.rmw.xor (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw.xor") (param i32 i64) (result i64)
+    (i64.atomic.rmw.xor (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw8.xor_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw8.xor_u (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw16.xor_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw16.xor_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw8.xor_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw8.xor_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw16.xor_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw16.xor_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw32.xor_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw32.xor_u (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw.xchg") (param i32 i32) (result i32)
+    (i32.atomic.rmw.xchg (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw.xchg") (param i32 i64) (result i64)
+    (i64.atomic.rmw.xchg (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw8.xchg_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw8.xchg_u (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw16.xchg_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw16.xchg_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw8.xchg_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw8.xchg_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw16.xchg_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw16.xchg_u (local.get 0) (local.get 1)))
+  (func (export "i64.atomic.rmw32.xchg_u") (param i32 i64) (result i64)
+    (i64.atomic.rmw32.xchg_u (local.get 0) (local.get 1)))
+  (func (export "i32.atomic.rmw.cmpxchg") (param i32 i32 i32) (result i32)
+    (i32.atomic.rmw.cmpxchg (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "i64.atomic.rmw.cmpxchg") (param i32 i64 i64) (result i64)
+    (i64.atomic.rmw.cmpxchg (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "i32.atomic.rmw8.cmpxchg_u") (param i32 i32 i32) (result i32)
+    (i32.atomic.rmw8.cmpxchg_u (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "i32.atomic.rmw16.cmpxchg_u") (param i32 i32 i32) (result i32)
+    (i32.atomic.rmw16.cmpxchg_u (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "i64.atomic.rmw8.cmpxchg_u") (param i32 i64 i64) (result i64)
+    (i64.atomic.rmw8.cmpxchg_u (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "i64.atomic.rmw16.cmpxchg_u") (param i32 i64 i64) (result i64)
+    (i64.atomic.rmw16.cmpxchg_u (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "i64.atomic.rmw32.cmpxchg_u") (param i32 i64 i64) (result i64)
+    (i64.atomic.rmw32.cmpxchg_u (local.get 0) (local.get 1) (local.get 2)))
+
+  ;; adds 1 to the i32 at the address, `count` times
+  (func (export "increment") (param $address i32) (param $count i32)
+    (block
+      (loop
+        (br_if 1 (i32.eqz (local.get $count)))
+        (drop (i32.atomic.rmw.add (local.get $address) (i32.const 1)))
+        (local.set $count (i32.sub (local.get $count) (i32.const 1)))
+        (br 0))))
+)